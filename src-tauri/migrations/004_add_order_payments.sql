@@ -0,0 +1,13 @@
+-- Formas de pagamento de cada pedido (permite pagamento dividido)
+CREATE TABLE IF NOT EXISTS order_payments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    order_id INTEGER NOT NULL,
+    method TEXT CHECK(method IN ('cash','pix','debit','credit','on_account')) NOT NULL,
+    amount REAL NOT NULL,
+    tendered REAL,
+    change_due REAL NOT NULL DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY(order_id) REFERENCES orders(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_order_payments_order_id ON order_payments(order_id);
@@ -0,0 +1,21 @@
+-- Limite de crédito (fiado) por cliente
+ALTER TABLE customers ADD COLUMN credit_limit REAL NOT NULL DEFAULT 0;
+
+-- Tabela: customer_ledger (conta corrente do cliente)
+-- amount positivo aumenta o saldo devedor (CHARGE), negativo reduz (PAYMENT)
+CREATE TABLE IF NOT EXISTS customer_ledger (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    customer_id INTEGER NOT NULL,
+    entry_type TEXT CHECK(entry_type IN ('CHARGE','PAYMENT','ADJUST')) NOT NULL,
+    amount REAL NOT NULL,
+    method TEXT,
+    order_id INTEGER,
+    user_id INTEGER,
+    description TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY(customer_id) REFERENCES customers(id),
+    FOREIGN KEY(order_id) REFERENCES orders(id),
+    FOREIGN KEY(user_id) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_customer_ledger_customer_id ON customer_ledger(customer_id);
@@ -0,0 +1,47 @@
+-- Tabela: cash_sessions (abertura/fechamento de caixa)
+CREATE TABLE IF NOT EXISTS cash_sessions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    status TEXT CHECK(status IN ('open','closed')) NOT NULL DEFAULT 'open',
+    opening_float REAL NOT NULL,
+    opened_by INTEGER NOT NULL,
+    opened_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    closed_by INTEGER,
+    closed_at DATETIME,
+    notes TEXT,
+    FOREIGN KEY(opened_by) REFERENCES users(id),
+    FOREIGN KEY(closed_by) REFERENCES users(id)
+);
+
+-- Apenas um caixa aberto por vez
+CREATE UNIQUE INDEX IF NOT EXISTS idx_cash_sessions_single_open
+    ON cash_sessions(status) WHERE status = 'open';
+
+-- Tabela: cash_movements (sangria e suprimento)
+CREATE TABLE IF NOT EXISTS cash_movements (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id INTEGER NOT NULL,
+    movement_type TEXT CHECK(movement_type IN ('WITHDRAWAL','SUPPLY')) NOT NULL,
+    amount REAL NOT NULL,
+    reason TEXT,
+    user_id INTEGER,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY(session_id) REFERENCES cash_sessions(id),
+    FOREIGN KEY(user_id) REFERENCES users(id)
+);
+
+-- Tabela: cash_session_counts (conferência no fechamento, por forma de pagamento)
+CREATE TABLE IF NOT EXISTS cash_session_counts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id INTEGER NOT NULL,
+    method TEXT NOT NULL,
+    expected REAL NOT NULL,
+    counted REAL NOT NULL,
+    FOREIGN KEY(session_id) REFERENCES cash_sessions(id)
+);
+
+-- Operador e caixa de cada venda
+ALTER TABLE orders ADD COLUMN user_id INTEGER REFERENCES users(id);
+ALTER TABLE orders ADD COLUMN session_id INTEGER REFERENCES cash_sessions(id);
+
+-- Recebimentos de fiado também entram no caixa aberto
+ALTER TABLE customer_ledger ADD COLUMN session_id INTEGER REFERENCES cash_sessions(id);
@@ -0,0 +1,20 @@
+-- Tabela: bottle_loans (comodato de vasilhame por cliente)
+-- LENT: cliente levou o produto sem devolver casco; RETURNED: casco devolvido
+CREATE TABLE IF NOT EXISTS bottle_loans (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    customer_id INTEGER NOT NULL,
+    product_id INTEGER NOT NULL,
+    entry_type TEXT CHECK(entry_type IN ('LENT','RETURNED')) NOT NULL,
+    quantity INTEGER NOT NULL CHECK(quantity > 0),
+    deposit_amount REAL NOT NULL DEFAULT 0,
+    order_id INTEGER,
+    user_id INTEGER,
+    notes TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY(customer_id) REFERENCES customers(id),
+    FOREIGN KEY(product_id) REFERENCES products(id),
+    FOREIGN KEY(order_id) REFERENCES orders(id),
+    FOREIGN KEY(user_id) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_bottle_loans_customer_id ON bottle_loans(customer_id);
@@ -0,0 +1,21 @@
+-- Troca com fornecedor (vazios enviados, cheios recebidos) e custo pago por unidade.
+-- O SQLite não altera CHECK, então a tabela é recriada.
+CREATE TABLE stock_movements_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    product_id INTEGER,
+    movement_type TEXT CHECK(movement_type IN ('IN','OUT','ADJUST','RETURN','REVERSAL','EXCHANGE')) NOT NULL,
+    stock_kind TEXT CHECK(stock_kind IN ('full','empty')) NOT NULL DEFAULT 'full',
+    quantity INTEGER NOT NULL,
+    unit_cost REAL,
+    order_id INTEGER,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY(product_id) REFERENCES products(id)
+);
+
+INSERT INTO stock_movements_new (id, product_id, movement_type, stock_kind, quantity, order_id, created_at)
+SELECT id, product_id, movement_type, stock_kind, quantity, order_id, created_at FROM stock_movements;
+
+DROP TABLE stock_movements;
+ALTER TABLE stock_movements_new RENAME TO stock_movements;
+
+CREATE INDEX IF NOT EXISTS idx_stock_movements_product_id ON stock_movements(product_id);
@@ -0,0 +1,52 @@
+-- Tabela: suppliers (fornecedores)
+CREATE TABLE IF NOT EXISTS suppliers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    document TEXT,
+    phone TEXT,
+    email TEXT,
+    notes TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Tabela: purchase_orders (pedidos de compra)
+CREATE TABLE IF NOT EXISTS purchase_orders (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    supplier_id INTEGER NOT NULL,
+    status TEXT CHECK(status IN ('open','partial','received','cancelled')) NOT NULL DEFAULT 'open',
+    expected_date DATE,
+    notes TEXT,
+    user_id INTEGER,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY(supplier_id) REFERENCES suppliers(id),
+    FOREIGN KEY(user_id) REFERENCES users(id)
+);
+
+-- Tabela: purchase_order_items
+CREATE TABLE IF NOT EXISTS purchase_order_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    purchase_order_id INTEGER NOT NULL,
+    product_id INTEGER NOT NULL,
+    quantity_ordered INTEGER NOT NULL CHECK(quantity_ordered > 0),
+    quantity_received INTEGER NOT NULL DEFAULT 0,
+    unit_cost REAL NOT NULL,
+    FOREIGN KEY(purchase_order_id) REFERENCES purchase_orders(id),
+    FOREIGN KEY(product_id) REFERENCES products(id)
+);
+
+-- Tabela: purchase_receipts (cada recebimento, com a nota fiscal correspondente)
+CREATE TABLE IF NOT EXISTS purchase_receipts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    purchase_order_id INTEGER NOT NULL,
+    invoice_number TEXT,
+    notes TEXT,
+    user_id INTEGER,
+    received_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY(purchase_order_id) REFERENCES purchase_orders(id),
+    FOREIGN KEY(user_id) REFERENCES users(id)
+);
+
+-- Entradas de estoque rastreáveis até o recebimento de origem
+ALTER TABLE stock_movements ADD COLUMN purchase_receipt_id INTEGER REFERENCES purchase_receipts(id);
+
+CREATE INDEX IF NOT EXISTS idx_purchase_order_items_po_id ON purchase_order_items(purchase_order_id);
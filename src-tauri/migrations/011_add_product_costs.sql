@@ -0,0 +1,6 @@
+-- Custo do último recebimento e custo médio ponderado do estoque de cheios
+ALTER TABLE products ADD COLUMN cost_price REAL NOT NULL DEFAULT 0;
+ALTER TABLE products ADD COLUMN average_cost REAL NOT NULL DEFAULT 0;
+
+-- Custo médio vigente no momento da venda, para cálculo de margem
+ALTER TABLE order_items ADD COLUMN unit_cost REAL NOT NULL DEFAULT 0;
@@ -0,0 +1,7 @@
+-- Situação do pedido: cancelamento substitui a exclusão física
+ALTER TABLE orders ADD COLUMN status TEXT CHECK(status IN ('open','completed','cancelled')) NOT NULL DEFAULT 'completed';
+ALTER TABLE orders ADD COLUMN cancel_reason TEXT;
+ALTER TABLE orders ADD COLUMN cancelled_by INTEGER REFERENCES users(id);
+ALTER TABLE orders ADD COLUMN cancelled_at DATETIME;
+
+CREATE INDEX IF NOT EXISTS idx_orders_status ON orders(status);
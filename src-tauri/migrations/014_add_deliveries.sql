@@ -0,0 +1,18 @@
+-- Tabela: deliveries (entrega vinculada ao pedido)
+CREATE TABLE IF NOT EXISTS deliveries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    order_id INTEGER NOT NULL UNIQUE,
+    address TEXT NOT NULL, -- Endereço no momento do pedido
+    driver_name TEXT,
+    delivery_fee REAL NOT NULL DEFAULT 0,
+    status TEXT CHECK(status IN ('pending','out_for_delivery','delivered','failed')) NOT NULL DEFAULT 'pending',
+    notes TEXT,
+    failure_reason TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    dispatched_at DATETIME,
+    delivered_at DATETIME,
+    failed_at DATETIME,
+    FOREIGN KEY(order_id) REFERENCES orders(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_deliveries_status ON deliveries(status);
@@ -0,0 +1,76 @@
+-- Tabela: drivers (entregadores)
+CREATE TABLE IF NOT EXISTS drivers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    phone TEXT,
+    active BOOLEAN NOT NULL DEFAULT 1,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Tabela: driver_shifts (saída do entregador e acerto no fim do turno)
+CREATE TABLE IF NOT EXISTS driver_shifts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    driver_id INTEGER NOT NULL,
+    status TEXT CHECK(status IN ('open','closed')) NOT NULL DEFAULT 'open',
+    change_fund REAL NOT NULL DEFAULT 0, -- Troco levado pelo entregador
+    cash_expected REAL,
+    cash_counted REAL,
+    opened_by INTEGER NOT NULL,
+    opened_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    closed_by INTEGER,
+    closed_at DATETIME,
+    notes TEXT,
+    FOREIGN KEY(driver_id) REFERENCES drivers(id),
+    FOREIGN KEY(opened_by) REFERENCES users(id),
+    FOREIGN KEY(closed_by) REFERENCES users(id)
+);
+
+-- Apenas um turno aberto por entregador
+CREATE UNIQUE INDEX IF NOT EXISTS idx_driver_shifts_single_open
+    ON driver_shifts(driver_id) WHERE status = 'open';
+
+-- Tabela: driver_shift_counts (conferência de vasilhames no acerto, por produto)
+CREATE TABLE IF NOT EXISTS driver_shift_counts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    shift_id INTEGER NOT NULL,
+    product_id INTEGER NOT NULL,
+    full_loaded INTEGER NOT NULL,
+    full_delivered INTEGER NOT NULL,
+    full_expected INTEGER NOT NULL,
+    full_returned INTEGER NOT NULL,
+    empty_expected INTEGER NOT NULL,
+    empty_returned INTEGER NOT NULL,
+    FOREIGN KEY(shift_id) REFERENCES driver_shifts(id),
+    FOREIGN KEY(product_id) REFERENCES products(id)
+);
+
+-- Entregador e turno de cada entrega
+ALTER TABLE deliveries ADD COLUMN driver_id INTEGER REFERENCES drivers(id);
+ALTER TABLE deliveries ADD COLUMN driver_shift_id INTEGER REFERENCES driver_shifts(id);
+
+-- Transferências entre a loja e o veículo do entregador.
+-- O SQLite não altera CHECK, então a tabela é recriada.
+CREATE TABLE stock_movements_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    product_id INTEGER,
+    movement_type TEXT CHECK(movement_type IN ('IN','OUT','ADJUST','RETURN','REVERSAL','EXCHANGE','TRANSFER')) NOT NULL,
+    stock_kind TEXT CHECK(stock_kind IN ('full','empty')) NOT NULL DEFAULT 'full',
+    quantity INTEGER NOT NULL,
+    unit_cost REAL,
+    order_id INTEGER,
+    purchase_receipt_id INTEGER REFERENCES purchase_receipts(id),
+    driver_shift_id INTEGER REFERENCES driver_shifts(id),
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY(product_id) REFERENCES products(id)
+);
+
+INSERT INTO stock_movements_new
+    (id, product_id, movement_type, stock_kind, quantity, unit_cost, order_id, purchase_receipt_id, created_at)
+SELECT id, product_id, movement_type, stock_kind, quantity, unit_cost, order_id, purchase_receipt_id, created_at
+FROM stock_movements;
+
+DROP TABLE stock_movements;
+ALTER TABLE stock_movements_new RENAME TO stock_movements;
+
+CREATE INDEX IF NOT EXISTS idx_stock_movements_product_id ON stock_movements(product_id);
+CREATE INDEX IF NOT EXISTS idx_stock_movements_driver_shift_id ON stock_movements(driver_shift_id);
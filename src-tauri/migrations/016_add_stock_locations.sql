@@ -0,0 +1,39 @@
+-- Tabela: stock_locations (loja, depósito e veículos)
+CREATE TABLE IF NOT EXISTS stock_locations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    kind TEXT CHECK(kind IN ('store','warehouse','vehicle')) NOT NULL,
+    driver_id INTEGER UNIQUE, -- Veículo do entregador
+    active BOOLEAN NOT NULL DEFAULT 1,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY(driver_id) REFERENCES drivers(id)
+);
+
+-- A loja é o local padrão e fica com o saldo atual
+INSERT INTO stock_locations (id, name, kind) VALUES (1, 'Loja', 'store');
+
+-- Cada entregador cadastrado ganha o seu veículo
+INSERT INTO stock_locations (name, kind, driver_id)
+SELECT 'Veículo - ' || name, 'vehicle', id FROM drivers;
+
+-- Tabela: product_stock (saldo por local; products mantém o total de todos os locais)
+CREATE TABLE IF NOT EXISTS product_stock (
+    product_id INTEGER NOT NULL,
+    location_id INTEGER NOT NULL,
+    stock_full INTEGER NOT NULL DEFAULT 0,
+    stock_empty INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY(product_id, location_id),
+    FOREIGN KEY(product_id) REFERENCES products(id) ON DELETE CASCADE,
+    FOREIGN KEY(location_id) REFERENCES stock_locations(id)
+);
+
+INSERT INTO product_stock (product_id, location_id, stock_full, stock_empty)
+SELECT id, 1, stock_full, stock_empty FROM products;
+
+-- Local de cada movimentação; o histórico anterior é todo da loja
+ALTER TABLE stock_movements ADD COLUMN location_id INTEGER REFERENCES stock_locations(id);
+UPDATE stock_movements SET location_id = 1;
+
+-- Local de onde a venda sai
+ALTER TABLE orders ADD COLUMN location_id INTEGER REFERENCES stock_locations(id);
+UPDATE orders SET location_id = 1;
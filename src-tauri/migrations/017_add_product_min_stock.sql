@@ -0,0 +1,4 @@
+-- Estoque mínimo e quantidade de reposição por produto.
+-- O mínimo começa em 10, o limite fixo usado até aqui no estoque crítico.
+ALTER TABLE products ADD COLUMN min_stock INTEGER NOT NULL DEFAULT 10;
+ALTER TABLE products ADD COLUMN reorder_qty INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,38 @@
+-- Tabela: stock_lots (lotes de cheios com validade própria, por local)
+CREATE TABLE IF NOT EXISTS stock_lots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    product_id INTEGER NOT NULL,
+    location_id INTEGER NOT NULL,
+    expiry_date DATE NOT NULL, -- 'YYYY-MM-DD'
+    quantity INTEGER NOT NULL DEFAULT 0 CHECK(quantity >= 0),
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(product_id, location_id, expiry_date),
+    FOREIGN KEY(product_id) REFERENCES products(id) ON DELETE CASCADE,
+    FOREIGN KEY(location_id) REFERENCES stock_locations(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_stock_lots_expiry ON stock_lots(expiry_date);
+
+-- Tabela: stock_movement_lots (quanto de cada lote a movimentação consumiu ou repôs)
+CREATE TABLE IF NOT EXISTS stock_movement_lots (
+    movement_id INTEGER NOT NULL,
+    lot_id INTEGER NOT NULL,
+    quantity INTEGER NOT NULL, -- Negativo no consumo
+    FOREIGN KEY(movement_id) REFERENCES stock_movements(id) ON DELETE CASCADE,
+    FOREIGN KEY(lot_id) REFERENCES stock_lots(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_stock_movement_lots_movement ON stock_movement_lots(movement_id);
+
+-- A validade única do produto vira um lote com o saldo de cada local,
+-- vencendo no último dia do mês informado
+INSERT INTO stock_lots (product_id, location_id, expiry_date, quantity)
+SELECT p.id, ps.location_id,
+       date(printf('%04d-%02d-01', p.expiry_year, p.expiry_month), '+1 month', '-1 day'),
+       ps.stock_full
+FROM products p
+JOIN product_stock ps ON ps.product_id = p.id
+WHERE p.expiry_month IS NOT NULL AND p.expiry_year IS NOT NULL AND ps.stock_full > 0;
+
+ALTER TABLE products DROP COLUMN expiry_month;
+ALTER TABLE products DROP COLUMN expiry_year;
@@ -0,0 +1,6 @@
+-- Tabela: app_settings (configurações do sistema em chave/valor)
+CREATE TABLE IF NOT EXISTS app_settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,3 @@
+-- Forma de pagamento da movimentação de caixa. Sangria e suprimento são em dinheiro;
+-- o estorno de um pedido sai na forma em que foi pago.
+ALTER TABLE cash_movements ADD COLUMN method TEXT NOT NULL DEFAULT 'cash';
@@ -0,0 +1,16 @@
+use crate::auth::AuthState;
+use crate::db::{audit, DbPool};
+use crate::errors::Result;
+use crate::guards;
+use crate::models::{AuditLogEntry, AuditLogFilter};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_audit_log(
+    filter: Option<AuditLogFilter>,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<Vec<AuditLogEntry>> {
+    let _user = guards::require_admin(&auth_state)?;
+    audit::get_audit_log(pool.inner(), filter.unwrap_or_default()).await
+}
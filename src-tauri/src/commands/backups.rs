@@ -0,0 +1,93 @@
+use crate::auth::AuthState;
+use crate::db::{audit, backups, DbPool};
+use crate::errors::Result;
+use crate::guards;
+use crate::models::{BackupCheck, BackupFile, BackupSettings, UpdateBackupSettingsPayload};
+use serde_json::json;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn get_backup_settings(
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<BackupSettings> {
+    guards::require_admin(&auth_state)?;
+    backups::get_backup_settings(pool.inner()).await
+}
+
+#[tauri::command]
+pub async fn update_backup_settings(
+    payload: UpdateBackupSettingsPayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<BackupSettings> {
+    let user = guards::require_admin(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = backups::get_backup_settings(&mut *tx).await?;
+    backups::update_backup_settings(&mut *tx, payload).await?;
+    let after = backups::get_backup_settings(&mut *tx).await?;
+
+    audit::record(
+        &mut tx, &user, "update", "backup_settings", None,
+        Some(json!(before)), Some(json!(after)),
+    ).await?;
+
+    tx.commit().await?;
+    Ok(after)
+}
+
+#[tauri::command]
+pub async fn create_backup(
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<BackupFile> {
+    let user = guards::require_admin(&auth_state)?;
+    let backup = backups::create_backup(pool.inner()).await?;
+
+    // VACUUM INTO não roda em transação: a auditoria é gravada depois da cópia
+    audit::record(
+        &mut *pool.acquire().await?, &user, "create", "backup", None, None,
+        Some(json!({ "file_name": backup.file_name, "size_bytes": backup.size_bytes })),
+    ).await?;
+
+    Ok(backup)
+}
+
+#[tauri::command]
+pub async fn list_backups(
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<Vec<BackupFile>> {
+    guards::require_admin(&auth_state)?;
+    backups::list_backups(pool.inner()).await
+}
+
+#[tauri::command]
+pub async fn verify_backup(
+    file_name: String,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<BackupCheck> {
+    guards::require_admin(&auth_state)?;
+    backups::verify_backup(pool.inner(), &file_name).await
+}
+
+/// Agenda a restauração e reinicia o app para aplicá-la
+#[tauri::command]
+pub async fn restore_backup(
+    file_name: String,
+    app: AppHandle,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<BackupFile> {
+    let user = guards::require_admin(&auth_state)?;
+    let safety = backups::schedule_restore(pool.inner(), &file_name).await?;
+
+    audit::record(
+        &mut *pool.acquire().await?, &user, "restore", "backup", None,
+        Some(json!({ "file_name": safety.file_name })),
+        Some(json!({ "file_name": file_name })),
+    ).await?;
+
+    app.restart();
+}
@@ -0,0 +1,42 @@
+use crate::auth::AuthState;
+use crate::db::{audit, bottle_loans, DbPool};
+use crate::errors::Result;
+use crate::guards;
+use crate::models::{BottleLoanEntry, CustomerBottleBalance, ReturnBottlesPayload};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_outstanding_bottles(
+    customer_id: Option<i64>,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<Vec<CustomerBottleBalance>> {
+    let _user = guards::get_authenticated_user(&auth_state)?;
+    bottle_loans::get_outstanding_bottles(pool.inner(), customer_id).await
+}
+
+#[tauri::command]
+pub async fn get_bottle_loan_history(
+    customer_id: i64,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<Vec<BottleLoanEntry>> {
+    let _user = guards::get_authenticated_user(&auth_state)?;
+    bottle_loans::get_loan_history(pool.inner(), customer_id).await
+}
+
+#[tauri::command]
+pub async fn return_customer_bottles(
+    customer_id: i64,
+    payload: ReturnBottlesPayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<i64> {
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let after = audit::snapshot(&payload);
+    let id = bottle_loans::return_bottles(&mut *tx, customer_id, payload, Some(user.id)).await?;
+    audit::record(&mut tx, &user, "return_bottles", "customer", Some(customer_id), None, after).await?;
+    tx.commit().await?;
+    Ok(id)
+}
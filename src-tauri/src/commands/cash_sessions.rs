@@ -0,0 +1,84 @@
+use crate::auth::AuthState;
+use crate::db::{audit, cash_sessions, DbPool};
+use crate::errors::Result;
+use crate::guards;
+use crate::models::{
+    CashMovementPayload, CashSession, CashSessionReport, CloseCashSessionPayload,
+    OpenCashSessionPayload,
+};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_open_cash_session(
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<Option<CashSession>> {
+    let _user = guards::get_authenticated_user(&auth_state)?;
+    cash_sessions::get_open_session(pool.inner()).await
+}
+
+#[tauri::command]
+pub async fn get_cash_sessions(
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<Vec<CashSession>> {
+    let _user = guards::require_admin(&auth_state)?;
+    cash_sessions::get_all_sessions(pool.inner()).await
+}
+
+#[tauri::command]
+pub async fn open_cash_session(
+    payload: OpenCashSessionPayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<i64> {
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let id = cash_sessions::open_session(&mut *tx, payload, user.id).await?;
+    let after = cash_sessions::get_session_by_id(&mut *tx, id).await?;
+    audit::record(&mut tx, &user, "open", "cash_session", Some(id), None, audit::snapshot(&after)).await?;
+    tx.commit().await?;
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn add_cash_movement(
+    payload: CashMovementPayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<i64> {
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let after = audit::snapshot(&payload);
+    let id = cash_sessions::add_movement(&mut *tx, payload, user.id).await?;
+    audit::record(&mut tx, &user, "create", "cash_movement", Some(id), None, after).await?;
+    tx.commit().await?;
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn get_cash_session_report(
+    session_id: i64,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<CashSessionReport> {
+    let _user = guards::get_authenticated_user(&auth_state)?;
+    cash_sessions::get_session_report(pool.inner(), session_id).await
+}
+
+#[tauri::command]
+pub async fn close_cash_session(
+    payload: CloseCashSessionPayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<CashSessionReport> {
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let report = cash_sessions::close_session(&mut *tx, payload, user.id).await?;
+    audit::record(
+        &mut tx, &user, "close", "cash_session", Some(report.session.id),
+        None, audit::snapshot(&report.lines),
+    ).await?;
+    tx.commit().await?;
+    Ok(report)
+}
@@ -0,0 +1,59 @@
+use crate::auth::AuthState;
+use crate::db::{audit, customer_accounts, DbPool};
+use crate::errors::Result;
+use crate::guards;
+use crate::models::{
+    CustomerAdjustmentPayload, CustomerBalance, CustomerPaymentPayload, CustomerStatement,
+};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_customer_statement(
+    customer_id: i64,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<CustomerStatement> {
+    let _user = guards::get_authenticated_user(&auth_state)?;
+    customer_accounts::get_customer_statement(pool.inner(), customer_id).await
+}
+
+#[tauri::command]
+pub async fn get_customer_balances(
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<Vec<CustomerBalance>> {
+    let _user = guards::get_authenticated_user(&auth_state)?;
+    customer_accounts::get_customer_balances(pool.inner()).await
+}
+
+#[tauri::command]
+pub async fn register_customer_payment(
+    customer_id: i64,
+    payload: CustomerPaymentPayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<i64> {
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let after = audit::snapshot(&payload);
+    let id = customer_accounts::register_payment(&mut *tx, customer_id, payload, Some(user.id)).await?;
+    audit::record(&mut tx, &user, "payment", "customer_account", Some(customer_id), None, after).await?;
+    tx.commit().await?;
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn adjust_customer_balance(
+    customer_id: i64,
+    payload: CustomerAdjustmentPayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<i64> {
+    let user = guards::require_admin(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let after = audit::snapshot(&payload);
+    let id = customer_accounts::adjust_balance(&mut *tx, customer_id, payload, Some(user.id)).await?;
+    audit::record(&mut tx, &user, "adjust", "customer_account", Some(customer_id), None, after).await?;
+    tx.commit().await?;
+    Ok(id)
+}
@@ -1,5 +1,5 @@
 use crate::auth::AuthState;
-use crate::db::{customers, DbPool};
+use crate::db::{audit, customers, DbPool};
 use crate::errors::Result;
 use crate::guards;
 use crate::models::{CreateCustomerPayload, Customer, UpdateCustomerPayload};
@@ -40,8 +40,13 @@ pub async fn create_customer(
     pool: State<'_, DbPool>,
     auth_state: State<'_, AuthState>,
 ) -> Result<i64> {
-    let _user = guards::get_authenticated_user(&auth_state)?;
-    customers::create_customer(pool.inner(), payload).await
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let id = customers::create_customer(&mut *tx, payload).await?;
+    let after = customers::get_customer_by_id(&mut *tx, id).await?;
+    audit::record(&mut tx, &user, "create", "customer", Some(id), None, audit::snapshot(&after)).await?;
+    tx.commit().await?;
+    Ok(id)
 }
 
 #[tauri::command]
@@ -51,8 +56,17 @@ pub async fn update_customer(
     pool: State<'_, DbPool>,
     auth_state: State<'_, AuthState>,
 ) -> Result<()> {
-    let _user = guards::get_authenticated_user(&auth_state)?;
-    customers::update_customer(pool.inner(), id, payload).await
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = customers::get_customer_by_id(&mut *tx, id).await?;
+    customers::update_customer(&mut *tx, id, payload).await?;
+    let after = customers::get_customer_by_id(&mut *tx, id).await?;
+    audit::record(
+        &mut tx, &user, "update", "customer", Some(id),
+        audit::snapshot(&before), audit::snapshot(&after),
+    ).await?;
+    tx.commit().await?;
+    Ok(())
 }
 
 #[tauri::command]
@@ -61,7 +75,12 @@ pub async fn delete_customer(
     pool: State<'_, DbPool>,
     auth_state: State<'_, AuthState>,
 ) -> Result<()> {
-    let _user = guards::require_admin(&auth_state)?;
-    customers::delete_customer(pool.inner(), id).await
+    let user = guards::require_admin(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = customers::get_customer_by_id(&mut *tx, id).await?;
+    customers::delete_customer(&mut *tx, id).await?;
+    audit::record(&mut tx, &user, "delete", "customer", Some(id), audit::snapshot(&before), None).await?;
+    tx.commit().await?;
+    Ok(())
 }
 
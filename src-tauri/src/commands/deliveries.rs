@@ -0,0 +1,84 @@
+use crate::auth::AuthState;
+use crate::db::{audit, deliveries, DbPool};
+use crate::errors::Result;
+use crate::guards;
+use crate::models::{Delivery, DispatchDeliveryPayload, FailDeliveryPayload};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_deliveries(
+    status: Option<String>,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<Vec<Delivery>> {
+    let _user = guards::get_authenticated_user(&auth_state)?;
+    deliveries::get_deliveries(pool.inner(), status).await
+}
+
+#[tauri::command]
+pub async fn get_delivery_queue(
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<Vec<Delivery>> {
+    let _user = guards::get_authenticated_user(&auth_state)?;
+    deliveries::get_delivery_queue(pool.inner()).await
+}
+
+#[tauri::command]
+pub async fn dispatch_delivery(
+    id: i64,
+    payload: DispatchDeliveryPayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<()> {
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = deliveries::get_delivery_by_id(&mut *tx, id).await?;
+    deliveries::dispatch_delivery(&mut *tx, id, payload).await?;
+    let after = deliveries::get_delivery_by_id(&mut *tx, id).await?;
+    audit::record(
+        &mut tx, &user, "dispatch", "delivery", Some(id),
+        audit::snapshot(&before), audit::snapshot(&after),
+    ).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn confirm_delivery(
+    id: i64,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<()> {
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = deliveries::get_delivery_by_id(&mut *tx, id).await?;
+    deliveries::confirm_delivery(&mut *tx, id).await?;
+    let after = deliveries::get_delivery_by_id(&mut *tx, id).await?;
+    audit::record(
+        &mut tx, &user, "confirm", "delivery", Some(id),
+        audit::snapshot(&before), audit::snapshot(&after),
+    ).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn fail_delivery(
+    id: i64,
+    payload: FailDeliveryPayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<()> {
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = deliveries::get_delivery_by_id(&mut *tx, id).await?;
+    deliveries::fail_delivery(&mut *tx, id, payload).await?;
+    let after = deliveries::get_delivery_by_id(&mut *tx, id).await?;
+    audit::record(
+        &mut tx, &user, "fail", "delivery", Some(id),
+        audit::snapshot(&before), audit::snapshot(&after),
+    ).await?;
+    tx.commit().await?;
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use crate::auth::AuthState;
+use crate::db::{audit, driver_shifts, drivers, DbPool};
+use crate::errors::Result;
+use crate::guards;
+use crate::models::{
+    CloseDriverShiftPayload, CreateDriverPayload, Driver, DriverLoadPayload, DriverShift,
+    DriverShiftReport, OpenDriverShiftPayload, UpdateDriverPayload,
+};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_drivers(
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<Vec<Driver>> {
+    let _user = guards::get_authenticated_user(&auth_state)?;
+    drivers::get_all_drivers(pool.inner()).await
+}
+
+#[tauri::command]
+pub async fn create_driver(
+    payload: CreateDriverPayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<i64> {
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let id = drivers::create_driver(&mut *tx, payload).await?;
+    let after = drivers::get_driver_by_id(&mut *tx, id).await?;
+    audit::record(&mut tx, &user, "create", "driver", Some(id), None, audit::snapshot(&after)).await?;
+    tx.commit().await?;
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn update_driver(
+    id: i64,
+    payload: UpdateDriverPayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<()> {
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = drivers::get_driver_by_id(&mut *tx, id).await?;
+    drivers::update_driver(&mut *tx, id, payload).await?;
+    let after = drivers::get_driver_by_id(&mut *tx, id).await?;
+    audit::record(
+        &mut tx, &user, "update", "driver", Some(id),
+        audit::snapshot(&before), audit::snapshot(&after),
+    ).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_driver_shifts(
+    status: Option<String>,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<Vec<DriverShift>> {
+    let _user = guards::get_authenticated_user(&auth_state)?;
+    driver_shifts::get_shifts(pool.inner(), status).await
+}
+
+#[tauri::command]
+pub async fn get_driver_shift_report(
+    shift_id: i64,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<DriverShiftReport> {
+    let _user = guards::get_authenticated_user(&auth_state)?;
+    driver_shifts::get_shift_report(pool.inner(), shift_id).await
+}
+
+#[tauri::command]
+pub async fn open_driver_shift(
+    payload: OpenDriverShiftPayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<i64> {
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let loads = audit::snapshot(&payload.loads);
+    let id = driver_shifts::open_shift(&mut *tx, payload, user.id).await?;
+    audit::record(&mut tx, &user, "open", "driver_shift", Some(id), None, loads).await?;
+    tx.commit().await?;
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn load_driver_shift(
+    shift_id: i64,
+    loads: Vec<DriverLoadPayload>,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<()> {
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let after = audit::snapshot(&loads);
+    driver_shifts::load_shift(&mut *tx, shift_id, loads).await?;
+    audit::record(&mut tx, &user, "load", "driver_shift", Some(shift_id), None, after).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn close_driver_shift(
+    shift_id: i64,
+    payload: CloseDriverShiftPayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<DriverShiftReport> {
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let report = driver_shifts::close_shift(&mut *tx, shift_id, payload, user.id).await?;
+    audit::record(
+        &mut tx, &user, "close", "driver_shift", Some(shift_id),
+        None, audit::snapshot(&report.lines),
+    ).await?;
+    tx.commit().await?;
+    Ok(report)
+}
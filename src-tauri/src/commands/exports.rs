@@ -0,0 +1,96 @@
+use crate::auth::AuthState;
+use crate::db::{audit, exports, DbPool};
+use crate::errors::{AppError, Result};
+use crate::export::{self, Table};
+use crate::guards;
+use crate::models::SafeUser;
+use serde_json::json;
+use std::path::Path;
+use tauri::State;
+
+/// Grava a planilha e registra a exportação na auditoria; devolve o número de linhas
+#[allow(clippy::too_many_arguments)]
+async fn save_export(
+    pool: &DbPool,
+    user: &SafeUser,
+    entity: &str,
+    table: Table,
+    path: &str,
+    format: &str,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+) -> Result<usize> {
+    if path.trim().is_empty() {
+        return Err(AppError::Validation("Export path is required".to_string()));
+    }
+
+    export::write_table(&table, Path::new(path), format)?;
+
+    let rows = table.rows.len();
+    audit::record(
+        &mut *pool.acquire().await?, user, "export", entity, None, None,
+        Some(json!({
+            "path": path,
+            "format": format,
+            "start_date": start_date,
+            "end_date": end_date,
+            "rows": rows,
+        })),
+    ).await?;
+
+    Ok(rows)
+}
+
+#[tauri::command]
+pub async fn export_orders(
+    path: String,
+    format: String,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<usize> {
+    let user = guards::require_admin(&auth_state)?;
+    export::validate_format(&format)?;
+    let rows = exports::get_order_rows(pool.inner(), start_date.as_deref(), end_date.as_deref()).await?;
+    save_export(
+        pool.inner(), &user, "orders", export::orders_table(rows),
+        &path, &format, start_date.as_deref(), end_date.as_deref(),
+    ).await
+}
+
+#[tauri::command]
+pub async fn export_stock_movements(
+    path: String,
+    format: String,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<usize> {
+    let user = guards::require_admin(&auth_state)?;
+    export::validate_format(&format)?;
+    let rows = exports::get_movement_rows(pool.inner(), start_date.as_deref(), end_date.as_deref()).await?;
+    save_export(
+        pool.inner(), &user, "stock_movements", export::movements_table(rows),
+        &path, &format, start_date.as_deref(), end_date.as_deref(),
+    ).await
+}
+
+#[tauri::command]
+pub async fn export_customers(
+    path: String,
+    format: String,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<usize> {
+    let user = guards::require_admin(&auth_state)?;
+    export::validate_format(&format)?;
+    let rows = exports::get_customer_rows(pool.inner(), start_date.as_deref(), end_date.as_deref()).await?;
+    save_export(
+        pool.inner(), &user, "customers", export::customers_table(rows),
+        &path, &format, start_date.as_deref(), end_date.as_deref(),
+    ).await
+}
@@ -0,0 +1,62 @@
+use crate::auth::AuthState;
+use crate::db::{audit, imports, DbPool};
+use crate::errors::Result;
+use crate::guards;
+use crate::models::{ImportReport, SafeUser};
+use serde_json::json;
+use sqlx::SqliteConnection;
+use tauri::State;
+
+/// Registra na auditoria só a importação que gravou dados
+async fn record_import(
+    conn: &mut SqliteConnection,
+    user: &SafeUser,
+    entity: &str,
+    path: &str,
+    report: &ImportReport,
+) -> Result<()> {
+    if !report.applied {
+        return Ok(());
+    }
+
+    audit::record(
+        conn, user, "import", entity, None, None,
+        Some(json!({
+            "path": path,
+            "created": report.created,
+            "updated": report.updated,
+        })),
+    ).await
+}
+
+#[tauri::command]
+pub async fn import_products(
+    path: String,
+    dry_run: bool,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<ImportReport> {
+    let user = guards::require_admin(&auth_state)?;
+    let content = std::fs::read_to_string(&path)?;
+    let mut tx = pool.begin().await?;
+    let report = imports::import_products(&mut *tx, &content, dry_run).await?;
+    record_import(&mut tx, &user, "product", &path, &report).await?;
+    tx.commit().await?;
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn import_customers(
+    path: String,
+    dry_run: bool,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<ImportReport> {
+    let user = guards::require_admin(&auth_state)?;
+    let content = std::fs::read_to_string(&path)?;
+    let mut tx = pool.begin().await?;
+    let report = imports::import_customers(&mut *tx, &content, dry_run).await?;
+    record_import(&mut tx, &user, "customer", &path, &report).await?;
+    tx.commit().await?;
+    Ok(report)
+}
@@ -1,16 +1,48 @@
 pub mod products;
 pub mod customers;
+pub mod customer_accounts;
+pub mod bottle_loans;
 pub mod orders;
+pub mod deliveries;
+pub mod drivers;
+pub mod cash_sessions;
 pub mod stock;
+pub mod stock_locations;
+pub mod suppliers;
+pub mod purchase_orders;
 pub mod receipts;
 pub mod dashboard;
+pub mod reports;
+pub mod exports;
+pub mod imports;
+pub mod backups;
+pub mod recovery;
+pub mod profiles;
+pub mod settings;
 pub mod users;
+pub mod audit;
 
 pub use products::*;
 pub use customers::*;
+pub use customer_accounts::*;
+pub use bottle_loans::*;
 pub use orders::*;
+pub use deliveries::*;
+pub use drivers::*;
+pub use cash_sessions::*;
 pub use stock::*;
+pub use stock_locations::*;
+pub use suppliers::*;
+pub use purchase_orders::*;
 pub use receipts::*;
 pub use dashboard::*;
+pub use reports::*;
+pub use exports::*;
+pub use imports::*;
+pub use backups::*;
+pub use recovery::*;
+pub use profiles::*;
+pub use settings::*;
 pub use users::*;
+pub use audit::*;
 
@@ -1,8 +1,10 @@
 use crate::auth::AuthState;
-use crate::db::{orders, DbPool};
+use crate::db::{audit, orders, DbPool};
 use crate::errors::Result;
 use crate::guards;
-use crate::models::{CreateOrderPayload, UpdateOrderPayload, OrderWithCustomer, OrderWithItems};
+use crate::models::{
+    CancelOrderPayload, CreateOrderPayload, UpdateOrderPayload, OrderWithCustomer, OrderWithItems,
+};
 use tauri::State;
 
 #[tauri::command]
@@ -11,8 +13,18 @@ pub async fn create_order(
     pool: State<'_, DbPool>,
     auth_state: State<'_, AuthState>,
 ) -> Result<i64> {
-    let _user = guards::get_authenticated_user(&auth_state)?;
-    orders::create_order(pool.inner(), payload).await
+    // Vender lote vencido exige admin
+    let user = if payload.allow_expired.unwrap_or(false) {
+        guards::require_admin(&auth_state)?
+    } else {
+        guards::get_authenticated_user(&auth_state)?
+    };
+    let mut tx = pool.begin().await?;
+    let id = orders::create_order(&mut *tx, payload, Some(user.id)).await?;
+    let after = orders::get_order_by_id(&mut *tx, id).await?;
+    audit::record(&mut tx, &user, "create", "order", Some(id), None, audit::snapshot(&after)).await?;
+    tx.commit().await?;
+    Ok(id)
 }
 
 #[tauri::command]
@@ -51,17 +63,36 @@ pub async fn update_order(
     pool: State<'_, DbPool>,
     auth_state: State<'_, AuthState>,
 ) -> Result<()> {
-    let _user = guards::require_admin(&auth_state)?;
-    orders::update_order(pool.inner(), id, payload).await
+    let user = guards::require_admin(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = orders::get_order_by_id(&mut *tx, id).await?;
+    orders::update_order(&mut *tx, id, payload).await?;
+    let after = orders::get_order_by_id(&mut *tx, id).await?;
+    audit::record(
+        &mut tx, &user, "update", "order", Some(id),
+        audit::snapshot(&before.order), audit::snapshot(&after.order),
+    ).await?;
+    tx.commit().await?;
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn delete_order(
+pub async fn cancel_order(
     id: i64,
+    payload: CancelOrderPayload,
     pool: State<'_, DbPool>,
     auth_state: State<'_, AuthState>,
 ) -> Result<()> {
-    let _user = guards::require_admin(&auth_state)?;
-    orders::delete_order(pool.inner(), id).await
+    let user = guards::require_admin(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = orders::get_order_by_id(&mut *tx, id).await?;
+    orders::cancel_order(&mut *tx, id, payload, user.id).await?;
+    let after = orders::get_order_by_id(&mut *tx, id).await?;
+    audit::record(
+        &mut tx, &user, "cancel", "order", Some(id),
+        audit::snapshot(&before.order), audit::snapshot(&after.order),
+    ).await?;
+    tx.commit().await?;
+    Ok(())
 }
 
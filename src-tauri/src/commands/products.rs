@@ -1,5 +1,5 @@
 use crate::auth::AuthState;
-use crate::db::{products, DbPool};
+use crate::db::{audit, products, DbPool};
 use crate::errors::Result;
 use crate::guards;
 use crate::models::{CreateProductPayload, Product, UpdateProductPayload};
@@ -30,8 +30,13 @@ pub async fn create_product(
     pool: State<'_, DbPool>,
     auth_state: State<'_, AuthState>,
 ) -> Result<i64> {
-    let _user = guards::get_authenticated_user(&auth_state)?;
-    products::create_product(pool.inner(), payload).await
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let id = products::create_product(&mut *tx, payload).await?;
+    let after = products::get_product_by_id(&mut *tx, id).await?;
+    audit::record(&mut tx, &user, "create", "product", Some(id), None, audit::snapshot(&after)).await?;
+    tx.commit().await?;
+    Ok(id)
 }
 
 #[tauri::command]
@@ -41,8 +46,17 @@ pub async fn update_product(
     pool: State<'_, DbPool>,
     auth_state: State<'_, AuthState>,
 ) -> Result<()> {
-    let _user = guards::get_authenticated_user(&auth_state)?;
-    products::update_product(pool.inner(), id, payload).await
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = products::get_product_by_id(&mut *tx, id).await?;
+    products::update_product(&mut *tx, id, payload).await?;
+    let after = products::get_product_by_id(&mut *tx, id).await?;
+    audit::record(
+        &mut tx, &user, "update", "product", Some(id),
+        audit::snapshot(&before), audit::snapshot(&after),
+    ).await?;
+    tx.commit().await?;
+    Ok(())
 }
 
 #[tauri::command]
@@ -51,7 +65,11 @@ pub async fn delete_product(
     pool: State<'_, DbPool>,
     auth_state: State<'_, AuthState>,
 ) -> Result<()> {
-    let _user = guards::require_admin(&auth_state)?;
-    products::delete_product(pool.inner(), id).await
+    let user = guards::require_admin(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = products::get_product_by_id(&mut *tx, id).await?;
+    products::delete_product(&mut *tx, id).await?;
+    audit::record(&mut tx, &user, "delete", "product", Some(id), audit::snapshot(&before), None).await?;
+    tx.commit().await?;
+    Ok(())
 }
-
@@ -0,0 +1,91 @@
+use crate::auth::AuthState;
+use crate::db::{app_data_dir, audit, DbPool};
+use crate::errors::{AppError, Result};
+use crate::guards;
+use crate::models::{CreateProfilePayload, Profile, ProfilesConfig};
+use crate::profiles;
+use serde_json::json;
+use std::path::PathBuf;
+use tauri::{AppHandle, State};
+
+// A lista de perfis é comum a todas as empresas, mas a auditoria fica no banco de
+// cada uma: criar, renomear ou trocar de perfil é registrado só no log da empresa
+// aberta no momento. O id do perfil é texto e não cabe em `entity_id`; vai no JSON
+// de antes/depois.
+
+fn config_dir() -> Result<PathBuf> {
+    app_data_dir().map_err(AppError::BusinessLogic)
+}
+
+/// Sem login: a escolha da empresa vem antes, na tela de entrada
+#[tauri::command]
+pub async fn get_profiles() -> Result<ProfilesConfig> {
+    profiles::load(&config_dir()?)
+}
+
+#[tauri::command]
+pub async fn create_profile(
+    payload: CreateProfilePayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<Profile> {
+    let user = guards::require_admin(&auth_state)?;
+    let profile = profiles::create_profile(&config_dir()?, payload)?;
+
+    audit::record(
+        &mut *pool.acquire().await?, &user, "create", "profile", None, None,
+        Some(json!(profile)),
+    ).await?;
+
+    Ok(profile)
+}
+
+#[tauri::command]
+pub async fn rename_profile(
+    id: String,
+    name: String,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<Profile> {
+    let user = guards::require_admin(&auth_state)?;
+    let dir = config_dir()?;
+    let before = profiles::load(&dir)?
+        .profiles
+        .into_iter()
+        .find(|p| p.id == id)
+        .ok_or_else(|| AppError::NotFound(format!("Profile {} not found", id)))?;
+    let profile = profiles::rename_profile(&dir, &id, &name)?;
+
+    audit::record(
+        &mut *pool.acquire().await?, &user, "update", "profile", None,
+        Some(json!(before)), Some(json!(profile)),
+    ).await?;
+
+    Ok(profile)
+}
+
+/// Troca o perfil ativo e reinicia o app para abrir o banco dele. Cada banco tem
+/// os próprios usuários: na tela de entrada, antes do login, a troca é livre; com
+/// alguém logado, fica registrada em nome dele na empresa que está sendo fechada.
+#[tauri::command]
+pub async fn switch_profile(
+    id: String,
+    app: AppHandle,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<()> {
+    let user = guards::current_user(&auth_state);
+
+    let dir = config_dir()?;
+    let before = profiles::active_profile(&dir)?;
+    let profile = profiles::switch_profile(&dir, &id)?;
+
+    if let Some(user) = user {
+        audit::record(
+            &mut *pool.acquire().await?, &user, "switch", "profile", None,
+            Some(json!(before)), Some(json!(profile)),
+        ).await?;
+    }
+
+    app.restart();
+}
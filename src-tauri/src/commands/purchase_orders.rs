@@ -0,0 +1,82 @@
+use crate::auth::AuthState;
+use crate::db::{audit, purchase_orders, DbPool};
+use crate::errors::Result;
+use crate::guards;
+use crate::models::{
+    CreatePurchaseOrderPayload, PurchaseOrder, PurchaseOrderWithItems, ReceivePurchaseOrderPayload,
+};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_purchase_orders(
+    status: Option<String>,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<Vec<PurchaseOrder>> {
+    let _user = guards::get_authenticated_user(&auth_state)?;
+    purchase_orders::get_all_purchase_orders(pool.inner(), status).await
+}
+
+#[tauri::command]
+pub async fn get_purchase_order(
+    id: i64,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<PurchaseOrderWithItems> {
+    let _user = guards::get_authenticated_user(&auth_state)?;
+    purchase_orders::get_purchase_order_by_id(pool.inner(), id).await
+}
+
+#[tauri::command]
+pub async fn create_purchase_order(
+    payload: CreatePurchaseOrderPayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<i64> {
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let id = purchase_orders::create_purchase_order(&mut *tx, payload, Some(user.id)).await?;
+    let after = purchase_orders::get_purchase_order_by_id(&mut *tx, id).await?;
+    audit::record(&mut tx, &user, "create", "purchase_order", Some(id), None, audit::snapshot(&after)).await?;
+    tx.commit().await?;
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn receive_purchase_order(
+    id: i64,
+    payload: ReceivePurchaseOrderPayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<i64> {
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = purchase_orders::get_purchase_order_by_id(&mut *tx, id).await?;
+    let receipt_id = purchase_orders::receive_purchase_order(&mut *tx, id, payload, Some(user.id)).await?;
+    let after = purchase_orders::get_purchase_order_by_id(&mut *tx, id).await?;
+    audit::record(
+        &mut tx, &user, "receive", "purchase_order", Some(id),
+        audit::snapshot(&before), audit::snapshot(&after),
+    ).await?;
+    tx.commit().await?;
+    Ok(receipt_id)
+}
+
+#[tauri::command]
+pub async fn cancel_purchase_order(
+    id: i64,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<()> {
+    let user = guards::require_admin(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = purchase_orders::get_purchase_order_by_id(&mut *tx, id).await?;
+    purchase_orders::cancel_purchase_order(&mut *tx, id).await?;
+    let after = purchase_orders::get_purchase_order_by_id(&mut *tx, id).await?;
+    audit::record(
+        &mut tx, &user, "cancel", "purchase_order", Some(id),
+        audit::snapshot(&before), audit::snapshot(&after),
+    ).await?;
+    tx.commit().await?;
+    Ok(())
+}
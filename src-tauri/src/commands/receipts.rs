@@ -1,9 +1,55 @@
 use crate::auth::AuthState;
+use crate::db::settings::{self, format_money};
 use crate::db::{orders, DbPool};
-use crate::errors::Result;
+use crate::errors::{AppError, Result};
+use crate::escpos::{columns_for, Align, EscPos};
 use crate::guards;
+use crate::models::{CompanySettings, OrderWithItems, PrinterSettings};
+use crate::printer;
 use tauri::State;
 
+fn payment_method_label(method: &str) -> &str {
+    match method {
+        "cash" => "Dinheiro",
+        "pix" => "Pix",
+        "debit" => "Cartão de Débito",
+        "credit" => "Cartão de Crédito",
+        "on_account" => "Fiado",
+        other => other,
+    }
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Cabeçalho com os dados da empresa cadastrados nas configurações
+fn company_header(company: &CompanySettings) -> String {
+    let mut lines = vec![format!("<h2>{}</h2>", escape_html(&company.company_name))];
+
+    if let Some(cnpj) = &company.cnpj {
+        lines.push(format!("<p>CNPJ: {}</p>", escape_html(cnpj)));
+    }
+    if let Some(address) = &company.address {
+        lines.push(format!("<p>{}</p>", escape_html(address)));
+    }
+
+    let contact: Vec<String> = [&company.phone, &company.email]
+        .into_iter()
+        .flatten()
+        .map(|value| escape_html(value))
+        .collect();
+    if !contact.is_empty() {
+        lines.push(format!("<p>{}</p>", contact.join(" | ")));
+    }
+
+    lines.join("\n        ")
+}
+
 #[tauri::command]
 pub async fn generate_receipt(
     order_id: i64,
@@ -12,6 +58,8 @@ pub async fn generate_receipt(
 ) -> Result<String> {
     let _user = guards::get_authenticated_user(&auth_state)?;
     let order = orders::get_order_by_id(pool.inner(), order_id).await?;
+    let company = settings::get_company_settings(pool.inner()).await?;
+    let money = |value: f64| format_money(&company, value);
 
     // Gera HTML do recibo
     let html = format!(
@@ -66,6 +114,7 @@ pub async fn generate_receipt(
 </head>
 <body>
     <div class="header">
+        {}
         <h1>RECIBO DE VENDA</h1>
         <p>Pedido #{}</p>
     </div>
@@ -73,6 +122,7 @@ pub async fn generate_receipt(
     <div class="info">
         <p><strong>Cliente:</strong> {}</p>
         <p><strong>Data:</strong> {}</p>
+        {}
     </div>
     
     <table>
@@ -90,32 +140,196 @@ pub async fn generate_receipt(
     </table>
     
     <div class="total">
-        <p>TOTAL: R$ {:.2}</p>
+        <p>TOTAL: {}</p>
+    </div>
+
+    <div class="info">
+        {}
     </div>
     
     <div class="footer">
-        <p>Obrigado pela preferência!</p>
+        <p>{}</p>
     </div>
 </body>
 </html>
         "#,
         order_id,
+        company_header(&company),
         order_id,
         order.order.customer_name.as_deref().unwrap_or("Consumidor Final"),
         order.order.created_at,
+        order.delivery.as_ref().map(|delivery| format!(
+            "<p><strong>Entrega:</strong> {}</p><p><strong>Taxa de entrega:</strong> {}</p>",
+            delivery.address,
+            money(delivery.delivery_fee)
+        )).unwrap_or_default(),
         order.items.iter().map(|item| {
             format!(
-                "<tr><td>{} {}</td><td>{}</td><td>R$ {:.2}</td><td>R$ {:.2}</td></tr>",
+                "<tr><td>{} {}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
                 item.product_name,
                 if item.returned_bottle { "(com casco)" } else { "" },
                 item.quantity,
-                item.unit_price,
-                item.quantity as f64 * item.unit_price
+                money(item.unit_price),
+                money(item.quantity as f64 * item.unit_price)
             )
         }).collect::<Vec<_>>().join(""),
-        order.order.total
+        money(order.order.total),
+        order.payments.iter().map(|payment| {
+            let change = if payment.change_due > 0.0 {
+                format!(" (Recebido: {} / Troco: {})", money(payment.tendered.unwrap_or(payment.amount)), money(payment.change_due))
+            } else {
+                String::new()
+            };
+            format!(
+                "<p><strong>{}:</strong> {}{}</p>",
+                payment_method_label(&payment.method),
+                money(payment.amount),
+                change
+            )
+        }).collect::<Vec<_>>().join(""),
+        escape_html(&company.receipt_footer)
     );
 
     Ok(html)
 }
 
+/// Cabeçalho do cupom térmico, centralizado e com o nome em negrito
+fn thermal_header(printer: &mut EscPos, company: &CompanySettings) {
+    printer.align(Align::Center).bold(true).text(&company.company_name).bold(false);
+
+    if let Some(cnpj) = &company.cnpj {
+        printer.text(&format!("CNPJ: {}", cnpj));
+    }
+    for line in [&company.address, &company.phone, &company.email].into_iter().flatten() {
+        printer.text(line);
+    }
+
+    printer.align(Align::Left).separator();
+}
+
+/// Mesmo conteúdo do recibo HTML, na largura da bobina
+fn thermal_receipt(order: &OrderWithItems, company: &CompanySettings, settings: &PrinterSettings) -> Vec<u8> {
+    let money = |value: f64| format_money(company, value);
+    let mut printer = EscPos::new(columns_for(settings.paper_width));
+
+    thermal_header(&mut printer, company);
+
+    printer
+        .align(Align::Center)
+        .bold(true)
+        .text("RECIBO DE VENDA")
+        .bold(false)
+        .text(&format!("Pedido #{}", order.order.id))
+        .align(Align::Left)
+        .text(&format!("Cliente: {}", order.order.customer_name.as_deref().unwrap_or("Consumidor Final")))
+        .text(&format!("Data: {}", order.order.created_at));
+
+    if let Some(delivery) = &order.delivery {
+        printer.text(&format!("Entrega: {}", delivery.address));
+    }
+
+    printer.separator();
+
+    for item in &order.items {
+        let name = if item.returned_bottle {
+            format!("{} (com casco)", item.product_name)
+        } else {
+            item.product_name.clone()
+        };
+        printer.text(&name).columns(
+            &format!("  {} x {}", item.quantity, money(item.unit_price)),
+            &money(item.quantity as f64 * item.unit_price),
+        );
+    }
+
+    printer.separator();
+
+    if let Some(delivery) = &order.delivery {
+        printer.columns("Taxa de entrega", &money(delivery.delivery_fee));
+    }
+
+    printer
+        .bold(true)
+        .double_height(true)
+        .columns("TOTAL", &money(order.order.total))
+        .double_height(false)
+        .bold(false);
+
+    for payment in &order.payments {
+        printer.columns(payment_method_label(&payment.method), &money(payment.amount));
+        if payment.change_due > 0.0 {
+            printer
+                .columns("  Recebido", &money(payment.tendered.unwrap_or(payment.amount)))
+                .columns("  Troco", &money(payment.change_due));
+        }
+    }
+
+    printer.separator().align(Align::Center).text(&company.receipt_footer);
+    thermal_footer(&mut printer, settings);
+
+    printer.into_bytes()
+}
+
+/// QR code opcional, avanço do papel e corte
+fn thermal_footer(printer: &mut EscPos, settings: &PrinterSettings) {
+    if let Some(qr_code) = &settings.qr_code {
+        printer.align(Align::Center).qr_code(qr_code);
+    }
+
+    printer.align(Align::Left).feed(4);
+
+    if settings.cut_paper {
+        printer.cut();
+    }
+}
+
+async fn configured_printer(pool: &DbPool) -> Result<PrinterSettings> {
+    let settings = settings::get_printer_settings(pool).await?;
+    if settings.connection == "none" {
+        return Err(AppError::BusinessLogic("No thermal printer configured".to_string()));
+    }
+
+    Ok(settings)
+}
+
+/// Imprime o recibo na impressora térmica configurada
+#[tauri::command]
+pub async fn print_receipt(
+    order_id: i64,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<()> {
+    let _user = guards::get_authenticated_user(&auth_state)?;
+    let printer_settings = configured_printer(pool.inner()).await?;
+    let order = orders::get_order_by_id(pool.inner(), order_id).await?;
+    let company = settings::get_company_settings(pool.inner()).await?;
+
+    printer::send(&printer_settings, &thermal_receipt(&order, &company, &printer_settings)).await
+}
+
+/// Página de teste para conferir conexão, largura do papel, acentos e corte
+#[tauri::command]
+pub async fn print_test_page(
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<()> {
+    guards::require_admin(&auth_state)?;
+    let printer_settings = configured_printer(pool.inner()).await?;
+    let company = settings::get_company_settings(pool.inner()).await?;
+
+    let mut printer = EscPos::new(columns_for(printer_settings.paper_width));
+    thermal_header(&mut printer, &company);
+    printer
+        .align(Align::Center)
+        .bold(true)
+        .text("TESTE DE IMPRESSÃO")
+        .bold(false)
+        .text(&format!("Papel de {} mm", printer_settings.paper_width))
+        .align(Align::Left)
+        .text("Acentuação: ÁÉÍÓÚ áéíóú ÃÕ ãõ Ç ç")
+        .columns("Valor", &format_money(&company, 1234.5))
+        .separator();
+    thermal_footer(&mut printer, &printer_settings);
+
+    printer::send(&printer_settings, &printer.into_bytes()).await
+}
@@ -0,0 +1,46 @@
+use crate::db::recovery::{self, RecoveryState};
+use crate::errors::Result;
+use crate::models::{BackupFile, RecoveryInfo};
+use tauri::{AppHandle, State};
+
+// Em modo de recuperação o banco está fechado: não há login nem auditoria. Por isso
+// nenhuma opção daqui descarta dados, e cada uma reinicia o app para valer.
+
+#[tauri::command]
+pub async fn get_recovery_status(recovery_state: State<'_, RecoveryState>) -> Result<Option<RecoveryInfo>> {
+    Ok(recovery_state.0.clone())
+}
+
+#[tauri::command]
+pub async fn list_recovery_backups(recovery_state: State<'_, RecoveryState>) -> Result<Vec<BackupFile>> {
+    recovery::list_backups(recovery_state.info()?)
+}
+
+#[tauri::command]
+pub async fn recovery_accept_checksums(
+    app: AppHandle,
+    recovery_state: State<'_, RecoveryState>,
+) -> Result<()> {
+    recovery::accept_checksums(recovery_state.info()?).await?;
+    app.restart();
+}
+
+#[tauri::command]
+pub async fn recovery_restore_backup(
+    file_name: String,
+    app: AppHandle,
+    recovery_state: State<'_, RecoveryState>,
+) -> Result<()> {
+    recovery::restore(recovery_state.info()?, &file_name).await?;
+    app.restart();
+}
+
+#[tauri::command]
+pub async fn recovery_set_aside_database(
+    app: AppHandle,
+    recovery_state: State<'_, RecoveryState>,
+) -> Result<()> {
+    let target = recovery::set_aside(recovery_state.info()?)?;
+    eprintln!("Database set aside at {}", target.display());
+    app.restart();
+}
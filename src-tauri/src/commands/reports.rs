@@ -0,0 +1,18 @@
+use crate::auth::AuthState;
+use crate::db::{reports, DbPool};
+use crate::errors::Result;
+use crate::guards;
+use crate::models::SalesReport;
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_sales_report(
+    start_date: String,
+    end_date: String,
+    group_by: String,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<SalesReport> {
+    let _user = guards::get_authenticated_user(&auth_state)?;
+    reports::get_sales_report(pool.inner(), &start_date, &end_date, &group_by).await
+}
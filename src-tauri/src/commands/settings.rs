@@ -0,0 +1,69 @@
+use crate::auth::AuthState;
+use crate::db::{audit, settings, DbPool};
+use crate::errors::Result;
+use crate::guards;
+use crate::models::{CompanySettings, PrinterSettings, UpdateCompanySettingsPayload, UpdatePrinterSettingsPayload};
+use serde_json::json;
+use tauri::State;
+
+/// Qualquer usuário lê: as telas usam a moeda e os prazos daqui
+#[tauri::command]
+pub async fn get_company_settings(
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<CompanySettings> {
+    guards::get_authenticated_user(&auth_state)?;
+    settings::get_company_settings(pool.inner()).await
+}
+
+#[tauri::command]
+pub async fn update_company_settings(
+    payload: UpdateCompanySettingsPayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<CompanySettings> {
+    let user = guards::require_admin(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = settings::get_company_settings(&mut *tx).await?;
+    settings::update_company_settings(&mut *tx, payload).await?;
+    let after = settings::get_company_settings(&mut *tx).await?;
+
+    audit::record(
+        &mut tx, &user, "update", "company_settings", None,
+        Some(json!(before)), Some(json!(after)),
+    ).await?;
+
+    tx.commit().await?;
+    Ok(after)
+}
+
+/// Qualquer usuário lê: o PDV decide entre cupom térmico e recibo HTML
+#[tauri::command]
+pub async fn get_printer_settings(
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<PrinterSettings> {
+    guards::get_authenticated_user(&auth_state)?;
+    settings::get_printer_settings(pool.inner()).await
+}
+
+#[tauri::command]
+pub async fn update_printer_settings(
+    payload: UpdatePrinterSettingsPayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<PrinterSettings> {
+    let user = guards::require_admin(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = settings::get_printer_settings(&mut *tx).await?;
+    settings::update_printer_settings(&mut *tx, payload).await?;
+    let after = settings::get_printer_settings(&mut *tx).await?;
+
+    audit::record(
+        &mut tx, &user, "update", "printer_settings", None,
+        Some(json!(before)), Some(json!(after)),
+    ).await?;
+
+    tx.commit().await?;
+    Ok(after)
+}
@@ -1,41 +1,160 @@
 use crate::auth::AuthState;
-use crate::db::{stock, DbPool};
+use crate::db::{audit, products, stock, stock_lots, DbPool};
 use crate::errors::Result;
 use crate::guards;
-use crate::models::StockMovementWithProduct;
+use crate::models::{
+    Product, ReorderSuggestion, SafeUser, StockLot, StockMovementWithProduct, TransferStockPayload,
+};
+use sqlx::SqliteConnection;
 use tauri::State;
 
+/// Audita a movimentação com o saldo do produto antes e depois
+async fn audit_stock_change(
+    conn: &mut SqliteConnection,
+    user: &SafeUser,
+    action: &str,
+    before: &Product,
+) -> Result<()> {
+    let after = products::get_product_by_id(&mut *conn, before.id).await?;
+    audit::record(
+        conn, user, action, "product", Some(before.id),
+        audit::snapshot(before), audit::snapshot(&after),
+    ).await
+}
+
 #[tauri::command]
 pub async fn stock_in(
     product_id: i64,
     quantity: i64,
+    unit_cost: Option<f64>,
+    location_id: Option<i64>,
+    expiry_date: Option<String>,
     pool: State<'_, DbPool>,
     auth_state: State<'_, AuthState>,
 ) -> Result<()> {
-    let _user = guards::get_authenticated_user(&auth_state)?;
-    stock::stock_in(pool.inner(), product_id, quantity).await
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = products::get_product_by_id(&mut *tx, product_id).await?;
+    stock::stock_in(&mut *tx, product_id, quantity, unit_cost, location_id, expiry_date).await?;
+    audit_stock_change(&mut tx, &user, "stock_in", &before).await?;
+    tx.commit().await?;
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn stock_out(
     product_id: i64,
     quantity: i64,
+    location_id: Option<i64>,
     pool: State<'_, DbPool>,
     auth_state: State<'_, AuthState>,
 ) -> Result<()> {
-    let _user = guards::get_authenticated_user(&auth_state)?;
-    stock::stock_out(pool.inner(), product_id, quantity).await
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = products::get_product_by_id(&mut *tx, product_id).await?;
+    stock::stock_out(&mut *tx, product_id, quantity, location_id).await?;
+    audit_stock_change(&mut tx, &user, "stock_out", &before).await?;
+    tx.commit().await?;
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn stock_adjust(
     product_id: i64,
     quantity: i64,
+    stock_kind: Option<String>,
+    location_id: Option<i64>,
     pool: State<'_, DbPool>,
     auth_state: State<'_, AuthState>,
 ) -> Result<()> {
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let stock_kind = stock_kind.as_deref().unwrap_or("full");
+    let mut tx = pool.begin().await?;
+    let before = products::get_product_by_id(&mut *tx, product_id).await?;
+    stock::stock_adjust(&mut *tx, product_id, quantity, stock_kind, location_id).await?;
+    audit_stock_change(&mut tx, &user, "stock_adjust", &before).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn supplier_exchange(
+    product_id: i64,
+    quantity: i64,
+    unit_cost: f64,
+    location_id: Option<i64>,
+    expiry_date: Option<String>,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<()> {
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = products::get_product_by_id(&mut *tx, product_id).await?;
+    stock::supplier_exchange(&mut *tx, product_id, quantity, unit_cost, location_id, expiry_date).await?;
+    audit_stock_change(&mut tx, &user, "supplier_exchange", &before).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn transfer_stock(
+    payload: TransferStockPayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<()> {
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let after = audit::snapshot(&payload);
+    let mut tx = pool.begin().await?;
+    stock::transfer_stock(&mut *tx, payload).await?;
+    audit::record(&mut tx, &user, "transfer", "stock", None, None, after).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_critical_stock(
+    location_id: Option<i64>,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<Vec<Product>> {
+    let _user = guards::get_authenticated_user(&auth_state)?;
+    stock::get_critical_stock(pool.inner(), location_id).await
+}
+
+#[tauri::command]
+pub async fn get_reorder_suggestions(
+    days: Option<i64>,
+    target_days: Option<i64>,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<Vec<ReorderSuggestion>> {
+    let _user = guards::get_authenticated_user(&auth_state)?;
+    stock::get_reorder_suggestions(
+        pool.inner(),
+        days.unwrap_or(stock::DEFAULT_SALES_WINDOW_DAYS),
+        target_days.unwrap_or(stock::DEFAULT_COVERAGE_DAYS),
+    ).await
+}
+
+#[tauri::command]
+pub async fn get_stock_lots(
+    product_id: Option<i64>,
+    location_id: Option<i64>,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<Vec<StockLot>> {
+    let _user = guards::get_authenticated_user(&auth_state)?;
+    stock_lots::get_lots(pool.inner(), product_id, location_id).await
+}
+
+#[tauri::command]
+pub async fn get_expiring_lots(
+    days: i64,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<Vec<StockLot>> {
     let _user = guards::get_authenticated_user(&auth_state)?;
-    stock::stock_adjust(pool.inner(), product_id, quantity).await
+    stock_lots::get_expiring_lots(pool.inner(), days).await
 }
 
 #[tauri::command]
@@ -0,0 +1,62 @@
+use crate::auth::AuthState;
+use crate::db::{audit, stock_locations, DbPool};
+use crate::errors::Result;
+use crate::guards;
+use crate::models::{
+    CreateStockLocationPayload, LocationStock, StockLocation, UpdateStockLocationPayload,
+};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_stock_locations(
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<Vec<StockLocation>> {
+    let _user = guards::get_authenticated_user(&auth_state)?;
+    stock_locations::get_all_locations(pool.inner()).await
+}
+
+#[tauri::command]
+pub async fn create_stock_location(
+    payload: CreateStockLocationPayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<i64> {
+    let user = guards::require_admin(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let id = stock_locations::create_location(&mut *tx, payload).await?;
+    let after = stock_locations::get_location_by_id(&mut *tx, id).await?;
+    audit::record(&mut tx, &user, "create", "stock_location", Some(id), None, audit::snapshot(&after)).await?;
+    tx.commit().await?;
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn update_stock_location(
+    id: i64,
+    payload: UpdateStockLocationPayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<()> {
+    let user = guards::require_admin(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = stock_locations::get_location_by_id(&mut *tx, id).await?;
+    stock_locations::update_location(&mut *tx, id, payload).await?;
+    let after = stock_locations::get_location_by_id(&mut *tx, id).await?;
+    audit::record(
+        &mut tx, &user, "update", "stock_location", Some(id),
+        audit::snapshot(&before), audit::snapshot(&after),
+    ).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_location_stock(
+    location_id: Option<i64>,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<Vec<LocationStock>> {
+    let _user = guards::get_authenticated_user(&auth_state)?;
+    stock_locations::get_location_stock(pool.inner(), location_id).await
+}
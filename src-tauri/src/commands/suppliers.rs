@@ -0,0 +1,75 @@
+use crate::auth::AuthState;
+use crate::db::{audit, suppliers, DbPool};
+use crate::errors::Result;
+use crate::guards;
+use crate::models::{CreateSupplierPayload, Supplier, UpdateSupplierPayload};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_suppliers(
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<Vec<Supplier>> {
+    let _user = guards::get_authenticated_user(&auth_state)?;
+    suppliers::get_all_suppliers(pool.inner()).await
+}
+
+#[tauri::command]
+pub async fn get_supplier(
+    id: i64,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<Supplier> {
+    let _user = guards::get_authenticated_user(&auth_state)?;
+    suppliers::get_supplier_by_id(pool.inner(), id).await
+}
+
+#[tauri::command]
+pub async fn create_supplier(
+    payload: CreateSupplierPayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<i64> {
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let id = suppliers::create_supplier(&mut *tx, payload).await?;
+    let after = suppliers::get_supplier_by_id(&mut *tx, id).await?;
+    audit::record(&mut tx, &user, "create", "supplier", Some(id), None, audit::snapshot(&after)).await?;
+    tx.commit().await?;
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn update_supplier(
+    id: i64,
+    payload: UpdateSupplierPayload,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<()> {
+    let user = guards::get_authenticated_user(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = suppliers::get_supplier_by_id(&mut *tx, id).await?;
+    suppliers::update_supplier(&mut *tx, id, payload).await?;
+    let after = suppliers::get_supplier_by_id(&mut *tx, id).await?;
+    audit::record(
+        &mut tx, &user, "update", "supplier", Some(id),
+        audit::snapshot(&before), audit::snapshot(&after),
+    ).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_supplier(
+    id: i64,
+    pool: State<'_, DbPool>,
+    auth_state: State<'_, AuthState>,
+) -> Result<()> {
+    let user = guards::require_admin(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = suppliers::get_supplier_by_id(&mut *tx, id).await?;
+    suppliers::delete_supplier(&mut *tx, id).await?;
+    audit::record(&mut tx, &user, "delete", "supplier", Some(id), audit::snapshot(&before), None).await?;
+    tx.commit().await?;
+    Ok(())
+}
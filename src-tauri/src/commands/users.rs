@@ -2,11 +2,12 @@ use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
 };
+use sqlx::SqliteConnection;
 use tauri::State;
 
 use crate::{
     auth::AuthState,
-    db::{self, users, DbPool},
+    db::{self, audit, users, DbPool},
     errors::{AppError, Result},
     guards,
     models::{SafeUser, UserListItem, CreateUserPayload, UpdateUserPayload},
@@ -39,6 +40,16 @@ pub(crate) async fn _internal_seed_admin_user(pool: &DbPool, password: &str) ->
     Ok(())
 }
 
+/// Dados do usuário para auditoria, sem o hash da senha
+async fn user_snapshot(conn: &mut SqliteConnection, id: i64) -> Result<SafeUser> {
+    let user = users::get_user_by_id(conn, id).await?;
+    Ok(SafeUser {
+        id: user.id,
+        username: user.username,
+        role: user.role,
+    })
+}
+
 #[tauri::command]
 pub async fn seed_admin_user(pool: State<'_, DbPool>, password: &str) -> Result<()> {
     _internal_seed_admin_user(pool.inner(), password).await
@@ -103,8 +114,13 @@ pub async fn create_user(
     pool: State<'_, DbPool>,
     auth_state: State<'_, AuthState>,
 ) -> Result<i64> {
-    let _user = guards::require_admin(&auth_state)?;
-    users::create_user(pool.inner(), payload).await
+    let user = guards::require_admin(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let id = users::create_user(&mut *tx, payload).await?;
+    let after = user_snapshot(&mut tx, id).await?;
+    audit::record(&mut tx, &user, "create", "user", Some(id), None, audit::snapshot(&after)).await?;
+    tx.commit().await?;
+    Ok(id)
 }
 
 #[tauri::command]
@@ -114,8 +130,17 @@ pub async fn update_user(
     pool: State<'_, DbPool>,
     auth_state: State<'_, AuthState>,
 ) -> Result<()> {
-    let _user = guards::require_admin(&auth_state)?;
-    users::update_user(pool.inner(), id, payload).await
+    let user = guards::require_admin(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = user_snapshot(&mut tx, id).await?;
+    users::update_user(&mut *tx, id, payload).await?;
+    let after = user_snapshot(&mut tx, id).await?;
+    audit::record(
+        &mut tx, &user, "update", "user", Some(id),
+        audit::snapshot(&before), audit::snapshot(&after),
+    ).await?;
+    tx.commit().await?;
+    Ok(())
 }
 
 #[tauri::command]
@@ -124,8 +149,13 @@ pub async fn delete_user(
     pool: State<'_, DbPool>,
     auth_state: State<'_, AuthState>,
 ) -> Result<()> {
-    let _user = guards::require_admin(&auth_state)?;
-    users::delete_user(pool.inner(), id).await
+    let user = guards::require_admin(&auth_state)?;
+    let mut tx = pool.begin().await?;
+    let before = user_snapshot(&mut tx, id).await?;
+    users::delete_user(&mut *tx, id).await?;
+    audit::record(&mut tx, &user, "delete", "user", Some(id), audit::snapshot(&before), None).await?;
+    tx.commit().await?;
+    Ok(())
 }
 
 #[cfg(test)]
@@ -169,4 +199,35 @@ mod tests {
         let result = users::get_user_by_username(&pool, "nonexistent").await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_delete_user_with_history() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        use crate::db::{cash_sessions, users};
+        use crate::models::OpenCashSessionPayload;
+
+        let new_user = |username: &str| CreateUserPayload {
+            username: username.to_string(),
+            password: "senha123".to_string(),
+            role: "operator".to_string(),
+        };
+
+        let cashier_id = users::create_user(&pool, new_user("caixa")).await.unwrap();
+        cash_sessions::open_session(&pool, OpenCashSessionPayload {
+            opening_float: 0.0,
+            notes: None,
+        }, cashier_id).await.unwrap();
+
+        // Operador que abriu caixa continua no histórico
+        let result = users::delete_user(&pool, cashier_id).await;
+        assert!(matches!(result, Err(AppError::BusinessLogic(_))));
+        assert!(users::get_user_by_id(&pool, cashier_id).await.is_ok());
+
+        // Sem movimentação pode ser removido
+        let trainee_id = users::create_user(&pool, new_user("estagiario")).await.unwrap();
+        users::delete_user(&pool, trainee_id).await.unwrap();
+        assert!(users::get_user_by_id(&pool, trainee_id).await.is_err());
+    }
 }
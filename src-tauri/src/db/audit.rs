@@ -0,0 +1,221 @@
+use crate::models::{AuditLogEntry, AuditLogFilter, SafeUser};
+use crate::errors::AppError;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{SqliteConnection, SqlitePool};
+
+const DEFAULT_LIMIT: i64 = 500;
+
+/// Converte uma entidade no JSON gravado como estado anterior/posterior
+pub fn snapshot<T: Serialize>(value: &T) -> Option<Value> {
+    serde_json::to_value(value).ok()
+}
+
+/// Registra quem alterou o quê, com o estado antes e depois da alteração. Roda na
+/// transação da própria alteração: se a auditoria falhar, a alteração é desfeita.
+pub async fn record(
+    conn: &mut SqliteConnection,
+    user: &SafeUser,
+    action: &str,
+    entity: &str,
+    entity_id: Option<i64>,
+    before: Option<Value>,
+    after: Option<Value>,
+) -> Result<(), AppError> {
+    sqlx::query(
+        "INSERT INTO audit_log (user_id, username, action, entity, entity_id, before_json, after_json)
+         VALUES (?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(user.id)
+    .bind(&user.username)
+    .bind(action)
+    .bind(entity)
+    .bind(entity_id)
+    .bind(before.map(|v| v.to_string()))
+    .bind(after.map(|v| v.to_string()))
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_audit_log(
+    pool: &SqlitePool,
+    filter: AuditLogFilter,
+) -> Result<Vec<AuditLogEntry>, AppError> {
+    let mut query = sqlx::QueryBuilder::new("SELECT * FROM audit_log WHERE 1 = 1");
+
+    if let Some(user_id) = filter.user_id {
+        query.push(" AND user_id = ");
+        query.push_bind(user_id);
+    }
+
+    if let Some(entity) = &filter.entity {
+        query.push(" AND entity = ");
+        query.push_bind(entity);
+    }
+
+    if let Some(entity_id) = filter.entity_id {
+        query.push(" AND entity_id = ");
+        query.push_bind(entity_id);
+    }
+
+    if let Some(action) = &filter.action {
+        query.push(" AND action = ");
+        query.push_bind(action);
+    }
+
+    if let Some(date_from) = &filter.date_from {
+        query.push(" AND DATE(created_at) >= DATE(");
+        query.push_bind(date_from);
+        query.push(")");
+    }
+
+    if let Some(date_to) = &filter.date_to {
+        query.push(" AND DATE(created_at) <= DATE(");
+        query.push_bind(date_to);
+        query.push(")");
+    }
+
+    query.push(" ORDER BY created_at DESC, id DESC LIMIT ");
+    query.push_bind(filter.limit.unwrap_or(DEFAULT_LIMIT));
+
+    let entries = query
+        .build_query_as::<AuditLogEntry>()
+        .fetch_all(pool)
+        .await?;
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_helpers::{setup_test_db, cleanup_test_db};
+    use serde_json::json;
+
+    fn test_user(id: i64, username: &str) -> SafeUser {
+        SafeUser {
+            id,
+            username: username.to_string(),
+            role: "admin".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_and_filter() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        let admin = test_user(1, "admin");
+        let operator = test_user(2, "caixa");
+
+        let mut conn = pool.acquire().await.unwrap();
+        record(
+            &mut conn, &admin, "update", "product", Some(10),
+            Some(json!({ "price_full": 250.0 })), Some(json!({ "price_full": 270.0 })),
+        ).await.unwrap();
+        record(&mut conn, &operator, "create", "order", Some(5), None, Some(json!({ "total": 100.0 })))
+            .await
+            .unwrap();
+        record(&mut conn, &admin, "update", "order", Some(5), None, None).await.unwrap();
+        drop(conn);
+
+        let all = get_audit_log(&pool, AuditLogFilter::default()).await.unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].entity, "order"); // mais recente primeiro
+
+        let price_changes = get_audit_log(&pool, AuditLogFilter {
+            entity: Some("product".to_string()),
+            entity_id: Some(10),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(price_changes.len(), 1);
+        assert_eq!(price_changes[0].username, "admin");
+        let before: Value = serde_json::from_str(price_changes[0].before_json.as_ref().unwrap()).unwrap();
+        assert_eq!(before["price_full"], 250.0);
+
+        let by_operator = get_audit_log(&pool, AuditLogFilter {
+            user_id: Some(2),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(by_operator.len(), 1);
+        assert_eq!(by_operator[0].action, "create");
+
+        let limited = get_audit_log(&pool, AuditLogFilter {
+            limit: Some(2),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(limited.len(), 2);
+
+        let future = get_audit_log(&pool, AuditLogFilter {
+            date_from: Some("2999-01-01".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        assert!(future.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failed_audit_rolls_back_change() {
+        use crate::db::{orders, products};
+        use crate::models::{CancelOrderPayload, UpdateProductPayload};
+        use crate::test_helpers::test_helpers::{
+            create_test_product, create_test_user, open_test_session, order_payload, product_payload,
+            product_update,
+        };
+
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let user_id = create_test_user(&pool).await;
+        let admin = test_user(user_id, "admin");
+        let product_id = create_test_product(&pool, product_payload("Gás P13", "gas")).await;
+        let order_id = orders::create_order(&pool, order_payload(None, product_id, 2, 100.0), None).await.unwrap();
+
+        sqlx::query(
+            "CREATE TRIGGER audit_log_fail BEFORE INSERT ON audit_log
+             BEGIN SELECT RAISE(ABORT, 'audit_log indisponível'); END"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Mesma sequência dos comandos: alteração e auditoria na mesma transação
+        let mut tx = pool.begin().await.unwrap();
+        products::update_product(&mut *tx, product_id, UpdateProductPayload {
+            price_full: Some(270.0),
+            ..product_update()
+        }).await.unwrap();
+        assert!(record(&mut tx, &admin, "update", "product", Some(product_id), None, None).await.is_err());
+        drop(tx);
+
+        let mut tx = pool.begin().await.unwrap();
+        orders::cancel_order(&mut *tx, order_id, CancelOrderPayload {
+            reason: "Cliente desistiu".to_string(),
+        }, user_id).await.unwrap();
+        assert!(record(&mut tx, &admin, "cancel", "order", Some(order_id), None, None).await.is_err());
+        drop(tx);
+
+        let product = products::get_product_by_id(&pool, product_id).await.unwrap();
+        assert_eq!(product.price_full, 250.0);
+        assert_eq!(product.stock_full, 18);
+
+        let order = orders::get_order_by_id(&pool, order_id).await.unwrap();
+        assert_eq!(order.order.status, "completed");
+
+        // Com a auditoria de volta, a alteração e o registro são gravados juntos
+        sqlx::query("DROP TRIGGER audit_log_fail").execute(&pool).await.unwrap();
+
+        let mut tx = pool.begin().await.unwrap();
+        orders::cancel_order(&mut *tx, order_id, CancelOrderPayload {
+            reason: "Cliente desistiu".to_string(),
+        }, user_id).await.unwrap();
+        record(&mut tx, &admin, "cancel", "order", Some(order_id), None, None).await.unwrap();
+        tx.commit().await.unwrap();
+
+        let order = orders::get_order_by_id(&pool, order_id).await.unwrap();
+        assert_eq!(order.order.status, "cancelled");
+        assert_eq!(get_audit_log(&pool, AuditLogFilter::default()).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,485 @@
+use crate::models::{BackupCheck, BackupFile, BackupSettings, UpdateBackupSettingsPayload};
+use crate::errors::AppError;
+use crate::db::init::{database_dir, latest_migration};
+use crate::db::settings::{get_int_setting, get_setting, set_setting};
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Acquire, ConnectOptions, Sqlite, SqliteExecutor, SqlitePool};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// Backups com o banco em uso (VACUUM INTO gera uma cópia consistente sem parar o
+// sistema). A restauração não troca o arquivo com o pool aberto: fica agendada num
+// marcador e é aplicada na próxima abertura do app, antes de conectar.
+
+const FILE_PREFIX: &str = "distribbuidora-";
+const FILE_SUFFIX: &str = ".db";
+
+// Marcas das cópias automáticas de segurança, no fim do nome do arquivo
+pub const PRE_RESTORE_TAG: &str = "pre-restore";
+pub const PRE_MIGRATION_TAG: &str = "pre-migration";
+pub const PRE_RECOVERY_TAG: &str = "pre-recovery";
+
+const RESTORE_MARKER: &str = "restore.pending";
+
+const DIR_KEY: &str = "backup.dir";
+const INTERVAL_KEY: &str = "backup.interval_hours";
+const KEEP_LAST_KEY: &str = "backup.keep_last";
+const KEEP_DAYS_KEY: &str = "backup.keep_days";
+
+const DEFAULT_INTERVAL_HOURS: i64 = 24;
+const DEFAULT_KEEP_LAST: i64 = 7;
+const DEFAULT_KEEP_DAYS: i64 = 30;
+
+/// De quanto em quanto tempo o agendador confere se o backup está vencido
+const SCHEDULER_TICK: Duration = Duration::from_secs(15 * 60);
+
+/// Sem pasta configurada, os backups ficam junto do banco, separados por perfil
+async fn default_dir(conn: impl SqliteExecutor<'_>) -> Result<PathBuf, AppError> {
+    Ok(database_dir(conn).await?.join("backups"))
+}
+
+pub async fn get_backup_settings(conn: impl Acquire<'_, Database = Sqlite>) -> Result<BackupSettings, AppError> {
+    let mut conn = conn.acquire().await?;
+
+    let dir = match get_setting(&mut *conn, DIR_KEY).await? {
+        Some(dir) => dir,
+        None => default_dir(&mut *conn).await?.to_string_lossy().into_owned(),
+    };
+
+    Ok(BackupSettings {
+        dir,
+        interval_hours: get_int_setting(&mut *conn, INTERVAL_KEY, DEFAULT_INTERVAL_HOURS).await?,
+        keep_last: get_int_setting(&mut *conn, KEEP_LAST_KEY, DEFAULT_KEEP_LAST).await?,
+        keep_days: get_int_setting(&mut *conn, KEEP_DAYS_KEY, DEFAULT_KEEP_DAYS).await?,
+    })
+}
+
+pub async fn update_backup_settings(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    payload: UpdateBackupSettingsPayload,
+) -> Result<(), AppError> {
+    if let Some(dir) = &payload.dir {
+        if !Path::new(dir).is_absolute() {
+            return Err(AppError::Validation("Backup folder must be an absolute path".to_string()));
+        }
+    }
+
+    if payload.interval_hours.is_some_and(|hours| hours < 0) {
+        return Err(AppError::Validation("Backup interval cannot be negative".to_string()));
+    }
+
+    if payload.keep_last.is_some_and(|keep| keep < 1) {
+        return Err(AppError::Validation("At least one backup must be kept".to_string()));
+    }
+
+    if payload.keep_days.is_some_and(|days| days < 0) {
+        return Err(AppError::Validation("Retention days cannot be negative".to_string()));
+    }
+
+    let mut tx = conn.begin().await?;
+
+    if let Some(dir) = &payload.dir {
+        // Pasta inacessível aparece já na configuração, não no primeiro backup
+        std::fs::create_dir_all(dir)?;
+        set_setting(&mut *tx, DIR_KEY, dir).await?;
+    }
+
+    for (key, value) in [
+        (INTERVAL_KEY, payload.interval_hours),
+        (KEEP_LAST_KEY, payload.keep_last),
+        (KEEP_DAYS_KEY, payload.keep_days),
+    ] {
+        if let Some(value) = value {
+            set_setting(&mut *tx, key, &value.to_string()).await?;
+        }
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// 'distribbuidora-20300131-235959[-pre-restore].db' -> ('2030-01-31 23:59:59', marca)
+fn parse_backup_name(file_name: &str) -> Option<(String, Option<String>)> {
+    let stamp = file_name.strip_prefix(FILE_PREFIX)?.strip_suffix(FILE_SUFFIX)?;
+    let (date, rest) = stamp.split_once('-')?;
+    let (time, tag) = match rest.split_once('-') {
+        Some((time, tag)) => (time, Some(tag)),
+        None => (rest, None),
+    };
+
+    let all_digits = |s: &str, len: usize| s.len() == len && s.chars().all(|c| c.is_ascii_digit());
+    if !all_digits(date, 8) || !all_digits(time, 6) {
+        return None;
+    }
+
+    if tag.is_some_and(|tag| tag.is_empty() || !tag.chars().all(|c| c.is_ascii_lowercase() || c == '-')) {
+        return None;
+    }
+
+    let created_at = format!(
+        "{}-{}-{} {}:{}:{}",
+        &date[0..4], &date[4..6], &date[6..8], &time[0..2], &time[2..4], &time[4..6]
+    );
+
+    Some((created_at, tag.map(str::to_string)))
+}
+
+fn backup_file(path: &Path) -> Option<BackupFile> {
+    let file_name = path.file_name()?.to_str()?.to_string();
+    let (created_at, tag) = parse_backup_name(&file_name)?;
+    let metadata = std::fs::metadata(path).ok()?;
+
+    Some(BackupFile {
+        file_name,
+        path: path.to_string_lossy().into_owned(),
+        size_bytes: metadata.len() as i64,
+        created_at,
+        tag,
+    })
+}
+
+/// Backups da pasta, do mais recente para o mais antigo
+pub fn list_backups_in(dir: &Path) -> Result<Vec<BackupFile>, AppError> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups: Vec<BackupFile> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| backup_file(&entry.path()))
+        .collect();
+
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.file_name.cmp(&a.file_name)));
+
+    Ok(backups)
+}
+
+pub async fn list_backups(pool: &SqlitePool) -> Result<Vec<BackupFile>, AppError> {
+    let settings = get_backup_settings(pool).await?;
+    list_backups_in(Path::new(&settings.dir))
+}
+
+/// Caminho de um backup da pasta, recusando nomes que apontem para fora dela
+pub(crate) fn resolve_backup(dir: &Path, file_name: &str) -> Result<PathBuf, AppError> {
+    if file_name.contains(['/', '\\']) || parse_backup_name(file_name).is_none() {
+        return Err(AppError::Validation(format!("Invalid backup file name: {}", file_name)));
+    }
+
+    let path = dir.join(file_name);
+    if !path.is_file() {
+        return Err(AppError::NotFound(format!("Backup {} not found", file_name)));
+    }
+
+    Ok(path)
+}
+
+async fn write_backup(pool: &SqlitePool, dir: &Path, tag: Option<&str>) -> Result<BackupFile, AppError> {
+    std::fs::create_dir_all(dir)?;
+
+    let stamp: (String,) = sqlx::query_as("SELECT strftime('%Y%m%d-%H%M%S', 'now', 'localtime')")
+        .fetch_one(pool)
+        .await?;
+    let tag = tag.map(|tag| format!("-{}", tag)).unwrap_or_default();
+    let path = dir.join(format!("{}{}{}{}", FILE_PREFIX, stamp.0, tag, FILE_SUFFIX));
+
+    if path.exists() {
+        return Err(AppError::BusinessLogic(format!(
+            "Backup {} already exists; try again in a second",
+            path.display()
+        )));
+    }
+
+    sqlx::query("VACUUM INTO ?")
+        .bind(path.to_string_lossy().as_ref())
+        .execute(pool)
+        .await?;
+
+    backup_file(&path).ok_or_else(|| AppError::BusinessLogic("Backup file was not created".to_string()))
+}
+
+/// Pasta configurada para os backups. Antes das migrações a tabela de
+/// configurações pode nem existir; nesse caso vale a pasta padrão.
+pub async fn backup_dir(pool: &SqlitePool) -> Result<PathBuf, AppError> {
+    match get_backup_settings(pool).await {
+        Ok(settings) => Ok(PathBuf::from(settings.dir)),
+        Err(_) => default_dir(pool).await,
+    }
+}
+
+/// Cópia de segurança marcada, feita antes de uma operação arriscada. Fica fora
+/// do agendamento, mas entra na retenção como os demais backups.
+pub async fn create_safety_backup(pool: &SqlitePool, tag: &str) -> Result<BackupFile, AppError> {
+    let dir = backup_dir(pool).await?;
+    write_backup(pool, &dir, Some(tag)).await
+}
+
+/// Backup imediato na pasta configurada, aplicando a retenção em seguida
+pub async fn create_backup(pool: &SqlitePool) -> Result<BackupFile, AppError> {
+    let settings = get_backup_settings(pool).await?;
+    let dir = Path::new(&settings.dir);
+
+    let backup = write_backup(pool, dir, None).await?;
+    prune_backups(dir, settings.keep_last, settings.keep_days)?;
+
+    Ok(backup)
+}
+
+/// Retenção: ficam sempre os `keep_last` mais recentes e todos os dos últimos
+/// `keep_days` dias; o resto é apagado. Devolve quantos foram apagados.
+pub fn prune_backups(dir: &Path, keep_last: i64, keep_days: i64) -> Result<usize, AppError> {
+    let max_age = Duration::from_secs(keep_days.max(0) as u64 * 24 * 60 * 60);
+    let mut removed = 0;
+
+    for backup in list_backups_in(dir)?.into_iter().skip(keep_last.max(1) as usize) {
+        let age = std::fs::metadata(&backup.path)?
+            .modified()?
+            .elapsed()
+            .unwrap_or_default();
+
+        if age > max_age {
+            std::fs::remove_file(&backup.path)?;
+            removed += 1;
+        }
+    }
+
+    Ok(removed)
+}
+
+/// Faz o backup se o intervalo configurado já passou desde o último.
+/// Intervalo zero desliga o backup automático.
+pub async fn run_scheduled_backup(pool: &SqlitePool) -> Result<Option<BackupFile>, AppError> {
+    let settings = get_backup_settings(pool).await?;
+    if settings.interval_hours == 0 {
+        return Ok(None);
+    }
+
+    let interval = Duration::from_secs(settings.interval_hours as u64 * 60 * 60);
+    let last = list_backups_in(Path::new(&settings.dir))?
+        .into_iter()
+        .find(|backup| backup.tag.is_none());
+
+    if let Some(last) = last {
+        let age = std::fs::metadata(&last.path)?.modified()?.elapsed().unwrap_or_default();
+        if age < interval {
+            return Ok(None);
+        }
+    }
+
+    create_backup(pool).await.map(Some)
+}
+
+/// Laço do backup automático, iniciado junto com o app
+pub async fn run_scheduler(pool: SqlitePool) {
+    loop {
+        if let Err(e) = run_scheduled_backup(&pool).await {
+            eprintln!("Scheduled backup failed: {}", e);
+        }
+        tokio::time::sleep(SCHEDULER_TICK).await;
+    }
+}
+
+/// Confere a integridade do arquivo e se ele é um banco deste sistema
+pub async fn verify_backup_file(path: &Path) -> BackupCheck {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let failed = |message: String| BackupCheck {
+        file_name: file_name.clone(),
+        ok: false,
+        schema_version: None,
+        message,
+    };
+
+    let mut conn = match SqliteConnectOptions::new().filename(path).read_only(true).connect().await {
+        Ok(conn) => conn,
+        Err(e) => return failed(format!("Cannot open backup: {}", e)),
+    };
+
+    let integrity: Vec<(String,)> = match sqlx::query_as("PRAGMA integrity_check").fetch_all(&mut conn).await {
+        Ok(rows) => rows,
+        Err(e) => return failed(format!("Integrity check failed: {}", e)),
+    };
+    if integrity.len() != 1 || integrity[0].0 != "ok" {
+        let problems: Vec<String> = integrity.into_iter().take(5).map(|row| row.0).collect();
+        return failed(format!("Integrity check failed: {}", problems.join("; ")));
+    }
+
+    let version: (i64,) = match sqlx::query_as(
+        "SELECT COALESCE(MAX(version), 0) FROM _sqlx_migrations WHERE success = 1"
+    )
+    .fetch_one(&mut conn)
+    .await
+    {
+        Ok(version) => version,
+        Err(_) => return failed("File is not a database of this system".to_string()),
+    };
+
+    if version.0 > latest_migration() {
+        return BackupCheck {
+            schema_version: Some(version.0),
+            ..failed(format!(
+                "Backup is from a newer version of the system (schema {}, this version supports up to {})",
+                version.0,
+                latest_migration()
+            ))
+        };
+    }
+
+    BackupCheck {
+        file_name,
+        ok: true,
+        schema_version: Some(version.0),
+        message: "ok".to_string(),
+    }
+}
+
+pub async fn verify_backup(pool: &SqlitePool, file_name: &str) -> Result<BackupCheck, AppError> {
+    let settings = get_backup_settings(pool).await?;
+    let path = resolve_backup(Path::new(&settings.dir), file_name)?;
+    Ok(verify_backup_file(&path).await)
+}
+
+/// Agenda a restauração para a próxima abertura do app. Antes, confere o backup e
+/// guarda o estado atual num backup "pre-restore", que é devolvido.
+pub async fn schedule_restore(pool: &SqlitePool, file_name: &str) -> Result<BackupFile, AppError> {
+    let settings = get_backup_settings(pool).await?;
+    let path = resolve_backup(Path::new(&settings.dir), file_name)?;
+
+    let check = verify_backup_file(&path).await;
+    if !check.ok {
+        return Err(AppError::BusinessLogic(format!("Backup {} is not usable: {}", file_name, check.message)));
+    }
+
+    let safety = create_safety_backup(pool, PRE_RESTORE_TAG).await?;
+    mark_restore(&database_dir(pool).await?, &path)?;
+
+    Ok(safety)
+}
+
+/// Deixa o backup marcado para substituir o banco na próxima abertura
+pub fn mark_restore(data_dir: &Path, backup: &Path) -> Result<(), AppError> {
+    std::fs::write(data_dir.join(RESTORE_MARKER), backup.to_string_lossy().as_bytes())?;
+    Ok(())
+}
+
+/// Aplica a restauração agendada, com o banco ainda fechado. Devolve se houve troca.
+pub fn apply_pending_restore(data_dir: &Path, db_path: &Path) -> Result<bool, AppError> {
+    let marker = data_dir.join(RESTORE_MARKER);
+    if !marker.exists() {
+        return Ok(false);
+    }
+
+    let backup = PathBuf::from(std::fs::read_to_string(&marker)?.trim());
+
+    // Copia para um temporário primeiro: falha no meio não deixa o banco pela metade
+    let staging = db_path.with_extension("db.restoring");
+    std::fs::copy(&backup, &staging)?;
+
+    for suffix in ["-wal", "-shm"] {
+        let journal = PathBuf::from(format!("{}{}", db_path.display(), suffix));
+        if journal.exists() {
+            std::fs::remove_file(journal)?;
+        }
+    }
+
+    std::fs::rename(&staging, db_path)?;
+    std::fs::remove_file(&marker)?;
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::customers;
+    use crate::models::CreateCustomerPayload;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("backup-test-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_backup_verify_and_restore() {
+        // Banco em arquivo: a partir de um banco em memória o VACUUM INTO não gera arquivo
+        let dir = temp_dir("restore");
+        let pool = SqlitePool::connect(&format!("sqlite:{}?mode=rwc", dir.join("source.db").display()))
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        let backup_dir = dir.join("backups");
+        update_backup_settings(&pool, UpdateBackupSettingsPayload {
+            dir: Some(backup_dir.to_string_lossy().into_owned()),
+            interval_hours: Some(24),
+            keep_last: Some(3),
+            keep_days: Some(0),
+        }).await.unwrap();
+
+        customers::create_customer(&pool, CreateCustomerPayload {
+            name: "Cliente do backup".to_string(),
+            phone: None,
+            address: None,
+            notes: None,
+            credit_limit: None,
+        }).await.unwrap();
+
+        // Primeira rodada do agendador faz o backup; a seguinte ainda não
+        let backup = run_scheduled_backup(&pool).await.unwrap().unwrap();
+        assert!(run_scheduled_backup(&pool).await.unwrap().is_none());
+        assert_eq!(list_backups(&pool).await.unwrap().len(), 1);
+
+        let check = verify_backup(&pool, &backup.file_name).await.unwrap();
+        assert!(check.ok, "{}", check.message);
+        assert_eq!(check.schema_version, Some(latest_migration()));
+
+        // Arquivo corrompido e nomes fora da pasta são recusados
+        let broken = backup_dir.join("distribbuidora-20200101-000000.db");
+        std::fs::write(&broken, b"not a database").unwrap();
+        assert!(!verify_backup(&pool, "distribbuidora-20200101-000000.db").await.unwrap().ok);
+        assert!(verify_backup(&pool, "../distribbuidora.db").await.is_err());
+        assert!(schedule_restore(&pool, "distribbuidora-20200101-000000.db").await.is_err());
+        std::fs::remove_file(&broken).unwrap();
+
+        let safety = schedule_restore(&pool, &backup.file_name).await.unwrap();
+        assert_eq!(safety.tag.as_deref(), Some(PRE_RESTORE_TAG));
+
+        // Na abertura seguinte o banco vira o backup
+        let db_path = dir.join("distribbuidora.db");
+        std::fs::write(&db_path, b"current").unwrap();
+        std::fs::write(dir.join("distribbuidora.db-wal"), b"stale").unwrap();
+        assert!(apply_pending_restore(&dir, &db_path).unwrap());
+        assert!(!dir.join("distribbuidora.db-wal").exists());
+        assert!(!apply_pending_restore(&dir, &db_path).unwrap());
+
+        let restored = SqlitePool::connect(&format!("sqlite:{}", db_path.display())).await.unwrap();
+        let names: Vec<(String,)> = sqlx::query_as("SELECT name FROM customers").fetch_all(&restored).await.unwrap();
+        assert_eq!(names, vec![("Cliente do backup".to_string(),)]);
+        restored.close().await;
+        pool.close().await;
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_prune_backups() {
+        let dir = temp_dir("prune");
+        for stamp in ["20300101-000000", "20300102-000000", "20300103-000000", "20300104-000000"] {
+            std::fs::write(dir.join(format!("{}{}{}", FILE_PREFIX, stamp, FILE_SUFFIX)), b"x").unwrap();
+        }
+        std::fs::write(dir.join("notes.txt"), b"x").unwrap();
+
+        // Todos são de agora: a regra de dias segura tudo
+        assert_eq!(prune_backups(&dir, 2, 1).unwrap(), 0);
+
+        assert_eq!(prune_backups(&dir, 2, 0).unwrap(), 2);
+        let left: Vec<String> = list_backups_in(&dir).unwrap().into_iter().map(|b| b.created_at).collect();
+        assert_eq!(left, vec!["2030-01-04 00:00:00".to_string(), "2030-01-03 00:00:00".to_string()]);
+        assert!(dir.join("notes.txt").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
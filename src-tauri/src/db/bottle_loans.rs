@@ -0,0 +1,450 @@
+use crate::models::{BottleLoanEntry, CustomerBottleBalance, ReturnBottlesPayload};
+use crate::errors::AppError;
+use crate::db::cash_sessions;
+use crate::db::customers::get_customer_by_id;
+use crate::db::stock::{apply_stock_change, StockChange};
+use sqlx::{Acquire, Sqlite, SqliteConnection, SqlitePool};
+
+/// Tipos de produto vendidos em vasilhame retornável
+pub const BOTTLE_PRODUCT_TYPES: [&str; 2] = ["water", "gas"];
+
+/// Registra o casco que o cliente levou sem devolver o vazio
+pub(crate) async fn lend_for_order(
+    conn: &mut SqliteConnection,
+    customer_id: i64,
+    product_id: i64,
+    quantity: i64,
+    deposit_amount: f64,
+    order_id: i64,
+) -> Result<(), AppError> {
+    sqlx::query(
+        "INSERT INTO bottle_loans (customer_id, product_id, entry_type, quantity, deposit_amount, order_id)
+         VALUES (?, ?, 'LENT', ?, ?, ?)"
+    )
+    .bind(customer_id)
+    .bind(product_id)
+    .bind(quantity)
+    .bind(deposit_amount)
+    .bind(order_id)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Encerra o comodato gerado por um pedido cancelado. Se o cliente já devolveu
+/// cascos, os vazios entraram no estoque e o pedido não pode mais ser cancelado.
+pub(crate) async fn release_for_order(
+    conn: &mut SqliteConnection,
+    order_id: i64,
+    user_id: i64,
+) -> Result<(), AppError> {
+    let loans: Vec<(i64, i64, i64, f64)> = sqlx::query_as(
+        "SELECT customer_id, product_id, quantity, deposit_amount
+         FROM bottle_loans
+         WHERE order_id = ? AND entry_type = 'LENT'"
+    )
+    .bind(order_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for (customer_id, product_id, lent, deposit) in loans {
+        let (outstanding, deposit_held) = get_balance(conn, customer_id, product_id).await?;
+        if outstanding < lent {
+            return Err(AppError::BusinessLogic(
+                format!("Customer already returned bottles of product {} lent by order {}, cannot cancel it",
+                    product_id, order_id)
+            ));
+        }
+
+        let deposit_amount = deposit.min(deposit_held).max(0.0);
+
+        sqlx::query(
+            "INSERT INTO bottle_loans (customer_id, product_id, entry_type, quantity, deposit_amount, order_id, user_id, notes)
+             VALUES (?, ?, 'RETURNED', ?, ?, ?, ?, ?)"
+        )
+        .bind(customer_id)
+        .bind(product_id)
+        .bind(lent)
+        .bind(deposit_amount)
+        .bind(order_id)
+        .bind(user_id)
+        .bind(format!("Cancelamento do pedido #{}", order_id))
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Saldo de cascos e caução em poder do cliente para um produto
+async fn get_balance(
+    conn: &mut SqliteConnection,
+    customer_id: i64,
+    product_id: i64,
+) -> Result<(i64, f64), AppError> {
+    let balance: (i64, f64) = sqlx::query_as(
+        "SELECT
+            COALESCE(SUM(CASE WHEN entry_type = 'LENT' THEN quantity ELSE -quantity END), 0),
+            COALESCE(SUM(CASE WHEN entry_type = 'LENT' THEN deposit_amount ELSE -deposit_amount END), 0.0)
+         FROM bottle_loans
+         WHERE customer_id = ? AND product_id = ?"
+    )
+    .bind(customer_id)
+    .bind(product_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(balance)
+}
+
+pub async fn get_outstanding_bottles(
+    pool: &SqlitePool,
+    customer_id: Option<i64>,
+) -> Result<Vec<CustomerBottleBalance>, AppError> {
+    let balances = sqlx::query_as::<_, CustomerBottleBalance>(
+        "SELECT c.id as customer_id, c.name as customer_name, c.phone,
+                p.id as product_id, p.name as product_name,
+                SUM(CASE WHEN bl.entry_type = 'LENT' THEN bl.quantity ELSE -bl.quantity END) as quantity,
+                SUM(CASE WHEN bl.entry_type = 'LENT' THEN bl.deposit_amount ELSE -bl.deposit_amount END) as deposit_held
+         FROM bottle_loans bl
+         JOIN customers c ON bl.customer_id = c.id
+         JOIN products p ON bl.product_id = p.id
+         WHERE ? IS NULL OR bl.customer_id = ?
+         GROUP BY c.id, c.name, c.phone, p.id, p.name
+         HAVING SUM(CASE WHEN bl.entry_type = 'LENT' THEN bl.quantity ELSE -bl.quantity END) > 0
+         ORDER BY c.name, p.name"
+    )
+    .bind(customer_id)
+    .bind(customer_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(balances)
+}
+
+pub async fn get_loan_history(
+    pool: &SqlitePool,
+    customer_id: i64,
+) -> Result<Vec<BottleLoanEntry>, AppError> {
+    let entries = sqlx::query_as::<_, BottleLoanEntry>(
+        "SELECT bl.id, bl.customer_id, bl.product_id, p.name as product_name, bl.entry_type,
+                bl.quantity, bl.deposit_amount, bl.order_id, bl.user_id, bl.notes, bl.created_at
+         FROM bottle_loans bl
+         JOIN products p ON bl.product_id = p.id
+         WHERE bl.customer_id = ?
+         ORDER BY bl.created_at DESC, bl.id DESC"
+    )
+    .bind(customer_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(entries)
+}
+
+pub async fn return_bottles(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    customer_id: i64,
+    payload: ReturnBottlesPayload,
+    user_id: Option<i64>,
+) -> Result<i64, AppError> {
+    if payload.quantity <= 0 {
+        return Err(AppError::Validation("Quantity must be positive".to_string()));
+    }
+
+    let refund_amount = payload.refund_amount.unwrap_or(0.0);
+    if refund_amount < 0.0 {
+        return Err(AppError::Validation("Refund amount cannot be negative".to_string()));
+    }
+
+    let mut tx = conn.begin().await?;
+
+    // Verifica se existe
+    get_customer_by_id(&mut *tx, customer_id).await?;
+
+    let (outstanding, deposit_held) = get_balance(&mut tx, customer_id, payload.product_id).await?;
+
+    if outstanding < payload.quantity {
+        return Err(AppError::BusinessLogic(
+            format!("Customer holds only {} bottle(s) of product {}, cannot return {}",
+                outstanding, payload.product_id, payload.quantity)
+        ));
+    }
+
+    if refund_amount > deposit_held + 0.005 {
+        return Err(AppError::BusinessLogic(
+            format!("Refund {:.2} exceeds deposit held {:.2}", refund_amount, deposit_held)
+        ));
+    }
+
+    let id = sqlx::query(
+        "INSERT INTO bottle_loans (customer_id, product_id, entry_type, quantity, deposit_amount, user_id, notes)
+         VALUES (?, ?, 'RETURNED', ?, ?, ?, ?)"
+    )
+    .bind(customer_id)
+    .bind(payload.product_id)
+    .bind(payload.quantity)
+    .bind(refund_amount)
+    .bind(user_id)
+    .bind(&payload.notes)
+    .execute(&mut *tx)
+    .await?
+    .last_insert_rowid();
+
+    // O casco volta para o estoque de vazios
+    apply_stock_change(&mut tx, StockChange {
+        product_id: payload.product_id,
+        stock_kind: "empty",
+        movement_type: "RETURN",
+        quantity: payload.quantity,
+        ..Default::default()
+    }).await?;
+
+    // Caução devolvida em dinheiro sai da gaveta do caixa aberto, como uma sangria
+    if refund_amount > 0.0 {
+        cash_sessions::withdraw(
+            &mut tx, "cash", refund_amount,
+            Some(&format!("Devolução de caução - cliente #{}", customer_id)), user_id,
+        ).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_helpers::{
+        setup_test_db, cleanup_test_db, create_test_user, open_test_session,
+        create_test_product, product_payload, create_test_customer, customer_payload, order_payload,
+    };
+    use crate::db::{orders, products};
+    use crate::models::{CancelOrderPayload, CloseCashSessionPayload};
+
+    async fn sell(
+        pool: &SqlitePool,
+        customer_id: Option<i64>,
+        product_id: i64,
+        quantity: i64,
+        returned_bottle: bool,
+    ) -> i64 {
+        let unit_price = if returned_bottle { 100.0 } else { 250.0 };
+        let mut payload = order_payload(customer_id, product_id, quantity, unit_price);
+        payload.items[0].returned_bottle = returned_bottle;
+        orders::create_order(pool, payload, None).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_order_without_bottle_lends_to_customer() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let product_id = create_test_product(&pool, product_payload("Gás P13", "gas")).await;
+        let customer_id = create_test_customer(&pool, customer_payload("João Silva")).await;
+
+        sell(&pool, Some(customer_id), product_id, 2, false).await;
+        // Troca com casco não altera o comodato
+        sell(&pool, Some(customer_id), product_id, 1, true).await;
+        // Consumidor final não é rastreado
+        sell(&pool, None, product_id, 1, false).await;
+
+        let balances = get_outstanding_bottles(&pool, None).await.unwrap();
+        assert_eq!(balances.len(), 1);
+        assert_eq!(balances[0].customer_id, customer_id);
+        assert_eq!(balances[0].quantity, 2);
+        assert_eq!(balances[0].deposit_held, 300.0); // 2 x (250 - 100)
+    }
+
+    #[tokio::test]
+    async fn test_non_bottle_products_not_lent() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let product_id = create_test_product(&pool, product_payload("Gás P13", "coal")).await;
+        let customer_id = create_test_customer(&pool, customer_payload("João Silva")).await;
+
+        sell(&pool, Some(customer_id), product_id, 1, false).await;
+
+        let balances = get_outstanding_bottles(&pool, Some(customer_id)).await.unwrap();
+        assert!(balances.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_return_bottles() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let product_id = create_test_product(&pool, product_payload("Gás P13", "gas")).await;
+        let customer_id = create_test_customer(&pool, customer_payload("João Silva")).await;
+
+        sell(&pool, Some(customer_id), product_id, 3, false).await;
+
+        return_bottles(&pool, customer_id, ReturnBottlesPayload {
+            product_id,
+            quantity: 2,
+            refund_amount: Some(150.0),
+            notes: None,
+        }, None).await.unwrap();
+
+        let balances = get_outstanding_bottles(&pool, Some(customer_id)).await.unwrap();
+        assert_eq!(balances[0].quantity, 1);
+        assert_eq!(balances[0].deposit_held, 300.0);
+
+        // Casco devolvido entra no estoque de vazios
+        let product = products::get_product_by_id(&pool, product_id).await.unwrap();
+        assert_eq!(product.stock_empty, 2);
+
+        let history = get_loan_history(&pool, customer_id).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].entry_type, "RETURNED");
+
+        // Mais cascos do que o cliente possui
+        assert!(return_bottles(&pool, customer_id, ReturnBottlesPayload {
+            product_id,
+            quantity: 2,
+            refund_amount: None,
+            notes: None,
+        }, None).await.is_err());
+
+        // Caução maior do que a retida
+        assert!(return_bottles(&pool, customer_id, ReturnBottlesPayload {
+            product_id,
+            quantity: 1,
+            refund_amount: Some(1000.0),
+            notes: None,
+        }, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_deposit_refund_comes_from_drawer() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let product_id = create_test_product(&pool, product_payload("Gás P13", "gas")).await;
+        let customer_id = create_test_customer(&pool, customer_payload("João Silva")).await;
+        let user_id = create_test_user(&pool).await;
+
+        // Venda no pix: a gaveta não recebe dinheiro
+        let mut payload = order_payload(Some(customer_id), product_id, 2, 250.0);
+        payload.items[0].returned_bottle = false;
+        payload.payments[0].method = "pix".to_string();
+        orders::create_order(&pool, payload, None).await.unwrap();
+
+        let refund = |quantity| ReturnBottlesPayload {
+            product_id,
+            quantity,
+            refund_amount: Some(150.0),
+            notes: None,
+        };
+
+        let err = return_bottles(&pool, customer_id, refund(1), Some(user_id)).await.unwrap_err();
+        assert!(matches!(err, AppError::BusinessLogic(msg) if msg.starts_with("Insufficient cash in drawer")));
+
+        cash_sessions::close_session(&pool, CloseCashSessionPayload {
+            counts: vec![],
+            notes: None,
+        }, user_id).await.unwrap();
+
+        // Sem caixa aberto a devolução não some
+        let err = return_bottles(&pool, customer_id, refund(1), Some(user_id)).await.unwrap_err();
+        assert!(matches!(err, AppError::BusinessLogic(msg) if msg == "No open cash session"));
+
+        // Nada foi devolvido
+        let balances = get_outstanding_bottles(&pool, Some(customer_id)).await.unwrap();
+        assert_eq!((balances[0].quantity, balances[0].deposit_held), (2, 300.0));
+        let product = products::get_product_by_id(&pool, product_id).await.unwrap();
+        assert_eq!(product.stock_empty, 0);
+
+        // Devolução sem caução não mexe no caixa
+        return_bottles(&pool, customer_id, ReturnBottlesPayload {
+            refund_amount: None,
+            ..refund(1)
+        }, Some(user_id)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fully_returned_customer_not_listed() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let product_id = create_test_product(&pool, product_payload("Gás P13", "gas")).await;
+        let customer_id = create_test_customer(&pool, customer_payload("João Silva")).await;
+
+        sell(&pool, Some(customer_id), product_id, 2, false).await;
+        return_bottles(&pool, customer_id, ReturnBottlesPayload {
+            product_id,
+            quantity: 2,
+            refund_amount: None,
+            notes: None,
+        }, None).await.unwrap();
+
+        // O filtro usa o saldo líquido, não a quantidade de cada lançamento
+        assert!(get_outstanding_bottles(&pool, None).await.unwrap().is_empty());
+        assert!(get_outstanding_bottles(&pool, Some(customer_id)).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_order_reverses_loans() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let product_id = create_test_product(&pool, product_payload("Gás P13", "water")).await;
+        let customer_id = create_test_customer(&pool, customer_payload("João Silva")).await;
+        let user_id = create_test_user(&pool).await;
+
+        let order_id = sell(&pool, Some(customer_id), product_id, 1, false).await;
+        orders::cancel_order(&pool, order_id, CancelOrderPayload {
+            reason: "Cliente desistiu".to_string(),
+        }, user_id).await.unwrap();
+
+        let balances = get_outstanding_bottles(&pool, Some(customer_id)).await.unwrap();
+        assert!(balances.is_empty());
+
+        // O histórico mantém o empréstimo e o estorno
+        let history = get_loan_history(&pool, customer_id).await.unwrap();
+        assert_eq!(history.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cannot_cancel_order_after_return() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let product_id = create_test_product(&pool, product_payload("Gás P13", "gas")).await;
+        let customer_id = create_test_customer(&pool, customer_payload("João Silva")).await;
+        let user_id = create_test_user(&pool).await;
+
+        // 3 cascos com caução de 150 cada; 2 voltam antes do cancelamento
+        let order_id = sell(&pool, Some(customer_id), product_id, 3, false).await;
+        return_bottles(&pool, customer_id, ReturnBottlesPayload {
+            product_id,
+            quantity: 2,
+            refund_amount: Some(300.0),
+            notes: None,
+        }, None).await.unwrap();
+
+        // Os vazios devolvidos já estão no estoque: estornar os cheios inflaria o estoque
+        assert!(matches!(orders::cancel_order(&pool, order_id, CancelOrderPayload {
+            reason: "Cliente desistiu".to_string(),
+        }, user_id).await, Err(AppError::BusinessLogic(_))));
+
+        // Nada foi estornado
+        let order = orders::get_order_by_id(&pool, order_id).await.unwrap();
+        assert_ne!(order.order.status, "cancelled");
+        let mut conn = pool.acquire().await.unwrap();
+        assert_eq!(get_balance(&mut conn, customer_id, product_id).await.unwrap(), (1, 150.0));
+        drop(conn);
+        let product = products::get_product_by_id(&pool, product_id).await.unwrap();
+        assert_eq!((product.stock_full, product.stock_empty), (17, 2));
+        assert_eq!(get_loan_history(&pool, customer_id).await.unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,519 @@
+use crate::models::{
+    CashSession, CashMovement, CashSessionReport, CashSessionReportLine,
+    OpenCashSessionPayload, CashMovementPayload, CloseCashSessionPayload,
+};
+use crate::errors::AppError;
+use sqlx::{Acquire, Sqlite, SqliteConnection, SqliteExecutor, SqlitePool};
+
+/// Formas de pagamento conferidas no fechamento (fiado não entra na gaveta)
+pub const COUNTED_METHODS: [&str; 4] = ["cash", "pix", "debit", "credit"];
+
+const SESSION_SELECT: &str =
+    "SELECT s.id, s.status, s.opening_float, s.opened_by, uo.username as opened_by_username,
+            s.opened_at, s.closed_by, uc.username as closed_by_username, s.closed_at, s.notes
+     FROM cash_sessions s
+     LEFT JOIN users uo ON s.opened_by = uo.id
+     LEFT JOIN users uc ON s.closed_by = uc.id";
+
+pub async fn get_open_session(conn: impl SqliteExecutor<'_>) -> Result<Option<CashSession>, AppError> {
+    let session = sqlx::query_as::<_, CashSession>(
+        &format!("{} WHERE s.status = 'open'", SESSION_SELECT)
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(session)
+}
+
+pub async fn get_session_by_id(conn: impl SqliteExecutor<'_>, id: i64) -> Result<CashSession, AppError> {
+    let session = sqlx::query_as::<_, CashSession>(
+        &format!("{} WHERE s.id = ?", SESSION_SELECT)
+    )
+    .bind(id)
+    .fetch_optional(conn)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Cash session with id {} not found", id)))?;
+
+    Ok(session)
+}
+
+pub async fn get_all_sessions(pool: &SqlitePool) -> Result<Vec<CashSession>, AppError> {
+    let sessions = sqlx::query_as::<_, CashSession>(
+        &format!("{} ORDER BY s.opened_at DESC, s.id DESC", SESSION_SELECT)
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(sessions)
+}
+
+pub async fn open_session(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    payload: OpenCashSessionPayload,
+    user_id: i64,
+) -> Result<i64, AppError> {
+    if payload.opening_float < 0.0 {
+        return Err(AppError::Validation("Opening float cannot be negative".to_string()));
+    }
+
+    let mut conn = conn.acquire().await?;
+
+    if let Some(session) = get_open_session(&mut *conn).await? {
+        return Err(AppError::BusinessLogic(
+            format!("Cash session #{} is already open", session.id)
+        ));
+    }
+
+    let id = sqlx::query(
+        "INSERT INTO cash_sessions (opening_float, opened_by, notes) VALUES (?, ?, ?)"
+    )
+    .bind(payload.opening_float)
+    .bind(user_id)
+    .bind(&payload.notes)
+    .execute(&mut *conn)
+    .await?
+    .last_insert_rowid();
+
+    Ok(id)
+}
+
+pub async fn add_movement(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    payload: CashMovementPayload,
+    user_id: i64,
+) -> Result<i64, AppError> {
+    if !["WITHDRAWAL", "SUPPLY"].contains(&payload.movement_type.as_str()) {
+        return Err(AppError::Validation("Invalid cash movement type".to_string()));
+    }
+
+    if payload.amount <= 0.0 {
+        return Err(AppError::Validation("Amount must be positive".to_string()));
+    }
+
+    let mut conn = conn.acquire().await?;
+
+    if payload.movement_type == "WITHDRAWAL" {
+        return withdraw(&mut conn, "cash", payload.amount, payload.reason.as_deref(), Some(user_id)).await;
+    }
+
+    let session = get_open_session(&mut *conn).await?
+        .ok_or_else(|| AppError::BusinessLogic("No open cash session".to_string()))?;
+
+    insert_movement(&mut conn, session.id, "SUPPLY", "cash", payload.amount, payload.reason.as_deref(), Some(user_id)).await
+}
+
+/// Saída de valores do caixa aberto: sangria, estorno de pedido ou devolução de caução.
+/// Saída em dinheiro não pode deixar a gaveta negativa.
+pub(crate) async fn withdraw(
+    conn: &mut SqliteConnection,
+    method: &str,
+    amount: f64,
+    reason: Option<&str>,
+    user_id: Option<i64>,
+) -> Result<i64, AppError> {
+    let session = get_open_session(&mut *conn).await?
+        .ok_or_else(|| AppError::BusinessLogic("No open cash session".to_string()))?;
+
+    if method == "cash" {
+        let expected_cash = expected_by_method(conn, &session).await?
+            .into_iter()
+            .find(|(method, _)| method == "cash")
+            .map(|(_, amount)| amount)
+            .unwrap_or(0.0);
+
+        if amount > expected_cash + 0.005 {
+            return Err(AppError::BusinessLogic(
+                format!("Insufficient cash in drawer. Available: {:.2}, Requested: {:.2}",
+                    expected_cash, amount)
+            ));
+        }
+    }
+
+    insert_movement(conn, session.id, "WITHDRAWAL", method, amount, reason, user_id).await
+}
+
+async fn insert_movement(
+    conn: &mut SqliteConnection,
+    session_id: i64,
+    movement_type: &str,
+    method: &str,
+    amount: f64,
+    reason: Option<&str>,
+    user_id: Option<i64>,
+) -> Result<i64, AppError> {
+    let id = sqlx::query(
+        "INSERT INTO cash_movements (session_id, movement_type, method, amount, reason, user_id)
+         VALUES (?, ?, ?, ?, ?, ?)"
+    )
+    .bind(session_id)
+    .bind(movement_type)
+    .bind(method)
+    .bind(amount)
+    .bind(reason)
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await?
+    .last_insert_rowid();
+
+    Ok(id)
+}
+
+pub async fn get_movements(conn: impl SqliteExecutor<'_>, session_id: i64) -> Result<Vec<CashMovement>, AppError> {
+    let movements = sqlx::query_as::<_, CashMovement>(
+        "SELECT * FROM cash_movements WHERE session_id = ? ORDER BY created_at, id"
+    )
+    .bind(session_id)
+    .fetch_all(conn)
+    .await?;
+
+    Ok(movements)
+}
+
+/// Valor esperado por forma de pagamento: vendas + recebimentos de fiado - estornos,
+/// mais fundo de troco, suprimentos e sangrias no caso do dinheiro.
+/// O dinheiro das entregas concluídas por um turno fica com o entregador e é
+/// conferido no acerto do turno, não na gaveta.
+async fn expected_by_method(
+    conn: &mut SqliteConnection,
+    session: &CashSession,
+) -> Result<Vec<(String, f64)>, AppError> {
+    let sales: Vec<(String, f64)> = sqlx::query_as(
+        "SELECT op.method, SUM(op.amount)
+         FROM order_payments op
+         JOIN orders o ON op.order_id = o.id
+         WHERE o.session_id = ? AND o.status != 'cancelled'
+           AND NOT (op.method = 'cash' AND EXISTS (
+               SELECT 1 FROM deliveries d
+               WHERE d.order_id = o.id AND d.driver_shift_id IS NOT NULL AND d.status = 'delivered'
+           ))
+         GROUP BY op.method"
+    )
+    .bind(session.id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let receipts: Vec<(String, f64)> = sqlx::query_as(
+        "SELECT method, SUM(-amount)
+         FROM customer_ledger
+         WHERE session_id = ? AND entry_type = 'PAYMENT'
+         GROUP BY method"
+    )
+    .bind(session.id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let movements: Vec<(String, f64)> = sqlx::query_as(
+        "SELECT method, SUM(CASE WHEN movement_type = 'SUPPLY' THEN amount ELSE -amount END)
+         FROM cash_movements
+         WHERE session_id = ?
+         GROUP BY method"
+    )
+    .bind(session.id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let sum_for = |rows: &[(String, f64)], method: &str| -> f64 {
+        rows.iter().filter(|(m, _)| m == method).map(|(_, v)| v).sum()
+    };
+
+    Ok(COUNTED_METHODS.iter().map(|method| {
+        let mut expected = sum_for(&sales, method) + sum_for(&receipts, method) + sum_for(&movements, method);
+        if *method == "cash" {
+            expected += session.opening_float;
+        }
+        (method.to_string(), expected)
+    }).collect())
+}
+
+/// Suprimentos e sangrias da gaveta (movimentações em dinheiro)
+async fn movement_totals(conn: &mut SqliteConnection, session_id: i64) -> Result<(f64, f64), AppError> {
+    let totals: (f64, f64) = sqlx::query_as(
+        "SELECT
+            COALESCE(SUM(CASE WHEN movement_type = 'SUPPLY' THEN amount ELSE 0 END), 0.0),
+            COALESCE(SUM(CASE WHEN movement_type = 'WITHDRAWAL' THEN amount ELSE 0 END), 0.0)
+         FROM cash_movements
+         WHERE session_id = ? AND method = 'cash'"
+    )
+    .bind(session_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(totals)
+}
+
+pub async fn get_session_report(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    session_id: i64,
+) -> Result<CashSessionReport, AppError> {
+    let mut conn = conn.acquire().await?;
+
+    let session = get_session_by_id(&mut *conn, session_id).await?;
+
+    // Caixa fechado usa a conferência gravada; caixa aberto mostra a prévia
+    let lines = if session.status == "closed" {
+        sqlx::query_as::<_, CashSessionReportLine>(
+            "SELECT method, expected, counted, counted - expected as difference
+             FROM cash_session_counts
+             WHERE session_id = ?
+             ORDER BY id"
+        )
+        .bind(session_id)
+        .fetch_all(&mut *conn)
+        .await?
+    } else {
+        expected_by_method(&mut conn, &session).await?
+            .into_iter()
+            .map(|(method, expected)| CashSessionReportLine {
+                method,
+                expected,
+                counted: None,
+                difference: None,
+            })
+            .collect()
+    };
+
+    let sales: (i64, f64) = sqlx::query_as(
+        "SELECT COUNT(*), COALESCE(SUM(total), 0.0) FROM orders
+         WHERE session_id = ? AND status != 'cancelled'"
+    )
+    .bind(session_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let on_account: (f64,) = sqlx::query_as(
+        "SELECT COALESCE(SUM(op.amount), 0.0)
+         FROM order_payments op
+         JOIN orders o ON op.order_id = o.id
+         WHERE o.session_id = ? AND o.status != 'cancelled' AND op.method = 'on_account'"
+    )
+    .bind(session_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let (supplies, withdrawals) = movement_totals(&mut conn, session_id).await?;
+    let movements = get_movements(&mut *conn, session_id).await?;
+
+    Ok(CashSessionReport {
+        session,
+        lines,
+        order_count: sales.0,
+        sales_total: sales.1,
+        on_account_total: on_account.0,
+        supplies,
+        withdrawals,
+        movements,
+    })
+}
+
+pub async fn close_session(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    payload: CloseCashSessionPayload,
+    user_id: i64,
+) -> Result<CashSessionReport, AppError> {
+    for count in &payload.counts {
+        if !COUNTED_METHODS.contains(&count.method.as_str()) {
+            return Err(AppError::Validation(format!("Invalid payment method: {}", count.method)));
+        }
+        if count.counted < 0.0 {
+            return Err(AppError::Validation("Counted amount cannot be negative".to_string()));
+        }
+    }
+
+    let mut tx = conn.begin().await?;
+
+    let session = get_open_session(&mut *tx).await?
+        .ok_or_else(|| AppError::BusinessLogic("No open cash session".to_string()))?;
+
+    let expected = expected_by_method(&mut tx, &session).await?;
+
+    // Grava a conferência; forma não informada conta como zero
+    for (method, expected_amount) in &expected {
+        let counted: f64 = payload.counts.iter()
+            .filter(|c| &c.method == method)
+            .map(|c| c.counted)
+            .sum();
+
+        sqlx::query(
+            "INSERT INTO cash_session_counts (session_id, method, expected, counted)
+             VALUES (?, ?, ?, ?)"
+        )
+        .bind(session.id)
+        .bind(method)
+        .bind(expected_amount)
+        .bind(counted)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query(
+        "UPDATE cash_sessions
+         SET status = 'closed', closed_by = ?, closed_at = CURRENT_TIMESTAMP,
+             notes = COALESCE(?, notes)
+         WHERE id = ?"
+    )
+    .bind(user_id)
+    .bind(&payload.notes)
+    .bind(session.id)
+    .execute(&mut *tx)
+    .await?;
+
+    let report = get_session_report(&mut *tx, session.id).await?;
+
+    tx.commit().await?;
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_helpers::{
+        setup_test_db, cleanup_test_db, create_test_user, create_test_product, product_payload, create_test_customer,
+        customer_payload, order_payload, payment,
+    };
+    use crate::db::{customer_accounts, orders};
+    use crate::models::{
+        CashCountPayload, CreateOrderPayload, CreateProductPayload, CustomerPaymentPayload,
+        OrderPaymentPayload,
+    };
+
+    async fn create_sale(pool: &SqlitePool, user_id: i64, payments: Vec<OrderPaymentPayload>) -> i64 {
+        let product_id = create_test_product(pool, CreateProductPayload {
+            stock_full: Some(100),
+            ..product_payload("Água 20L", "water")
+        }).await;
+
+        let quantity = payments.iter().map(|p| p.amount).sum::<f64>() as i64 / 10;
+
+        orders::create_order(pool, CreateOrderPayload {
+            payments,
+            ..order_payload(None, product_id, quantity, 10.0)
+        }, Some(user_id))
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_open_session_only_once() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        let user_id = create_test_user(&pool).await;
+
+        let payload = OpenCashSessionPayload { opening_float: 100.0, notes: None };
+        let session_id = open_session(&pool, payload, user_id).await.unwrap();
+
+        let open = get_open_session(&pool).await.unwrap().unwrap();
+        assert_eq!(open.id, session_id);
+        assert_eq!(open.opened_by_username, Some("admin".to_string()));
+
+        // Já existe um caixa aberto
+        let payload = OpenCashSessionPayload { opening_float: 50.0, notes: None };
+        assert!(open_session(&pool, payload, user_id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_orders_attached_to_open_session() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        let user_id = create_test_user(&pool).await;
+
+        let session_id = open_session(&pool, OpenCashSessionPayload {
+            opening_float: 0.0,
+            notes: None,
+        }, user_id).await.unwrap();
+        let order_id = create_sale(&pool, user_id, vec![payment("pix", 20.0)]).await;
+
+        let order = orders::get_order_by_id(&pool, order_id).await.unwrap();
+        assert_eq!(order.order.session_id, Some(session_id));
+        assert_eq!(order.order.operator_name, Some("admin".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_close_session_report() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        let user_id = create_test_user(&pool).await;
+        let session_id = open_session(&pool, OpenCashSessionPayload {
+            opening_float: 100.0,
+            notes: None,
+        }, user_id).await.unwrap();
+
+        create_sale(&pool, user_id, vec![payment("cash", 30.0), payment("pix", 20.0)]).await;
+        create_sale(&pool, user_id, vec![payment("debit", 40.0)]).await;
+
+        // Recebimento de fiado em dinheiro entra na gaveta
+        let customer_id = create_test_customer(&pool, customer_payload("Maria")).await;
+        customer_accounts::register_payment(&pool, customer_id, CustomerPaymentPayload {
+            amount: 15.0,
+            method: "cash".to_string(),
+            description: None,
+        }, Some(user_id)).await.unwrap();
+
+        add_movement(&pool, CashMovementPayload {
+            movement_type: "WITHDRAWAL".to_string(),
+            amount: 50.0,
+            reason: Some("Depósito".to_string()),
+        }, user_id).await.unwrap();
+        add_movement(&pool, CashMovementPayload {
+            movement_type: "SUPPLY".to_string(),
+            amount: 10.0,
+            reason: None,
+        }, user_id).await.unwrap();
+
+        // Sangria maior que o dinheiro em caixa
+        assert!(add_movement(&pool, CashMovementPayload {
+            movement_type: "WITHDRAWAL".to_string(),
+            amount: 1000.0,
+            reason: None,
+        }, user_id).await.is_err());
+
+        let report = close_session(&pool, CloseCashSessionPayload {
+            counts: vec![
+                CashCountPayload { method: "cash".to_string(), counted: 100.0 },
+                CashCountPayload { method: "pix".to_string(), counted: 20.0 },
+                CashCountPayload { method: "debit".to_string(), counted: 40.0 },
+            ],
+            notes: None,
+        }, user_id).await.unwrap();
+
+        assert_eq!(report.session.id, session_id);
+        assert_eq!(report.session.status, "closed");
+        assert_eq!(report.order_count, 2);
+        assert_eq!(report.sales_total, 90.0);
+        assert_eq!(report.withdrawals, 50.0);
+        assert_eq!(report.supplies, 10.0);
+
+        // 100 + 30 + 15 + 10 - 50 = 105
+        let cash = report.lines.iter().find(|l| l.method == "cash").unwrap();
+        assert_eq!(cash.expected, 105.0);
+        assert_eq!(cash.counted, Some(100.0));
+        assert_eq!(cash.difference, Some(-5.0));
+
+        let credit = report.lines.iter().find(|l| l.method == "credit").unwrap();
+        assert_eq!(credit.expected, 0.0);
+        assert_eq!(credit.difference, Some(0.0));
+
+        assert!(get_open_session(&pool).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_movement_requires_open_session() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        let user_id = create_test_user(&pool).await;
+
+        let payload = CashMovementPayload {
+            movement_type: "SUPPLY".to_string(),
+            amount: 10.0,
+            reason: None,
+        };
+        assert!(add_movement(&pool, payload, user_id).await.is_err());
+
+        let payload = CloseCashSessionPayload { counts: vec![], notes: None };
+        assert!(close_session(&pool, payload, user_id).await.is_err());
+    }
+}
@@ -0,0 +1,369 @@
+use crate::models::{
+    CustomerBalance, CustomerLedgerEntry, CustomerStatement,
+    CustomerPaymentPayload, CustomerAdjustmentPayload,
+};
+use crate::errors::AppError;
+use crate::db::customers::get_customer_by_id;
+use sqlx::{Acquire, Sqlite, SqliteConnection, SqlitePool};
+
+/// Saldo devedor atual do cliente (usado dentro de transações)
+pub(crate) async fn get_balance(
+    conn: &mut SqliteConnection,
+    customer_id: i64,
+) -> Result<f64, AppError> {
+    let balance: (f64,) = sqlx::query_as(
+        "SELECT COALESCE(SUM(amount), 0.0) FROM customer_ledger WHERE customer_id = ?"
+    )
+    .bind(customer_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(balance.0)
+}
+
+/// Lança uma venda fiado na conta do cliente, respeitando o limite de crédito
+pub(crate) async fn charge_order(
+    conn: &mut SqliteConnection,
+    customer_id: i64,
+    order_id: i64,
+    amount: f64,
+) -> Result<(), AppError> {
+    let credit_limit: (f64,) = sqlx::query_as(
+        "SELECT credit_limit FROM customers WHERE id = ?"
+    )
+    .bind(customer_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Customer with id {} not found", customer_id)))?;
+
+    let balance = get_balance(conn, customer_id).await?;
+
+    if balance + amount > credit_limit.0 + 0.005 {
+        return Err(AppError::BusinessLogic(
+            format!("Credit limit exceeded. Balance: {:.2}, Limit: {:.2}, Requested: {:.2}",
+                balance, credit_limit.0, amount)
+        ));
+    }
+
+    sqlx::query(
+        "INSERT INTO customer_ledger (customer_id, entry_type, amount, order_id, description)
+         VALUES (?, 'CHARGE', ?, ?, ?)"
+    )
+    .bind(customer_id)
+    .bind(amount)
+    .bind(order_id)
+    .bind(format!("Pedido #{}", order_id))
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn register_payment(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    customer_id: i64,
+    payload: CustomerPaymentPayload,
+    user_id: Option<i64>,
+) -> Result<i64, AppError> {
+    if payload.amount <= 0.0 {
+        return Err(AppError::Validation("Payment amount must be positive".to_string()));
+    }
+
+    if !["cash", "pix", "debit", "credit"].contains(&payload.method.as_str()) {
+        return Err(AppError::Validation(format!("Invalid payment method: {}", payload.method)));
+    }
+
+    let mut conn = conn.acquire().await?;
+
+    // Verifica se existe
+    get_customer_by_id(&mut *conn, customer_id).await?;
+
+    let id = sqlx::query(
+        "INSERT INTO customer_ledger (customer_id, entry_type, amount, method, user_id, description, session_id)
+         VALUES (?, 'PAYMENT', ?, ?, ?, ?, (SELECT id FROM cash_sessions WHERE status = 'open'))"
+    )
+    .bind(customer_id)
+    .bind(-payload.amount)
+    .bind(&payload.method)
+    .bind(user_id)
+    .bind(&payload.description)
+    .execute(&mut *conn)
+    .await?
+    .last_insert_rowid();
+
+    Ok(id)
+}
+
+pub async fn adjust_balance(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    customer_id: i64,
+    payload: CustomerAdjustmentPayload,
+    user_id: Option<i64>,
+) -> Result<i64, AppError> {
+    if payload.amount == 0.0 {
+        return Err(AppError::Validation("Adjustment amount cannot be zero".to_string()));
+    }
+
+    if payload.description.trim().is_empty() {
+        return Err(AppError::Validation("Adjustment description cannot be empty".to_string()));
+    }
+
+    let mut conn = conn.acquire().await?;
+
+    // Verifica se existe
+    get_customer_by_id(&mut *conn, customer_id).await?;
+
+    let id = sqlx::query(
+        "INSERT INTO customer_ledger (customer_id, entry_type, amount, user_id, description)
+         VALUES (?, 'ADJUST', ?, ?, ?)"
+    )
+    .bind(customer_id)
+    .bind(payload.amount)
+    .bind(user_id)
+    .bind(&payload.description)
+    .execute(&mut *conn)
+    .await?
+    .last_insert_rowid();
+
+    Ok(id)
+}
+
+pub async fn get_customer_statement(
+    pool: &SqlitePool,
+    customer_id: i64,
+) -> Result<CustomerStatement, AppError> {
+    let customer = get_customer_by_id(pool, customer_id).await?;
+
+    // Saldo acumulado calculado lançamento a lançamento
+    let entries = sqlx::query_as::<_, CustomerLedgerEntry>(
+        "SELECT id, customer_id, entry_type, amount, method, order_id, user_id, session_id, description, created_at,
+                SUM(amount) OVER (ORDER BY created_at, id) as running_balance
+         FROM customer_ledger
+         WHERE customer_id = ?
+         ORDER BY created_at, id"
+    )
+    .bind(customer_id)
+    .fetch_all(pool)
+    .await?;
+
+    let balance = entries.last().map(|e| e.running_balance).unwrap_or(0.0);
+
+    Ok(CustomerStatement {
+        available_credit: (customer.credit_limit - balance).max(0.0),
+        customer,
+        balance,
+        entries,
+    })
+}
+
+pub async fn get_customer_balances(pool: &SqlitePool) -> Result<Vec<CustomerBalance>, AppError> {
+    let balances = sqlx::query_as::<_, CustomerBalance>(
+        "SELECT c.id as customer_id, c.name as customer_name, c.phone, c.credit_limit,
+                SUM(l.amount) as balance
+         FROM customers c
+         JOIN customer_ledger l ON l.customer_id = c.id
+         GROUP BY c.id, c.name, c.phone, c.credit_limit
+         HAVING ABS(SUM(l.amount)) >= 0.005
+         ORDER BY balance DESC"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(balances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_helpers::{
+        setup_test_db, cleanup_test_db, open_test_session, create_test_product, product_payload,
+        create_test_customer, customer_payload, order_payload, payment,
+    };
+    use crate::db::{orders, products};
+    use crate::models::{CreateCustomerPayload, CreateOrderPayload, CreateProductPayload};
+
+    async fn customer_with_limit(pool: &SqlitePool, credit_limit: f64) -> i64 {
+        create_test_customer(pool, CreateCustomerPayload {
+            credit_limit: Some(credit_limit),
+            ..customer_payload("João Silva")
+        }).await
+    }
+
+    async fn create_on_account_order(
+        pool: &SqlitePool,
+        customer_id: i64,
+        amount: f64,
+    ) -> Result<i64, AppError> {
+        let product_id = create_test_product(pool, CreateProductPayload {
+            price_refill: amount,
+            price_full: amount,
+            stock_full: Some(10),
+            ..product_payload("Gás P13", "gas")
+        }).await;
+
+        orders::create_order(pool, CreateOrderPayload {
+            payments: vec![payment("on_account", amount)],
+            ..order_payload(Some(customer_id), product_id, 1, amount)
+        }, None)
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_on_account_order_charges_customer() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let customer_id = customer_with_limit(&pool, 200.0).await;
+        let order_id = create_on_account_order(&pool, customer_id, 120.0).await.unwrap();
+
+        let statement = get_customer_statement(&pool, customer_id).await.unwrap();
+        assert_eq!(statement.balance, 120.0);
+        assert_eq!(statement.available_credit, 80.0);
+        assert_eq!(statement.entries.len(), 1);
+        assert_eq!(statement.entries[0].entry_type, "CHARGE");
+        assert_eq!(statement.entries[0].order_id, Some(order_id));
+    }
+
+    #[tokio::test]
+    async fn test_credit_limit_enforced() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let customer_id = customer_with_limit(&pool, 100.0).await;
+        create_on_account_order(&pool, customer_id, 80.0).await.unwrap();
+
+        // Ultrapassa o limite
+        assert!(create_on_account_order(&pool, customer_id, 30.0).await.is_err());
+
+        // Cliente sem limite não pode comprar fiado
+        let no_credit_id = customer_with_limit(&pool, 0.0).await;
+        assert!(create_on_account_order(&pool, no_credit_id, 10.0).await.is_err());
+
+        let statement = get_customer_statement(&pool, customer_id).await.unwrap();
+        assert_eq!(statement.balance, 80.0);
+    }
+
+    #[tokio::test]
+    async fn test_register_payment_and_adjustment() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let customer_id = customer_with_limit(&pool, 500.0).await;
+        create_on_account_order(&pool, customer_id, 100.0).await.unwrap();
+
+        register_payment(&pool, customer_id, CustomerPaymentPayload {
+            amount: 60.0,
+            method: "pix".to_string(),
+            description: None,
+        }, None).await.unwrap();
+
+        adjust_balance(&pool, customer_id, CustomerAdjustmentPayload {
+            amount: -5.0,
+            description: "Desconto".to_string(),
+        }, None).await.unwrap();
+
+        let statement = get_customer_statement(&pool, customer_id).await.unwrap();
+        assert_eq!(statement.entries.len(), 3);
+        assert_eq!(statement.entries[0].running_balance, 100.0);
+        assert_eq!(statement.entries[1].running_balance, 40.0);
+        assert_eq!(statement.balance, 35.0);
+
+        let balances = get_customer_balances(&pool).await.unwrap();
+        assert_eq!(balances.len(), 1);
+        assert_eq!(balances[0].balance, 35.0);
+    }
+
+    #[tokio::test]
+    async fn test_register_payment_validation() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        let customer_id = customer_with_limit(&pool, 100.0).await;
+
+        // Valor não positivo
+        let payload = CustomerPaymentPayload {
+            amount: 0.0,
+            method: "cash".to_string(),
+            description: None,
+        };
+        assert!(register_payment(&pool, customer_id, payload, None).await.is_err());
+
+        // Fiado não quita fiado
+        let payload = CustomerPaymentPayload {
+            amount: 10.0,
+            method: "on_account".to_string(),
+            description: None,
+        };
+        assert!(register_payment(&pool, customer_id, payload, None).await.is_err());
+
+        // Cliente inexistente
+        let payload = CustomerPaymentPayload {
+            amount: 10.0,
+            method: "cash".to_string(),
+            description: None,
+        };
+        assert!(register_payment(&pool, 99999, payload, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rejected_on_account_order_leaves_nothing_behind() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let customer_id = customer_with_limit(&pool, 50.0).await;
+        let product_id = create_test_product(&pool, product_payload("Gás P13", "gas")).await;
+
+        // Parte à vista, parte fiado acima do limite: a venda inteira é recusada
+        let payload = CreateOrderPayload {
+            payments: vec![payment("cash", 100.0), payment("on_account", 100.0)],
+            ..order_payload(Some(customer_id), product_id, 2, 100.0)
+        };
+        let err = orders::create_order(&pool, payload, None).await.unwrap_err();
+        assert!(matches!(err, AppError::BusinessLogic(_)));
+
+        let statement = get_customer_statement(&pool, customer_id).await.unwrap();
+        assert!(statement.entries.is_empty());
+        assert!(orders::get_all_orders(&pool).await.unwrap().is_empty());
+        let product = products::get_product_by_id(&pool, product_id).await.unwrap();
+        assert_eq!((product.stock_full, product.stock_empty), (20, 0));
+    }
+
+    #[tokio::test]
+    async fn test_adjust_balance_validation() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        let customer_id = customer_with_limit(&pool, 100.0).await;
+        let adjustment = |amount: f64, description: &str| CustomerAdjustmentPayload {
+            amount,
+            description: description.to_string(),
+        };
+
+        // Valor zerado e motivo em branco
+        assert!(matches!(
+            adjust_balance(&pool, customer_id, adjustment(0.0, "Acerto"), None).await,
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            adjust_balance(&pool, customer_id, adjustment(10.0, "  "), None).await,
+            Err(AppError::Validation(_))
+        ));
+
+        // Cliente inexistente
+        assert!(matches!(
+            adjust_balance(&pool, 99999, adjustment(10.0, "Acerto"), None).await,
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(get_customer_statement(&pool, 99999).await, Err(AppError::NotFound(_))));
+
+        // Nada foi lançado
+        let statement = get_customer_statement(&pool, customer_id).await.unwrap();
+        assert!(statement.entries.is_empty());
+        assert_eq!(statement.available_credit, 100.0);
+        assert!(get_customer_balances(&pool).await.unwrap().is_empty());
+    }
+}
@@ -1,6 +1,6 @@
 use crate::models::{Customer, CreateCustomerPayload, UpdateCustomerPayload};
 use crate::errors::AppError;
-use sqlx::SqlitePool;
+use sqlx::{Acquire, Sqlite, SqliteConnection, SqliteExecutor, SqlitePool};
 
 pub async fn get_all_customers(pool: &SqlitePool) -> Result<Vec<Customer>, AppError> {
     let customers = sqlx::query_as::<_, Customer>(
@@ -12,12 +12,12 @@ pub async fn get_all_customers(pool: &SqlitePool) -> Result<Vec<Customer>, AppEr
     Ok(customers)
 }
 
-pub async fn get_customer_by_id(pool: &SqlitePool, id: i64) -> Result<Customer, AppError> {
+pub async fn get_customer_by_id(conn: impl SqliteExecutor<'_>, id: i64) -> Result<Customer, AppError> {
     let customer = sqlx::query_as::<_, Customer>(
         "SELECT * FROM customers WHERE id = ?"
     )
     .bind(id)
-    .fetch_optional(pool)
+    .fetch_optional(conn)
     .await?
     .ok_or_else(|| AppError::NotFound(format!("Customer with id {} not found", id)))?;
     
@@ -35,23 +35,43 @@ pub async fn search_customers_by_phone(pool: &SqlitePool, phone: &str) -> Result
     Ok(customers)
 }
 
-pub async fn create_customer(
-    pool: &SqlitePool,
-    payload: CreateCustomerPayload,
-) -> Result<i64, AppError> {
+/// Regras do cadastro de cliente; valem também para a importação
+pub(crate) fn validate_new_customer(payload: &CreateCustomerPayload) -> Result<(), AppError> {
     if payload.name.is_empty() {
         return Err(AppError::Validation("Customer name cannot be empty".to_string()));
     }
 
+    if payload.credit_limit.is_some_and(|limit| limit < 0.0) {
+        return Err(AppError::Validation("Credit limit cannot be negative".to_string()));
+    }
+
+    Ok(())
+}
+
+pub async fn create_customer(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    payload: CreateCustomerPayload,
+) -> Result<i64, AppError> {
+    validate_new_customer(&payload)?;
+
+    let mut conn = conn.acquire().await?;
+    insert_customer(&mut conn, &payload).await
+}
+
+pub(crate) async fn insert_customer(
+    conn: &mut SqliteConnection,
+    payload: &CreateCustomerPayload,
+) -> Result<i64, AppError> {
     let id = sqlx::query(
-        "INSERT INTO customers (name, phone, address, notes)
-         VALUES (?, ?, ?, ?)"
+        "INSERT INTO customers (name, phone, address, notes, credit_limit)
+         VALUES (?, ?, ?, ?, ?)"
     )
     .bind(&payload.name)
     .bind(&payload.phone)
     .bind(&payload.address)
     .bind(&payload.notes)
-    .execute(pool)
+    .bind(payload.credit_limit.unwrap_or(0.0))
+    .execute(&mut *conn)
     .await?
     .last_insert_rowid();
 
@@ -59,12 +79,14 @@ pub async fn create_customer(
 }
 
 pub async fn update_customer(
-    pool: &SqlitePool,
+    conn: impl Acquire<'_, Database = Sqlite>,
     id: i64,
     payload: UpdateCustomerPayload,
 ) -> Result<(), AppError> {
+    let mut conn = conn.acquire().await?;
+
     // Verifica se existe
-    get_customer_by_id(pool, id).await?;
+    get_customer_by_id(&mut *conn, id).await?;
 
     // Constrói query dinamicamente
     let mut query = sqlx::QueryBuilder::new("UPDATE customers SET ");
@@ -107,6 +129,18 @@ pub async fn update_customer(
         has_updates = true;
     }
 
+    if let Some(credit_limit) = payload.credit_limit {
+        if credit_limit < 0.0 {
+            return Err(AppError::Validation("Credit limit cannot be negative".to_string()));
+        }
+        if has_updates {
+            query.push(", ");
+        }
+        query.push("credit_limit = ");
+        query.push_bind(credit_limit);
+        has_updates = true;
+    }
+
     if !has_updates {
         return Ok(());
     }
@@ -114,18 +148,20 @@ pub async fn update_customer(
     query.push(" WHERE id = ");
     query.push_bind(id);
 
-    query.build().execute(pool).await?;
+    query.build().execute(&mut *conn).await?;
 
     Ok(())
 }
 
-pub async fn delete_customer(pool: &SqlitePool, id: i64) -> Result<(), AppError> {
+pub async fn delete_customer(conn: impl Acquire<'_, Database = Sqlite>, id: i64) -> Result<(), AppError> {
+    let mut conn = conn.acquire().await?;
+
     // Verifica se existe
-    get_customer_by_id(pool, id).await?;
+    get_customer_by_id(&mut *conn, id).await?;
 
     sqlx::query("DELETE FROM customers WHERE id = ?")
         .bind(id)
-        .execute(pool)
+        .execute(&mut *conn)
         .await?;
 
     Ok(())
@@ -146,6 +182,7 @@ mod tests {
             phone: Some("11999999999".to_string()),
             address: Some("Rua Teste, 123".to_string()),
             notes: Some("Cliente preferencial".to_string()),
+            credit_limit: None,
         };
 
         let id = create_customer(&pool, payload).await.unwrap();
@@ -167,6 +204,7 @@ mod tests {
             phone: None,
             address: None,
             notes: None,
+            credit_limit: None,
         };
         assert!(create_customer(&pool, payload).await.is_err());
     }
@@ -181,6 +219,7 @@ mod tests {
             phone: None,
             address: None,
             notes: None,
+            credit_limit: None,
         };
         create_customer(&pool, payload1).await.unwrap();
 
@@ -189,6 +228,7 @@ mod tests {
             phone: None,
             address: None,
             notes: None,
+            credit_limit: None,
         };
         create_customer(&pool, payload2).await.unwrap();
 
@@ -206,6 +246,7 @@ mod tests {
             phone: Some("11999999999".to_string()),
             address: None,
             notes: None,
+            credit_limit: None,
         };
         create_customer(&pool, payload1).await.unwrap();
 
@@ -214,6 +255,7 @@ mod tests {
             phone: Some("11888888888".to_string()),
             address: None,
             notes: None,
+            credit_limit: None,
         };
         create_customer(&pool, payload2).await.unwrap();
 
@@ -232,6 +274,7 @@ mod tests {
             phone: Some("11999999999".to_string()),
             address: None,
             notes: None,
+            credit_limit: None,
         };
 
         let id = create_customer(&pool, payload).await.unwrap();
@@ -241,6 +284,7 @@ mod tests {
             phone: Some("11777777777".to_string()),
             address: Some("Nova Rua".to_string()),
             notes: None,
+            credit_limit: None,
         };
 
         update_customer(&pool, id, update_payload).await.unwrap();
@@ -261,6 +305,7 @@ mod tests {
             phone: None,
             address: None,
             notes: None,
+            credit_limit: None,
         };
 
         let id = create_customer(&pool, payload).await.unwrap();
@@ -271,6 +316,7 @@ mod tests {
             phone: None,
             address: None,
             notes: None,
+            credit_limit: None,
         };
         assert!(update_customer(&pool, id, update_payload).await.is_err());
     }
@@ -285,6 +331,7 @@ mod tests {
             phone: None,
             address: None,
             notes: None,
+            credit_limit: None,
         };
 
         let id = create_customer(&pool, payload).await.unwrap();
@@ -0,0 +1,392 @@
+use crate::models::{Delivery, DeliveryPayload, DispatchDeliveryPayload, FailDeliveryPayload};
+use crate::errors::AppError;
+use crate::db::{driver_shifts, drivers};
+use sqlx::{Acquire, Sqlite, SqliteConnection, SqliteExecutor, SqlitePool};
+
+pub const DELIVERY_STATUSES: [&str; 4] = ["pending", "out_for_delivery", "delivered", "failed"];
+
+const DELIVERY_SELECT: &str =
+    "SELECT d.id, d.order_id, o.customer_id, c.name as customer_name, c.phone,
+            d.address, d.driver_id, d.driver_name, d.driver_shift_id, d.delivery_fee,
+            o.total as order_total, d.status, d.notes, d.failure_reason,
+            d.created_at, d.dispatched_at, d.delivered_at, d.failed_at
+     FROM deliveries d
+     JOIN orders o ON d.order_id = o.id
+     LEFT JOIN customers c ON o.customer_id = c.id";
+
+/// Valida a taxa de entrega antes de compor o total do pedido
+pub(crate) fn validate_delivery(payload: &DeliveryPayload) -> Result<f64, AppError> {
+    let fee = payload.delivery_fee.unwrap_or(0.0);
+    if fee < 0.0 {
+        return Err(AppError::Validation("Delivery fee cannot be negative".to_string()));
+    }
+    Ok(fee)
+}
+
+/// Cria a entrega do pedido com o endereço congelado no momento da venda
+pub(crate) async fn create_for_order(
+    conn: &mut SqliteConnection,
+    order_id: i64,
+    customer_id: Option<i64>,
+    payload: &DeliveryPayload,
+) -> Result<(), AppError> {
+    let customer_address = match customer_id {
+        Some(customer_id) => {
+            let row: (Option<String>,) = sqlx::query_as("SELECT address FROM customers WHERE id = ?")
+                .bind(customer_id)
+                .fetch_optional(&mut *conn)
+                .await?
+                .ok_or_else(|| AppError::NotFound(format!("Customer with id {} not found", customer_id)))?;
+            row.0
+        }
+        None => None,
+    };
+
+    let address = payload.address.clone()
+        .or(customer_address)
+        .filter(|a| !a.trim().is_empty())
+        .ok_or_else(|| AppError::Validation("Delivery address is required".to_string()))?;
+
+    sqlx::query(
+        "INSERT INTO deliveries (order_id, address, delivery_fee, notes)
+         VALUES (?, ?, ?, ?)"
+    )
+    .bind(order_id)
+    .bind(address.trim())
+    .bind(payload.delivery_fee.unwrap_or(0.0))
+    .bind(&payload.notes)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_delivery_by_id(conn: impl SqliteExecutor<'_>, id: i64) -> Result<Delivery, AppError> {
+    let delivery = sqlx::query_as::<_, Delivery>(&format!("{} WHERE d.id = ?", DELIVERY_SELECT))
+        .bind(id)
+        .fetch_optional(conn)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Delivery with id {} not found", id)))?;
+
+    Ok(delivery)
+}
+
+pub async fn get_delivery_by_order(conn: impl SqliteExecutor<'_>, order_id: i64) -> Result<Option<Delivery>, AppError> {
+    let delivery = sqlx::query_as::<_, Delivery>(&format!("{} WHERE d.order_id = ?", DELIVERY_SELECT))
+        .bind(order_id)
+        .fetch_optional(conn)
+        .await?;
+
+    Ok(delivery)
+}
+
+pub async fn get_deliveries(
+    pool: &SqlitePool,
+    status: Option<String>,
+) -> Result<Vec<Delivery>, AppError> {
+    if let Some(status) = &status {
+        if !DELIVERY_STATUSES.contains(&status.as_str()) {
+            return Err(AppError::Validation(format!("Invalid delivery status: {}", status)));
+        }
+    }
+
+    let deliveries = sqlx::query_as::<_, Delivery>(&format!(
+        "{} WHERE ? IS NULL OR d.status = ? ORDER BY d.created_at DESC, d.id DESC",
+        DELIVERY_SELECT
+    ))
+    .bind(&status)
+    .bind(&status)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(deliveries)
+}
+
+pub async fn get_deliveries_by_shift(conn: impl SqliteExecutor<'_>, shift_id: i64) -> Result<Vec<Delivery>, AppError> {
+    let deliveries = sqlx::query_as::<_, Delivery>(&format!(
+        "{} WHERE d.driver_shift_id = ? ORDER BY d.dispatched_at, d.id",
+        DELIVERY_SELECT
+    ))
+    .bind(shift_id)
+    .fetch_all(conn)
+    .await?;
+
+    Ok(deliveries)
+}
+
+/// Fila de entregas: aguardando saída e em rota, da mais antiga para a mais nova
+pub async fn get_delivery_queue(pool: &SqlitePool) -> Result<Vec<Delivery>, AppError> {
+    let deliveries = sqlx::query_as::<_, Delivery>(&format!(
+        "{} WHERE d.status IN ('pending', 'out_for_delivery') AND o.status != 'cancelled'
+         ORDER BY d.created_at, d.id",
+        DELIVERY_SELECT
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    Ok(deliveries)
+}
+
+/// Despacha a entrega com o entregador. Se ele estiver com turno aberto,
+/// os cheios do pedido saem do veículo em vez da loja.
+pub async fn dispatch_delivery(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    id: i64,
+    payload: DispatchDeliveryPayload,
+) -> Result<(), AppError> {
+    let mut tx = conn.begin().await?;
+
+    let driver = drivers::get_active_driver(&mut *tx, payload.driver_id).await?;
+    let shift = driver_shifts::get_open_shift_for_driver(&mut *tx, driver.id).await?;
+
+    let delivery = get_active_delivery(&mut tx, id).await?;
+
+    // Entrega que falhou pode sair novamente
+    if delivery.status != "pending" && delivery.status != "failed" {
+        return Err(AppError::BusinessLogic(
+            format!("Delivery {} is {} and cannot be dispatched", id, delivery.status)
+        ));
+    }
+
+    let shift_id = shift.map(|s| s.id);
+
+    sqlx::query(
+        "UPDATE deliveries
+         SET status = 'out_for_delivery', driver_id = ?, driver_name = ?, driver_shift_id = ?,
+             dispatched_at = CURRENT_TIMESTAMP, failure_reason = NULL, failed_at = NULL
+         WHERE id = ?"
+    )
+    .bind(driver.id)
+    .bind(&driver.name)
+    .bind(shift_id)
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+
+    if let Some(shift_id) = shift_id {
+        driver_shifts::take_order_from_vehicle(&mut tx, shift_id, delivery.order_id).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn confirm_delivery(conn: impl Acquire<'_, Database = Sqlite>, id: i64) -> Result<(), AppError> {
+    let mut tx = conn.begin().await?;
+
+    let delivery = get_active_delivery(&mut tx, id).await?;
+
+    if delivery.status != "out_for_delivery" {
+        return Err(AppError::BusinessLogic(
+            format!("Delivery {} is {} and cannot be confirmed", id, delivery.status)
+        ));
+    }
+
+    sqlx::query(
+        "UPDATE deliveries SET status = 'delivered', delivered_at = CURRENT_TIMESTAMP WHERE id = ?"
+    )
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+
+    // Pedido de entrega só é concluído quando chega ao cliente
+    sqlx::query("UPDATE orders SET status = 'completed' WHERE id = ?")
+        .bind(delivery.order_id)
+        .execute(&mut *tx)
+        .await?;
+
+    if let Some(shift_id) = delivery.driver_shift_id {
+        driver_shifts::collect_order_empties(&mut tx, shift_id, delivery.order_id).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn fail_delivery(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    id: i64,
+    payload: FailDeliveryPayload,
+) -> Result<(), AppError> {
+    if payload.reason.trim().is_empty() {
+        return Err(AppError::Validation("Failure reason cannot be empty".to_string()));
+    }
+
+    let mut tx = conn.begin().await?;
+
+    let delivery = get_active_delivery(&mut tx, id).await?;
+
+    if delivery.status != "out_for_delivery" {
+        return Err(AppError::BusinessLogic(
+            format!("Delivery {} is {} and cannot be marked as failed", id, delivery.status)
+        ));
+    }
+
+    sqlx::query(
+        "UPDATE deliveries
+         SET status = 'failed', failure_reason = ?, failed_at = CURRENT_TIMESTAMP
+         WHERE id = ?"
+    )
+    .bind(payload.reason.trim())
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+
+    // Os cheios voltam para o veículo até a próxima saída ou o acerto
+    if let Some(shift_id) = delivery.driver_shift_id {
+        driver_shifts::release_order(&mut tx, shift_id, delivery.order_id).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Busca a entrega garantindo que o pedido não foi cancelado
+async fn get_active_delivery(conn: &mut SqliteConnection, id: i64) -> Result<Delivery, AppError> {
+    let delivery = get_delivery_by_id(&mut *conn, id).await?;
+
+    let order_status: (String,) = sqlx::query_as("SELECT status FROM orders WHERE id = ?")
+        .bind(delivery.order_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    if order_status.0 == "cancelled" {
+        return Err(AppError::BusinessLogic(
+            format!("Order {} is cancelled", delivery.order_id)
+        ));
+    }
+
+    Ok(delivery)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_helpers::{
+        setup_test_db, cleanup_test_db, open_test_session, create_test_product, product_payload,
+        create_test_customer, customer_payload, order_payload, payment,
+    };
+    use crate::db::{customers, orders};
+    use crate::models::{
+        CreateCustomerPayload, CreateDriverPayload, CreateOrderPayload, CreateProductPayload,
+    };
+
+    async fn create_delivery_order(
+        pool: &SqlitePool,
+        customer_address: Option<&str>,
+        delivery: DeliveryPayload,
+    ) -> Result<i64, AppError> {
+        let product_id = create_test_product(pool, CreateProductPayload {
+            stock_full: Some(10),
+            ..product_payload("Gás P13", "gas")
+        }).await;
+
+        let customer_id = create_test_customer(pool, CreateCustomerPayload {
+            phone: Some("11999999999".to_string()),
+            address: customer_address.map(|a| a.to_string()),
+            ..customer_payload("João Silva")
+        }).await;
+
+        let fee = delivery.delivery_fee.unwrap_or(0.0);
+        orders::create_order(pool, CreateOrderPayload {
+            payments: vec![payment("cash", 100.0 + fee)],
+            delivery: Some(delivery),
+            ..order_payload(Some(customer_id), product_id, 1, 100.0)
+        }, None).await
+    }
+
+    #[tokio::test]
+    async fn test_delivery_order_snapshots_address() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let order_id = create_delivery_order(&pool, Some("Rua A, 10"), DeliveryPayload {
+            address: None,
+            delivery_fee: Some(5.0),
+            notes: None,
+        }).await.unwrap();
+
+        let order = orders::get_order_by_id(&pool, order_id).await.unwrap();
+        assert_eq!(order.order.total, 105.0);
+        assert_eq!(order.order.status, "open");
+
+        let delivery = order.delivery.unwrap();
+        assert_eq!(delivery.address, "Rua A, 10");
+        assert_eq!(delivery.delivery_fee, 5.0);
+        assert_eq!(delivery.status, "pending");
+
+        // Mudança de endereço do cliente não altera a entrega
+        customers::update_customer(&pool, delivery.customer_id.unwrap(), crate::models::UpdateCustomerPayload {
+            name: None,
+            phone: None,
+            address: Some("Rua B, 20".to_string()),
+            notes: None,
+            credit_limit: None,
+        }).await.unwrap();
+        let delivery = get_delivery_by_id(&pool, delivery.id).await.unwrap();
+        assert_eq!(delivery.address, "Rua A, 10");
+
+        // Sem endereço no cliente nem no pedido
+        assert!(create_delivery_order(&pool, None, DeliveryPayload {
+            address: None,
+            delivery_fee: None,
+            notes: None,
+        }).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_delivery_status_flow() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let order_id = create_delivery_order(&pool, None, DeliveryPayload {
+            address: Some("Rua C, 30".to_string()),
+            delivery_fee: None,
+            notes: Some("Portão azul".to_string()),
+        }).await.unwrap();
+        let id = get_delivery_by_order(&pool, order_id).await.unwrap().unwrap().id;
+        let driver_id = drivers::create_driver(&pool, CreateDriverPayload {
+            name: "Carlos".to_string(),
+            phone: None,
+        }).await.unwrap();
+
+        // Só confirma o que saiu para entrega
+        assert!(confirm_delivery(&pool, id).await.is_err());
+
+        dispatch_delivery(&pool, id, DispatchDeliveryPayload { driver_id })
+            .await
+            .unwrap();
+        fail_delivery(&pool, id, FailDeliveryPayload { reason: "Cliente ausente".to_string() })
+            .await
+            .unwrap();
+
+        let delivery = get_delivery_by_id(&pool, id).await.unwrap();
+        assert_eq!(delivery.status, "failed");
+        assert_eq!(delivery.failure_reason, Some("Cliente ausente".to_string()));
+        assert!(get_delivery_queue(&pool).await.unwrap().is_empty());
+
+        // Nova tentativa
+        dispatch_delivery(&pool, id, DispatchDeliveryPayload { driver_id })
+            .await
+            .unwrap();
+        assert_eq!(get_delivery_queue(&pool).await.unwrap().len(), 1);
+
+        confirm_delivery(&pool, id).await.unwrap();
+
+        let delivery = get_delivery_by_id(&pool, id).await.unwrap();
+        assert_eq!(delivery.status, "delivered");
+        assert_eq!(delivery.driver_name, Some("Carlos".to_string()));
+        assert!(delivery.delivered_at.is_some());
+
+        let order = orders::get_order_by_id(&pool, order_id).await.unwrap();
+        assert_eq!(order.order.status, "completed");
+
+        assert_eq!(get_deliveries(&pool, Some("delivered".to_string())).await.unwrap().len(), 1);
+        assert!(get_deliveries(&pool, Some("lost".to_string())).await.is_err());
+    }
+}
@@ -0,0 +1,771 @@
+use crate::models::{
+    DriverShift, DriverShiftLine, DriverShiftReport, DriverLoadPayload,
+    OpenDriverShiftPayload, CloseDriverShiftPayload,
+};
+use crate::errors::AppError;
+use crate::db::{deliveries, drivers};
+use crate::db::stock::{apply_stock_change, move_stock, StockChange};
+use crate::db::stock_locations::{location_stock, DEFAULT_LOCATION_ID};
+use sqlx::{Acquire, Sqlite, SqliteConnection, SqliteExecutor, SqlitePool};
+
+// O veículo do entregador é um local de estoque (stock_locations.driver_id): cada
+// carga, entrega e devolução é uma transferência entre o veículo e a loja (ou o local
+// da venda) vinculada ao turno. A prévia do acerto soma as movimentações do lado do veículo.
+
+const SHIFT_SELECT: &str =
+    "SELECT s.id, s.driver_id, d.name as driver_name, s.status, s.change_fund,
+            s.cash_expected, s.cash_counted, s.opened_by, uo.username as opened_by_username,
+            s.opened_at, s.closed_by, uc.username as closed_by_username, s.closed_at, s.notes
+     FROM driver_shifts s
+     JOIN drivers d ON s.driver_id = d.id
+     LEFT JOIN users uo ON s.opened_by = uo.id
+     LEFT JOIN users uc ON s.closed_by = uc.id";
+
+pub async fn get_shift_by_id(conn: impl SqliteExecutor<'_>, id: i64) -> Result<DriverShift, AppError> {
+    let shift = sqlx::query_as::<_, DriverShift>(&format!("{} WHERE s.id = ?", SHIFT_SELECT))
+        .bind(id)
+        .fetch_optional(conn)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Driver shift with id {} not found", id)))?;
+
+    Ok(shift)
+}
+
+pub async fn get_open_shift_for_driver(
+    conn: impl SqliteExecutor<'_>,
+    driver_id: i64,
+) -> Result<Option<DriverShift>, AppError> {
+    let shift = sqlx::query_as::<_, DriverShift>(
+        &format!("{} WHERE s.driver_id = ? AND s.status = 'open'", SHIFT_SELECT)
+    )
+    .bind(driver_id)
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(shift)
+}
+
+pub async fn get_shifts(
+    pool: &SqlitePool,
+    status: Option<String>,
+) -> Result<Vec<DriverShift>, AppError> {
+    if let Some(status) = &status {
+        if !["open", "closed"].contains(&status.as_str()) {
+            return Err(AppError::Validation(format!("Invalid shift status: {}", status)));
+        }
+    }
+
+    let shifts = sqlx::query_as::<_, DriverShift>(&format!(
+        "{} WHERE ? IS NULL OR s.status = ? ORDER BY s.opened_at DESC, s.id DESC",
+        SHIFT_SELECT
+    ))
+    .bind(&status)
+    .bind(&status)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(shifts)
+}
+
+/// Abre o turno do entregador carregando o veículo com cheios da loja
+pub async fn open_shift(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    payload: OpenDriverShiftPayload,
+    user_id: i64,
+) -> Result<i64, AppError> {
+    if payload.change_fund < 0.0 {
+        return Err(AppError::Validation("Change fund cannot be negative".to_string()));
+    }
+
+    let mut tx = conn.begin().await?;
+
+    let driver = drivers::get_active_driver(&mut *tx, payload.driver_id).await?;
+
+    if let Some(shift) = get_open_shift_for_driver(&mut *tx, driver.id).await? {
+        return Err(AppError::BusinessLogic(
+            format!("Driver {} already has shift #{} open", driver.name, shift.id)
+        ));
+    }
+
+    let id = sqlx::query(
+        "INSERT INTO driver_shifts (driver_id, change_fund, opened_by, notes) VALUES (?, ?, ?, ?)"
+    )
+    .bind(driver.id)
+    .bind(payload.change_fund)
+    .bind(user_id)
+    .bind(&payload.notes)
+    .execute(&mut *tx)
+    .await?
+    .last_insert_rowid();
+
+    let vehicle_id = shift_vehicle(&mut tx, id).await?;
+    load_vehicle(&mut tx, id, vehicle_id, &payload.loads).await?;
+
+    tx.commit().await?;
+
+    Ok(id)
+}
+
+/// Carga adicional durante o turno
+pub async fn load_shift(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    id: i64,
+    loads: Vec<DriverLoadPayload>,
+) -> Result<(), AppError> {
+    if loads.is_empty() {
+        return Err(AppError::Validation("Load must have at least one item".to_string()));
+    }
+
+    let mut tx = conn.begin().await?;
+
+    let shift = get_shift_by_id(&mut *tx, id).await?;
+
+    if shift.status != "open" {
+        return Err(AppError::BusinessLogic(format!("Driver shift #{} is closed", id)));
+    }
+
+    let vehicle_id = shift_vehicle(&mut tx, id).await?;
+    load_vehicle(&mut tx, id, vehicle_id, &loads).await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+async fn load_vehicle(
+    conn: &mut SqliteConnection,
+    shift_id: i64,
+    vehicle_id: i64,
+    loads: &[DriverLoadPayload],
+) -> Result<(), AppError> {
+    for load in loads {
+        if load.quantity <= 0 {
+            return Err(AppError::Validation("Quantity must be positive".to_string()));
+        }
+
+        // Verifica estoque disponível na loja
+        let (available, _) = location_stock(conn, load.product_id, DEFAULT_LOCATION_ID).await?;
+
+        if available < load.quantity {
+            return Err(AppError::BusinessLogic(
+                format!("Insufficient stock for product {}. Available: {}, Requested: {}",
+                    load.product_id, available, load.quantity)
+            ));
+        }
+
+        move_stock(conn, StockChange {
+            product_id: load.product_id,
+            stock_kind: "full",
+            quantity: load.quantity,
+            driver_shift_id: Some(shift_id),
+            location_id: Some(DEFAULT_LOCATION_ID),
+            ..Default::default()
+        }, vehicle_id).await?;
+    }
+
+    Ok(())
+}
+
+/// Local de estoque do veículo do entregador do turno
+async fn shift_vehicle(conn: &mut SqliteConnection, shift_id: i64) -> Result<i64, AppError> {
+    let vehicle: (i64,) = sqlx::query_as(
+        "SELECT l.id FROM driver_shifts s
+         JOIN stock_locations l ON l.driver_id = s.driver_id
+         WHERE s.id = ?"
+    )
+    .bind(shift_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Vehicle for driver shift {} not found", shift_id)))?;
+
+    Ok(vehicle.0)
+}
+
+/// Local de onde a venda saiu
+async fn order_location(conn: &mut SqliteConnection, order_id: i64) -> Result<i64, AppError> {
+    let location: (Option<i64>,) = sqlx::query_as("SELECT location_id FROM orders WHERE id = ?")
+        .bind(order_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    Ok(location.0.unwrap_or(DEFAULT_LOCATION_ID))
+}
+
+/// Na saída da entrega, os cheios do pedido passam a sair do veículo.
+/// A venda já baixou o seu local, que recebe de volta o que o veículo entrega.
+pub(crate) async fn take_order_from_vehicle(
+    conn: &mut SqliteConnection,
+    shift_id: i64,
+    order_id: i64,
+) -> Result<(), AppError> {
+    let items: Vec<(i64, i64)> = sqlx::query_as(
+        "SELECT product_id, SUM(quantity) FROM order_items WHERE order_id = ? GROUP BY product_id"
+    )
+    .bind(order_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let vehicle_id = shift_vehicle(conn, shift_id).await?;
+    let location_id = order_location(conn, order_id).await?;
+
+    for (product_id, quantity) in items {
+        let (available, _) = location_stock(conn, product_id, vehicle_id).await?;
+        if available < quantity {
+            return Err(AppError::BusinessLogic(
+                format!("Insufficient stock on the vehicle for product {}. Available: {}, Requested: {}",
+                    product_id, available, quantity)
+            ));
+        }
+
+        move_stock(conn, StockChange {
+            product_id,
+            stock_kind: "full",
+            quantity,
+            order_id: Some(order_id),
+            driver_shift_id: Some(shift_id),
+            location_id: Some(vehicle_id),
+            ..Default::default()
+        }, location_id).await?;
+    }
+
+    Ok(())
+}
+
+/// Na confirmação da entrega, os cascos recolhidos ficam no veículo até o acerto
+pub(crate) async fn collect_order_empties(
+    conn: &mut SqliteConnection,
+    shift_id: i64,
+    order_id: i64,
+) -> Result<(), AppError> {
+    let items: Vec<(i64, i64)> = sqlx::query_as(
+        "SELECT product_id, SUM(quantity) FROM order_items
+         WHERE order_id = ? AND returned_bottle = 1
+         GROUP BY product_id"
+    )
+    .bind(order_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let vehicle_id = shift_vehicle(conn, shift_id).await?;
+    let location_id = order_location(conn, order_id).await?;
+
+    for (product_id, quantity) in items {
+        move_stock(conn, StockChange {
+            product_id,
+            stock_kind: "empty",
+            quantity,
+            order_id: Some(order_id),
+            driver_shift_id: Some(shift_id),
+            location_id: Some(location_id),
+            ..Default::default()
+        }, vehicle_id).await?;
+    }
+
+    Ok(())
+}
+
+/// Desfaz as transferências do pedido (entrega não realizada ou pedido cancelado):
+/// os vasilhames voltam a contar no veículo
+pub(crate) async fn release_order(
+    conn: &mut SqliteConnection,
+    shift_id: i64,
+    order_id: i64,
+) -> Result<(), AppError> {
+    let vehicle_id = shift_vehicle(conn, shift_id).await?;
+    let location_id = order_location(conn, order_id).await?;
+
+    // Saldo do pedido do lado do veículo: negativo para cheios entregues,
+    // positivo para cascos recolhidos
+    let transfers: Vec<(i64, String, i64)> = sqlx::query_as(
+        "SELECT product_id, stock_kind, SUM(quantity) FROM stock_movements
+         WHERE movement_type = 'TRANSFER' AND driver_shift_id = ? AND order_id = ? AND location_id = ?
+         GROUP BY product_id, stock_kind
+         HAVING SUM(quantity) != 0"
+    )
+    .bind(shift_id)
+    .bind(order_id)
+    .bind(vehicle_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for (product_id, stock_kind, quantity) in transfers {
+        move_stock(conn, StockChange {
+            product_id,
+            stock_kind: &stock_kind,
+            quantity,
+            order_id: Some(order_id),
+            driver_shift_id: Some(shift_id),
+            location_id: Some(vehicle_id),
+            ..Default::default()
+        }, location_id).await?;
+    }
+
+    Ok(())
+}
+
+/// Prévia do acerto calculada a partir das transferências do turno
+async fn live_lines(
+    conn: &mut SqliteConnection,
+    shift_id: i64,
+) -> Result<Vec<DriverShiftLine>, AppError> {
+    let vehicle_id = shift_vehicle(conn, shift_id).await?;
+
+    let lines = sqlx::query_as::<_, DriverShiftLine>(
+        "SELECT sm.product_id, p.name as product_name,
+                SUM(CASE WHEN sm.stock_kind = 'full' AND sm.order_id IS NULL AND sm.quantity > 0
+                    THEN sm.quantity ELSE 0 END) as full_loaded,
+                SUM(CASE WHEN sm.stock_kind = 'full' AND sm.order_id IS NOT NULL
+                    THEN -sm.quantity ELSE 0 END) as full_delivered,
+                SUM(CASE WHEN sm.stock_kind = 'full' THEN sm.quantity ELSE 0 END) as full_expected,
+                NULL as full_returned,
+                NULL as full_difference,
+                SUM(CASE WHEN sm.stock_kind = 'empty' THEN sm.quantity ELSE 0 END) as empty_expected,
+                NULL as empty_returned,
+                NULL as empty_difference
+         FROM stock_movements sm
+         JOIN products p ON sm.product_id = p.id
+         WHERE sm.movement_type = 'TRANSFER' AND sm.driver_shift_id = ? AND sm.location_id = ?
+         GROUP BY sm.product_id, p.name
+         ORDER BY p.name"
+    )
+    .bind(shift_id)
+    .bind(vehicle_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(lines)
+}
+
+/// Dinheiro recebido nas entregas concluídas pelo turno (fora da conferência da gaveta)
+async fn cash_collected(conn: &mut SqliteConnection, shift_id: i64) -> Result<f64, AppError> {
+    let total: (f64,) = sqlx::query_as(
+        "SELECT COALESCE(SUM(op.amount), 0.0)
+         FROM order_payments op
+         JOIN deliveries d ON d.order_id = op.order_id
+         JOIN orders o ON o.id = op.order_id
+         WHERE d.driver_shift_id = ? AND d.status = 'delivered'
+           AND o.status != 'cancelled' AND op.method = 'cash'"
+    )
+    .bind(shift_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(total.0)
+}
+
+pub async fn get_shift_report(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    id: i64,
+) -> Result<DriverShiftReport, AppError> {
+    let mut conn = conn.acquire().await?;
+
+    let shift = get_shift_by_id(&mut *conn, id).await?;
+
+    // Turno fechado usa a conferência gravada; turno aberto mostra a prévia
+    let lines = if shift.status == "closed" {
+        sqlx::query_as::<_, DriverShiftLine>(
+            "SELECT c.product_id, p.name as product_name, c.full_loaded, c.full_delivered,
+                    c.full_expected, c.full_returned, c.full_returned - c.full_expected as full_difference,
+                    c.empty_expected, c.empty_returned, c.empty_returned - c.empty_expected as empty_difference
+             FROM driver_shift_counts c
+             JOIN products p ON c.product_id = p.id
+             WHERE c.shift_id = ?
+             ORDER BY p.name"
+        )
+        .bind(id)
+        .fetch_all(&mut *conn)
+        .await?
+    } else {
+        live_lines(&mut conn, id).await?
+    };
+
+    let cash_collected = cash_collected(&mut conn, id).await?;
+    let deliveries = deliveries::get_deliveries_by_shift(&mut *conn, id).await?;
+
+    let cash_expected = shift.cash_expected.unwrap_or(shift.change_fund + cash_collected);
+    let cash_counted = shift.cash_counted;
+
+    Ok(DriverShiftReport {
+        shift,
+        lines,
+        deliveries,
+        cash_collected,
+        cash_expected,
+        cash_counted,
+        cash_difference: cash_counted.map(|counted| counted - cash_expected),
+    })
+}
+
+/// Acerto do fim do turno: confere os cheios e cascos que voltaram no veículo
+/// e o dinheiro recebido. O que voltou retorna ao estoque da loja; a diferença
+/// fica registrada na conferência e é baixada do veículo como ajuste.
+pub async fn close_shift(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    id: i64,
+    payload: CloseDriverShiftPayload,
+    user_id: i64,
+) -> Result<DriverShiftReport, AppError> {
+    if payload.cash_counted < 0.0 {
+        return Err(AppError::Validation("Counted amount cannot be negative".to_string()));
+    }
+
+    for ret in &payload.returns {
+        if ret.full_returned < 0 || ret.empty_returned < 0 {
+            return Err(AppError::Validation("Returned quantities cannot be negative".to_string()));
+        }
+    }
+
+    let mut tx = conn.begin().await?;
+
+    let shift = get_shift_by_id(&mut *tx, id).await?;
+
+    if shift.status != "open" {
+        return Err(AppError::BusinessLogic(format!("Driver shift #{} is already closed", id)));
+    }
+
+    let in_route: (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM deliveries WHERE driver_shift_id = ? AND status = 'out_for_delivery'"
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if in_route.0 > 0 {
+        return Err(AppError::BusinessLogic(
+            format!("Driver shift #{} still has {} delivery(ies) in route", id, in_route.0)
+        ));
+    }
+
+    let vehicle_id = shift_vehicle(&mut tx, id).await?;
+    let lines = live_lines(&mut tx, id).await?;
+
+    if let Some(ret) = payload.returns.iter().find(|r| !lines.iter().any(|l| l.product_id == r.product_id)) {
+        return Err(AppError::Validation(
+            format!("Product {} was not carried in driver shift #{}", ret.product_id, id)
+        ));
+    }
+
+    // Grava a conferência; produto não informado conta como zero
+    for line in &lines {
+        let (full_returned, empty_returned) = payload.returns.iter()
+            .filter(|r| r.product_id == line.product_id)
+            .fold((0, 0), |(full, empty), r| (full + r.full_returned, empty + r.empty_returned));
+
+        sqlx::query(
+            "INSERT INTO driver_shift_counts
+                (shift_id, product_id, full_loaded, full_delivered, full_expected, full_returned,
+                 empty_expected, empty_returned)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(id)
+        .bind(line.product_id)
+        .bind(line.full_loaded)
+        .bind(line.full_delivered)
+        .bind(line.full_expected)
+        .bind(full_returned)
+        .bind(line.empty_expected)
+        .bind(empty_returned)
+        .execute(&mut *tx)
+        .await?;
+
+        for (stock_kind, expected, returned) in [
+            ("full", line.full_expected, full_returned),
+            ("empty", line.empty_expected, empty_returned),
+        ] {
+            // Falta (ou sobra) acerta o veículo para o que de fato voltou
+            if returned != expected {
+                apply_stock_change(&mut tx, StockChange {
+                    product_id: line.product_id,
+                    stock_kind,
+                    movement_type: "ADJUST",
+                    quantity: returned - expected,
+                    driver_shift_id: Some(id),
+                    location_id: Some(vehicle_id),
+                    ..Default::default()
+                }).await?;
+            }
+
+            if returned > 0 {
+                move_stock(&mut tx, StockChange {
+                    product_id: line.product_id,
+                    stock_kind,
+                    quantity: returned,
+                    driver_shift_id: Some(id),
+                    location_id: Some(vehicle_id),
+                    ..Default::default()
+                }, DEFAULT_LOCATION_ID).await?;
+            }
+        }
+    }
+
+    let cash_expected = shift.change_fund + cash_collected(&mut tx, id).await?;
+
+    sqlx::query(
+        "UPDATE driver_shifts
+         SET status = 'closed', cash_expected = ?, cash_counted = ?, closed_by = ?,
+             closed_at = CURRENT_TIMESTAMP, notes = COALESCE(?, notes)
+         WHERE id = ?"
+    )
+    .bind(cash_expected)
+    .bind(payload.cash_counted)
+    .bind(user_id)
+    .bind(&payload.notes)
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+
+    let report = get_shift_report(&mut *tx, id).await?;
+
+    tx.commit().await?;
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_helpers::{
+        setup_test_db, cleanup_test_db, create_test_user, open_test_session, create_test_product,
+        product_payload, create_test_customer, customer_payload, order_payload,
+    };
+    use crate::db::{cash_sessions, orders, products, stock_locations};
+    use crate::models::{
+        CreateCustomerPayload, CreateDriverPayload, CreateOrderPayload, CreateProductPayload,
+        DeliveryPayload, DispatchDeliveryPayload, DriverReturnPayload, FailDeliveryPayload,
+    };
+
+    async fn create_delivery_order(pool: &SqlitePool, customer_id: i64, product_id: i64, quantity: i64) -> i64 {
+        let order_id = orders::create_order(pool, CreateOrderPayload {
+            delivery: Some(DeliveryPayload { address: None, delivery_fee: None, notes: None }),
+            ..order_payload(Some(customer_id), product_id, quantity, 100.0)
+        }, None).await.unwrap();
+
+        deliveries::get_delivery_by_order(pool, order_id).await.unwrap().unwrap().id
+    }
+
+    /// Saldo (cheios, vazios) na loja
+    async fn stock(pool: &SqlitePool, product_id: i64) -> (i64, i64) {
+        let mut conn = pool.acquire().await.unwrap();
+        location_stock(&mut conn, product_id, DEFAULT_LOCATION_ID).await.unwrap()
+    }
+
+    async fn vehicle(pool: &SqlitePool, driver_id: i64, product_id: i64) -> (i64, i64) {
+        let mut conn = pool.acquire().await.unwrap();
+        let vehicle_id = stock_locations::vehicle_for_driver(&mut conn, driver_id).await.unwrap();
+        location_stock(&mut conn, product_id, vehicle_id).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_driver_shift_settlement() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let product_id = create_test_product(&pool, CreateProductPayload {
+            stock_full: Some(10),
+            ..product_payload("Gás P13", "gas")
+        }).await;
+        let customer_id = create_test_customer(&pool, CreateCustomerPayload {
+            address: Some("Rua das Flores, 15".to_string()),
+            ..customer_payload("Maria Souza")
+        }).await;
+        let driver_id = drivers::create_driver(&pool, CreateDriverPayload {
+            name: "Carlos".to_string(),
+            phone: Some("11988887777".to_string()),
+        }).await.unwrap();
+        let user_id = create_test_user(&pool).await;
+
+        let shift_id = open_shift(&pool, OpenDriverShiftPayload {
+            driver_id,
+            change_fund: 50.0,
+            loads: vec![DriverLoadPayload { product_id, quantity: 5 }],
+            notes: None,
+        }, user_id).await.unwrap();
+        assert_eq!(stock(&pool, product_id).await, (5, 0));
+        assert_eq!(vehicle(&pool, driver_id, product_id).await, (5, 0));
+
+        // Um turno aberto por entregador
+        assert!(open_shift(&pool, OpenDriverShiftPayload {
+            driver_id,
+            change_fund: 0.0,
+            loads: vec![],
+            notes: None,
+        }, user_id).await.is_err());
+
+        // Entrega concluída: cheios saem do veículo, casco volta no veículo
+        let delivered = create_delivery_order(&pool, customer_id, product_id, 2).await;
+        assert_eq!(stock(&pool, product_id).await, (3, 2));
+        deliveries::dispatch_delivery(&pool, delivered, DispatchDeliveryPayload { driver_id })
+            .await
+            .unwrap();
+        assert_eq!(stock(&pool, product_id).await, (5, 2));
+        deliveries::confirm_delivery(&pool, delivered).await.unwrap();
+        assert_eq!(stock(&pool, product_id).await, (5, 0));
+        assert_eq!(vehicle(&pool, driver_id, product_id).await, (3, 2));
+
+        // Entrega não realizada: o cheio continua no veículo
+        let failed = create_delivery_order(&pool, customer_id, product_id, 1).await;
+        deliveries::dispatch_delivery(&pool, failed, DispatchDeliveryPayload { driver_id })
+            .await
+            .unwrap();
+
+        // Não fecha com entrega em rota
+        assert!(close_shift(&pool, shift_id, CloseDriverShiftPayload {
+            cash_counted: 0.0,
+            returns: vec![],
+            notes: None,
+        }, user_id).await.is_err());
+
+        deliveries::fail_delivery(&pool, failed, FailDeliveryPayload { reason: "Cliente ausente".to_string() })
+            .await
+            .unwrap();
+        assert_eq!(stock(&pool, product_id).await, (4, 1));
+
+        let report = get_shift_report(&pool, shift_id).await.unwrap();
+        assert_eq!(report.lines.len(), 1);
+        assert_eq!(report.lines[0].full_loaded, 5);
+        assert_eq!(report.lines[0].full_delivered, 2);
+        assert_eq!(report.lines[0].full_expected, 3);
+        assert_eq!(report.lines[0].empty_expected, 2);
+        assert_eq!(report.cash_collected, 200.0);
+        assert_eq!(report.cash_expected, 250.0);
+        assert_eq!(report.deliveries.len(), 2);
+
+        // Voltou um casco a menos e faltaram 10 reais
+        let report = close_shift(&pool, shift_id, CloseDriverShiftPayload {
+            cash_counted: 240.0,
+            returns: vec![DriverReturnPayload {
+                product_id,
+                full_returned: 3,
+                empty_returned: 1,
+            }],
+            notes: Some("Casco esquecido no cliente".to_string()),
+        }, user_id).await.unwrap();
+
+        assert_eq!(report.shift.status, "closed");
+        assert_eq!(report.lines[0].full_difference, Some(0));
+        assert_eq!(report.lines[0].empty_difference, Some(-1));
+        assert_eq!(report.cash_difference, Some(-10.0));
+        assert_eq!(stock(&pool, product_id).await, (7, 2));
+        assert_eq!(vehicle(&pool, driver_id, product_id).await, (0, 0));
+
+        // O casco perdido sai do total do produto
+        let product = products::get_product_by_id(&pool, product_id).await.unwrap();
+        assert_eq!((product.stock_full, product.stock_empty), (7, 2));
+
+        assert!(close_shift(&pool, shift_id, CloseDriverShiftPayload {
+            cash_counted: 0.0,
+            returns: vec![],
+            notes: None,
+        }, user_id).await.is_err());
+    }
+
+    /// Dinheiro esperado na gaveta do caixa
+    async fn drawer_cash(pool: &SqlitePool, session_id: i64) -> f64 {
+        let report = cash_sessions::get_session_report(pool, session_id).await.unwrap();
+        report.lines.iter().find(|l| l.method == "cash").unwrap().expected
+    }
+
+    #[tokio::test]
+    async fn test_delivery_cash_counted_once() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        let session_id = open_test_session(&pool).await;
+
+        let product_id = create_test_product(&pool, product_payload("Gás P13", "gas")).await;
+        let customer_id = create_test_customer(&pool, CreateCustomerPayload {
+            address: Some("Rua das Flores, 15".to_string()),
+            ..customer_payload("Maria Souza")
+        }).await;
+        let driver_id = drivers::create_driver(&pool, CreateDriverPayload {
+            name: "Carlos".to_string(),
+            phone: None,
+        }).await.unwrap();
+        let user_id = create_test_user(&pool).await;
+
+        let shift_id = open_shift(&pool, OpenDriverShiftPayload {
+            driver_id,
+            change_fund: 20.0,
+            loads: vec![DriverLoadPayload { product_id, quantity: 1 }],
+            notes: None,
+        }, user_id).await.unwrap();
+
+        // Até a entrega ser concluída o pedido conta na gaveta
+        let delivery_id = create_delivery_order(&pool, customer_id, product_id, 1).await;
+        assert_eq!(drawer_cash(&pool, session_id).await, 100.0);
+
+        deliveries::dispatch_delivery(&pool, delivery_id, DispatchDeliveryPayload { driver_id })
+            .await
+            .unwrap();
+        deliveries::confirm_delivery(&pool, delivery_id).await.unwrap();
+
+        // O dinheiro está com o entregador: sai da gaveta e entra no acerto
+        assert_eq!(drawer_cash(&pool, session_id).await, 0.0);
+        let report = close_shift(&pool, shift_id, CloseDriverShiftPayload {
+            cash_counted: 120.0,
+            returns: vec![DriverReturnPayload { product_id, full_returned: 0, empty_returned: 1 }],
+            notes: None,
+        }, user_id).await.unwrap();
+        assert_eq!(report.cash_collected, 100.0);
+        assert_eq!(report.cash_expected, 120.0);
+        assert_eq!(report.cash_difference, Some(0.0));
+        assert_eq!(drawer_cash(&pool, session_id).await, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_vehicle_stock_limits_dispatch() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let product_id = create_test_product(&pool, CreateProductPayload {
+            stock_full: Some(10),
+            ..product_payload("Gás P13", "gas")
+        }).await;
+        let customer_id = create_test_customer(&pool, CreateCustomerPayload {
+            address: Some("Rua das Flores, 15".to_string()),
+            ..customer_payload("Maria Souza")
+        }).await;
+        let driver_id = drivers::create_driver(&pool, CreateDriverPayload {
+            name: "Carlos".to_string(),
+            phone: Some("11988887777".to_string()),
+        }).await.unwrap();
+        let user_id = create_test_user(&pool).await;
+
+        let shift_id = open_shift(&pool, OpenDriverShiftPayload {
+            driver_id,
+            change_fund: 0.0,
+            loads: vec![DriverLoadPayload { product_id, quantity: 1 }],
+            notes: None,
+        }, user_id).await.unwrap();
+
+        // Veículo com um cheio não atende pedido de dois
+        let delivery_id = create_delivery_order(&pool, customer_id, product_id, 2).await;
+        assert!(deliveries::dispatch_delivery(&pool, delivery_id, DispatchDeliveryPayload { driver_id })
+            .await
+            .is_err());
+
+        load_shift(&pool, shift_id, vec![DriverLoadPayload { product_id, quantity: 1 }])
+            .await
+            .unwrap();
+        deliveries::dispatch_delivery(&pool, delivery_id, DispatchDeliveryPayload { driver_id })
+            .await
+            .unwrap();
+
+        // Cancelar o pedido em rota devolve os cheios ao veículo, não à loja
+        let order_id = deliveries::get_delivery_by_id(&pool, delivery_id).await.unwrap().order_id;
+        orders::cancel_order(&pool, order_id, crate::models::CancelOrderPayload {
+            reason: "Cliente desistiu".to_string(),
+        }, user_id).await.unwrap();
+
+        // 10 - 2 carregados - 2 vendidos + 2 entregues pelo veículo - 2 devolvidos ao veículo + 2 estornados
+        assert_eq!(stock(&pool, product_id).await, (8, 0));
+        assert_eq!(vehicle(&pool, driver_id, product_id).await, (2, 0));
+        let report = get_shift_report(&pool, shift_id).await.unwrap();
+        assert_eq!(report.lines[0].full_expected, 2);
+
+        // Carga maior que o estoque da loja
+        assert!(load_shift(&pool, shift_id, vec![DriverLoadPayload { product_id, quantity: 50 }])
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,119 @@
+use crate::models::{Driver, CreateDriverPayload, UpdateDriverPayload};
+use crate::errors::AppError;
+use crate::db::stock_locations;
+use sqlx::{Acquire, Sqlite, SqliteExecutor, SqlitePool};
+
+pub async fn get_all_drivers(pool: &SqlitePool) -> Result<Vec<Driver>, AppError> {
+    let drivers = sqlx::query_as::<_, Driver>(
+        "SELECT * FROM drivers ORDER BY active DESC, name"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(drivers)
+}
+
+pub async fn get_driver_by_id(conn: impl SqliteExecutor<'_>, id: i64) -> Result<Driver, AppError> {
+    let driver = sqlx::query_as::<_, Driver>(
+        "SELECT * FROM drivers WHERE id = ?"
+    )
+    .bind(id)
+    .fetch_optional(conn)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Driver with id {} not found", id)))?;
+
+    Ok(driver)
+}
+
+/// Busca o entregador garantindo que está ativo para novas saídas
+pub async fn get_active_driver(conn: impl SqliteExecutor<'_>, id: i64) -> Result<Driver, AppError> {
+    let driver = get_driver_by_id(conn, id).await?;
+
+    if !driver.active {
+        return Err(AppError::BusinessLogic(format!("Driver {} is inactive", driver.name)));
+    }
+
+    Ok(driver)
+}
+
+pub async fn create_driver(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    payload: CreateDriverPayload,
+) -> Result<i64, AppError> {
+    if payload.name.trim().is_empty() {
+        return Err(AppError::Validation("Driver name cannot be empty".to_string()));
+    }
+
+    let mut tx = conn.begin().await?;
+
+    let id = sqlx::query(
+        "INSERT INTO drivers (name, phone) VALUES (?, ?)"
+    )
+    .bind(payload.name.trim())
+    .bind(&payload.phone)
+    .execute(&mut *tx)
+    .await?
+    .last_insert_rowid();
+
+    stock_locations::create_vehicle_for_driver(&mut tx, id, payload.name.trim()).await?;
+
+    tx.commit().await?;
+
+    Ok(id)
+}
+
+/// Entregador não é excluído: o histórico de entregas e acertos aponta para ele,
+/// então o desligamento é feito marcando-o como inativo
+pub async fn update_driver(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    id: i64,
+    payload: UpdateDriverPayload,
+) -> Result<(), AppError> {
+    let mut conn = conn.acquire().await?;
+
+    // Verifica se existe
+    get_driver_by_id(&mut *conn, id).await?;
+
+    // Constrói query dinamicamente
+    let mut query = sqlx::QueryBuilder::new("UPDATE drivers SET ");
+
+    let mut has_updates = false;
+
+    if let Some(name) = &payload.name {
+        if name.trim().is_empty() {
+            return Err(AppError::Validation("Driver name cannot be empty".to_string()));
+        }
+        query.push("name = ");
+        query.push_bind(name.trim());
+        has_updates = true;
+    }
+
+    if let Some(phone) = &payload.phone {
+        if has_updates {
+            query.push(", ");
+        }
+        query.push("phone = ");
+        query.push_bind(phone);
+        has_updates = true;
+    }
+
+    if let Some(active) = payload.active {
+        if has_updates {
+            query.push(", ");
+        }
+        query.push("active = ");
+        query.push_bind(active);
+        has_updates = true;
+    }
+
+    if !has_updates {
+        return Ok(());
+    }
+
+    query.push(" WHERE id = ");
+    query.push_bind(id);
+
+    query.build().execute(&mut *conn).await?;
+
+    Ok(())
+}
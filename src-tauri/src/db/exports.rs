@@ -0,0 +1,169 @@
+use crate::models::{CustomerExportRow, OrderExportRow, StockMovementWithProduct};
+use crate::errors::AppError;
+use crate::db::reports::validate_period;
+use sqlx::SqlitePool;
+
+// Período opcional nas duas pontas, comparado pela data (sem hora)
+const PERIOD_FILTER: &str =
+    "(? IS NULL OR DATE({col}) >= ?) AND (? IS NULL OR DATE({col}) <= ?)";
+
+fn period_filter(column: &str) -> String {
+    PERIOD_FILTER.replace("{col}", column)
+}
+
+/// Itens dos pedidos do período, incluindo os cancelados (a situação vai na linha)
+pub async fn get_order_rows(
+    pool: &SqlitePool,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+) -> Result<Vec<OrderExportRow>, AppError> {
+    validate_period(pool, start_date, end_date).await?;
+
+    let rows = sqlx::query_as::<_, OrderExportRow>(&format!(
+        "SELECT o.id as order_id, o.created_at, o.status, c.name as customer_name,
+                u.username as operator_name, l.name as location_name,
+                (SELECT GROUP_CONCAT(method, ', ') FROM
+                    (SELECT DISTINCT method FROM order_payments WHERE order_id = o.id)) as payment_methods,
+                o.total as order_total, p.name as product_name, oi.quantity, oi.returned_bottle,
+                oi.unit_price, oi.unit_cost
+         FROM order_items oi
+         JOIN orders o ON oi.order_id = o.id
+         JOIN products p ON oi.product_id = p.id
+         LEFT JOIN customers c ON o.customer_id = c.id
+         LEFT JOIN users u ON o.user_id = u.id
+         LEFT JOIN stock_locations l ON o.location_id = l.id
+         WHERE {}
+         ORDER BY o.created_at, o.id, oi.id",
+        period_filter("o.created_at")
+    ))
+    .bind(start_date)
+    .bind(start_date)
+    .bind(end_date)
+    .bind(end_date)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Movimentações de estoque do período, da mais antiga para a mais recente
+pub async fn get_movement_rows(
+    pool: &SqlitePool,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+) -> Result<Vec<StockMovementWithProduct>, AppError> {
+    validate_period(pool, start_date, end_date).await?;
+
+    let rows = sqlx::query_as::<_, StockMovementWithProduct>(&format!(
+        "SELECT sm.id, sm.product_id, p.name as product_name, sm.movement_type,
+                sm.stock_kind, sm.quantity, sm.unit_cost, sm.order_id, sm.purchase_receipt_id,
+                sm.driver_shift_id, sm.location_id, l.name as location_name, sm.created_at
+         FROM stock_movements sm
+         JOIN products p ON sm.product_id = p.id
+         LEFT JOIN stock_locations l ON sm.location_id = l.id
+         WHERE {}
+         ORDER BY sm.created_at, sm.id",
+        period_filter("sm.created_at")
+    ))
+    .bind(start_date)
+    .bind(start_date)
+    .bind(end_date)
+    .bind(end_date)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Todos os clientes, com saldo fiado atual e compras no período
+pub async fn get_customer_rows(
+    pool: &SqlitePool,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+) -> Result<Vec<CustomerExportRow>, AppError> {
+    validate_period(pool, start_date, end_date).await?;
+
+    let rows = sqlx::query_as::<_, CustomerExportRow>(&format!(
+        "SELECT c.id, c.name, c.phone, c.address, c.notes, c.credit_limit,
+                COALESCE((SELECT SUM(amount) FROM customer_ledger WHERE customer_id = c.id), 0.0) as balance,
+                COUNT(o.id) as order_count,
+                COALESCE(SUM(o.total), 0.0) as total_purchased
+         FROM customers c
+         LEFT JOIN orders o ON o.customer_id = c.id AND o.status != 'cancelled' AND {}
+         GROUP BY c.id
+         ORDER BY c.name",
+        period_filter("o.created_at")
+    ))
+    .bind(start_date)
+    .bind(start_date)
+    .bind(end_date)
+    .bind(end_date)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_helpers::{
+        setup_test_db, cleanup_test_db, open_test_session, create_test_product, product_payload,
+        create_test_customer, customer_payload, order_payload, payment,
+    };
+    use crate::db::orders;
+    use crate::models::{CreateCustomerPayload, CreateOrderPayload, CreateProductPayload};
+
+    #[tokio::test]
+    async fn test_export_rows_by_period() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let product_id = create_test_product(&pool, CreateProductPayload {
+            stock_full: Some(50),
+            ..product_payload("Água 20L", "water")
+        }).await;
+        let customer_id = create_test_customer(&pool, CreateCustomerPayload {
+            credit_limit: Some(100.0),
+            ..customer_payload("Ana")
+        }).await;
+
+        for (created_at, amount) in [("2030-03-31 20:00:00", 24.0), ("2030-04-10 09:00:00", 36.0)] {
+            let order_id = orders::create_order(&pool, CreateOrderPayload {
+                payments: vec![payment("on_account", amount)],
+                ..order_payload(Some(customer_id), product_id, (amount / 12.0) as i64, 12.0)
+            }, None).await.unwrap();
+            sqlx::query("UPDATE orders SET created_at = ? WHERE id = ?")
+                .bind(created_at)
+                .bind(order_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let rows = get_order_rows(&pool, Some("2030-04-01"), Some("2030-04-30")).await.unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].quantity, 3);
+        assert_eq!(rows[0].payment_methods.as_deref(), Some("on_account"));
+        assert_eq!(rows[0].customer_name.as_deref(), Some("Ana"));
+
+        // Sem período, tudo
+        assert_eq!(get_order_rows(&pool, None, None).await.unwrap().len(), 2);
+
+        // O cliente sai mesmo sem compras no período, com o saldo fiado total
+        let customers = get_customer_rows(&pool, Some("2030-04-01"), None).await.unwrap();
+        assert_eq!(customers.len(), 1);
+        assert_eq!((customers[0].order_count, customers[0].total_purchased), (1, 36.0));
+        assert_eq!(customers[0].balance, 60.0);
+        let customers = get_customer_rows(&pool, Some("2030-05-01"), None).await.unwrap();
+        assert_eq!((customers[0].order_count, customers[0].total_purchased), (0, 0.0));
+
+        // Movimentações seguem a data gravada nelas (hoje)
+        assert!(get_movement_rows(&pool, Some("2030-01-01"), None).await.unwrap().is_empty());
+        let movements = get_movement_rows(&pool, None, None).await.unwrap();
+        assert_eq!(movements.iter().filter(|m| m.movement_type == "OUT").count(), 2);
+
+        assert!(get_order_rows(&pool, Some("2030-04-30"), Some("2030-04-01")).await.is_err());
+    }
+}
@@ -0,0 +1,369 @@
+use crate::models::{CreateCustomerPayload, CreateProductPayload, ImportReport, ImportRowResult};
+use crate::errors::AppError;
+use crate::db::customers::{insert_customer, validate_new_customer};
+use crate::db::products::{insert_product, validate_new_product};
+use crate::import::{read_csv, CsvRow};
+use sqlx::{Acquire, Sqlite, SqliteConnection, Transaction};
+use std::collections::HashMap;
+
+// Importação em lote. Tudo roda numa transação: a simulação (dry-run) executa as
+// mesmas gravações e desfaz no fim, e a importação de verdade só confirma se
+// nenhuma linha tiver erro.
+
+pub const PRODUCT_COLUMNS: [&str; 11] = [
+    "name", "description", "type", "price_refill", "price_full", "stock_full", "stock_empty",
+    "cost_price", "min_stock", "reorder_qty", "expiry_date",
+];
+
+pub const CUSTOMER_COLUMNS: [&str; 5] = ["name", "phone", "address", "notes", "credit_limit"];
+
+fn row_error(error: AppError) -> String {
+    match error {
+        AppError::Validation(message) | AppError::BusinessLogic(message) => message,
+        other => other.to_string(),
+    }
+}
+
+/// Aceita o código do tipo ou o nome usado na tela
+fn product_type(value: &str) -> String {
+    match value.to_lowercase().as_str() {
+        "água" | "agua" => "water".to_string(),
+        "gás" | "gas" => "gas".to_string(),
+        "carvão" | "carvao" => "coal".to_string(),
+        "outro" => "other".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn product_payload(row: &CsvRow, errors: &mut Vec<String>) -> CreateProductPayload {
+    let mut required_price = |column: &str| {
+        let price = row.decimal(column, errors);
+        if price.is_none() && row.text(column).is_none() {
+            errors.push(format!("Missing value for {}", column));
+        }
+        price.unwrap_or(0.0)
+    };
+    let price_refill = required_price("price_refill");
+    let price_full = required_price("price_full");
+
+    CreateProductPayload {
+        name: row.text("name").unwrap_or_default(),
+        description: row.text("description"),
+        r#type: row.text("type").map(|t| product_type(&t)).unwrap_or_default(),
+        price_refill,
+        price_full,
+        stock_full: row.integer("stock_full", errors),
+        stock_empty: row.integer("stock_empty", errors),
+        expiry_date: row.text("expiry_date"),
+        cost_price: row.decimal("cost_price", errors),
+        min_stock: row.integer("min_stock", errors),
+        reorder_qty: row.integer("reorder_qty", errors),
+    }
+}
+
+fn customer_payload(row: &CsvRow, errors: &mut Vec<String>) -> CreateCustomerPayload {
+    CreateCustomerPayload {
+        name: row.text("name").unwrap_or_default(),
+        phone: row.text("phone"),
+        address: row.text("address"),
+        notes: row.text("notes"),
+        credit_limit: row.decimal("credit_limit", errors),
+    }
+}
+
+/// Telefone só com dígitos, para "(11) 99999-0000" casar com "11999990000"
+fn phone_key(phone: &str) -> String {
+    phone.chars().filter(char::is_ascii_digit).collect()
+}
+
+/// Dados cadastrais de produto existente. Estoque inicial e validade só valem para
+/// produto novo; saldo de produto existente se acerta pela tela de estoque.
+async fn update_product_row(
+    conn: &mut SqliteConnection,
+    id: i64,
+    payload: &CreateProductPayload,
+) -> Result<(), AppError> {
+    sqlx::query(
+        "UPDATE products SET name = ?, description = COALESCE(?, description), type = ?,
+                price_refill = ?, price_full = ?,
+                cost_price = COALESCE(?, cost_price), average_cost = COALESCE(?, average_cost),
+                min_stock = COALESCE(?, min_stock), reorder_qty = COALESCE(?, reorder_qty)
+         WHERE id = ?"
+    )
+    .bind(&payload.name)
+    .bind(&payload.description)
+    .bind(&payload.r#type)
+    .bind(payload.price_refill)
+    .bind(payload.price_full)
+    .bind(payload.cost_price)
+    .bind(payload.cost_price)
+    .bind(payload.min_stock)
+    .bind(payload.reorder_qty)
+    .bind(id)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+async fn update_customer_row(
+    conn: &mut SqliteConnection,
+    id: i64,
+    payload: &CreateCustomerPayload,
+) -> Result<(), AppError> {
+    sqlx::query(
+        "UPDATE customers SET name = ?, address = COALESCE(?, address), notes = COALESCE(?, notes),
+                credit_limit = COALESCE(?, credit_limit)
+         WHERE id = ?"
+    )
+    .bind(&payload.name)
+    .bind(&payload.address)
+    .bind(&payload.notes)
+    .bind(payload.credit_limit)
+    .bind(id)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Confirma ou desfaz a transação e monta o resumo
+async fn finish(
+    tx: Transaction<'_, Sqlite>,
+    dry_run: bool,
+    rows: Vec<ImportRowResult>,
+) -> Result<ImportReport, AppError> {
+    let count = |action: &str| rows.iter().filter(|r| r.action == action).count() as i64;
+    let (created, updated, failed) = (count("create"), count("update"), count("error"));
+
+    let applied = !dry_run && failed == 0;
+    if applied {
+        tx.commit().await?;
+    } else {
+        tx.rollback().await?;
+    }
+
+    Ok(ImportReport { dry_run, applied, created, updated, failed, rows })
+}
+
+/// Importa produtos do CSV, atualizando os que já existem pelo nome (sem diferenciar
+/// maiúsculas). Colunas aceitas em `PRODUCT_COLUMNS`.
+pub async fn import_products(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    content: &str,
+    dry_run: bool,
+) -> Result<ImportReport, AppError> {
+    let csv_rows = read_csv(content, &PRODUCT_COLUMNS)?;
+
+    let mut tx = conn.begin().await?;
+
+    let existing: Vec<(i64, String)> = sqlx::query_as("SELECT id, name FROM products")
+        .fetch_all(&mut *tx)
+        .await?;
+    let mut by_name: HashMap<String, i64> = existing
+        .into_iter()
+        .map(|(id, name)| (name.trim().to_lowercase(), id))
+        .collect();
+    let mut seen: HashMap<String, u64> = HashMap::new();
+
+    let mut rows = Vec::new();
+    for row in &csv_rows {
+        let mut errors = Vec::new();
+        let payload = product_payload(row, &mut errors);
+        let key = payload.name.to_lowercase();
+
+        if let Err(e) = validate_new_product(&payload) {
+            errors.push(row_error(e));
+        }
+        if !key.is_empty() {
+            if let Some(first_line) = seen.insert(key.clone(), row.line) {
+                errors.push(format!("Duplicate name in file (line {})", first_line));
+            }
+        }
+
+        let mut action = "error";
+        if errors.is_empty() {
+            let result = match by_name.get(&key) {
+                Some(&id) => update_product_row(&mut tx, id, &payload).await.map(|_| "update"),
+                None => insert_product(&mut tx, &payload).await.map(|id| {
+                    by_name.insert(key, id);
+                    "create"
+                }),
+            };
+            match result {
+                Ok(done) => action = done,
+                Err(e) => errors.push(row_error(e)),
+            }
+        }
+
+        rows.push(ImportRowResult {
+            line: row.line,
+            key: row.text("name"),
+            action: action.to_string(),
+            errors,
+        });
+    }
+
+    finish(tx, dry_run, rows).await
+}
+
+/// Importa clientes do CSV, atualizando os que já existem pelo telefone (só os
+/// dígitos). Linha sem telefone sempre cadastra um cliente novo.
+pub async fn import_customers(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    content: &str,
+    dry_run: bool,
+) -> Result<ImportReport, AppError> {
+    let csv_rows = read_csv(content, &CUSTOMER_COLUMNS)?;
+
+    let mut tx = conn.begin().await?;
+
+    let existing: Vec<(i64, String)> =
+        sqlx::query_as("SELECT id, phone FROM customers WHERE phone IS NOT NULL")
+            .fetch_all(&mut *tx)
+            .await?;
+    let mut by_phone: HashMap<String, Vec<i64>> = HashMap::new();
+    for (id, phone) in existing {
+        let key = phone_key(&phone);
+        if !key.is_empty() {
+            by_phone.entry(key).or_default().push(id);
+        }
+    }
+    let mut seen: HashMap<String, u64> = HashMap::new();
+
+    let mut rows = Vec::new();
+    for row in &csv_rows {
+        let mut errors = Vec::new();
+        let payload = customer_payload(row, &mut errors);
+        let key = payload.phone.as_deref().map(phone_key).unwrap_or_default();
+
+        if let Err(e) = validate_new_customer(&payload) {
+            errors.push(row_error(e));
+        }
+        if !key.is_empty() {
+            if let Some(first_line) = seen.insert(key.clone(), row.line) {
+                errors.push(format!("Duplicate phone in file (line {})", first_line));
+            }
+        }
+
+        let matches = by_phone.get(&key).cloned().unwrap_or_default();
+        if matches.len() > 1 {
+            errors.push(format!("Phone matches {} existing customers", matches.len()));
+        }
+
+        let mut action = "error";
+        if errors.is_empty() {
+            let result = match matches.first() {
+                Some(&id) => update_customer_row(&mut tx, id, &payload).await.map(|_| "update"),
+                None => insert_customer(&mut tx, &payload).await.map(|id| {
+                    if !key.is_empty() {
+                        by_phone.insert(key, vec![id]);
+                    }
+                    "create"
+                }),
+            };
+            match result {
+                Ok(done) => action = done,
+                Err(e) => errors.push(row_error(e)),
+            }
+        }
+
+        rows.push(ImportRowResult {
+            line: row.line,
+            key: payload.phone.clone(),
+            action: action.to_string(),
+            errors,
+        });
+    }
+
+    finish(tx, dry_run, rows).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_helpers::{setup_test_db, cleanup_test_db};
+    use crate::db::{customers, products, stock_lots};
+
+    #[tokio::test]
+    async fn test_import_products() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        let csv = "name;type;price_refill;price_full;stock_full;cost_price;expiry_date\n\
+                   Água 20L;Água;12,00;30,00;10;7,50;2031-01-31\n\
+                   Gás P13;gas;100;250;5;;\n";
+
+        // Simulação não grava nada
+        let report = import_products(&pool, csv, true).await.unwrap();
+        assert!(!report.applied);
+        assert_eq!((report.created, report.updated, report.failed), (2, 0, 0));
+        assert!(products::get_all_products(&pool).await.unwrap().is_empty());
+
+        let report = import_products(&pool, csv, false).await.unwrap();
+        assert!(report.applied);
+        let all = products::get_all_products(&pool).await.unwrap();
+        assert_eq!(all.len(), 2);
+        let water = all.iter().find(|p| p.name == "Água 20L").unwrap();
+        assert_eq!((water.r#type.as_str(), water.stock_full, water.average_cost), ("water", 10, 7.5));
+        assert_eq!(stock_lots::get_lots(&pool, Some(water.id), None).await.unwrap().len(), 1);
+
+        // Reimportar atualiza pelo nome e não mexe no estoque
+        let csv = "name,type,price_refill,price_full,stock_full\nágua 20l,water,13.5,31,99\n";
+        let report = import_products(&pool, csv, false).await.unwrap();
+        assert_eq!((report.created, report.updated), (0, 1));
+        let water = products::get_product_by_id(&pool, water.id).await.unwrap();
+        assert_eq!((water.price_refill, water.stock_full, water.average_cost), (13.5, 10, 7.5));
+
+        // Uma linha com erro barra a importação inteira
+        let csv = "name;type;price_refill;price_full\n\
+                   Carvão 5kg;coal;20;20\n\
+                   ;gas;1;1\n\
+                   Lenha;madeira;10;-1\n\
+                   Carvão 5KG;coal;20;x\n";
+        let report = import_products(&pool, csv, false).await.unwrap();
+        assert!(!report.applied);
+        assert_eq!((report.created, report.failed), (1, 3));
+        assert_eq!(report.rows[1].line, 3);
+        assert_eq!(report.rows[1].errors, vec!["Product name cannot be empty".to_string()]);
+        assert_eq!(report.rows[2].errors.len(), 1); // Tipo inválido, antes do preço negativo
+        assert_eq!(report.rows[3].errors.len(), 2); // Preço inválido e nome repetido
+        assert_eq!(products::get_all_products(&pool).await.unwrap().len(), 2);
+
+        assert!(import_products(&pool, "nome;preco\nÁgua;10\n", true).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_import_customers() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        customers::create_customer(&pool, crate::models::CreateCustomerPayload {
+            name: "José".to_string(),
+            phone: Some("(11) 98888-7777".to_string()),
+            address: Some("Rua A, 10".to_string()),
+            notes: None,
+            credit_limit: Some(50.0),
+        }).await.unwrap();
+
+        let csv = "name;phone;address;credit_limit\n\
+                   José da Silva;11988887777;;100\n\
+                   Maria;11 97777-6666;Rua B, 20;\n\
+                   Balcão;;;\n";
+        let report = import_customers(&pool, csv, false).await.unwrap();
+        assert!(report.applied);
+        assert_eq!((report.created, report.updated, report.failed), (2, 1, 0));
+
+        let all = customers::get_all_customers(&pool).await.unwrap();
+        assert_eq!(all.len(), 3);
+        let jose = all.iter().find(|c| c.name == "José da Silva").unwrap();
+        assert_eq!(jose.address.as_deref(), Some("Rua A, 10")); // Vazio mantém o atual
+        assert_eq!(jose.credit_limit, 100.0);
+
+        let csv = "name;phone;credit_limit\nAna;1191111-2222;-5\nBia;11911112222;\n";
+        let report = import_customers(&pool, csv, true).await.unwrap();
+        assert_eq!(report.failed, 2);
+        assert_eq!(report.rows[0].errors, vec!["Credit limit cannot be negative".to_string()]);
+        assert!(report.rows[1].errors[0].starts_with("Duplicate phone"));
+    }
+}
@@ -1,18 +1,52 @@
+use crate::db::{backups, recovery};
+use crate::errors::AppError;
+use crate::models::RecoveryInfo;
+use crate::profiles;
 use sqlx::{
-    migrate::{MigrateError, Migrator},
-    sqlite::SqlitePoolOptions,
-    SqlitePool,
+    migrate::Migrator,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    SqliteConnection, SqliteExecutor, SqlitePool,
 };
+use std::path::{Path, PathBuf};
 
 pub type DbPool = SqlitePool;
 
 static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
 
-pub async fn init_db() -> Result<SqlitePool, Box<dyn std::error::Error>> {
+/// Diretório de dados do app (configuração dos perfis e banco do perfil principal)
+pub fn app_data_dir() -> Result<PathBuf, String> {
+    dirs::data_dir()
+        .map(|dir| dir.join("distribbuidora-pdv"))
+        .ok_or_else(|| "Failed to get app data directory".to_string())
+}
+
+/// Versão da última migração embutida no app
+pub fn latest_migration() -> i64 {
+    MIGRATOR.iter().map(|m| m.version).max().unwrap_or(0)
+}
+
+/// Pasta do arquivo do banco aberto; backups e marcadores do perfil ficam junto dele
+pub async fn database_dir(conn: impl SqliteExecutor<'_>) -> Result<PathBuf, AppError> {
+    let file: (String,) = sqlx::query_as("SELECT file FROM pragma_database_list WHERE name = 'main'")
+        .fetch_one(conn)
+        .await?;
+
+    Path::new(&file.0)
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .map(Path::to_path_buf)
+        .ok_or_else(|| AppError::BusinessLogic("Database is not stored in a file".to_string()))
+}
+
+/// Resultado da abertura do banco: pronto para uso ou parado para recuperação
+pub enum DbStartup {
+    Ready(SqlitePool),
+    Recovery(Box<RecoveryInfo>),
+}
+
+pub async fn init_db() -> Result<DbStartup, Box<dyn std::error::Error>> {
     // Obtém o diretório de dados do app usando dirs crate
-    let app_data = dirs::data_dir()
-        .ok_or("Failed to get app data directory")?
-        .join("distribbuidora-pdv");
+    let app_data = app_data_dir()?;
     
     // Garante que o diretório existe
     std::fs::create_dir_all(&app_data)
@@ -25,66 +59,97 @@ pub async fn init_db() -> Result<SqlitePool, Box<dyn std::error::Error>> {
     if !metadata.is_dir() {
         return Err(format!("App data path exists but is not a directory: {:?}", app_data).into());
     }
-    
-    let db_path = app_data.join("distribbuidora.db");
-    
-    // Converte o caminho para string absoluta
-    let db_path_str = db_path
-        .to_str()
-        .ok_or(format!("Invalid database path: {:?}", db_path))?
-        .to_string();
-    
-    // SQLite URL - usa sqlite:// para caminhos de arquivo.
-    // No Windows, precisamos usar barras normais ou barras invertidas duplicadas
-    let db_url = format!("sqlite:{}", db_path_str.replace("\\", "/"));
+
+    // Cada perfil (empresa) tem a própria pasta com banco e backups
+    let profile = profiles::active_profile(&app_data)?;
+    let profile_dir = PathBuf::from(&profile.dir);
+    std::fs::create_dir_all(&profile_dir)
+        .map_err(|e| format!("Failed to create profile directory: {} (path: {:?})", e, profile_dir))?;
+
+    let db_path = profiles::db_path(&profile);
+
+    // Restauração agendada pelo admin troca o arquivo antes de abrir o banco
+    if backups::apply_pending_restore(&profile_dir, &db_path)? {
+        eprintln!("Database restored from backup");
+    }
+
+    let options = SqliteConnectOptions::new()
+        .filename(&db_path)
+        .create_if_missing(true);
 
     // Cria pool de conexões
     let pool = SqlitePoolOptions::new()
         .max_connections(1)
-        .connect(&db_url)
+        .connect_with(options)
         .await
-        .map_err(|e| format!("Failed to connect to database at {}: {} (dir exists: {}, dir writable: {}, path: {:?})", 
-            db_url, 
-            e, 
-            app_data.exists(),
-            metadata.permissions().readonly() == false,
+        .map_err(|e| format!("Failed to connect to database: {} (dir exists: {}, dir writable: {}, path: {:?})",
+            e,
+            profile_dir.exists(),
+            !std::fs::metadata(&profile_dir).map(|m| m.permissions().readonly()).unwrap_or(true),
             db_path))?;
 
-    apply_migrations(pool, &db_url, &db_path).await
+    apply_migrations(pool, &db_path).await
 }
 
-async fn apply_migrations(
+/// Aplica as migrações pendentes. Se falharem, o banco não é tocado: vira
+/// diagnóstico para a tela de recuperação, com uma cópia de segurança.
+pub(crate) async fn apply_migrations(
     pool: SqlitePool,
-    db_url: &str,
-    db_path: &std::path::Path,
-) -> Result<SqlitePool, Box<dyn std::error::Error>> {
+    db_path: &Path,
+) -> Result<DbStartup, Box<dyn std::error::Error>> {
+    if has_pending_migrations(&pool).await? {
+        // Migração que falha no meio não pode levar os dados junto
+        if let Err(e) = backups::create_safety_backup(&pool, backups::PRE_MIGRATION_TAG).await {
+            eprintln!("Failed to back up database before migrating: {}", e);
+        }
+    }
+
     match MIGRATOR.run(&pool).await {
-        Ok(_) => Ok(pool),
-        Err(MigrateError::VersionMismatch(_)) => {
-            // Se houver incompatibilidade de versão, deleta o banco e recria
-            drop(pool);
-
-            if db_path.exists() {
-                std::fs::remove_file(db_path)
-                    .map_err(|e| format!("Failed to remove old database: {}", e))?;
-            }
-
-            // Garante que o diretório existe antes de reconectar
-            if let Some(parent_dir) = db_path.parent() {
-                std::fs::create_dir_all(parent_dir)
-                    .map_err(|e| format!("Failed to recreate app data directory: {} (path: {:?})", e, parent_dir))?;
-            }
-
-            let new_pool = SqlitePoolOptions::new()
-                .max_connections(1)
-                .connect(db_url)
-                .await
-                .map_err(|e| format!("Failed to reconnect to database: {} (path: {:?})", e, db_path))?;
-
-            MIGRATOR.run(&new_pool).await?;
-
-            Ok(new_pool)
+        Ok(_) => Ok(DbStartup::Ready(pool)),
+        Err(e) => {
+            let info = recovery::prepare(&pool, db_path, &e).await;
+            pool.close().await;
+            Ok(DbStartup::Recovery(Box::new(info)))
         }
-        Err(e) => Err(Box::new(e)),
     }
 }
+
+/// Banco já em uso com migrações a aplicar; banco novo não tem o que proteger
+async fn has_pending_migrations(pool: &SqlitePool) -> Result<bool, AppError> {
+    let table: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations'"
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    if table.is_none() {
+        return Ok(false);
+    }
+
+    let applied: Vec<(i64,)> = sqlx::query_as("SELECT version FROM _sqlx_migrations")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(MIGRATOR
+        .iter()
+        .any(|migration| !applied.iter().any(|(version,)| *version == migration.version)))
+}
+
+/// Grava no banco o checksum das migrações embutidas, para as que já foram aplicadas
+pub(crate) async fn accept_embedded_checksums(conn: &mut SqliteConnection) -> Result<u64, AppError> {
+    let mut updated = 0;
+
+    for migration in MIGRATOR.iter() {
+        updated += sqlx::query(
+            "UPDATE _sqlx_migrations SET checksum = ? WHERE version = ? AND checksum != ?"
+        )
+        .bind(migration.checksum.as_ref())
+        .bind(migration.version)
+        .bind(migration.checksum.as_ref())
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+    }
+
+    Ok(updated)
+}
@@ -1,9 +1,26 @@
 pub mod init;
 pub mod products;
 pub mod customers;
+pub mod customer_accounts;
+pub mod bottle_loans;
 pub mod orders;
+pub mod deliveries;
+pub mod drivers;
+pub mod driver_shifts;
+pub mod cash_sessions;
 pub mod stock;
+pub mod stock_locations;
+pub mod stock_lots;
+pub mod suppliers;
+pub mod purchase_orders;
+pub mod reports;
+pub mod exports;
+pub mod imports;
+pub mod settings;
+pub mod backups;
+pub mod recovery;
 pub mod users;
+pub mod audit;
 
 pub use init::*;
 
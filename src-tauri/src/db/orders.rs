@@ -1,94 +1,226 @@
 use crate::models::{
-    OrderWithCustomer, CreateOrderPayload, UpdateOrderPayload,
-    OrderWithItems, OrderItemWithProduct,
+    OrderWithCustomer, CreateOrderPayload, UpdateOrderPayload, CancelOrderPayload,
+    OrderWithItems, OrderItemWithProduct, OrderPayment, OrderPaymentPayload,
 };
 use crate::errors::AppError;
-use sqlx::SqlitePool;
+use crate::db::customer_accounts;
+use crate::db::cash_sessions::{self, COUNTED_METHODS};
+use crate::db::{deliveries, driver_shifts};
+use crate::db::bottle_loans::{self, BOTTLE_PRODUCT_TYPES};
+use crate::db::stock::{apply_stock_change, StockChange};
+use crate::db::stock_locations::{self, location_stock};
+use crate::db::stock_lots::{self, PERISHABLE_PRODUCT_TYPES};
+use sqlx::{Acquire, Sqlite, SqlitePool};
+
+pub const PAYMENT_METHODS: [&str; 5] = ["cash", "pix", "debit", "credit", "on_account"];
+
+fn validate_payments(
+    payments: &[OrderPaymentPayload],
+    total: f64,
+    customer_id: Option<i64>,
+) -> Result<(), AppError> {
+    if payments.is_empty() {
+        return Err(AppError::Validation("Order must have at least one payment".to_string()));
+    }
+
+    for payment in payments {
+        if !PAYMENT_METHODS.contains(&payment.method.as_str()) {
+            return Err(AppError::Validation(format!("Invalid payment method: {}", payment.method)));
+        }
+
+        if payment.amount <= 0.0 {
+            return Err(AppError::Validation("Payment amount must be positive".to_string()));
+        }
+
+        if let Some(tendered) = payment.tendered {
+            if payment.method != "cash" {
+                return Err(AppError::Validation("Only cash payments can have a tendered amount".to_string()));
+            }
+            if tendered < payment.amount {
+                return Err(AppError::Validation(
+                    format!("Tendered amount {:.2} is less than payment amount {:.2}", tendered, payment.amount)
+                ));
+            }
+        }
+
+        if payment.method == "on_account" && customer_id.is_none() {
+            return Err(AppError::Validation("On-account payments require a customer".to_string()));
+        }
+    }
+
+    // Soma dos pagamentos deve fechar com o total (tolerância de meio centavo)
+    let paid: f64 = payments.iter().map(|p| p.amount).sum();
+    if (paid - total).abs() > 0.005 {
+        return Err(AppError::Validation(
+            format!("Payments total {:.2} does not match order total {:.2}", paid, total)
+        ));
+    }
+
+    Ok(())
+}
 
 pub async fn create_order(
-    pool: &SqlitePool,
+    conn: impl Acquire<'_, Database = Sqlite>,
     payload: CreateOrderPayload,
+    user_id: Option<i64>,
 ) -> Result<i64, AppError> {
     if payload.items.is_empty() {
         return Err(AppError::Validation("Order must have at least one item".to_string()));
     }
 
     // Inicia transação
-    let mut tx = pool.begin().await?;
+    let mut tx = conn.begin().await?;
+
+    let location = stock_locations::get_active_location(&mut *tx, payload.location_id).await?;
+
+    // Toda venda entra no caixa aberto; sem ele ficaria fora dos fechamentos
+    let session: (i64,) = sqlx::query_as("SELECT id FROM cash_sessions WHERE status = 'open'")
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::BusinessLogic("Open the cash register first".to_string()))?;
+
+    // Calcula total, incluindo a taxa de entrega
+    let delivery_fee = match &payload.delivery {
+        Some(delivery) => deliveries::validate_delivery(delivery)?,
+        None => 0.0,
+    };
 
-    // Calcula total
     let total: f64 = payload.items.iter()
         .map(|item| item.unit_price * item.quantity as f64)
-        .sum();
+        .sum::<f64>() + delivery_fee;
 
-    // Insere pedido
+    validate_payments(&payload.payments, total, payload.customer_id)?;
+
+    // Pedido de entrega fica em aberto até a confirmação da entrega
+    let status = if payload.delivery.is_some() { "open" } else { "completed" };
+
+    // Insere pedido, vinculado ao operador e ao caixa aberto
     let order_id = sqlx::query(
-        "INSERT INTO orders (customer_id, total) VALUES (?, ?)"
+        "INSERT INTO orders (customer_id, total, user_id, session_id, status, location_id)
+         VALUES (?, ?, ?, ?, ?, ?)"
     )
     .bind(payload.customer_id)
     .bind(total)
+    .bind(user_id)
+    .bind(session.0)
+    .bind(status)
+    .bind(location.id)
     .execute(&mut *tx)
     .await?
     .last_insert_rowid();
 
     // Insere itens e atualiza estoque
     for item in &payload.items {
-        // Valida estoque
-        let product: (i64, i64) = sqlx::query_as(
-            "SELECT stock_full, stock_empty FROM products WHERE id = ?"
+        let product: (String, f64, f64, String) = sqlx::query_as(
+            "SELECT type, price_refill, average_cost, name FROM products WHERE id = ?"
         )
         .bind(item.product_id)
         .fetch_optional(&mut *tx)
         .await?
         .ok_or_else(|| AppError::NotFound(format!("Product {} not found", item.product_id)))?;
 
-        if product.0 < item.quantity {
+        // Valida estoque no local da venda
+        let (available, _) = location_stock(&mut tx, item.product_id, location.id).await?;
+
+        if available < item.quantity {
             return Err(AppError::BusinessLogic(
-                format!("Insufficient stock for product {}. Available: {}, Requested: {}", 
-                    item.product_id, product.0, item.quantity)
+                format!("Insufficient stock for product {} at {}. Available: {}, Requested: {}", 
+                    item.product_id, location.name, available, item.quantity)
             ));
         }
 
-        // Insere item do pedido
+        // Lote vencido sai primeiro (FEFO); a venda só passa com liberação do admin
+        if PERISHABLE_PRODUCT_TYPES.contains(&product.0.as_str()) && !payload.allow_expired.unwrap_or(false) {
+            let expired = stock_lots::expired_quantity(&mut tx, item.product_id, location.id).await?;
+            if expired > 0 {
+                return Err(AppError::BusinessLogic(
+                    format!("Expired stock for {} at {}: {} unit(s) past expiry date",
+                        product.3, location.name, expired)
+                ));
+            }
+        }
+
+        // Insere item do pedido, guardando o custo médio vigente para cálculo de margem
         sqlx::query(
-            "INSERT INTO order_items (order_id, product_id, quantity, returned_bottle, unit_price)
-             VALUES (?, ?, ?, ?, ?)"
+            "INSERT INTO order_items (order_id, product_id, quantity, returned_bottle, unit_price, unit_cost)
+             VALUES (?, ?, ?, ?, ?, ?)"
         )
         .bind(order_id)
         .bind(item.product_id)
         .bind(item.quantity)
         .bind(item.returned_bottle)
         .bind(item.unit_price)
+        .bind(product.2)
         .execute(&mut *tx)
         .await?;
 
         // Atualiza estoque: sempre diminui stock_full
-        sqlx::query("UPDATE products SET stock_full = stock_full - ? WHERE id = ?")
-            .bind(item.quantity)
-            .bind(item.product_id)
-            .execute(&mut *tx)
-            .await?;
+        apply_stock_change(&mut tx, StockChange {
+            product_id: item.product_id,
+            stock_kind: "full",
+            movement_type: "OUT",
+            quantity: item.quantity,
+            order_id: Some(order_id),
+            location_id: Some(location.id),
+            ..Default::default()
+        }).await?;
 
         // Se trouxe o casco, aumenta stock_empty
         if item.returned_bottle {
-            sqlx::query("UPDATE products SET stock_empty = stock_empty + ? WHERE id = ?")
-                .bind(item.quantity)
-                .bind(item.product_id)
-                .execute(&mut *tx)
-                .await?;
+            apply_stock_change(&mut tx, StockChange {
+                product_id: item.product_id,
+                stock_kind: "empty",
+                movement_type: "RETURN",
+                quantity: item.quantity,
+                order_id: Some(order_id),
+                location_id: Some(location.id),
+                ..Default::default()
+            }).await?;
         }
 
-        // Registra movimentação de estoque
+        // Sem casco de volta, o cliente fica com o vasilhame em comodato;
+        // o que pagou acima do preço de recarga é a caução
+        if let (Some(customer_id), false) = (payload.customer_id, item.returned_bottle) {
+            if BOTTLE_PRODUCT_TYPES.contains(&product.0.as_str()) {
+                let deposit = (item.unit_price - product.1).max(0.0) * item.quantity as f64;
+                bottle_loans::lend_for_order(
+                    &mut tx, customer_id, item.product_id, item.quantity, deposit, order_id,
+                ).await?;
+            }
+        }
+    }
+
+    // Registra os pagamentos
+    for payment in &payload.payments {
+        let change_due = payment.tendered.map(|t| t - payment.amount).unwrap_or(0.0);
+
         sqlx::query(
-            "INSERT INTO stock_movements (product_id, movement_type, quantity)
-             VALUES (?, 'OUT', ?)"
+            "INSERT INTO order_payments (order_id, method, amount, tendered, change_due)
+             VALUES (?, ?, ?, ?, ?)"
         )
-        .bind(item.product_id)
-        .bind(item.quantity)
+        .bind(order_id)
+        .bind(&payment.method)
+        .bind(payment.amount)
+        .bind(payment.tendered)
+        .bind(change_due)
         .execute(&mut *tx)
         .await?;
     }
 
+    if let Some(delivery) = &payload.delivery {
+        deliveries::create_for_order(&mut tx, order_id, payload.customer_id, delivery).await?;
+    }
+
+    // Lança a parte fiado na conta do cliente
+    let on_account: f64 = payload.payments.iter()
+        .filter(|p| p.method == "on_account")
+        .map(|p| p.amount)
+        .sum();
+
+    if let (Some(customer_id), true) = (payload.customer_id, on_account > 0.0) {
+        customer_accounts::charge_order(&mut tx, customer_id, order_id, on_account).await?;
+    }
+
     // Commit transação
     tx.commit().await?;
 
@@ -97,9 +229,13 @@ pub async fn create_order(
 
 pub async fn get_all_orders(pool: &SqlitePool) -> Result<Vec<OrderWithCustomer>, AppError> {
     let orders = sqlx::query_as::<_, OrderWithCustomer>(
-        "SELECT o.id, o.customer_id, c.name as customer_name, o.total, o.created_at
+        "SELECT o.id, o.customer_id, c.name as customer_name, o.total, o.created_at,
+                o.user_id, u.username as operator_name, o.session_id, o.status,
+                o.cancel_reason, o.cancelled_by, cu.username as cancelled_by_username, o.cancelled_at
          FROM orders o
          LEFT JOIN customers c ON o.customer_id = c.id
+         LEFT JOIN users u ON o.user_id = u.id
+         LEFT JOIN users cu ON o.cancelled_by = cu.id
          ORDER BY o.created_at DESC"
     )
     .fetch_all(pool)
@@ -108,39 +244,59 @@ pub async fn get_all_orders(pool: &SqlitePool) -> Result<Vec<OrderWithCustomer>,
     Ok(orders)
 }
 
-pub async fn get_order_by_id(pool: &SqlitePool, id: i64) -> Result<OrderWithItems, AppError> {
+pub async fn get_order_by_id(conn: impl Acquire<'_, Database = Sqlite>, id: i64) -> Result<OrderWithItems, AppError> {
     // Busca pedido
+    let mut conn = conn.acquire().await?;
+
     let order = sqlx::query_as::<_, OrderWithCustomer>(
-        "SELECT o.id, o.customer_id, c.name as customer_name, o.total, o.created_at
+        "SELECT o.id, o.customer_id, c.name as customer_name, o.total, o.created_at,
+                o.user_id, u.username as operator_name, o.session_id, o.status,
+                o.cancel_reason, o.cancelled_by, cu.username as cancelled_by_username, o.cancelled_at
          FROM orders o
          LEFT JOIN customers c ON o.customer_id = c.id
+         LEFT JOIN users u ON o.user_id = u.id
+         LEFT JOIN users cu ON o.cancelled_by = cu.id
          WHERE o.id = ?"
     )
     .bind(id)
-    .fetch_optional(pool)
+    .fetch_optional(&mut *conn)
     .await?
     .ok_or_else(|| AppError::NotFound(format!("Order with id {} not found", id)))?;
 
     // Busca itens
     let items = sqlx::query_as::<_, OrderItemWithProduct>(
         "SELECT oi.id, oi.order_id, oi.product_id, p.name as product_name,
-                oi.quantity, oi.returned_bottle, oi.unit_price
+                oi.quantity, oi.returned_bottle, oi.unit_price, oi.unit_cost
          FROM order_items oi
          JOIN products p ON oi.product_id = p.id
          WHERE oi.order_id = ?"
     )
     .bind(id)
-    .fetch_all(pool)
+    .fetch_all(&mut *conn)
     .await?;
 
-    Ok(OrderWithItems { order, items })
+    // Busca pagamentos
+    let payments = sqlx::query_as::<_, OrderPayment>(
+        "SELECT * FROM order_payments WHERE order_id = ? ORDER BY id"
+    )
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let delivery = deliveries::get_delivery_by_order(&mut *conn, id).await?;
+
+    Ok(OrderWithItems { order, items, payments, delivery })
 }
 
 pub async fn get_orders_by_customer(pool: &SqlitePool, customer_id: i64) -> Result<Vec<OrderWithCustomer>, AppError> {
     let orders = sqlx::query_as::<_, OrderWithCustomer>(
-        "SELECT o.id, o.customer_id, c.name as customer_name, o.total, o.created_at
+        "SELECT o.id, o.customer_id, c.name as customer_name, o.total, o.created_at,
+                o.user_id, u.username as operator_name, o.session_id, o.status,
+                o.cancel_reason, o.cancelled_by, cu.username as cancelled_by_username, o.cancelled_at
          FROM orders o
          LEFT JOIN customers c ON o.customer_id = c.id
+         LEFT JOIN users u ON o.user_id = u.id
+         LEFT JOIN users cu ON o.cancelled_by = cu.id
          WHERE o.customer_id = ?
          ORDER BY o.created_at DESC"
     )
@@ -151,43 +307,133 @@ pub async fn get_orders_by_customer(pool: &SqlitePool, customer_id: i64) -> Resu
     Ok(orders)
 }
 
-pub async fn delete_order(pool: &SqlitePool, id: i64) -> Result<(), AppError> {
-    // Verifica se o pedido existe
-    let order = get_order_by_id(pool, id).await?;
+/// Cancela o pedido mantendo o registro: estorna o estoque, o comodato e o fiado
+/// com lançamentos de reversão e grava o motivo, o responsável e a data
+pub async fn cancel_order(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    id: i64,
+    payload: CancelOrderPayload,
+    user_id: i64,
+) -> Result<(), AppError> {
+    if payload.reason.trim().is_empty() {
+        return Err(AppError::Validation("Cancellation reason cannot be empty".to_string()));
+    }
 
     // Inicia transação
-    let mut tx = pool.begin().await?;
+    let mut tx = conn.begin().await?;
+
+    // Verifica se o pedido existe
+    let order = get_order_by_id(&mut *tx, id).await?;
+
+    if order.order.status == "cancelled" {
+        return Err(AppError::BusinessLogic(format!("Order {} is already cancelled", id)));
+    }
+
+    // O estoque volta para o local de onde a venda saiu
+    let location: (Option<i64>,) = sqlx::query_as("SELECT location_id FROM orders WHERE id = ?")
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    // Entrega atendida por um turno ainda aberto: os vasilhames estão no veículo
+    let open_shift_id = match order.delivery.as_ref().and_then(|d| d.driver_shift_id) {
+        Some(shift_id) => {
+            let shift = driver_shifts::get_shift_by_id(&mut *tx, shift_id).await?;
+            (shift.status == "open").then_some(shift_id)
+        }
+        None => None,
+    };
+
+    if let Some(shift_id) = open_shift_id {
+        driver_shifts::release_order(&mut tx, shift_id, id).await?;
+    }
+
+    // Lotes consumidos pela venda voltam junto com o estorno
+    let mut consumed_lots = stock_lots::order_lots(&mut tx, id).await?;
 
     // Reverte o estoque para cada item do pedido
     for item in &order.items {
+        let lots = stock_lots::take_lots(&mut consumed_lots, item.product_id, item.quantity);
+
         // Adiciona de volta ao estoque
-        sqlx::query("UPDATE products SET stock_full = stock_full + ? WHERE id = ?")
-            .bind(item.quantity)
-            .bind(item.product_id)
-            .execute(&mut *tx)
-            .await?;
+        apply_stock_change(&mut tx, StockChange {
+            product_id: item.product_id,
+            stock_kind: "full",
+            movement_type: "REVERSAL",
+            quantity: item.quantity,
+            order_id: Some(id),
+            location_id: location.0,
+            lots: &lots,
+            ..Default::default()
+        }).await?;
 
         // Se tinha casco retornado, remove do stock_empty
         if item.returned_bottle {
-            sqlx::query("UPDATE products SET stock_empty = stock_empty - ? WHERE id = ?")
-                .bind(item.quantity)
-                .bind(item.product_id)
-                .execute(&mut *tx)
-                .await?;
+            apply_stock_change(&mut tx, StockChange {
+                product_id: item.product_id,
+                stock_kind: "empty",
+                movement_type: "REVERSAL",
+                quantity: -item.quantity,
+                order_id: Some(id),
+                location_id: location.0,
+                ..Default::default()
+            }).await?;
         }
     }
 
-    // Deleta os itens do pedido
-    sqlx::query("DELETE FROM order_items WHERE order_id = ?")
-        .bind(id)
-        .execute(&mut *tx)
-        .await?;
+    // Encerra o comodato gerado pelo pedido
+    bottle_loans::release_for_order(&mut tx, id, user_id).await?;
 
-    // Deleta o pedido
-    sqlx::query("DELETE FROM orders WHERE id = ?")
-        .bind(id)
-        .execute(&mut *tx)
-        .await?;
+    // Estorna o lançamento fiado do pedido
+    sqlx::query(
+        "INSERT INTO customer_ledger (customer_id, entry_type, amount, order_id, user_id, description)
+         SELECT customer_id, 'ADJUST', -amount, order_id, ?, ?
+         FROM customer_ledger
+         WHERE order_id = ? AND entry_type = 'CHARGE'"
+    )
+    .bind(user_id)
+    .bind(format!("Cancelamento do pedido #{}", id))
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+
+    // Enquanto o pedido conta no caixa aberto (ou no acerto de um turno aberto),
+    // o cancelamento já o tira da conferência. Senão o valor pago é devolvido
+    // como estorno do caixa aberto, na mesma forma de pagamento.
+    let open_session = cash_sessions::get_open_session(&mut *tx).await?;
+    let in_open_session = open_session.is_some_and(|s| order.order.session_id == Some(s.id));
+    let settled_by_driver = order.delivery.as_ref()
+        .is_some_and(|d| d.driver_shift_id.is_some() && d.status == "delivered");
+
+    for method in COUNTED_METHODS {
+        let amount: f64 = order.payments.iter()
+            .filter(|p| p.method == method)
+            .map(|p| p.amount)
+            .sum();
+
+        let still_counted = if method == "cash" && settled_by_driver {
+            open_shift_id.is_some()
+        } else {
+            in_open_session
+        };
+
+        if amount > 0.0 && !still_counted {
+            cash_sessions::withdraw(
+                &mut tx, method, amount, Some(&format!("Estorno do pedido #{}", id)), Some(user_id),
+            ).await?;
+        }
+    }
+
+    sqlx::query(
+        "UPDATE orders
+         SET status = 'cancelled', cancel_reason = ?, cancelled_by = ?, cancelled_at = CURRENT_TIMESTAMP
+         WHERE id = ?"
+    )
+    .bind(payload.reason.trim())
+    .bind(user_id)
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
 
     // Commit transação
     tx.commit().await?;
@@ -196,19 +442,21 @@ pub async fn delete_order(pool: &SqlitePool, id: i64) -> Result<(), AppError> {
 }
 
 pub async fn update_order(
-    pool: &SqlitePool,
+    conn: impl Acquire<'_, Database = Sqlite>,
     id: i64,
     payload: UpdateOrderPayload,
 ) -> Result<(), AppError> {
+    let mut conn = conn.acquire().await?;
+
     // Verifica se o pedido existe
-    get_order_by_id(pool, id).await?;
+    get_order_by_id(&mut *conn, id).await?;
 
     // Atualiza apenas a data se fornecida
     if let Some(created_at) = payload.created_at {
         sqlx::query("UPDATE orders SET created_at = ? WHERE id = ?")
             .bind(created_at)
             .bind(id)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
     }
 
@@ -218,10 +466,22 @@ pub async fn update_order(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_helpers::test_helpers::{setup_test_db, cleanup_test_db};
+    use crate::test_helpers::test_helpers::{setup_test_db, cleanup_test_db, create_test_user, open_test_session};
     use crate::db::products;
     use crate::db::customers;
-    use crate::models::{CreateProductPayload, CreateCustomerPayload};
+    use crate::db::{customer_accounts, stock};
+    use crate::models::{
+        CreateProductPayload, CreateCustomerPayload, OrderItemPayload, CashMovementPayload,
+        CloseCashSessionPayload, OpenCashSessionPayload,
+    };
+
+    fn cash_payment(amount: f64) -> Vec<OrderPaymentPayload> {
+        vec![OrderPaymentPayload {
+            method: "cash".to_string(),
+            amount,
+            tendered: None,
+        }]
+    }
 
     async fn create_test_product(pool: &SqlitePool) -> i64 {
         let payload = CreateProductPayload {
@@ -232,6 +492,10 @@ mod tests {
             price_full: 10.0,
             stock_full: Some(100),
             stock_empty: Some(0),
+            expiry_date: None,
+            cost_price: None,
+            min_stock: None,
+            reorder_qty: None,
         };
         products::create_product(pool, payload).await.unwrap()
     }
@@ -242,6 +506,7 @@ mod tests {
             phone: None,
             address: None,
             notes: None,
+            credit_limit: None,
         };
         customers::create_customer(pool, payload).await.unwrap()
     }
@@ -250,6 +515,7 @@ mod tests {
     async fn test_create_order() {
         let pool = setup_test_db().await;
         cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
 
         let product_id = create_test_product(&pool).await;
         let customer_id = create_test_customer(&pool).await;
@@ -264,9 +530,13 @@ mod tests {
                     unit_price: 10.0,
                 },
             ],
+            payments: cash_payment(20.0),
+            delivery: None,
+            location_id: None,
+            allow_expired: None,
         };
 
-        let order_id = create_order(&pool, payload).await.unwrap();
+        let order_id = create_order(&pool, payload, None).await.unwrap();
         assert!(order_id > 0);
 
         let order = get_order_by_id(&pool, order_id).await.unwrap();
@@ -279,10 +549,44 @@ mod tests {
         assert_eq!(product.stock_full, 98); // 100 - 2
     }
 
+    #[tokio::test]
+    async fn test_create_order_requires_open_session() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        let product_id = create_test_product(&pool).await;
+        let payload = || CreateOrderPayload {
+            customer_id: None,
+            items: vec![OrderItemPayload {
+                product_id,
+                quantity: 1,
+                returned_bottle: true,
+                unit_price: 5.0,
+            }],
+            payments: cash_payment(5.0),
+            delivery: None,
+            location_id: None,
+            allow_expired: None,
+        };
+
+        let err = create_order(&pool, payload(), None).await.unwrap_err();
+        assert!(matches!(err, AppError::BusinessLogic(_)));
+
+        // Nada foi gravado nem saiu do estoque
+        assert!(get_all_orders(&pool).await.unwrap().is_empty());
+        assert_eq!(products::get_product_by_id(&pool, product_id).await.unwrap().stock_full, 100);
+
+        let session_id = open_test_session(&pool).await;
+        let order_id = create_order(&pool, payload(), None).await.unwrap();
+        let order = get_order_by_id(&pool, order_id).await.unwrap();
+        assert_eq!(order.order.session_id, Some(session_id));
+    }
+
     #[tokio::test]
     async fn test_create_order_with_returned_bottle() {
         let pool = setup_test_db().await;
         cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
 
         let product_id = create_test_product(&pool).await;
 
@@ -296,9 +600,13 @@ mod tests {
                     unit_price: 5.0,
                 },
             ],
+            payments: cash_payment(5.0),
+            delivery: None,
+            location_id: None,
+            allow_expired: None,
         };
 
-        create_order(&pool, payload).await.unwrap();
+        create_order(&pool, payload, None).await.unwrap();
 
         // Verifica que o estoque foi atualizado corretamente
         let product = products::get_product_by_id(&pool, product_id).await.unwrap();
@@ -310,13 +618,18 @@ mod tests {
     async fn test_create_order_validation() {
         let pool = setup_test_db().await;
         cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
 
         // Pedido sem itens
         let payload = CreateOrderPayload {
             customer_id: None,
             items: vec![],
+            payments: vec![],
+            delivery: None,
+            location_id: None,
+            allow_expired: None,
         };
-        assert!(create_order(&pool, payload).await.is_err());
+        assert!(create_order(&pool, payload, None).await.is_err());
 
         // Produto inexistente
         let payload = CreateOrderPayload {
@@ -329,14 +642,19 @@ mod tests {
                     unit_price: 10.0,
                 },
             ],
+            payments: cash_payment(10.0),
+            delivery: None,
+            location_id: None,
+            allow_expired: None,
         };
-        assert!(create_order(&pool, payload).await.is_err());
+        assert!(create_order(&pool, payload, None).await.is_err());
     }
 
     #[tokio::test]
     async fn test_create_order_insufficient_stock() {
         let pool = setup_test_db().await;
         cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
 
         let product_id = create_test_product(&pool).await;
 
@@ -350,15 +668,183 @@ mod tests {
                     unit_price: 10.0,
                 },
             ],
+            payments: cash_payment(1010.0),
+            delivery: None,
+            location_id: None,
+            allow_expired: None,
+        };
+
+        assert!(create_order(&pool, payload, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_order_with_split_payments() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let product_id = create_test_product(&pool).await;
+
+        let payload = CreateOrderPayload {
+            customer_id: None,
+            items: vec![
+                OrderItemPayload {
+                    product_id,
+                    quantity: 3,
+                    returned_bottle: false,
+                    unit_price: 10.0,
+                },
+            ],
+            payments: vec![
+                OrderPaymentPayload {
+                    method: "cash".to_string(),
+                    amount: 10.0,
+                    tendered: Some(20.0),
+                },
+                OrderPaymentPayload {
+                    method: "pix".to_string(),
+                    amount: 20.0,
+                    tendered: None,
+                },
+            ],
+            delivery: None,
+            location_id: None,
+            allow_expired: None,
+        };
+
+        let order_id = create_order(&pool, payload, None).await.unwrap();
+        let order = get_order_by_id(&pool, order_id).await.unwrap();
+
+        assert_eq!(order.payments.len(), 2);
+        assert_eq!(order.payments[0].method, "cash");
+        assert_eq!(order.payments[0].tendered, Some(20.0));
+        assert_eq!(order.payments[0].change_due, 10.0);
+        assert_eq!(order.payments[1].method, "pix");
+        assert_eq!(order.payments[1].change_due, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_create_order_payment_validation() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let product_id = create_test_product(&pool).await;
+        let item = || vec![
+            OrderItemPayload {
+                product_id,
+                quantity: 1,
+                returned_bottle: false,
+                unit_price: 10.0,
+            },
+        ];
+
+        // Pagamentos não fecham com o total
+        let payload = CreateOrderPayload {
+            customer_id: None,
+            items: item(),
+            payments: cash_payment(5.0),
+            delivery: None,
+            location_id: None,
+            allow_expired: None,
+        };
+        assert!(create_order(&pool, payload, None).await.is_err());
+
+        // Forma de pagamento inválida
+        let payload = CreateOrderPayload {
+            customer_id: None,
+            items: item(),
+            payments: vec![OrderPaymentPayload {
+                method: "cheque".to_string(),
+                amount: 10.0,
+                tendered: None,
+            }],
+            delivery: None,
+            location_id: None,
+            allow_expired: None,
+        };
+        assert!(create_order(&pool, payload, None).await.is_err());
+
+        // Valor entregue menor que o valor pago
+        let payload = CreateOrderPayload {
+            customer_id: None,
+            items: item(),
+            payments: vec![OrderPaymentPayload {
+                method: "cash".to_string(),
+                amount: 10.0,
+                tendered: Some(5.0),
+            }],
+            delivery: None,
+            location_id: None,
+            allow_expired: None,
+        };
+        assert!(create_order(&pool, payload, None).await.is_err());
+
+        // Fiado sem cliente
+        let payload = CreateOrderPayload {
+            customer_id: None,
+            items: item(),
+            payments: vec![OrderPaymentPayload {
+                method: "on_account".to_string(),
+                amount: 10.0,
+                tendered: None,
+            }],
+            delivery: None,
+            location_id: None,
+            allow_expired: None,
+        };
+        assert!(create_order(&pool, payload, None).await.is_err());
+
+        // Troco só em dinheiro
+        let payload = CreateOrderPayload {
+            customer_id: None,
+            items: item(),
+            payments: vec![OrderPaymentPayload {
+                method: "pix".to_string(),
+                amount: 10.0,
+                tendered: Some(20.0),
+            }],
+            delivery: None,
+            location_id: None,
+            allow_expired: None,
+        };
+        assert!(matches!(create_order(&pool, payload, None).await, Err(AppError::Validation(_))));
+
+        // Valor zerado
+        let mut payments = cash_payment(0.0);
+        payments.extend(cash_payment(10.0));
+        let payload = CreateOrderPayload {
+            customer_id: None,
+            items: item(),
+            payments,
+            delivery: None,
+            location_id: None,
+            allow_expired: None,
+        };
+        assert!(matches!(create_order(&pool, payload, None).await, Err(AppError::Validation(_))));
+
+        // Nenhum pagamento
+        let payload = CreateOrderPayload {
+            customer_id: None,
+            items: item(),
+            payments: vec![],
+            delivery: None,
+            location_id: None,
+            allow_expired: None,
         };
+        assert!(matches!(create_order(&pool, payload, None).await, Err(AppError::Validation(_))));
 
-        assert!(create_order(&pool, payload).await.is_err());
+        // Nenhum pedido deve ter sido gravado
+        assert!(get_all_orders(&pool).await.unwrap().is_empty());
+        let product = products::get_product_by_id(&pool, product_id).await.unwrap();
+        assert_eq!(product.stock_full, 100);
     }
 
     #[tokio::test]
     async fn test_get_all_orders() {
         let pool = setup_test_db().await;
         cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
 
         let product_id = create_test_product(&pool).await;
 
@@ -372,8 +858,12 @@ mod tests {
                     unit_price: 10.0,
                 },
             ],
+            payments: cash_payment(10.0),
+            delivery: None,
+            location_id: None,
+            allow_expired: None,
         };
-        create_order(&pool, payload1).await.unwrap();
+        create_order(&pool, payload1, None).await.unwrap();
 
         let payload2 = CreateOrderPayload {
             customer_id: None,
@@ -385,8 +875,12 @@ mod tests {
                     unit_price: 10.0,
                 },
             ],
+            payments: cash_payment(20.0),
+            delivery: None,
+            location_id: None,
+            allow_expired: None,
         };
-        create_order(&pool, payload2).await.unwrap();
+        create_order(&pool, payload2, None).await.unwrap();
 
         let orders = get_all_orders(&pool).await.unwrap();
         assert_eq!(orders.len(), 2);
@@ -396,6 +890,7 @@ mod tests {
     async fn test_get_orders_by_customer() {
         let pool = setup_test_db().await;
         cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
 
         let product_id = create_test_product(&pool).await;
         let customer_id = create_test_customer(&pool).await;
@@ -410,8 +905,12 @@ mod tests {
                     unit_price: 10.0,
                 },
             ],
+            payments: cash_payment(10.0),
+            delivery: None,
+            location_id: None,
+            allow_expired: None,
         };
-        create_order(&pool, payload1).await.unwrap();
+        create_order(&pool, payload1, None).await.unwrap();
 
         let payload2 = CreateOrderPayload {
             customer_id: None,
@@ -423,11 +922,157 @@ mod tests {
                     unit_price: 10.0,
                 },
             ],
+            payments: cash_payment(10.0),
+            delivery: None,
+            location_id: None,
+            allow_expired: None,
         };
-        create_order(&pool, payload2).await.unwrap();
+        create_order(&pool, payload2, None).await.unwrap();
 
         let orders = get_orders_by_customer(&pool, customer_id).await.unwrap();
         assert_eq!(orders.len(), 1);
     }
-}
 
+    #[tokio::test]
+    async fn test_cancel_order() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let product_id = create_test_product(&pool).await;
+        let user_id = create_test_user(&pool).await;
+        let customer_id = customers::create_customer(&pool, CreateCustomerPayload {
+            name: "João Silva".to_string(),
+            phone: None,
+            address: None,
+            notes: None,
+            credit_limit: Some(100.0),
+        }).await.unwrap();
+
+        let order_id = create_order(&pool, CreateOrderPayload {
+            customer_id: Some(customer_id),
+            items: vec![OrderItemPayload {
+                product_id,
+                quantity: 2,
+                returned_bottle: true,
+                unit_price: 5.0,
+            }],
+            payments: vec![OrderPaymentPayload {
+                method: "on_account".to_string(),
+                amount: 10.0,
+                tendered: None,
+            }],
+            delivery: None,
+            location_id: None,
+            allow_expired: None,
+        }, None).await.unwrap();
+
+        // Motivo obrigatório
+        assert!(cancel_order(&pool, order_id, CancelOrderPayload {
+            reason: "  ".to_string(),
+        }, user_id).await.is_err());
+
+        // Pedido inexistente
+        assert!(matches!(cancel_order(&pool, 99999, CancelOrderPayload {
+            reason: "Teste".to_string(),
+        }, user_id).await, Err(AppError::NotFound(_))));
+
+        cancel_order(&pool, order_id, CancelOrderPayload {
+            reason: "Cliente desistiu".to_string(),
+        }, user_id).await.unwrap();
+
+        // Pedido permanece registrado como cancelado
+        let order = get_order_by_id(&pool, order_id).await.unwrap();
+        assert_eq!(order.order.status, "cancelled");
+        assert_eq!(order.order.cancel_reason, Some("Cliente desistiu".to_string()));
+        assert_eq!(order.order.cancelled_by, Some(user_id));
+        assert_eq!(order.order.cancelled_by_username, Some("admin".to_string()));
+        assert!(order.order.cancelled_at.is_some());
+        assert_eq!(order.items.len(), 1);
+
+        let product = products::get_product_by_id(&pool, product_id).await.unwrap();
+        assert_eq!(product.stock_full, 100);
+        assert_eq!(product.stock_empty, 0);
+
+        // Fiado estornado
+        let statement = customer_accounts::get_customer_statement(&pool, customer_id).await.unwrap();
+        assert_eq!(statement.balance, 0.0);
+        assert_eq!(statement.entries.len(), 2);
+
+        // Cancelado não entra nos totais
+        let stats = stock::get_dashboard_stats(&pool).await.unwrap();
+        assert_eq!(stats.sales_today, 0.0);
+        assert!(stats.top_products.is_empty());
+
+        // Não cancela duas vezes
+        assert!(cancel_order(&pool, order_id, CancelOrderPayload {
+            reason: "Outra vez".to_string(),
+        }, user_id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_order_from_closed_session_refunds() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let product_id = create_test_product(&pool).await;
+        let user_id = create_test_user(&pool).await;
+
+        let order_id = create_order(&pool, CreateOrderPayload {
+            customer_id: None,
+            items: vec![OrderItemPayload {
+                product_id,
+                quantity: 2,
+                returned_bottle: true,
+                unit_price: 5.0,
+            }],
+            payments: vec![
+                OrderPaymentPayload { method: "cash".to_string(), amount: 4.0, tendered: None },
+                OrderPaymentPayload { method: "pix".to_string(), amount: 6.0, tendered: None },
+            ],
+            delivery: None,
+            location_id: None,
+            allow_expired: None,
+        }, None).await.unwrap();
+
+        cash_sessions::close_session(&pool, CloseCashSessionPayload {
+            counts: vec![],
+            notes: None,
+        }, user_id).await.unwrap();
+
+        // Sem caixa aberto não há de onde estornar
+        assert!(matches!(cancel_order(&pool, order_id, CancelOrderPayload {
+            reason: "Cliente desistiu".to_string(),
+        }, user_id).await, Err(AppError::BusinessLogic(_))));
+        assert_ne!(get_order_by_id(&pool, order_id).await.unwrap().order.status, "cancelled");
+
+        // Gaveta sem dinheiro suficiente para o estorno
+        let session_id = cash_sessions::open_session(&pool, OpenCashSessionPayload {
+            opening_float: 2.0,
+            notes: None,
+        }, user_id).await.unwrap();
+        assert!(matches!(cancel_order(&pool, order_id, CancelOrderPayload {
+            reason: "Cliente desistiu".to_string(),
+        }, user_id).await, Err(AppError::BusinessLogic(_))));
+
+        cash_sessions::add_movement(&pool, CashMovementPayload {
+            movement_type: "SUPPLY".to_string(),
+            amount: 48.0,
+            reason: None,
+        }, user_id).await.unwrap();
+
+        cancel_order(&pool, order_id, CancelOrderPayload {
+            reason: "Cliente desistiu".to_string(),
+        }, user_id).await.unwrap();
+
+        // O caixa fechado mantém a conferência; o estorno sai do caixa aberto
+        let report = cash_sessions::get_session_report(&pool, session_id).await.unwrap();
+        let expected = |method: &str| report.lines.iter().find(|l| l.method == method).unwrap().expected;
+        assert_eq!(expected("cash"), 46.0);
+        assert_eq!(expected("pix"), -6.0);
+        assert_eq!(report.withdrawals, 4.0);
+        assert_eq!(report.movements.len(), 3);
+        assert_eq!(report.movements[2].method, "pix");
+    }
+}
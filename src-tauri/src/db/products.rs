@@ -1,6 +1,11 @@
 use crate::models::{Product, CreateProductPayload, UpdateProductPayload};
 use crate::errors::AppError;
-use sqlx::SqlitePool;
+use crate::db::stock::{adjust_location_stock, record_movement, StockChange};
+use crate::db::stock_locations::{location_stock, DEFAULT_LOCATION_ID};
+use crate::db::stock_lots;
+use crate::db::reports::validate_date;
+use crate::db::settings::{get_int_setting, DEFAULT_MIN_STOCK, DEFAULT_MIN_STOCK_KEY};
+use sqlx::{Acquire, Sqlite, SqliteConnection, SqliteExecutor, SqlitePool};
 
 pub async fn get_all_products(pool: &SqlitePool) -> Result<Vec<Product>, AppError> {
     let products = sqlx::query_as::<_, Product>(
@@ -12,23 +17,28 @@ pub async fn get_all_products(pool: &SqlitePool) -> Result<Vec<Product>, AppErro
     Ok(products)
 }
 
-pub async fn get_product_by_id(pool: &SqlitePool, id: i64) -> Result<Product, AppError> {
+pub async fn get_product_by_id(conn: impl SqliteExecutor<'_>, id: i64) -> Result<Product, AppError> {
     let product = sqlx::query_as::<_, Product>(
         "SELECT * FROM products WHERE id = ?"
     )
     .bind(id)
-    .fetch_optional(pool)
+    .fetch_optional(conn)
     .await?
     .ok_or_else(|| AppError::NotFound(format!("Product with id {} not found", id)))?;
     
     Ok(product)
 }
 
-pub async fn create_product(
-    pool: &SqlitePool,
-    payload: CreateProductPayload,
-) -> Result<i64, AppError> {
-    // Validação
+fn validate_reorder_levels(min_stock: Option<i64>, reorder_qty: Option<i64>) -> Result<(), AppError> {
+    if min_stock.is_some_and(|min| min < 0) || reorder_qty.is_some_and(|qty| qty < 0) {
+        return Err(AppError::Validation("Minimum stock and reorder quantity cannot be negative".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Regras do cadastro de produto; valem também para a importação
+pub(crate) fn validate_new_product(payload: &CreateProductPayload) -> Result<(), AppError> {
     if payload.name.is_empty() {
         return Err(AppError::Validation("Product name cannot be empty".to_string()));
     }
@@ -41,9 +51,41 @@ pub async fn create_product(
         return Err(AppError::Validation("Prices cannot be negative".to_string()));
     }
 
+    if payload.cost_price.is_some_and(|cost| cost < 0.0) {
+        return Err(AppError::Validation("Cost cannot be negative".to_string()));
+    }
+
+    validate_reorder_levels(payload.min_stock, payload.reorder_qty)
+}
+
+pub async fn create_product(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    payload: CreateProductPayload,
+) -> Result<i64, AppError> {
+    validate_new_product(&payload)?;
+
+    let mut tx = conn.begin().await?;
+    let id = insert_product(&mut tx, &payload).await?;
+    tx.commit().await?;
+
+    Ok(id)
+}
+
+/// Insere o produto já validado, com o estoque inicial na loja
+pub(crate) async fn insert_product(
+    conn: &mut SqliteConnection,
+    payload: &CreateProductPayload,
+) -> Result<i64, AppError> {
+    // O custo informado no cadastro vale como custo médio do estoque inicial
+    let cost_price = payload.cost_price.unwrap_or(0.0);
+    let min_stock = match payload.min_stock {
+        Some(min_stock) => min_stock,
+        None => get_int_setting(&mut *conn, DEFAULT_MIN_STOCK_KEY, DEFAULT_MIN_STOCK).await?,
+    };
+
     let id = sqlx::query(
-        "INSERT INTO products (name, description, type, price_refill, price_full, stock_full, stock_empty, expiry_month, expiry_year)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        "INSERT INTO products (name, description, type, price_refill, price_full, stock_full, stock_empty, cost_price, average_cost, min_stock, reorder_qty)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, COALESCE(?, 0))"
     )
     .bind(&payload.name)
     .bind(&payload.description)
@@ -52,22 +94,47 @@ pub async fn create_product(
     .bind(payload.price_full)
     .bind(payload.stock_full.unwrap_or(0))
     .bind(payload.stock_empty.unwrap_or(0))
-    .bind(&payload.expiry_month)
-    .bind(&payload.expiry_year)
-    .execute(pool)
+    .bind(cost_price)
+    .bind(cost_price)
+    .bind(min_stock)
+    .bind(payload.reorder_qty)
+    .execute(&mut *conn)
     .await?
     .last_insert_rowid();
 
+    // Estoque inicial fica na loja
+    sqlx::query(
+        "INSERT INTO product_stock (product_id, location_id, stock_full, stock_empty) VALUES (?, ?, ?, ?)"
+    )
+    .bind(id)
+    .bind(DEFAULT_LOCATION_ID)
+    .bind(payload.stock_full.unwrap_or(0))
+    .bind(payload.stock_empty.unwrap_or(0))
+    .execute(&mut *conn)
+    .await?;
+
+    // Estoque inicial com validade vira o primeiro lote
+    if let Some(expiry_date) = &payload.expiry_date {
+        validate_date(&mut *conn, "expiry date", expiry_date).await?;
+
+        let stock_full = payload.stock_full.unwrap_or(0);
+        if stock_full > 0 {
+            stock_lots::add_to_lot(conn, None, id, DEFAULT_LOCATION_ID, expiry_date, stock_full).await?;
+        }
+    }
+
     Ok(id)
 }
 
 pub async fn update_product(
-    pool: &SqlitePool,
+    conn: impl Acquire<'_, Database = Sqlite>,
     id: i64,
     payload: UpdateProductPayload,
 ) -> Result<(), AppError> {
+    let mut tx = conn.begin().await?;
+
     // Verifica se existe
-    get_product_by_id(pool, id).await?;
+    let current = get_product_by_id(&mut *tx, id).await?;
 
     // Validações
     if let Some(ref name) = payload.name {
@@ -94,6 +161,12 @@ pub async fn update_product(
         }
     }
 
+    if payload.cost_price.is_some_and(|cost| cost < 0.0) {
+        return Err(AppError::Validation("Cost cannot be negative".to_string()));
+    }
+
+    validate_reorder_levels(payload.min_stock, payload.reorder_qty)?;
+
     // Constrói query dinamicamente usando QueryBuilder
     let mut query_builder = sqlx::QueryBuilder::new("UPDATE products SET ");
     let mut has_updates = false;
@@ -161,21 +234,33 @@ pub async fn update_product(
         has_updates = true;
     }
 
-    if payload.expiry_month.is_some() {
+    // Correção manual do custo redefine também o custo médio
+    if let Some(cost_price) = payload.cost_price {
         if has_updates {
             query_builder.push(", ");
         }
-        query_builder.push("expiry_month = ");
-        query_builder.push_bind(&payload.expiry_month);
+        query_builder.push("cost_price = ");
+        query_builder.push_bind(cost_price);
+        query_builder.push(", average_cost = ");
+        query_builder.push_bind(cost_price);
         has_updates = true;
     }
 
-    if payload.expiry_year.is_some() {
+    if let Some(min_stock) = payload.min_stock {
         if has_updates {
             query_builder.push(", ");
         }
-        query_builder.push("expiry_year = ");
-        query_builder.push_bind(&payload.expiry_year);
+        query_builder.push("min_stock = ");
+        query_builder.push_bind(min_stock);
+        has_updates = true;
+    }
+
+    if let Some(reorder_qty) = payload.reorder_qty {
+        if has_updates {
+            query_builder.push(", ");
+        }
+        query_builder.push("reorder_qty = ");
+        query_builder.push_bind(reorder_qty);
         has_updates = true;
     }
 
@@ -186,20 +271,63 @@ pub async fn update_product(
     query_builder.push(" WHERE id = ");
     query_builder.push_bind(id);
 
-    query_builder.build().execute(pool).await?;
+    query_builder.build().execute(&mut *tx).await?;
+
+    // Edição direta de saldo fica registrada como ajuste da loja no histórico
+    let stock_changes = [
+        ("full", payload.stock_full.map(|v| v - current.stock_full)),
+        ("empty", payload.stock_empty.map(|v| v - current.stock_empty)),
+    ];
+
+    for (stock_kind, delta) in stock_changes {
+        if let Some(delta) = delta.filter(|d| *d != 0) {
+            // A baixa sai só da loja; o que está em outros locais não pode ser zerado aqui
+            if delta < 0 {
+                let (store_full, store_empty) = location_stock(&mut tx, id, DEFAULT_LOCATION_ID).await?;
+                let available = if stock_kind == "full" { store_full } else { store_empty };
+
+                if available < -delta {
+                    return Err(AppError::BusinessLogic(
+                        format!("Insufficient {} stock in the store for product {}. Available: {}, Requested: {}",
+                            stock_kind, id, available, -delta)
+                    ));
+                }
+            }
+
+            adjust_location_stock(&mut tx, id, DEFAULT_LOCATION_ID, stock_kind, delta).await?;
+            let movement_id = record_movement(&mut tx, &StockChange {
+                product_id: id,
+                stock_kind,
+                movement_type: "ADJUST",
+                quantity: delta,
+                ..Default::default()
+            }).await?;
+
+            // Redução de cheios sai dos lotes que vencem antes
+            if stock_kind == "full" && delta < 0 {
+                stock_lots::consume_lots(&mut tx, movement_id, id, DEFAULT_LOCATION_ID, -delta).await?;
+            }
+        }
+    }
+
+    tx.commit().await?;
 
     Ok(())
 }
 
-pub async fn delete_product(pool: &SqlitePool, id: i64) -> Result<(), AppError> {
+pub async fn delete_product(conn: impl Acquire<'_, Database = Sqlite>, id: i64) -> Result<(), AppError> {
+    let mut tx = conn.begin().await?;
+
     // Verifica se existe
-    get_product_by_id(pool, id).await?;
+    get_product_by_id(&mut *tx, id).await?;
 
     sqlx::query("DELETE FROM products WHERE id = ?")
         .bind(id)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
 
+    tx.commit().await?;
+
     Ok(())
 }
 
@@ -221,6 +349,10 @@ mod tests {
             price_full: 10.0,
             stock_full: Some(100),
             stock_empty: Some(0),
+            expiry_date: None,
+            cost_price: None,
+            min_stock: None,
+            reorder_qty: None,
         };
 
         let id = create_product(&pool, payload).await.unwrap();
@@ -247,6 +379,10 @@ mod tests {
             price_full: 10.0,
             stock_full: None,
             stock_empty: None,
+            expiry_date: None,
+            cost_price: None,
+            min_stock: None,
+            reorder_qty: None,
         };
         assert!(create_product(&pool, payload).await.is_err());
 
@@ -259,6 +395,10 @@ mod tests {
             price_full: 10.0,
             stock_full: None,
             stock_empty: None,
+            expiry_date: None,
+            cost_price: None,
+            min_stock: None,
+            reorder_qty: None,
         };
         assert!(create_product(&pool, payload).await.is_err());
 
@@ -271,6 +411,10 @@ mod tests {
             price_full: 10.0,
             stock_full: None,
             stock_empty: None,
+            expiry_date: None,
+            cost_price: None,
+            min_stock: None,
+            reorder_qty: None,
         };
         assert!(create_product(&pool, payload).await.is_err());
     }
@@ -289,6 +433,10 @@ mod tests {
             price_full: 10.0,
             stock_full: None,
             stock_empty: None,
+            expiry_date: None,
+            cost_price: None,
+            min_stock: None,
+            reorder_qty: None,
         };
         create_product(&pool, payload1).await.unwrap();
 
@@ -300,6 +448,10 @@ mod tests {
             price_full: 80.0,
             stock_full: None,
             stock_empty: None,
+            expiry_date: None,
+            cost_price: None,
+            min_stock: None,
+            reorder_qty: None,
         };
         create_product(&pool, payload2).await.unwrap();
 
@@ -322,6 +474,10 @@ mod tests {
             price_full: 10.0,
             stock_full: None,
             stock_empty: None,
+            expiry_date: None,
+            cost_price: None,
+            min_stock: None,
+            reorder_qty: None,
         };
 
         let id = create_product(&pool, payload).await.unwrap();
@@ -347,6 +503,10 @@ mod tests {
             price_full: 10.0,
             stock_full: None,
             stock_empty: None,
+            expiry_date: None,
+            cost_price: None,
+            min_stock: None,
+            reorder_qty: None,
         };
 
         let id = create_product(&pool, payload).await.unwrap();
@@ -359,6 +519,9 @@ mod tests {
             price_full: None,
             stock_full: Some(150),
             stock_empty: None,
+            cost_price: None,
+            min_stock: None,
+            reorder_qty: None,
         };
 
         update_product(&pool, id, update_payload).await.unwrap();
@@ -384,6 +547,10 @@ mod tests {
             price_full: 10.0,
             stock_full: None,
             stock_empty: None,
+            expiry_date: None,
+            cost_price: None,
+            min_stock: None,
+            reorder_qty: None,
         };
 
         let id = create_product(&pool, payload).await.unwrap();
@@ -397,6 +564,9 @@ mod tests {
             price_full: None,
             stock_full: None,
             stock_empty: None,
+            cost_price: None,
+            min_stock: None,
+            reorder_qty: None,
         };
         assert!(update_product(&pool, id, update_payload).await.is_err());
 
@@ -409,6 +579,9 @@ mod tests {
             price_full: None,
             stock_full: None,
             stock_empty: None,
+            cost_price: None,
+            min_stock: None,
+            reorder_qty: None,
         };
         assert!(update_product(&pool, id, update_payload).await.is_err());
 
@@ -421,6 +594,9 @@ mod tests {
             price_full: None,
             stock_full: None,
             stock_empty: None,
+            cost_price: None,
+            min_stock: None,
+            reorder_qty: None,
         };
         assert!(update_product(&pool, id, update_payload).await.is_err());
     }
@@ -438,6 +614,10 @@ mod tests {
             price_full: 10.0,
             stock_full: None,
             stock_empty: None,
+            expiry_date: None,
+            cost_price: None,
+            min_stock: None,
+            reorder_qty: None,
         };
 
         let id = create_product(&pool, payload).await.unwrap();
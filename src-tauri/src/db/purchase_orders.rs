@@ -0,0 +1,403 @@
+use crate::models::{
+    PurchaseOrder, PurchaseOrderItem, PurchaseOrderWithItems, PurchaseReceipt,
+    CreatePurchaseOrderPayload, ReceivePurchaseOrderPayload,
+};
+use crate::errors::AppError;
+use crate::db::suppliers::get_supplier_by_id;
+use crate::db::products::get_product_by_id;
+use crate::db::reports::validate_date;
+use crate::db::stock::{apply_stock_change, StockChange};
+use sqlx::{Acquire, Sqlite, SqlitePool};
+
+const PURCHASE_ORDER_SELECT: &str =
+    "SELECT po.id, po.supplier_id, s.name as supplier_name, po.status, po.expected_date,
+            po.notes, po.user_id, po.created_at,
+            COALESCE((SELECT SUM(quantity_ordered * unit_cost)
+                      FROM purchase_order_items WHERE purchase_order_id = po.id), 0.0) as total_cost
+     FROM purchase_orders po
+     JOIN suppliers s ON po.supplier_id = s.id";
+
+pub async fn get_all_purchase_orders(
+    pool: &SqlitePool,
+    status: Option<String>,
+) -> Result<Vec<PurchaseOrder>, AppError> {
+    let orders = sqlx::query_as::<_, PurchaseOrder>(&format!(
+        "{} WHERE ? IS NULL OR po.status = ? ORDER BY po.created_at DESC, po.id DESC",
+        PURCHASE_ORDER_SELECT
+    ))
+    .bind(&status)
+    .bind(&status)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(orders)
+}
+
+pub async fn get_purchase_order_by_id(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    id: i64,
+) -> Result<PurchaseOrderWithItems, AppError> {
+    let mut conn = conn.acquire().await?;
+
+    let order = sqlx::query_as::<_, PurchaseOrder>(&format!("{} WHERE po.id = ?", PURCHASE_ORDER_SELECT))
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Purchase order with id {} not found", id)))?;
+
+    let items = sqlx::query_as::<_, PurchaseOrderItem>(
+        "SELECT poi.id, poi.purchase_order_id, poi.product_id, p.name as product_name,
+                poi.quantity_ordered, poi.quantity_received, poi.unit_cost
+         FROM purchase_order_items poi
+         JOIN products p ON poi.product_id = p.id
+         WHERE poi.purchase_order_id = ?
+         ORDER BY poi.id"
+    )
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let receipts = sqlx::query_as::<_, PurchaseReceipt>(
+        "SELECT * FROM purchase_receipts WHERE purchase_order_id = ? ORDER BY received_at, id"
+    )
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(PurchaseOrderWithItems { order, items, receipts })
+}
+
+pub async fn create_purchase_order(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    payload: CreatePurchaseOrderPayload,
+    user_id: Option<i64>,
+) -> Result<i64, AppError> {
+    if payload.items.is_empty() {
+        return Err(AppError::Validation("Purchase order must have at least one item".to_string()));
+    }
+
+    for item in &payload.items {
+        if item.quantity <= 0 {
+            return Err(AppError::Validation("Quantity must be positive".to_string()));
+        }
+        if item.unit_cost < 0.0 {
+            return Err(AppError::Validation("Unit cost cannot be negative".to_string()));
+        }
+    }
+
+    let mut tx = conn.begin().await?;
+
+    if let Some(expected_date) = &payload.expected_date {
+        validate_date(&mut *tx, "expected date", expected_date).await?;
+    }
+
+    // Verifica se existem
+    get_supplier_by_id(&mut *tx, payload.supplier_id).await?;
+    for item in &payload.items {
+        get_product_by_id(&mut *tx, item.product_id).await?;
+    }
+
+    let id = sqlx::query(
+        "INSERT INTO purchase_orders (supplier_id, expected_date, notes, user_id)
+         VALUES (?, ?, ?, ?)"
+    )
+    .bind(payload.supplier_id)
+    .bind(&payload.expected_date)
+    .bind(&payload.notes)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?
+    .last_insert_rowid();
+
+    for item in &payload.items {
+        sqlx::query(
+            "INSERT INTO purchase_order_items (purchase_order_id, product_id, quantity_ordered, unit_cost)
+             VALUES (?, ?, ?, ?)"
+        )
+        .bind(id)
+        .bind(item.product_id)
+        .bind(item.quantity)
+        .bind(item.unit_cost)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(id)
+}
+
+/// Recebe total ou parcialmente um pedido de compra, dando entrada no estoque
+/// de cheios com o custo do pedido e vinculando cada movimentação ao recebimento
+pub async fn receive_purchase_order(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    id: i64,
+    payload: ReceivePurchaseOrderPayload,
+    user_id: Option<i64>,
+) -> Result<i64, AppError> {
+    if payload.lines.is_empty() {
+        return Err(AppError::Validation("Receipt must have at least one line".to_string()));
+    }
+
+    let mut tx = conn.begin().await?;
+
+    let status: (String,) = sqlx::query_as("SELECT status FROM purchase_orders WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Purchase order with id {} not found", id)))?;
+
+    if status.0 != "open" && status.0 != "partial" {
+        return Err(AppError::BusinessLogic(
+            format!("Purchase order {} is {} and cannot be received", id, status.0)
+        ));
+    }
+
+    let receipt_id = sqlx::query(
+        "INSERT INTO purchase_receipts (purchase_order_id, invoice_number, notes, user_id)
+         VALUES (?, ?, ?, ?)"
+    )
+    .bind(id)
+    .bind(&payload.invoice_number)
+    .bind(&payload.notes)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?
+    .last_insert_rowid();
+
+    for line in &payload.lines {
+        if line.quantity <= 0 {
+            return Err(AppError::Validation("Quantity must be positive".to_string()));
+        }
+
+        let item: (i64, i64, i64, f64) = sqlx::query_as(
+            "SELECT product_id, quantity_ordered, quantity_received, unit_cost
+             FROM purchase_order_items WHERE id = ? AND purchase_order_id = ?"
+        )
+        .bind(line.item_id)
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound(
+            format!("Item {} not found in purchase order {}", line.item_id, id)
+        ))?;
+
+        let (product_id, ordered, received, unit_cost) = item;
+
+        if received + line.quantity > ordered {
+            return Err(AppError::BusinessLogic(
+                format!("Item {} has {} pending unit(s), cannot receive {}",
+                    line.item_id, ordered - received, line.quantity)
+            ));
+        }
+
+        sqlx::query(
+            "UPDATE purchase_order_items SET quantity_received = quantity_received + ? WHERE id = ?"
+        )
+        .bind(line.quantity)
+        .bind(line.item_id)
+        .execute(&mut *tx)
+        .await?;
+
+        apply_stock_change(&mut tx, StockChange {
+            product_id,
+            stock_kind: "full",
+            movement_type: "IN",
+            quantity: line.quantity,
+            unit_cost: Some(unit_cost),
+            purchase_receipt_id: Some(receipt_id),
+            expiry_date: line.expiry_date.as_deref(),
+            ..Default::default()
+        }).await?;
+    }
+
+    // Pedido fica parcial até que todos os itens tenham sido recebidos
+    sqlx::query(
+        "UPDATE purchase_orders
+         SET status = CASE WHEN EXISTS (
+                 SELECT 1 FROM purchase_order_items
+                 WHERE purchase_order_id = ? AND quantity_received < quantity_ordered
+             ) THEN 'partial' ELSE 'received' END
+         WHERE id = ?"
+    )
+    .bind(id)
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(receipt_id)
+}
+
+/// Cancela o saldo pendente do pedido; o que já foi recebido permanece no estoque
+pub async fn cancel_purchase_order(conn: impl Acquire<'_, Database = Sqlite>, id: i64) -> Result<(), AppError> {
+    let mut conn = conn.acquire().await?;
+
+    let order = get_purchase_order_by_id(&mut *conn, id).await?;
+
+    if order.order.status != "open" && order.order.status != "partial" {
+        return Err(AppError::BusinessLogic(
+            format!("Purchase order {} is {} and cannot be cancelled", id, order.order.status)
+        ));
+    }
+
+    sqlx::query("UPDATE purchase_orders SET status = 'cancelled' WHERE id = ?")
+        .bind(id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_helpers::{
+        setup_test_db, cleanup_test_db, create_test_product, product_payload, create_test_supplier,
+    };
+    use crate::db::{products, stock, suppliers};
+    use crate::models::{CreateProductPayload, PurchaseOrderItemPayload, ReceiveLinePayload};
+
+    async fn create_test_purchase_order(pool: &SqlitePool, supplier_id: i64, product_id: i64) -> i64 {
+        create_purchase_order(pool, CreatePurchaseOrderPayload {
+            supplier_id,
+            expected_date: Some("2026-11-01".to_string()),
+            notes: None,
+            items: vec![PurchaseOrderItemPayload {
+                product_id,
+                quantity: 10,
+                unit_cost: 70.0,
+            }],
+        }, None).await.unwrap()
+    }
+
+    fn receive_payload(item_id: i64, quantity: i64, invoice_number: &str) -> ReceivePurchaseOrderPayload {
+        ReceivePurchaseOrderPayload {
+            invoice_number: Some(invoice_number.to_string()),
+            notes: None,
+            lines: vec![ReceiveLinePayload { item_id, quantity, expiry_date: None }],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_purchase_order() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        let product_id = create_test_product(&pool, CreateProductPayload {
+            stock_full: Some(0),
+            ..product_payload("Gás P13", "gas")
+        }).await;
+        let supplier_id = create_test_supplier(&pool, "Distribuidora Central").await;
+        let id = create_test_purchase_order(&pool, supplier_id, product_id).await;
+
+        let order = get_purchase_order_by_id(&pool, id).await.unwrap();
+        assert_eq!(order.order.status, "open");
+        assert_eq!(order.order.supplier_name, "Distribuidora Central");
+        assert_eq!(order.order.total_cost, 700.0);
+        assert_eq!(order.items.len(), 1);
+        assert_eq!(order.items[0].quantity_received, 0);
+
+        // Data inválida ou inexistente
+        for expected_date in ["amanhã", "2030-02-31"] {
+            assert!(matches!(create_purchase_order(&pool, CreatePurchaseOrderPayload {
+                supplier_id,
+                expected_date: Some(expected_date.to_string()),
+                notes: None,
+                items: vec![PurchaseOrderItemPayload { product_id, quantity: 1, unit_cost: 70.0 }],
+            }, None).await, Err(AppError::Validation(_))));
+        }
+
+        // Produto inexistente
+        assert!(matches!(create_purchase_order(&pool, CreatePurchaseOrderPayload {
+            supplier_id,
+            expected_date: None,
+            notes: None,
+            items: vec![PurchaseOrderItemPayload { product_id: 99999, quantity: 1, unit_cost: 70.0 }],
+        }, None).await, Err(AppError::NotFound(_))));
+        assert_eq!(get_all_purchase_orders(&pool, None).await.unwrap().len(), 1);
+
+        // Fornecedor com pedidos não pode ser excluído
+        assert!(suppliers::delete_supplier(&pool, supplier_id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_partial_receiving() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        let product_id = create_test_product(&pool, CreateProductPayload {
+            stock_full: Some(0),
+            ..product_payload("Gás P13", "gas")
+        }).await;
+        let supplier_id = create_test_supplier(&pool, "Distribuidora Central").await;
+        let id = create_test_purchase_order(&pool, supplier_id, product_id).await;
+        let item_id = get_purchase_order_by_id(&pool, id).await.unwrap().items[0].id;
+
+        let first = receive_purchase_order(&pool, id, receive_payload(item_id, 4, "NF-001"), None)
+            .await
+            .unwrap();
+
+        let order = get_purchase_order_by_id(&pool, id).await.unwrap();
+        assert_eq!(order.order.status, "partial");
+        assert_eq!(order.items[0].quantity_received, 4);
+
+        let product = products::get_product_by_id(&pool, product_id).await.unwrap();
+        assert_eq!(product.stock_full, 4);
+
+        // Entrada rastreável até o recebimento e com o custo do pedido
+        let movements = stock::get_all_movements(&pool).await.unwrap();
+        assert_eq!(movements[0].movement_type, "IN");
+        assert_eq!(movements[0].purchase_receipt_id, Some(first));
+        assert_eq!(movements[0].unit_cost, Some(70.0));
+
+        // Não recebe além do saldo pendente
+        assert!(receive_purchase_order(&pool, id, receive_payload(item_id, 7, "NF-002"), None)
+            .await
+            .is_err());
+
+        receive_purchase_order(&pool, id, receive_payload(item_id, 6, "NF-002"), None)
+            .await
+            .unwrap();
+
+        let order = get_purchase_order_by_id(&pool, id).await.unwrap();
+        assert_eq!(order.order.status, "received");
+        assert_eq!(order.receipts.len(), 2);
+        assert_eq!(order.receipts[1].invoice_number, Some("NF-002".to_string()));
+
+        let product = products::get_product_by_id(&pool, product_id).await.unwrap();
+        assert_eq!(product.stock_full, 10);
+
+        // Pedido recebido não pode ser cancelado nem recebido de novo
+        assert!(cancel_purchase_order(&pool, id).await.is_err());
+        assert!(receive_purchase_order(&pool, id, receive_payload(item_id, 1, "NF-003"), None)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_purchase_order() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        let product_id = create_test_product(&pool, CreateProductPayload {
+            stock_full: Some(0),
+            ..product_payload("Gás P13", "gas")
+        }).await;
+        let supplier_id = create_test_supplier(&pool, "Distribuidora Central").await;
+        let id = create_test_purchase_order(&pool, supplier_id, product_id).await;
+        let item_id = get_purchase_order_by_id(&pool, id).await.unwrap().items[0].id;
+
+        cancel_purchase_order(&pool, id).await.unwrap();
+
+        let open = get_all_purchase_orders(&pool, Some("open".to_string())).await.unwrap();
+        assert!(open.is_empty());
+        let cancelled = get_all_purchase_orders(&pool, Some("cancelled".to_string())).await.unwrap();
+        assert_eq!(cancelled.len(), 1);
+
+        assert!(receive_purchase_order(&pool, id, receive_payload(item_id, 1, "NF-001"), None)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,213 @@
+use crate::models::{BackupFile, RecoveryInfo};
+use crate::errors::AppError;
+use crate::db::backups::{self, PRE_RECOVERY_TAG};
+use crate::db::init::accept_embedded_checksums;
+use sqlx::migrate::MigrateError;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{ConnectOptions, Connection, SqlitePool};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Quando as migrações falham o app não abre o banco: guarda uma cópia, sobe em modo
+// de recuperação e deixa o responsável escolher o conserto. Nada aqui apaga dados;
+// no pior caso o arquivo é renomeado e fica ao lado do novo.
+
+/// Estado gerenciado pelo Tauri; `None` quando o banco abriu normalmente
+#[derive(Default)]
+pub struct RecoveryState(pub Option<RecoveryInfo>);
+
+impl RecoveryState {
+    pub fn info(&self) -> Result<&RecoveryInfo, AppError> {
+        self.0
+            .as_ref()
+            .ok_or_else(|| AppError::BusinessLogic("App is not in recovery mode".to_string()))
+    }
+}
+
+fn describe(error: &MigrateError) -> (&'static str, Option<i64>, String) {
+    match error {
+        MigrateError::VersionMismatch(version) => (
+            "checksum_mismatch",
+            Some(*version),
+            format!("Migration {} was changed after being applied to this database", version),
+        ),
+        MigrateError::VersionMissing(version) => (
+            "newer_database",
+            Some(*version),
+            format!(
+                "Database was updated by a newer version of the system (migration {} is unknown to this version)",
+                version
+            ),
+        ),
+        MigrateError::Dirty(version) => (
+            "dirty",
+            Some(*version),
+            format!("Migration {} was left partially applied", version),
+        ),
+        other => ("migration_failed", None, format!("Failed to update database: {}", other)),
+    }
+}
+
+/// Monta o diagnóstico e tira a cópia de segurança, com o banco ainda aberto
+pub async fn prepare(pool: &SqlitePool, db_path: &Path, error: &MigrateError) -> RecoveryInfo {
+    let (kind, version, message) = describe(error);
+    eprintln!("Database needs recovery: {}", message);
+
+    let backup_dir = backups::backup_dir(pool)
+        .await
+        .map(|dir| dir.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let (safety_backup, backup_error) = match backups::create_safety_backup(pool, PRE_RECOVERY_TAG).await {
+        Ok(backup) => (Some(backup), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+
+    RecoveryInfo {
+        kind: kind.to_string(),
+        version,
+        message,
+        db_path: db_path.to_string_lossy().into_owned(),
+        backup_dir,
+        safety_backup,
+        backup_error,
+    }
+}
+
+pub fn list_backups(info: &RecoveryInfo) -> Result<Vec<BackupFile>, AppError> {
+    backups::list_backups_in(Path::new(&info.backup_dir))
+}
+
+/// Aceita as migrações embutidas como estão. Serve para quando o arquivo da migração
+/// mudou só na forma (comentário, espaçamento) e o esquema do banco é o mesmo.
+pub async fn accept_checksums(info: &RecoveryInfo) -> Result<u64, AppError> {
+    if info.kind != "checksum_mismatch" {
+        return Err(AppError::BusinessLogic(
+            "Checksums can only be accepted when a migration was changed".to_string(),
+        ));
+    }
+
+    let mut conn = SqliteConnectOptions::new()
+        .filename(&info.db_path)
+        .connect()
+        .await?;
+
+    let updated = accept_embedded_checksums(&mut conn).await?;
+    conn.close().await?;
+
+    Ok(updated)
+}
+
+/// Agenda a troca do banco pelo backup, aplicada na próxima abertura
+pub async fn restore(info: &RecoveryInfo, file_name: &str) -> Result<(), AppError> {
+    let path = backups::resolve_backup(Path::new(&info.backup_dir), file_name)?;
+
+    let check = backups::verify_backup_file(&path).await;
+    if !check.ok {
+        return Err(AppError::BusinessLogic(format!("Backup {} is not usable: {}", file_name, check.message)));
+    }
+
+    backups::mark_restore(data_dir(info)?, &path)
+}
+
+/// Tira o banco do caminho para o app criar um novo na próxima abertura.
+/// O arquivo é renomeado junto com o WAL, então nada do que estava nele se perde.
+pub fn set_aside(info: &RecoveryInfo) -> Result<PathBuf, AppError> {
+    let db_path = Path::new(&info.db_path);
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    let target = data_dir(info)?.join(format!("distribbuidora-set-aside-{}.db", stamp));
+
+    if db_path.exists() {
+        std::fs::rename(db_path, &target)?;
+    }
+
+    for suffix in ["-wal", "-shm"] {
+        let journal = PathBuf::from(format!("{}{}", info.db_path, suffix));
+        if journal.exists() {
+            std::fs::rename(journal, format!("{}{}", target.display(), suffix))?;
+        }
+    }
+
+    Ok(target)
+}
+
+fn data_dir(info: &RecoveryInfo) -> Result<&Path, AppError> {
+    Path::new(&info.db_path)
+        .parent()
+        .ok_or_else(|| AppError::BusinessLogic(format!("Invalid database path: {}", info.db_path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init::{apply_migrations, DbStartup};
+    use crate::db::settings::set_setting;
+
+    async fn open(path: &Path) -> SqlitePool {
+        SqlitePool::connect(&format!("sqlite:{}?mode=rwc", path.display())).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_recovery_from_changed_migration() {
+        let dir = std::env::temp_dir().join(format!("recovery-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join("distribbuidora.db");
+
+        let pool = match apply_migrations(open(&db_path).await, &db_path).await.unwrap() {
+            DbStartup::Ready(pool) => pool,
+            DbStartup::Recovery(info) => panic!("{}", info.message),
+        };
+        set_setting(&pool, "backup.dir", &dir.join("backups").to_string_lossy()).await.unwrap();
+        sqlx::query("INSERT INTO customers (name) VALUES ('Cliente antigo')").execute(&pool).await.unwrap();
+        sqlx::query("UPDATE _sqlx_migrations SET checksum = x'00' WHERE version = 1").execute(&pool).await.unwrap();
+        pool.close().await;
+
+        // O banco fica intacto e ganha uma cópia antes de qualquer conserto
+        let info = match apply_migrations(open(&db_path).await, &db_path).await.unwrap() {
+            DbStartup::Recovery(info) => info,
+            DbStartup::Ready(_) => panic!("changed migration was accepted silently"),
+        };
+        assert_eq!((info.kind.as_str(), info.version), ("checksum_mismatch", Some(1)));
+        let safety = info.safety_backup.clone().unwrap();
+        assert_eq!(safety.tag.as_deref(), Some(PRE_RECOVERY_TAG));
+        assert!(backups::verify_backup_file(Path::new(&safety.path)).await.ok);
+        assert!(list_backups(&info).unwrap().iter().any(|b| b.file_name == safety.file_name));
+
+        assert!(restore(&info, "../distribbuidora.db").await.is_err());
+        assert_eq!(accept_checksums(&info).await.unwrap(), 1);
+
+        let pool = match apply_migrations(open(&db_path).await, &db_path).await.unwrap() {
+            DbStartup::Ready(pool) => pool,
+            DbStartup::Recovery(info) => panic!("{}", info.message),
+        };
+        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM customers").fetch_one(&pool).await.unwrap();
+        assert_eq!(count.0, 1);
+
+        // Banco de versão mais nova não tem checksum a aceitar; pode ser posto de lado
+        sqlx::query(
+            "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+             VALUES (9999, 'future', 1, x'00', 0)"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool.close().await;
+
+        let info = match apply_migrations(open(&db_path).await, &db_path).await.unwrap() {
+            DbStartup::Recovery(info) => info,
+            DbStartup::Ready(_) => panic!("newer database was opened"),
+        };
+        assert_eq!((info.kind.as_str(), info.version), ("newer_database", Some(9999)));
+        assert!(accept_checksums(&info).await.is_err());
+
+        let aside = set_aside(&info).unwrap();
+        assert!(aside.exists());
+        assert!(!db_path.exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
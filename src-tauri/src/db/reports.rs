@@ -0,0 +1,310 @@
+use crate::models::{SalesReport, SalesReportLine};
+use crate::errors::AppError;
+use sqlx::{SqliteExecutor, SqlitePool};
+
+pub const REPORT_GROUPINGS: [&str; 8] = [
+    "day", "week", "month", "product", "product_type", "customer", "operator", "payment_method",
+];
+
+// Pedidos válidos no período; o filtro entra em todas as consultas do relatório
+const PERIOD_FILTER: &str =
+    "o.status != 'cancelled' AND DATE(o.created_at) BETWEEN ? AND ?";
+
+// Quantidade de itens por pedido
+const ORDER_QUANTITY_JOIN: &str =
+    "LEFT JOIN (SELECT order_id, SUM(quantity) AS quantity FROM order_items GROUP BY order_id) iq
+        ON iq.order_id = o.id";
+
+/// Valida uma data no formato 'YYYY-MM-DD'; `field` nomeia o campo na mensagem de erro
+pub(crate) async fn validate_date(conn: impl SqliteExecutor<'_>, field: &str, date: &str) -> Result<(), AppError> {
+    // O modificador normaliza datas inexistentes (31/02 vira março) e elas deixam de bater
+    let valid: (bool,) = sqlx::query_as("SELECT COALESCE(date(?, '+0 days') = ?, 0)")
+        .bind(date)
+        .bind(date)
+        .fetch_one(conn)
+        .await?;
+
+    if !valid.0 {
+        return Err(AppError::Validation(format!("Invalid {}: {}", field, date)));
+    }
+
+    Ok(())
+}
+
+/// Valida um período de datas 'YYYY-MM-DD'; qualquer ponta pode ficar em aberto
+pub(crate) async fn validate_period(
+    pool: &SqlitePool,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+) -> Result<(), AppError> {
+    if let Some(start_date) = start_date {
+        validate_date(pool, "start date", start_date).await?;
+    }
+    if let Some(end_date) = end_date {
+        validate_date(pool, "end date", end_date).await?;
+    }
+
+    if let (Some(start_date), Some(end_date)) = (start_date, end_date) {
+        if start_date > end_date {
+            return Err(AppError::Validation("Start date must not be after end date".to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Monta a consulta das linhas conforme o agrupamento.
+/// Agrupamentos por pedido somam o total do pedido (com taxa de entrega); por produto
+/// somam só os itens; por forma de pagamento somam o valor pago em cada forma, e um
+/// pedido com pagamento dividido conta em cada uma delas.
+fn lines_query(group_by: &str) -> Result<String, AppError> {
+    let order_level = |key: &str, label: &str, order: &str| {
+        format!(
+            "SELECT {key} AS key, {label} AS label, COUNT(*) AS order_count,
+                    COALESCE(SUM(iq.quantity), 0) AS quantity, COALESCE(SUM(o.total), 0.0) AS total
+             FROM orders o
+             {ORDER_QUANTITY_JOIN}
+             LEFT JOIN customers c ON o.customer_id = c.id
+             LEFT JOIN users u ON o.user_id = u.id
+             WHERE {PERIOD_FILTER}
+             GROUP BY {key}
+             ORDER BY {order}"
+        )
+    };
+
+    let item_level = |key: &str, label: &str| {
+        format!(
+            "SELECT {key} AS key, {label} AS label, COUNT(DISTINCT o.id) AS order_count,
+                    SUM(oi.quantity) AS quantity, SUM(oi.quantity * oi.unit_price) AS total
+             FROM order_items oi
+             JOIN orders o ON oi.order_id = o.id
+             JOIN products p ON oi.product_id = p.id
+             WHERE {PERIOD_FILTER}
+             GROUP BY {key}
+             ORDER BY total DESC, key"
+        )
+    };
+
+    let query = match group_by {
+        "day" => order_level("DATE(o.created_at)", "NULL", "key"),
+        // Semana identificada pela segunda-feira
+        "week" => order_level("DATE(o.created_at, 'weekday 0', '-6 days')", "NULL", "key"),
+        "month" => order_level("strftime('%Y-%m', o.created_at)", "NULL", "key"),
+        "customer" => order_level("CAST(o.customer_id AS TEXT)", "c.name", "total DESC, key"),
+        "operator" => order_level("CAST(o.user_id AS TEXT)", "u.username", "total DESC, key"),
+        "product" => item_level("CAST(oi.product_id AS TEXT)", "p.name"),
+        "product_type" => item_level("p.type", "NULL"),
+        "payment_method" => format!(
+            "SELECT op.method AS key, NULL AS label, COUNT(*) AS order_count,
+                    COALESCE(SUM(iq.quantity), 0) AS quantity, SUM(op.amount) AS total
+             FROM (SELECT order_id, method, SUM(amount) AS amount
+                   FROM order_payments GROUP BY order_id, method) op
+             JOIN orders o ON op.order_id = o.id
+             {ORDER_QUANTITY_JOIN}
+             WHERE {PERIOD_FILTER}
+             GROUP BY op.method
+             ORDER BY total DESC, key"
+        ),
+        other => {
+            return Err(AppError::Validation(format!(
+                "Invalid grouping: {}. Expected one of: {}",
+                other,
+                REPORT_GROUPINGS.join(", ")
+            )))
+        }
+    };
+
+    Ok(query)
+}
+
+/// Vendas (pedidos não cancelados) entre duas datas, inclusive, agrupadas
+pub async fn get_sales_report(
+    pool: &SqlitePool,
+    start_date: &str,
+    end_date: &str,
+    group_by: &str,
+) -> Result<SalesReport, AppError> {
+    validate_period(pool, Some(start_date), Some(end_date)).await?;
+
+    let lines = sqlx::query_as::<_, SalesReportLine>(&lines_query(group_by)?)
+        .bind(start_date)
+        .bind(end_date)
+        .fetch_all(pool)
+        .await?;
+
+    // Totais do período, independentes do agrupamento
+    let totals: (i64, i64, f64) = sqlx::query_as(&format!(
+        "SELECT COUNT(*), COALESCE(SUM(iq.quantity), 0), COALESCE(SUM(o.total), 0.0)
+         FROM orders o
+         {ORDER_QUANTITY_JOIN}
+         WHERE {PERIOD_FILTER}"
+    ))
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(SalesReport {
+        start_date: start_date.to_string(),
+        end_date: end_date.to_string(),
+        group_by: group_by.to_string(),
+        order_count: totals.0,
+        quantity: totals.1,
+        total: totals.2,
+        lines,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_helpers::{
+        setup_test_db, cleanup_test_db, create_test_user, open_test_session, create_test_product,
+        product_payload, create_test_customer, customer_payload, payment,
+    };
+    use crate::db::orders;
+    use crate::models::{
+        CancelOrderPayload, CreateOrderPayload, CreateProductPayload, OrderItemPayload,
+    };
+
+    async fn create_product(pool: &SqlitePool, name: &str, product_type: &str) -> i64 {
+        create_test_product(pool, CreateProductPayload {
+            stock_full: Some(100),
+            ..product_payload(name, product_type)
+        }).await
+    }
+
+    fn order(
+        customer_id: Option<i64>,
+        items: Vec<(i64, i64, f64)>,
+        payments: Vec<(&str, f64)>,
+    ) -> CreateOrderPayload {
+        CreateOrderPayload {
+            customer_id,
+            items: items.into_iter().map(|(product_id, quantity, unit_price)| OrderItemPayload {
+                product_id,
+                quantity,
+                returned_bottle: true,
+                unit_price,
+            }).collect(),
+            payments: payments.into_iter().map(|(method, amount)| payment(method, amount)).collect(),
+            delivery: None,
+            location_id: None,
+            allow_expired: None,
+        }
+    }
+
+    async fn set_order_date(pool: &SqlitePool, order_id: i64, created_at: &str) {
+        sqlx::query("UPDATE orders SET created_at = ? WHERE id = ?")
+            .bind(created_at)
+            .bind(order_id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sales_report_groupings() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let water = create_product(&pool, "Água 20L", "water").await;
+        let gas = create_product(&pool, "Gás P13", "gas").await;
+        let customer_id = create_test_customer(&pool, customer_payload("Maria")).await;
+        let user_id = create_test_user(&pool).await;
+
+        // Segunda e quarta da mesma semana, e um pedido no mês seguinte
+        let first = orders::create_order(
+            &pool,
+            order(Some(customer_id), vec![(water, 2, 10.0), (gas, 1, 100.0)], vec![("cash", 70.0), ("pix", 50.0)]),
+            Some(user_id),
+        ).await.unwrap();
+        set_order_date(&pool, first, "2030-04-29 10:00:00").await;
+
+        let second = orders::create_order(
+            &pool, order(None, vec![(water, 3, 10.0)], vec![("pix", 30.0)]), None,
+        ).await.unwrap();
+        set_order_date(&pool, second, "2030-05-01 18:30:00").await;
+
+        let third = orders::create_order(
+            &pool, order(None, vec![(gas, 1, 100.0)], vec![("cash", 100.0)]), None,
+        ).await.unwrap();
+        set_order_date(&pool, third, "2030-06-03 09:00:00").await;
+
+        // Cancelado não entra
+        let cancelled = orders::create_order(
+            &pool, order(None, vec![(water, 5, 10.0)], vec![("cash", 50.0)]), None,
+        ).await.unwrap();
+        set_order_date(&pool, cancelled, "2030-05-02 12:00:00").await;
+        orders::cancel_order(&pool, cancelled, CancelOrderPayload {
+            reason: "Cliente desistiu".to_string(),
+        }, user_id).await.unwrap();
+
+        let report = get_sales_report(&pool, "2030-04-01", "2030-05-31", "day").await.unwrap();
+        assert_eq!((report.order_count, report.quantity, report.total), (2, 6, 150.0));
+        let days: Vec<_> = report.lines.iter()
+            .map(|l| (l.key.clone().unwrap(), l.order_count, l.quantity, l.total))
+            .collect();
+        assert_eq!(days, vec![
+            ("2030-04-29".to_string(), 1, 3, 120.0),
+            ("2030-05-01".to_string(), 1, 3, 30.0),
+        ]);
+
+        let report = get_sales_report(&pool, "2030-04-01", "2030-06-30", "week").await.unwrap();
+        let weeks: Vec<_> = report.lines.iter().map(|l| (l.key.clone().unwrap(), l.order_count)).collect();
+        assert_eq!(weeks, vec![("2030-04-29".to_string(), 2), ("2030-06-03".to_string(), 1)]);
+
+        let report = get_sales_report(&pool, "2030-04-01", "2030-06-30", "month").await.unwrap();
+        let months: Vec<_> = report.lines.iter().map(|l| (l.key.clone().unwrap(), l.total)).collect();
+        assert_eq!(months, vec![
+            ("2030-04".to_string(), 120.0),
+            ("2030-05".to_string(), 30.0),
+            ("2030-06".to_string(), 100.0),
+        ]);
+
+        let report = get_sales_report(&pool, "2030-04-01", "2030-06-30", "product").await.unwrap();
+        let by_product: Vec<_> = report.lines.iter()
+            .map(|l| (l.label.clone().unwrap(), l.order_count, l.quantity, l.total))
+            .collect();
+        assert_eq!(by_product, vec![
+            ("Gás P13".to_string(), 2, 2, 200.0),
+            ("Água 20L".to_string(), 2, 5, 50.0),
+        ]);
+
+        let report = get_sales_report(&pool, "2030-04-01", "2030-06-30", "product_type").await.unwrap();
+        assert_eq!(report.lines[0].key.as_deref(), Some("gas"));
+        assert_eq!(report.lines[1].key.as_deref(), Some("water"));
+
+        let report = get_sales_report(&pool, "2030-04-01", "2030-06-30", "customer").await.unwrap();
+        assert_eq!(report.lines.len(), 2);
+        assert_eq!(report.lines[0].label.as_deref(), None); // Sem cliente
+        assert_eq!(report.lines[0].total, 130.0);
+        assert_eq!(report.lines[1].label.as_deref(), Some("Maria"));
+
+        let report = get_sales_report(&pool, "2030-04-01", "2030-06-30", "operator").await.unwrap();
+        let operator = report.lines.iter().find(|l| l.key.is_some()).unwrap();
+        assert_eq!((operator.order_count, operator.total), (1, 120.0));
+
+        // Pagamento dividido conta nas duas formas
+        let report = get_sales_report(&pool, "2030-04-01", "2030-06-30", "payment_method").await.unwrap();
+        let methods: Vec<_> = report.lines.iter()
+            .map(|l| (l.key.clone().unwrap(), l.order_count, l.total))
+            .collect();
+        assert_eq!(methods, vec![("cash".to_string(), 2, 170.0), ("pix".to_string(), 2, 80.0)]);
+    }
+
+    #[tokio::test]
+    async fn test_sales_report_validation() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        assert!(get_sales_report(&pool, "2030-05-01", "2030-05-31", "hour").await.is_err());
+        assert!(get_sales_report(&pool, "2030-02-30", "2030-05-31", "day").await.is_err());
+        assert!(get_sales_report(&pool, "2030-05-31", "2030-05-01", "day").await.is_err());
+
+        let report = get_sales_report(&pool, "2030-05-01", "2030-05-31", "day").await.unwrap();
+        assert!(report.lines.is_empty());
+        assert_eq!(report.total, 0.0);
+    }
+}
@@ -0,0 +1,421 @@
+use crate::models::{CompanySettings, PrinterSettings, UpdateCompanySettingsPayload, UpdatePrinterSettingsPayload};
+use crate::errors::AppError;
+use crate::db::stock_lots::EXPIRY_WARNING_DAYS;
+use crate::escpos::PAPER_WIDTHS;
+use sqlx::{Acquire, Executor, Sqlite};
+
+// Configurações guardadas em chave/valor; cada módulo dá tipo e padrão às suas chaves
+
+pub async fn get_setting<'e, E>(executor: E, key: &str) -> Result<Option<String>, AppError>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let value: Option<(String,)> = sqlx::query_as("SELECT value FROM app_settings WHERE key = ?")
+        .bind(key)
+        .fetch_optional(executor)
+        .await?;
+
+    Ok(value.map(|v| v.0))
+}
+
+pub async fn set_setting<'e, E>(executor: E, key: &str, value: &str) -> Result<(), AppError>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    sqlx::query(
+        "INSERT INTO app_settings (key, value) VALUES (?, ?)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = CURRENT_TIMESTAMP"
+    )
+    .bind(key)
+    .bind(value)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Configuração numérica; valor gravado que não é número volta ao padrão
+pub async fn get_int_setting<'e, E>(executor: E, key: &str, default: i64) -> Result<i64, AppError>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    Ok(get_setting(executor, key)
+        .await?
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default))
+}
+
+// ========== DADOS DA EMPRESA ==========
+
+const COMPANY_NAME_KEY: &str = "company.name";
+const CNPJ_KEY: &str = "company.cnpj";
+const ADDRESS_KEY: &str = "company.address";
+const PHONE_KEY: &str = "company.phone";
+const EMAIL_KEY: &str = "company.email";
+const RECEIPT_FOOTER_KEY: &str = "receipt.footer";
+pub(crate) const EXPIRY_WARNING_DAYS_KEY: &str = "thresholds.expiry_warning_days";
+pub(crate) const DEFAULT_MIN_STOCK_KEY: &str = "thresholds.default_min_stock";
+const CURRENCY_SYMBOL_KEY: &str = "currency.symbol";
+const DECIMAL_SEPARATOR_KEY: &str = "currency.decimal_separator";
+
+const DEFAULT_COMPANY_NAME: &str = "Distribuidora";
+const DEFAULT_RECEIPT_FOOTER: &str = "Obrigado pela preferência!";
+pub(crate) const DEFAULT_MIN_STOCK: i64 = 10;
+
+async fn get_text<'e, E>(executor: E, key: &str) -> Result<Option<String>, AppError>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    Ok(get_setting(executor, key).await?.filter(|value| !value.is_empty()))
+}
+
+pub async fn get_company_settings(conn: impl Acquire<'_, Database = Sqlite>) -> Result<CompanySettings, AppError> {
+    let mut conn = conn.acquire().await?;
+
+    Ok(CompanySettings {
+        company_name: get_text(&mut *conn, COMPANY_NAME_KEY).await?
+            .unwrap_or_else(|| DEFAULT_COMPANY_NAME.to_string()),
+        cnpj: get_text(&mut *conn, CNPJ_KEY).await?,
+        address: get_text(&mut *conn, ADDRESS_KEY).await?,
+        phone: get_text(&mut *conn, PHONE_KEY).await?,
+        email: get_text(&mut *conn, EMAIL_KEY).await?,
+        receipt_footer: get_text(&mut *conn, RECEIPT_FOOTER_KEY).await?
+            .unwrap_or_else(|| DEFAULT_RECEIPT_FOOTER.to_string()),
+        expiry_warning_days: get_int_setting(&mut *conn, EXPIRY_WARNING_DAYS_KEY, EXPIRY_WARNING_DAYS).await?,
+        default_min_stock: get_int_setting(&mut *conn, DEFAULT_MIN_STOCK_KEY, DEFAULT_MIN_STOCK).await?,
+        currency_symbol: get_text(&mut *conn, CURRENCY_SYMBOL_KEY).await?
+            .unwrap_or_else(|| "R$".to_string()),
+        decimal_separator: get_text(&mut *conn, DECIMAL_SEPARATOR_KEY).await?
+            .unwrap_or_else(|| ",".to_string()),
+    })
+}
+
+/// Confere os dígitos verificadores e devolve o CNPJ formatado (00.000.000/0000-00)
+fn normalize_cnpj(value: &str) -> Result<String, AppError> {
+    let digits: Vec<u32> = value.chars().filter_map(|c| c.to_digit(10)).collect();
+    let invalid = || AppError::Validation(format!("Invalid CNPJ: {}", value));
+
+    if digits.len() != 14 || digits.iter().all(|d| *d == digits[0]) {
+        return Err(invalid());
+    }
+
+    let check_digit = |len: usize| {
+        let weights = [6, 5, 4, 3, 2, 9, 8, 7, 6, 5, 4, 3, 2];
+        let sum: u32 = digits[..len]
+            .iter()
+            .zip(&weights[13 - len..])
+            .map(|(digit, weight)| digit * weight)
+            .sum();
+        match sum % 11 {
+            0 | 1 => 0,
+            rest => 11 - rest,
+        }
+    };
+
+    if check_digit(12) != digits[12] || check_digit(13) != digits[13] {
+        return Err(invalid());
+    }
+
+    let d: String = digits.iter().map(|d| char::from_digit(*d, 10).unwrap_or('0')).collect();
+    Ok(format!("{}.{}.{}/{}-{}", &d[0..2], &d[2..5], &d[5..8], &d[8..12], &d[12..14]))
+}
+
+/// Campo opcional: `None` não mexe, texto vazio apaga
+fn optional_text(value: Option<String>) -> Option<String> {
+    value.map(|value| value.trim().to_string())
+}
+
+pub async fn update_company_settings(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    payload: UpdateCompanySettingsPayload,
+) -> Result<(), AppError> {
+    let company_name = optional_text(payload.company_name);
+    if company_name.as_deref().is_some_and(str::is_empty) {
+        return Err(AppError::Validation("Company name cannot be empty".to_string()));
+    }
+
+    let cnpj = match optional_text(payload.cnpj) {
+        Some(cnpj) if !cnpj.is_empty() => Some(normalize_cnpj(&cnpj)?),
+        other => other,
+    };
+
+    let email = optional_text(payload.email);
+    if email.as_deref().is_some_and(|email| !email.is_empty() && !email.contains('@')) {
+        return Err(AppError::Validation("Invalid email".to_string()));
+    }
+
+    if payload.expiry_warning_days.is_some_and(|days| !(0..=365).contains(&days)) {
+        return Err(AppError::Validation("Expiry warning must be between 0 and 365 days".to_string()));
+    }
+
+    if payload.default_min_stock.is_some_and(|min| min < 0) {
+        return Err(AppError::Validation("Default minimum stock cannot be negative".to_string()));
+    }
+
+    let currency_symbol = optional_text(payload.currency_symbol);
+    if currency_symbol.as_deref().is_some_and(|symbol| symbol.is_empty() || symbol.chars().count() > 5) {
+        return Err(AppError::Validation("Currency symbol must have 1 to 5 characters".to_string()));
+    }
+
+    if payload.decimal_separator.as_deref().is_some_and(|sep| sep != "," && sep != ".") {
+        return Err(AppError::Validation("Decimal separator must be ',' or '.'".to_string()));
+    }
+
+    let values = [
+        (COMPANY_NAME_KEY, company_name),
+        (CNPJ_KEY, cnpj),
+        (ADDRESS_KEY, optional_text(payload.address)),
+        (PHONE_KEY, optional_text(payload.phone)),
+        (EMAIL_KEY, email),
+        (RECEIPT_FOOTER_KEY, optional_text(payload.receipt_footer)),
+        (EXPIRY_WARNING_DAYS_KEY, payload.expiry_warning_days.map(|days| days.to_string())),
+        (DEFAULT_MIN_STOCK_KEY, payload.default_min_stock.map(|min| min.to_string())),
+        (CURRENCY_SYMBOL_KEY, currency_symbol),
+        (DECIMAL_SEPARATOR_KEY, payload.decimal_separator),
+    ];
+
+    let mut tx = conn.begin().await?;
+    for (key, value) in values {
+        if let Some(value) = value {
+            set_setting(&mut *tx, key, &value).await?;
+        }
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// 1234.5 -> "R$ 1.234,50" (ou "R$ 1,234.50" com ponto decimal)
+pub fn format_money(settings: &CompanySettings, value: f64) -> String {
+    let (thousands, decimal) = if settings.decimal_separator == "." { (',', '.') } else { ('.', ',') };
+
+    let cents = (value.abs() * 100.0).round() as u64;
+    let integer = (cents / 100).to_string();
+    let mut grouped: Vec<char> = Vec::new();
+    for (i, digit) in integer.chars().rev().enumerate() {
+        if i > 0 && i % 3 == 0 {
+            grouped.push(thousands);
+        }
+        grouped.push(digit);
+    }
+    let grouped: String = grouped.into_iter().rev().collect();
+
+    let sign = if value < 0.0 && cents > 0 { "-" } else { "" };
+    format!("{}{} {}{}{:02}", sign, settings.currency_symbol, grouped, decimal, cents % 100)
+}
+
+// ========== IMPRESSORA TÉRMICA ==========
+
+const PRINTER_CONNECTION_KEY: &str = "printer.connection";
+const PRINTER_DEVICE_KEY: &str = "printer.device_path";
+const PRINTER_HOST_KEY: &str = "printer.host";
+const PRINTER_PORT_KEY: &str = "printer.port";
+const PRINTER_PAPER_WIDTH_KEY: &str = "printer.paper_width";
+const PRINTER_CUT_KEY: &str = "printer.cut_paper";
+const PRINTER_QR_CODE_KEY: &str = "printer.qr_code";
+
+pub const PRINTER_CONNECTIONS: [&str; 3] = ["none", "device", "network"];
+const DEFAULT_PRINTER_PORT: i64 = 9100;
+const DEFAULT_PAPER_WIDTH: i64 = 80;
+
+pub async fn get_printer_settings(conn: impl Acquire<'_, Database = Sqlite>) -> Result<PrinterSettings, AppError> {
+    let mut conn = conn.acquire().await?;
+
+    Ok(PrinterSettings {
+        connection: get_text(&mut *conn, PRINTER_CONNECTION_KEY).await?
+            .unwrap_or_else(|| "none".to_string()),
+        device_path: get_text(&mut *conn, PRINTER_DEVICE_KEY).await?,
+        host: get_text(&mut *conn, PRINTER_HOST_KEY).await?,
+        port: get_int_setting(&mut *conn, PRINTER_PORT_KEY, DEFAULT_PRINTER_PORT).await?,
+        paper_width: get_int_setting(&mut *conn, PRINTER_PAPER_WIDTH_KEY, DEFAULT_PAPER_WIDTH).await?,
+        cut_paper: get_text(&mut *conn, PRINTER_CUT_KEY).await?.as_deref() != Some("false"),
+        qr_code: get_text(&mut *conn, PRINTER_QR_CODE_KEY).await?,
+    })
+}
+
+/// Valida a configuração já combinada com a gravada: trocar só a conexão para
+/// 'network' sem endereço cadastrado é recusado
+pub async fn update_printer_settings(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    payload: UpdatePrinterSettingsPayload,
+) -> Result<(), AppError> {
+    let mut tx = conn.begin().await?;
+    let mut settings = get_printer_settings(&mut *tx).await?;
+
+    if let Some(connection) = payload.connection {
+        settings.connection = connection;
+    }
+    if let Some(device_path) = optional_text(payload.device_path) {
+        settings.device_path = Some(device_path).filter(|path| !path.is_empty());
+    }
+    if let Some(host) = optional_text(payload.host) {
+        settings.host = Some(host).filter(|host| !host.is_empty());
+    }
+    if let Some(qr_code) = optional_text(payload.qr_code) {
+        settings.qr_code = Some(qr_code).filter(|qr_code| !qr_code.is_empty());
+    }
+    settings.port = payload.port.unwrap_or(settings.port);
+    settings.paper_width = payload.paper_width.unwrap_or(settings.paper_width);
+    settings.cut_paper = payload.cut_paper.unwrap_or(settings.cut_paper);
+
+    if !PRINTER_CONNECTIONS.contains(&settings.connection.as_str()) {
+        return Err(AppError::Validation(format!("Invalid printer connection: {}", settings.connection)));
+    }
+
+    if settings.connection == "device" && settings.device_path.is_none() {
+        return Err(AppError::Validation("Printer device path is required".to_string()));
+    }
+
+    if settings.connection == "network" && settings.host.is_none() {
+        return Err(AppError::Validation("Printer host is required".to_string()));
+    }
+
+    if !(1..=65535).contains(&settings.port) {
+        return Err(AppError::Validation("Printer port must be between 1 and 65535".to_string()));
+    }
+
+    if !PAPER_WIDTHS.contains(&settings.paper_width) {
+        return Err(AppError::Validation("Paper width must be 58 or 80 mm".to_string()));
+    }
+
+    let values = [
+        (PRINTER_CONNECTION_KEY, settings.connection),
+        (PRINTER_DEVICE_KEY, settings.device_path.unwrap_or_default()),
+        (PRINTER_HOST_KEY, settings.host.unwrap_or_default()),
+        (PRINTER_PORT_KEY, settings.port.to_string()),
+        (PRINTER_PAPER_WIDTH_KEY, settings.paper_width.to_string()),
+        (PRINTER_CUT_KEY, settings.cut_paper.to_string()),
+        (PRINTER_QR_CODE_KEY, settings.qr_code.unwrap_or_default()),
+    ];
+
+    for (key, value) in values {
+        set_setting(&mut *tx, key, &value).await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_helpers::{setup_test_db, cleanup_test_db};
+
+    fn empty_payload() -> UpdateCompanySettingsPayload {
+        UpdateCompanySettingsPayload {
+            company_name: None,
+            cnpj: None,
+            address: None,
+            phone: None,
+            email: None,
+            receipt_footer: None,
+            expiry_warning_days: None,
+            default_min_stock: None,
+            currency_symbol: None,
+            decimal_separator: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_company_settings() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        let settings = get_company_settings(&pool).await.unwrap();
+        assert_eq!(settings.company_name, "Distribuidora");
+        assert_eq!(settings.expiry_warning_days, EXPIRY_WARNING_DAYS);
+        assert_eq!(format_money(&settings, 1234.5), "R$ 1.234,50");
+        assert_eq!(format_money(&settings, -0.5), "-R$ 0,50");
+
+        update_company_settings(&pool, UpdateCompanySettingsPayload {
+            company_name: Some(" Água Boa ".to_string()),
+            cnpj: Some("11222333000181".to_string()),
+            phone: Some("(11) 3333-4444".to_string()),
+            expiry_warning_days: Some(15),
+            decimal_separator: Some(".".to_string()),
+            ..empty_payload()
+        }).await.unwrap();
+
+        let settings = get_company_settings(&pool).await.unwrap();
+        assert_eq!(settings.company_name, "Água Boa");
+        assert_eq!(settings.cnpj.as_deref(), Some("11.222.333/0001-81"));
+        assert_eq!(settings.expiry_warning_days, 15);
+        assert_eq!(format_money(&settings, 1234567.0), "R$ 1,234,567.00");
+
+        // Texto vazio apaga o campo opcional; campo ausente fica como estava
+        update_company_settings(&pool, UpdateCompanySettingsPayload {
+            phone: Some(String::new()),
+            ..empty_payload()
+        }).await.unwrap();
+        let settings = get_company_settings(&pool).await.unwrap();
+        assert_eq!(settings.phone, None);
+        assert_eq!(settings.company_name, "Água Boa");
+
+        for invalid in [
+            UpdateCompanySettingsPayload { cnpj: Some("11.222.333/0001-82".to_string()), ..empty_payload() },
+            UpdateCompanySettingsPayload { cnpj: Some("11111111111111".to_string()), ..empty_payload() },
+            UpdateCompanySettingsPayload { company_name: Some("  ".to_string()), ..empty_payload() },
+            UpdateCompanySettingsPayload { email: Some("sem-arroba".to_string()), ..empty_payload() },
+            UpdateCompanySettingsPayload { decimal_separator: Some(";".to_string()), ..empty_payload() },
+            UpdateCompanySettingsPayload { default_min_stock: Some(-1), ..empty_payload() },
+        ] {
+            assert!(update_company_settings(&pool, invalid).await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_printer_settings() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        let printer_payload = || UpdatePrinterSettingsPayload {
+            connection: None,
+            device_path: None,
+            host: None,
+            port: None,
+            paper_width: None,
+            cut_paper: None,
+            qr_code: None,
+        };
+
+        let settings = get_printer_settings(&pool).await.unwrap();
+        assert_eq!(settings.connection, "none");
+        assert_eq!((settings.port, settings.paper_width, settings.cut_paper), (9100, 80, true));
+
+        // Rede sem endereço, largura e porta fora do padrão são recusadas
+        for invalid in [
+            UpdatePrinterSettingsPayload { connection: Some("network".to_string()), ..printer_payload() },
+            UpdatePrinterSettingsPayload { connection: Some("usb".to_string()), ..printer_payload() },
+            UpdatePrinterSettingsPayload { paper_width: Some(76), ..printer_payload() },
+            UpdatePrinterSettingsPayload { port: Some(0), ..printer_payload() },
+        ] {
+            assert!(update_printer_settings(&pool, invalid).await.is_err());
+        }
+
+        update_printer_settings(&pool, UpdatePrinterSettingsPayload {
+            connection: Some("network".to_string()),
+            host: Some(" 192.168.0.50 ".to_string()),
+            paper_width: Some(58),
+            cut_paper: Some(false),
+            qr_code: Some("pix@aguaboa.com.br".to_string()),
+            ..printer_payload()
+        }).await.unwrap();
+
+        let settings = get_printer_settings(&pool).await.unwrap();
+        assert_eq!(settings.host.as_deref(), Some("192.168.0.50"));
+        assert_eq!((settings.paper_width, settings.cut_paper), (58, false));
+
+        // Apagar o host da conexão de rede ativa é recusado; o QR code pode sair
+        assert!(update_printer_settings(&pool, UpdatePrinterSettingsPayload {
+            host: Some(String::new()),
+            ..printer_payload()
+        }).await.is_err());
+        update_printer_settings(&pool, UpdatePrinterSettingsPayload {
+            qr_code: Some(String::new()),
+            ..printer_payload()
+        }).await.unwrap();
+        let settings = get_printer_settings(&pool).await.unwrap();
+        assert_eq!(settings.qr_code, None);
+        assert_eq!(settings.host.as_deref(), Some("192.168.0.50"));
+    }
+}
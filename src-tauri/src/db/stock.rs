@@ -1,97 +1,380 @@
-use crate::models::{StockMovementWithProduct, Product, TopProduct, DashboardStats};
+use crate::models::{
+    StockMovementWithProduct, Product, TopProduct, DashboardStats, TransferStockPayload,
+    ReorderSuggestion,
+};
 use crate::errors::AppError;
-use sqlx::SqlitePool;
+use crate::db::stock_locations::{self, location_stock, DEFAULT_LOCATION_ID};
+use crate::db::stock_lots::{self, LotUsage, EXPIRY_WARNING_DAYS};
+use crate::db::settings::{get_int_setting, EXPIRY_WARNING_DAYS_KEY};
+use sqlx::{Acquire, Sqlite, SqliteConnection, SqlitePool};
 
-pub async fn stock_in(pool: &SqlitePool, product_id: i64, quantity: i64) -> Result<(), AppError> {
-    if quantity <= 0 {
-        return Err(AppError::Validation("Quantity must be positive".to_string()));
+pub const STOCK_KINDS: [&str; 2] = ["full", "empty"];
+
+/// Alteração de estoque a ser aplicada em `products` e registrada em `stock_movements`.
+/// `quantity` segue a convenção do histórico: 'OUT' grava a quantidade positiva
+/// (saída), os demais tipos gravam a variação com sinal. Sem `location_id`, a
+/// alteração é feita na loja. Entradas de cheios vão para o lote de `expiry_date`
+/// e para os lotes de `lots` (devolução do que saiu de outro local ou de uma venda).
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct StockChange<'a> {
+    pub product_id: i64,
+    pub stock_kind: &'a str,
+    pub movement_type: &'a str,
+    pub quantity: i64,
+    pub unit_cost: Option<f64>,
+    pub order_id: Option<i64>,
+    pub purchase_receipt_id: Option<i64>,
+    pub driver_shift_id: Option<i64>,
+    pub location_id: Option<i64>,
+    pub expiry_date: Option<&'a str>,
+    pub lots: &'a [LotUsage],
+}
+
+/// Aplica a alteração e devolve os lotes consumidos quando há saída de cheios
+pub(crate) async fn apply_stock_change(
+    conn: &mut SqliteConnection,
+    change: StockChange<'_>,
+) -> Result<Vec<LotUsage>, AppError> {
+    let delta = if change.movement_type == "OUT" { -change.quantity } else { change.quantity };
+
+    // Entrada de cheios com custo atualiza o último custo e o custo médio ponderado,
+    // calculado sobre o saldo anterior à entrada
+    if let (Some(unit_cost), "full", true) = (change.unit_cost, change.stock_kind, delta > 0) {
+        sqlx::query(
+            "UPDATE products
+             SET average_cost = CASE WHEN stock_full > 0
+                     THEN (stock_full * average_cost + ? * ?) / (stock_full + ?)
+                     ELSE ? END,
+                 cost_price = ?
+             WHERE id = ?"
+        )
+        .bind(delta)
+        .bind(unit_cost)
+        .bind(delta)
+        .bind(unit_cost)
+        .bind(unit_cost)
+        .bind(change.product_id)
+        .execute(&mut *conn)
+        .await?;
     }
 
-    let mut tx = pool.begin().await?;
+    let update = match change.stock_kind {
+        "full" => "UPDATE products SET stock_full = stock_full + ? WHERE id = ?",
+        "empty" => "UPDATE products SET stock_empty = stock_empty + ? WHERE id = ?",
+        other => return Err(AppError::Validation(format!("Invalid stock kind: {}", other))),
+    };
+
+    let result = sqlx::query(update)
+        .bind(delta)
+        .bind(change.product_id)
+        .execute(&mut *conn)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Product {} not found", change.product_id)));
+    }
+
+    let location_id = change.location_id.unwrap_or(DEFAULT_LOCATION_ID);
+    adjust_location_stock(conn, change.product_id, location_id, change.stock_kind, delta).await?;
+
+    let movement_id = record_movement(conn, &change).await?;
 
-    // Atualiza estoque
-    sqlx::query("UPDATE products SET stock_full = stock_full + ? WHERE id = ?")
-        .bind(quantity)
+    if change.stock_kind != "full" {
+        return Ok(Vec::new());
+    }
+
+    if delta < 0 {
+        return stock_lots::consume_lots(conn, movement_id, change.product_id, location_id, -delta).await;
+    }
+
+    if let Some(expiry_date) = change.expiry_date {
+        stock_lots::add_to_lot(conn, Some(movement_id), change.product_id, location_id, expiry_date, delta).await?;
+    }
+
+    for lot in change.lots {
+        stock_lots::add_to_lot(conn, Some(movement_id), change.product_id, location_id, &lot.expiry_date, lot.quantity).await?;
+    }
+
+    Ok(Vec::new())
+}
+
+/// Atualiza o saldo do local sem mexer no total do produto
+pub(crate) async fn adjust_location_stock(
+    conn: &mut SqliteConnection,
+    product_id: i64,
+    location_id: i64,
+    stock_kind: &str,
+    delta: i64,
+) -> Result<(), AppError> {
+    let upsert = match stock_kind {
+        "full" => "INSERT INTO product_stock (product_id, location_id, stock_full) VALUES (?, ?, ?)
+                   ON CONFLICT(product_id, location_id) DO UPDATE SET stock_full = stock_full + excluded.stock_full",
+        "empty" => "INSERT INTO product_stock (product_id, location_id, stock_empty) VALUES (?, ?, ?)
+                    ON CONFLICT(product_id, location_id) DO UPDATE SET stock_empty = stock_empty + excluded.stock_empty",
+        other => return Err(AppError::Validation(format!("Invalid stock kind: {}", other))),
+    };
+
+    sqlx::query(upsert)
         .bind(product_id)
-        .execute(&mut *tx)
+        .bind(location_id)
+        .bind(delta)
+        .execute(&mut *conn)
         .await?;
 
-    // Registra movimentação
-    sqlx::query(
-        "INSERT INTO stock_movements (product_id, movement_type, quantity)
-         VALUES (?, 'IN', ?)"
+    Ok(())
+}
+
+/// Transfere entre locais: sai da origem (`change.location_id`) e entra no destino,
+/// com uma movimentação 'TRANSFER' em cada ponta. Os lotes consumidos na origem
+/// seguem para o destino. Quantidade negativa desfaz a transferência.
+pub(crate) async fn move_stock(
+    conn: &mut SqliteConnection,
+    change: StockChange<'_>,
+    to_location_id: i64,
+) -> Result<(), AppError> {
+    let from_location_id = change.location_id.unwrap_or(DEFAULT_LOCATION_ID);
+    let (from, to, quantity) = if change.quantity < 0 {
+        (to_location_id, from_location_id, -change.quantity)
+    } else {
+        (from_location_id, to_location_id, change.quantity)
+    };
+
+    let lots = apply_stock_change(conn, StockChange {
+        movement_type: "TRANSFER",
+        quantity: -quantity,
+        location_id: Some(from),
+        ..change
+    }).await?;
+
+    apply_stock_change(conn, StockChange {
+        movement_type: "TRANSFER",
+        quantity,
+        location_id: Some(to),
+        lots: &lots,
+        ..change
+    }).await?;
+
+    Ok(())
+}
+
+/// Registra a movimentação sem alterar o estoque (quando o saldo já foi gravado)
+pub(crate) async fn record_movement(
+    conn: &mut SqliteConnection,
+    change: &StockChange<'_>,
+) -> Result<i64, AppError> {
+    let id = sqlx::query(
+        "INSERT INTO stock_movements
+            (product_id, movement_type, stock_kind, quantity, unit_cost, order_id,
+             purchase_receipt_id, driver_shift_id, location_id)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
     )
-    .bind(product_id)
-    .bind(quantity)
-    .execute(&mut *tx)
-    .await?;
+    .bind(change.product_id)
+    .bind(change.movement_type)
+    .bind(change.stock_kind)
+    .bind(change.quantity)
+    .bind(change.unit_cost)
+    .bind(change.order_id)
+    .bind(change.purchase_receipt_id)
+    .bind(change.driver_shift_id)
+    .bind(change.location_id.unwrap_or(DEFAULT_LOCATION_ID))
+    .execute(&mut *conn)
+    .await?
+    .last_insert_rowid();
+
+    Ok(id)
+}
+
+pub async fn stock_in(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    product_id: i64,
+    quantity: i64,
+    unit_cost: Option<f64>,
+    location_id: Option<i64>,
+    expiry_date: Option<String>,
+) -> Result<(), AppError> {
+    if quantity <= 0 {
+        return Err(AppError::Validation("Quantity must be positive".to_string()));
+    }
+
+    if unit_cost.is_some_and(|cost| cost < 0.0) {
+        return Err(AppError::Validation("Unit cost cannot be negative".to_string()));
+    }
+
+    let mut tx = conn.begin().await?;
+
+    let location = stock_locations::get_active_location(&mut *tx, location_id).await?;
+
+    apply_stock_change(&mut tx, StockChange {
+        product_id,
+        stock_kind: "full",
+        movement_type: "IN",
+        quantity,
+        unit_cost,
+        location_id: Some(location.id),
+        expiry_date: expiry_date.as_deref(),
+        ..Default::default()
+    }).await?;
 
     tx.commit().await?;
     Ok(())
 }
 
-pub async fn stock_out(pool: &SqlitePool, product_id: i64, quantity: i64) -> Result<(), AppError> {
+pub async fn stock_out(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    product_id: i64,
+    quantity: i64,
+    location_id: Option<i64>,
+) -> Result<(), AppError> {
     if quantity <= 0 {
         return Err(AppError::Validation("Quantity must be positive".to_string()));
     }
 
-    // Verifica estoque disponível
-    let stock: (i64,) = sqlx::query_as(
-        "SELECT stock_full FROM products WHERE id = ?"
-    )
-    .bind(product_id)
-    .fetch_optional(pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound(format!("Product {} not found", product_id)))?;
+    let mut tx = conn.begin().await?;
+
+    let location = stock_locations::get_active_location(&mut *tx, location_id).await?;
+
+    // Verifica estoque disponível no local
+    let (available, _) = location_stock(&mut tx, product_id, location.id).await?;
 
-    if stock.0 < quantity {
+    if available < quantity {
         return Err(AppError::BusinessLogic(
-            format!("Insufficient stock. Available: {}, Requested: {}", stock.0, quantity)
+            format!("Insufficient stock. Available: {}, Requested: {}", available, quantity)
         ));
     }
 
-    let mut tx = pool.begin().await?;
+    apply_stock_change(&mut tx, StockChange {
+        product_id,
+        stock_kind: "full",
+        movement_type: "OUT",
+        quantity,
+        location_id: Some(location.id),
+        ..Default::default()
+    }).await?;
 
-    // Atualiza estoque
-    sqlx::query("UPDATE products SET stock_full = stock_full - ? WHERE id = ?")
-        .bind(quantity)
-        .bind(product_id)
-        .execute(&mut *tx)
-        .await?;
+    tx.commit().await?;
+    Ok(())
+}
 
-    // Registra movimentação
-    sqlx::query(
-        "INSERT INTO stock_movements (product_id, movement_type, quantity)
-         VALUES (?, 'OUT', ?)"
-    )
-    .bind(product_id)
-    .bind(quantity)
-    .execute(&mut *tx)
-    .await?;
+pub async fn stock_adjust(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    product_id: i64,
+    quantity: i64,
+    stock_kind: &str,
+    location_id: Option<i64>,
+) -> Result<(), AppError> {
+    if !STOCK_KINDS.contains(&stock_kind) {
+        return Err(AppError::Validation(format!("Invalid stock kind: {}", stock_kind)));
+    }
+
+    let mut tx = conn.begin().await?;
+
+    let location = stock_locations::get_location_by_id(&mut *tx, location_id.unwrap_or(DEFAULT_LOCATION_ID)).await?;
+
+    // Ajuste pode ser positivo ou negativo
+    apply_stock_change(&mut tx, StockChange {
+        product_id,
+        stock_kind,
+        movement_type: "ADJUST",
+        quantity,
+        location_id: Some(location.id),
+        ..Default::default()
+    }).await?;
 
     tx.commit().await?;
     Ok(())
 }
 
-pub async fn stock_adjust(pool: &SqlitePool, product_id: i64, quantity: i64) -> Result<(), AppError> {
-    let mut tx = pool.begin().await?;
+/// Troca com o fornecedor: envia cascos vazios e recebe a mesma quantidade cheia
+pub async fn supplier_exchange(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    product_id: i64,
+    quantity: i64,
+    unit_cost: f64,
+    location_id: Option<i64>,
+    expiry_date: Option<String>,
+) -> Result<(), AppError> {
+    if quantity <= 0 {
+        return Err(AppError::Validation("Quantity must be positive".to_string()));
+    }
 
-    // Atualiza estoque (ajuste pode ser positivo ou negativo)
-    sqlx::query("UPDATE products SET stock_full = stock_full + ? WHERE id = ?")
-        .bind(quantity)
-        .bind(product_id)
-        .execute(&mut *tx)
-        .await?;
+    if unit_cost < 0.0 {
+        return Err(AppError::Validation("Unit cost cannot be negative".to_string()));
+    }
 
-    // Registra movimentação
-    sqlx::query(
-        "INSERT INTO stock_movements (product_id, movement_type, quantity)
-         VALUES (?, 'ADJUST', ?)"
-    )
-    .bind(product_id)
-    .bind(quantity)
-    .execute(&mut *tx)
-    .await?;
+    let mut tx = conn.begin().await?;
+
+    let location = stock_locations::get_active_location(&mut *tx, location_id).await?;
+
+    // Verifica cascos vazios disponíveis no local
+    let (_, available) = location_stock(&mut tx, product_id, location.id).await?;
+
+    if available < quantity {
+        return Err(AppError::BusinessLogic(
+            format!("Insufficient empty bottles. Available: {}, Requested: {}", available, quantity)
+        ));
+    }
+
+    apply_stock_change(&mut tx, StockChange {
+        product_id,
+        stock_kind: "empty",
+        movement_type: "EXCHANGE",
+        quantity: -quantity,
+        location_id: Some(location.id),
+        ..Default::default()
+    }).await?;
+
+    apply_stock_change(&mut tx, StockChange {
+        product_id,
+        stock_kind: "full",
+        movement_type: "EXCHANGE",
+        quantity,
+        unit_cost: Some(unit_cost),
+        location_id: Some(location.id),
+        expiry_date: expiry_date.as_deref(),
+        ..Default::default()
+    }).await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn transfer_stock(conn: impl Acquire<'_, Database = Sqlite>, payload: TransferStockPayload) -> Result<(), AppError> {
+    let stock_kind = payload.stock_kind.as_deref().unwrap_or("full");
+    if !STOCK_KINDS.contains(&stock_kind) {
+        return Err(AppError::Validation(format!("Invalid stock kind: {}", stock_kind)));
+    }
+
+    if payload.quantity <= 0 {
+        return Err(AppError::Validation("Quantity must be positive".to_string()));
+    }
+
+    if payload.from_location_id == payload.to_location_id {
+        return Err(AppError::Validation("Source and destination must be different".to_string()));
+    }
+
+    let mut tx = conn.begin().await?;
+
+    let from = stock_locations::get_location_by_id(&mut *tx, payload.from_location_id).await?;
+    let to = stock_locations::get_active_location(&mut *tx, Some(payload.to_location_id)).await?;
+
+    // Verifica saldo na origem
+    let (full, empty) = location_stock(&mut tx, payload.product_id, from.id).await?;
+    let available = if stock_kind == "full" { full } else { empty };
+
+    if available < payload.quantity {
+        return Err(AppError::BusinessLogic(
+            format!("Insufficient stock at {}. Available: {}, Requested: {}",
+                from.name, available, payload.quantity)
+        ));
+    }
+
+    move_stock(&mut tx, StockChange {
+        product_id: payload.product_id,
+        stock_kind,
+        quantity: payload.quantity,
+        location_id: Some(from.id),
+        ..Default::default()
+    }, to.id).await?;
 
     tx.commit().await?;
     Ok(())
@@ -100,10 +383,12 @@ pub async fn stock_adjust(pool: &SqlitePool, product_id: i64, quantity: i64) ->
 pub async fn get_all_movements(pool: &SqlitePool) -> Result<Vec<StockMovementWithProduct>, AppError> {
     let movements = sqlx::query_as::<_, StockMovementWithProduct>(
         "SELECT sm.id, sm.product_id, p.name as product_name, sm.movement_type,
-                sm.quantity, sm.created_at
+                sm.stock_kind, sm.quantity, sm.unit_cost, sm.order_id, sm.purchase_receipt_id,
+                sm.driver_shift_id, sm.location_id, l.name as location_name, sm.created_at
          FROM stock_movements sm
          JOIN products p ON sm.product_id = p.id
-         ORDER BY sm.created_at DESC"
+         LEFT JOIN stock_locations l ON sm.location_id = l.id
+         ORDER BY sm.created_at DESC, sm.id DESC"
     )
     .fetch_all(pool)
     .await?;
@@ -111,15 +396,116 @@ pub async fn get_all_movements(pool: &SqlitePool) -> Result<Vec<StockMovementWit
     Ok(movements)
 }
 
-pub async fn get_critical_stock(pool: &SqlitePool, threshold: i64) -> Result<Vec<Product>, AppError> {
-    let products = sqlx::query_as::<_, Product>(
-        "SELECT * FROM products WHERE stock_full <= ? ORDER BY stock_full ASC"
+/// Produtos com cheios no mínimo cadastrado ou abaixo dele. Com local informado,
+/// o saldo considerado (e devolvido em `stock_full`/`stock_empty`) é o do local;
+/// sem local, o total.
+pub async fn get_critical_stock(
+    pool: &SqlitePool,
+    location_id: Option<i64>,
+) -> Result<Vec<Product>, AppError> {
+    let products = match location_id {
+        Some(location_id) => {
+            sqlx::query_as::<_, Product>(
+                "SELECT p.id, p.name, p.description, p.type, p.price_refill, p.price_full,
+                        COALESCE(ps.stock_full, 0) as stock_full,
+                        COALESCE(ps.stock_empty, 0) as stock_empty,
+                        p.cost_price, p.average_cost,
+                        p.min_stock, p.reorder_qty
+                 FROM products p
+                 LEFT JOIN product_stock ps ON ps.product_id = p.id AND ps.location_id = ?
+                 WHERE COALESCE(ps.stock_full, 0) <= p.min_stock
+                 ORDER BY stock_full ASC"
+            )
+            .bind(location_id)
+            .fetch_all(pool)
+            .await?
+        }
+        None => {
+            sqlx::query_as::<_, Product>(
+                "SELECT * FROM products WHERE stock_full <= min_stock ORDER BY stock_full ASC"
+            )
+            .fetch_all(pool)
+            .await?
+        }
+    };
+
+    Ok(products)
+}
+
+/// Janela de vendas usada para calcular o giro
+pub const DEFAULT_SALES_WINDOW_DAYS: i64 = 30;
+
+/// Cobertura desejada após a compra
+pub const DEFAULT_COVERAGE_DAYS: i64 = 7;
+
+/// Lista de compra pelo giro: o consumo médio diário vem das vendas dos últimos
+/// `days` dias (como em `get_top_products`) e o estoque alvo é o maior entre o
+/// mínimo do produto e o consumo de `target_days` dias. Produto no alvo ou abaixo
+/// compra o lote de reposição ou, se não bastar, o que falta para o alvo,
+/// descontando o que já foi pedido e não recebido.
+pub async fn get_reorder_suggestions(
+    pool: &SqlitePool,
+    days: i64,
+    target_days: i64,
+) -> Result<Vec<ReorderSuggestion>, AppError> {
+    if days <= 0 || target_days <= 0 {
+        return Err(AppError::Validation("Days must be positive".to_string()));
+    }
+
+    let rows: Vec<(i64, String, i64, i64, i64, i64, i64)> = sqlx::query_as(
+        "SELECT p.id, p.name, p.stock_full, p.min_stock, p.reorder_qty,
+                COALESCE((SELECT SUM(poi.quantity_ordered - poi.quantity_received)
+                          FROM purchase_order_items poi
+                          JOIN purchase_orders po ON poi.purchase_order_id = po.id
+                          WHERE poi.product_id = p.id AND po.status IN ('open', 'partial')), 0),
+                COALESCE((SELECT SUM(oi.quantity)
+                          FROM order_items oi
+                          JOIN orders o ON oi.order_id = o.id
+                          WHERE oi.product_id = p.id
+                            AND o.created_at >= datetime('now', '-' || ? || ' days')
+                            AND o.status != 'cancelled'), 0)
+         FROM products p
+         ORDER BY p.name"
     )
-    .bind(threshold)
+    .bind(days)
     .fetch_all(pool)
     .await?;
-    
-    Ok(products)
+
+    let mut suggestions: Vec<ReorderSuggestion> = rows
+        .into_iter()
+        .filter_map(|(product_id, product_name, stock_full, min_stock, reorder_qty, on_order, sold_quantity)| {
+            let avg_daily_sales = sold_quantity as f64 / days as f64;
+            let target_stock = min_stock.max((avg_daily_sales * target_days as f64).ceil() as i64);
+
+            if stock_full > target_stock {
+                return None;
+            }
+
+            let suggested_qty = reorder_qty.max(target_stock - stock_full) - on_order;
+            (suggested_qty > 0).then(|| ReorderSuggestion {
+                product_id,
+                product_name,
+                stock_full,
+                min_stock,
+                reorder_qty,
+                on_order,
+                sold_quantity,
+                avg_daily_sales,
+                days_of_cover: (avg_daily_sales > 0.0).then(|| stock_full as f64 / avg_daily_sales),
+                target_stock,
+                suggested_qty,
+            })
+        })
+        .collect();
+
+    // Mais urgentes primeiro; sem vendas vai para o fim
+    suggestions.sort_by(|a, b| {
+        a.days_of_cover
+            .unwrap_or(f64::INFINITY)
+            .total_cmp(&b.days_of_cover.unwrap_or(f64::INFINITY))
+    });
+
+    Ok(suggestions)
 }
 
 pub async fn get_top_products(
@@ -132,11 +518,14 @@ pub async fn get_top_products(
             oi.product_id,
             p.name as product_name,
             SUM(oi.quantity) as total_quantity,
-            SUM(oi.quantity * oi.unit_price) as total_revenue
+            SUM(oi.quantity * oi.unit_price) as total_revenue,
+            SUM(oi.quantity * oi.unit_cost) as total_cost,
+            SUM(oi.quantity * (oi.unit_price - oi.unit_cost)) as gross_margin
          FROM order_items oi
          JOIN products p ON oi.product_id = p.id
          JOIN orders o ON oi.order_id = o.id
          WHERE o.created_at >= datetime('now', '-' || ? || ' days')
+           AND o.status != 'cancelled'
          GROUP BY oi.product_id, p.name
          ORDER BY total_quantity DESC
          LIMIT ?"
@@ -152,22 +541,44 @@ pub async fn get_top_products(
 pub async fn get_dashboard_stats(pool: &SqlitePool) -> Result<DashboardStats, AppError> {
     // Vendas do dia
     let sales_today: (Option<f64>,) = sqlx::query_as(
-        "SELECT COALESCE(SUM(total), 0) FROM orders 
-         WHERE DATE(created_at) = DATE('now')"
+        "SELECT COALESCE(SUM(total), 0.0) FROM orders 
+         WHERE DATE(created_at) = DATE('now') AND status != 'cancelled'"
     )
     .fetch_one(pool)
     .await?;
 
     // Vendas do mês
     let sales_month: (Option<f64>,) = sqlx::query_as(
-        "SELECT COALESCE(SUM(total), 0) FROM orders 
-         WHERE strftime('%Y-%m', created_at) = strftime('%Y-%m', 'now')"
+        "SELECT COALESCE(SUM(total), 0.0) FROM orders 
+         WHERE strftime('%Y-%m', created_at) = strftime('%Y-%m', 'now')
+         AND status != 'cancelled'"
+    )
+    .fetch_one(pool)
+    .await?;
+
+    // Margem bruta do dia e do mês (receita menos custo médio na venda)
+    let margins: (f64, f64) = sqlx::query_as(
+        "SELECT
+            COALESCE(SUM(CASE WHEN DATE(o.created_at) = DATE('now')
+                THEN oi.quantity * (oi.unit_price - oi.unit_cost) END), 0.0),
+            COALESCE(SUM(oi.quantity * (oi.unit_price - oi.unit_cost)), 0.0)
+         FROM order_items oi
+         JOIN orders o ON oi.order_id = o.id
+         WHERE strftime('%Y-%m', o.created_at) = strftime('%Y-%m', 'now')
+           AND o.status != 'cancelled'"
     )
     .fetch_one(pool)
     .await?;
 
-    // Estoque crítico (threshold = 10)
-    let critical_stock = get_critical_stock(pool, 10).await?;
+    // Estoque crítico (mínimo de cada produto)
+    let critical_stock = get_critical_stock(pool, None).await?;
+
+    // Lotes vencidos e a vencer no prazo de alerta
+    let warning_days = get_int_setting(pool, EXPIRY_WARNING_DAYS_KEY, EXPIRY_WARNING_DAYS).await?;
+    let (expired_lots, expiring_lots) = stock_lots::get_expiring_lots(pool, warning_days)
+        .await?
+        .into_iter()
+        .partition(|lot| lot.days_to_expiry < 0);
 
     // Top produtos (últimos 30 dias)
     let top_products = get_top_products(pool, 5, 30).await?;
@@ -176,7 +587,8 @@ pub async fn get_dashboard_stats(pool: &SqlitePool) -> Result<DashboardStats, Ap
     let active_customers: (i64,) = sqlx::query_as(
         "SELECT COUNT(DISTINCT customer_id) FROM orders 
          WHERE customer_id IS NOT NULL 
-         AND strftime('%Y-%m', created_at) = strftime('%Y-%m', 'now')"
+         AND strftime('%Y-%m', created_at) = strftime('%Y-%m', 'now')
+         AND status != 'cancelled'"
     )
     .fetch_one(pool)
     .await?;
@@ -184,9 +596,364 @@ pub async fn get_dashboard_stats(pool: &SqlitePool) -> Result<DashboardStats, Ap
     Ok(DashboardStats {
         sales_today: sales_today.0.unwrap_or(0.0),
         sales_month: sales_month.0.unwrap_or(0.0),
+        margin_today: margins.0,
+        margin_month: margins.1,
         critical_stock,
+        expired_lots,
+        expiring_lots,
         top_products,
         active_customers: active_customers.0,
     })
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_helpers::{
+        setup_test_db, cleanup_test_db, create_test_user, open_test_session, create_test_product,
+        product_payload, product_update, order_payload, payment, create_test_supplier,
+    };
+    use crate::db::{orders, products, purchase_orders};
+    use crate::models::{
+        CancelOrderPayload, CreateProductPayload, CreatePurchaseOrderPayload,
+        CreateStockLocationPayload, OrderItemPayload,
+        PurchaseOrderItemPayload, UpdateProductPayload, UpdateStockLocationPayload,
+    };
+
+    /// 20 cheios e 5 vazios na loja
+    async fn gas(pool: &SqlitePool) -> i64 {
+        create_test_product(pool, CreateProductPayload {
+            stock_empty: Some(5),
+            ..product_payload("Gás P13", "gas")
+        }).await
+    }
+
+    #[tokio::test]
+    async fn test_stock_in_out_adjust() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        let product_id = gas(&pool).await;
+
+        stock_in(&pool, product_id, 10, None, None, None).await.unwrap();
+        stock_out(&pool, product_id, 3, None).await.unwrap();
+        stock_adjust(&pool, product_id, -2, "full", None).await.unwrap();
+        stock_adjust(&pool, product_id, 4, "empty", None).await.unwrap();
+
+        let product = products::get_product_by_id(&pool, product_id).await.unwrap();
+        assert_eq!(product.stock_full, 25); // 20 + 10 - 3 - 2
+        assert_eq!(product.stock_empty, 9); // 5 + 4
+
+        let movements = get_all_movements(&pool).await.unwrap();
+        assert_eq!(movements.len(), 4);
+        assert_eq!(movements.iter().filter(|m| m.stock_kind == "empty").count(), 1);
+
+        // Validações
+        assert!(stock_in(&pool, product_id, 0, None, None, None).await.is_err());
+        assert!(stock_out(&pool, product_id, 1000, None).await.is_err());
+        assert!(stock_adjust(&pool, product_id, 1, "broken", None).await.is_err());
+        assert!(stock_in(&pool, 99999, 1, None, None, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rejected_stock_operations_leave_no_trace() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        let product_id = gas(&pool).await;
+        let warehouse_id = stock_locations::create_location(&pool, CreateStockLocationPayload {
+            name: "Depósito".to_string(),
+            kind: "warehouse".to_string(),
+        }).await.unwrap();
+        stock_locations::update_location(&pool, warehouse_id, UpdateStockLocationPayload {
+            name: None,
+            active: Some(false),
+        }).await.unwrap();
+
+        let transfer = |from_location_id: i64, to_location_id: i64, stock_kind: &str, quantity: i64| TransferStockPayload {
+            product_id,
+            from_location_id,
+            to_location_id,
+            stock_kind: Some(stock_kind.to_string()),
+            quantity,
+        };
+
+        // Entradas e saídas inválidas
+        assert!(matches!(stock_in(&pool, product_id, -1, None, None, None).await, Err(AppError::Validation(_))));
+        assert!(matches!(stock_in(&pool, product_id, 1, Some(-5.0), None, None).await, Err(AppError::Validation(_))));
+        assert!(matches!(stock_in(&pool, product_id, 1, None, Some(warehouse_id), None).await, Err(AppError::BusinessLogic(_))));
+        assert!(matches!(stock_in(&pool, product_id, 1, None, Some(99999), None).await, Err(AppError::NotFound(_))));
+        assert!(matches!(stock_out(&pool, product_id, 0, None).await, Err(AppError::Validation(_))));
+        assert!(matches!(stock_out(&pool, product_id, 21, None).await, Err(AppError::BusinessLogic(_))));
+
+        // Ajuste de produto inexistente não grava movimentação órfã
+        assert!(matches!(stock_adjust(&pool, 99999, 3, "full", None).await, Err(AppError::NotFound(_))));
+        assert!(matches!(stock_adjust(&pool, product_id, 3, "full", Some(99999)).await, Err(AppError::NotFound(_))));
+
+        // Troca com fornecedor
+        assert!(matches!(supplier_exchange(&pool, product_id, 6, 85.0, None, None).await, Err(AppError::BusinessLogic(_))));
+        assert!(matches!(supplier_exchange(&pool, 99999, 1, 85.0, None, None).await, Err(AppError::NotFound(_))));
+
+        // Transferências
+        let store = DEFAULT_LOCATION_ID;
+        assert!(matches!(transfer_stock(&pool, transfer(store, store, "full", 1)).await, Err(AppError::Validation(_))));
+        assert!(matches!(transfer_stock(&pool, transfer(store, warehouse_id, "broken", 1)).await, Err(AppError::Validation(_))));
+        assert!(matches!(transfer_stock(&pool, transfer(store, warehouse_id, "full", 0)).await, Err(AppError::Validation(_))));
+        assert!(matches!(transfer_stock(&pool, transfer(store, warehouse_id, "full", 1)).await, Err(AppError::BusinessLogic(_))));
+        assert!(matches!(transfer_stock(&pool, transfer(warehouse_id, store, "empty", 1)).await, Err(AppError::BusinessLogic(_))));
+
+        // Nenhum saldo mudou e o histórico continua vazio
+        let product = products::get_product_by_id(&pool, product_id).await.unwrap();
+        assert_eq!((product.stock_full, product.stock_empty), (20, 5));
+        let mut conn = pool.acquire().await.unwrap();
+        assert_eq!(location_stock(&mut conn, product_id, store).await.unwrap(), (20, 5));
+        assert_eq!(location_stock(&mut conn, product_id, warehouse_id).await.unwrap(), (0, 0));
+        drop(conn);
+        assert!(get_all_movements(&pool).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_order_rolls_back_when_any_item_fails() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let first_id = gas(&pool).await;
+        let second_id = gas(&pool).await;
+
+        // O primeiro item passa; o segundo pede mais do que há na loja
+        let mut payload = order_payload(None, first_id, 2, 100.0);
+        payload.items.push(OrderItemPayload {
+            product_id: second_id,
+            quantity: 21,
+            returned_bottle: true,
+            unit_price: 100.0,
+        });
+        payload.payments = vec![payment("cash", 2300.0)];
+
+        let err = orders::create_order(&pool, payload, None).await.unwrap_err();
+        assert!(matches!(err, AppError::BusinessLogic(_)));
+
+        // A baixa do primeiro item foi desfeita junto
+        for product_id in [first_id, second_id] {
+            let product = products::get_product_by_id(&pool, product_id).await.unwrap();
+            assert_eq!((product.stock_full, product.stock_empty), (20, 5));
+        }
+        assert!(get_all_movements(&pool).await.unwrap().is_empty());
+        assert!(orders::get_all_orders(&pool).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_order_bottle_movements() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let product_id = gas(&pool).await;
+        let user_id = create_test_user(&pool).await;
+
+        let order_id = orders::create_order(&pool, order_payload(None, product_id, 2, 100.0), None).await.unwrap();
+
+        let movements = get_all_movements(&pool).await.unwrap();
+        assert_eq!(movements.len(), 2);
+        assert!(movements.iter().any(|m|
+            m.movement_type == "OUT" && m.stock_kind == "full" && m.quantity == 2 && m.order_id == Some(order_id)
+        ));
+        assert!(movements.iter().any(|m|
+            m.movement_type == "RETURN" && m.stock_kind == "empty" && m.quantity == 2
+        ));
+
+        orders::cancel_order(&pool, order_id, CancelOrderPayload {
+            reason: "Pedido lançado em duplicidade".to_string(),
+        }, user_id).await.unwrap();
+
+        let movements = get_all_movements(&pool).await.unwrap();
+        assert_eq!(movements.len(), 4);
+        assert!(movements.iter().any(|m|
+            m.movement_type == "REVERSAL" && m.stock_kind == "full" && m.quantity == 2
+        ));
+        assert!(movements.iter().any(|m|
+            m.movement_type == "REVERSAL" && m.stock_kind == "empty" && m.quantity == -2
+        ));
+
+        let product = products::get_product_by_id(&pool, product_id).await.unwrap();
+        assert_eq!(product.stock_full, 20);
+        assert_eq!(product.stock_empty, 5);
+    }
+
+    #[tokio::test]
+    async fn test_supplier_exchange() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        let product_id = gas(&pool).await;
+
+        supplier_exchange(&pool, product_id, 4, 85.0, None, None).await.unwrap();
+
+        let product = products::get_product_by_id(&pool, product_id).await.unwrap();
+        assert_eq!(product.stock_full, 24); // 20 + 4
+        assert_eq!(product.stock_empty, 1); // 5 - 4
+
+        let movements = get_all_movements(&pool).await.unwrap();
+        assert_eq!(movements.len(), 2);
+        assert!(movements.iter().all(|m| m.movement_type == "EXCHANGE"));
+        let full = movements.iter().find(|m| m.stock_kind == "full").unwrap();
+        assert_eq!(full.quantity, 4);
+        assert_eq!(full.unit_cost, Some(85.0));
+        let empty = movements.iter().find(|m| m.stock_kind == "empty").unwrap();
+        assert_eq!(empty.quantity, -4);
+
+        // Mais vazios do que o disponível
+        assert!(supplier_exchange(&pool, product_id, 2, 85.0, None, None).await.is_err());
+        assert!(supplier_exchange(&pool, product_id, 0, 85.0, None, None).await.is_err());
+        assert!(supplier_exchange(&pool, product_id, 1, -1.0, None, None).await.is_err());
+
+        let product = products::get_product_by_id(&pool, product_id).await.unwrap();
+        assert_eq!(product.stock_empty, 1);
+    }
+
+    #[tokio::test]
+    async fn test_update_product_records_adjustments() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        let product_id = gas(&pool).await;
+
+        products::update_product(&pool, product_id, UpdateProductPayload {
+            stock_full: Some(18),
+            stock_empty: Some(8),
+            ..product_update()
+        }).await.unwrap();
+
+        let movements = get_all_movements(&pool).await.unwrap();
+        assert_eq!(movements.len(), 2);
+        assert!(movements.iter().any(|m| m.stock_kind == "full" && m.quantity == -2));
+        assert!(movements.iter().any(|m| m.stock_kind == "empty" && m.quantity == 3));
+    }
+
+    #[tokio::test]
+    async fn test_average_cost_and_margin() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let product_id = gas(&pool).await;
+
+        products::update_product(&pool, product_id, UpdateProductPayload {
+            cost_price: Some(60.0),
+            ..product_update()
+        }).await.unwrap();
+
+        stock_in(&pool, product_id, 10, Some(90.0), None, None).await.unwrap();
+        // Entrada sem custo não altera o custo médio
+        stock_in(&pool, product_id, 5, None, None, None).await.unwrap();
+
+        let product = products::get_product_by_id(&pool, product_id).await.unwrap();
+        assert_eq!(product.cost_price, 90.0);
+        assert_eq!(product.average_cost, 70.0); // (20 x 60 + 10 x 90) / 30
+
+        orders::create_order(&pool, order_payload(None, product_id, 2, 100.0), None).await.unwrap();
+
+        let top = get_top_products(&pool, 5, 30).await.unwrap();
+        assert_eq!(top[0].total_revenue, 200.0);
+        assert_eq!(top[0].total_cost, 140.0);
+        assert_eq!(top[0].gross_margin, 60.0);
+
+        let stats = get_dashboard_stats(&pool).await.unwrap();
+        assert_eq!(stats.margin_today, 60.0);
+        assert_eq!(stats.margin_month, 60.0);
+    }
+
+    #[tokio::test]
+    async fn test_critical_stock_and_reorder_suggestions() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        let product = |name: &str, stock_full: i64, min_stock: i64, reorder_qty: i64| CreateProductPayload {
+            stock_full: Some(stock_full),
+            min_stock: Some(min_stock),
+            reorder_qty: Some(reorder_qty),
+            ..product_payload(name, "water")
+        };
+
+        // Giro alto: 20 cheios ainda é pouco; giro baixo: 3 cheios bastam
+        let fast = products::create_product(&pool, product("Água 20L", 20, 25, 30)).await.unwrap();
+        let slow = products::create_product(&pool, product("Água 10L", 3, 3, 0)).await.unwrap();
+        gas(&pool).await; // 20 cheios, mínimo padrão de 10
+
+        let critical = get_critical_stock(&pool, None).await.unwrap();
+        assert_eq!(critical.len(), 2);
+        assert!(critical.iter().any(|p| p.id == fast));
+        assert!(critical.iter().any(|p| p.id == slow));
+
+        // Lote de reposição quando cobre o mínimo; senão, o que falta para o mínimo
+        let suggestions = get_reorder_suggestions(&pool, DEFAULT_SALES_WINDOW_DAYS, DEFAULT_COVERAGE_DAYS).await.unwrap();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].product_id, fast);
+        assert_eq!(suggestions[0].suggested_qty, 30);
+
+        products::update_product(&pool, slow, UpdateProductPayload {
+            min_stock: Some(8),
+            ..product_update()
+        }).await.unwrap();
+
+        // Pedido de compra em aberto desconta da sugestão
+        let supplier_id = create_test_supplier(&pool, "Distribuidora Serra").await;
+        purchase_orders::create_purchase_order(&pool, CreatePurchaseOrderPayload {
+            supplier_id,
+            expected_date: None,
+            notes: None,
+            items: vec![PurchaseOrderItemPayload { product_id: fast, quantity: 12, unit_cost: 8.0 }],
+        }, None).await.unwrap();
+
+        let suggestions = get_reorder_suggestions(&pool, DEFAULT_SALES_WINDOW_DAYS, DEFAULT_COVERAGE_DAYS).await.unwrap();
+        assert_eq!(suggestions.len(), 2);
+        let fast_line = suggestions.iter().find(|s| s.product_id == fast).unwrap();
+        assert_eq!((fast_line.on_order, fast_line.suggested_qty), (12, 18));
+        let slow_line = suggestions.iter().find(|s| s.product_id == slow).unwrap();
+        assert_eq!(slow_line.suggested_qty, 5);
+
+        // Quantidade negativa é rejeitada
+        assert!(products::create_product(&pool, product("Carvão 5kg", 0, -1, 0)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reorder_suggestions_by_sales_velocity() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let product_id = create_test_product(&pool, CreateProductPayload {
+            stock_full: Some(40),
+            min_stock: Some(5),
+            ..product_payload("Água 20L", "water")
+        }).await;
+
+        // 30 vendidos em 30 dias: 1 por dia
+        let order = |quantity: i64| order_payload(None, product_id, quantity, 10.0);
+        orders::create_order(&pool, order(20), None).await.unwrap();
+        orders::create_order(&pool, order(10), None).await.unwrap();
+
+        // Venda cancelada não conta no giro
+        let user_id = create_test_user(&pool).await;
+        let cancelled = orders::create_order(&pool, order(5), None).await.unwrap();
+        orders::cancel_order(&pool, cancelled, CancelOrderPayload {
+            reason: "Cliente desistiu".to_string(),
+        }, user_id).await.unwrap();
+
+        // 10 cheios cobrem 10 dias: acima do alvo de 7 dias, nada a comprar
+        assert!(get_reorder_suggestions(&pool, 30, 7).await.unwrap().is_empty());
+
+        // Para 20 dias de cobertura faltam 10
+        let suggestions = get_reorder_suggestions(&pool, 30, 20).await.unwrap();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].sold_quantity, 30);
+        assert_eq!(suggestions[0].avg_daily_sales, 1.0);
+        assert_eq!(suggestions[0].days_of_cover, Some(10.0));
+        assert_eq!(suggestions[0].target_stock, 20);
+        assert_eq!(suggestions[0].suggested_qty, 10);
+
+        assert!(get_reorder_suggestions(&pool, 0, 7).await.is_err());
+    }
+}
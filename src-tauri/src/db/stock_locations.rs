@@ -0,0 +1,352 @@
+use crate::models::{
+    StockLocation, LocationStock, CreateStockLocationPayload, UpdateStockLocationPayload,
+};
+use crate::errors::AppError;
+use sqlx::{Acquire, Sqlite, SqliteConnection, SqliteExecutor, SqlitePool};
+
+/// Loja: local padrão das vendas, compras e ajustes sem local informado
+pub const DEFAULT_LOCATION_ID: i64 = 1;
+
+pub const LOCATION_KINDS: [&str; 3] = ["store", "warehouse", "vehicle"];
+
+pub async fn get_all_locations(pool: &SqlitePool) -> Result<Vec<StockLocation>, AppError> {
+    let locations = sqlx::query_as::<_, StockLocation>(
+        "SELECT * FROM stock_locations ORDER BY id"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(locations)
+}
+
+pub async fn get_location_by_id(conn: impl SqliteExecutor<'_>, id: i64) -> Result<StockLocation, AppError> {
+    let location = sqlx::query_as::<_, StockLocation>(
+        "SELECT * FROM stock_locations WHERE id = ?"
+    )
+    .bind(id)
+    .fetch_optional(conn)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Stock location with id {} not found", id)))?;
+
+    Ok(location)
+}
+
+/// Resolve o local informado (ou a loja) garantindo que ainda está em uso
+pub async fn get_active_location(
+    conn: impl SqliteExecutor<'_>,
+    id: Option<i64>,
+) -> Result<StockLocation, AppError> {
+    let location = get_location_by_id(conn, id.unwrap_or(DEFAULT_LOCATION_ID)).await?;
+
+    if !location.active {
+        return Err(AppError::BusinessLogic(format!("Stock location {} is inactive", location.name)));
+    }
+
+    Ok(location)
+}
+
+pub async fn create_location(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    payload: CreateStockLocationPayload,
+) -> Result<i64, AppError> {
+    if payload.name.trim().is_empty() {
+        return Err(AppError::Validation("Location name cannot be empty".to_string()));
+    }
+
+    if !LOCATION_KINDS.contains(&payload.kind.as_str()) {
+        return Err(AppError::Validation(format!("Invalid location kind: {}", payload.kind)));
+    }
+
+    let mut conn = conn.acquire().await?;
+
+    let id = sqlx::query(
+        "INSERT INTO stock_locations (name, kind) VALUES (?, ?)"
+    )
+    .bind(payload.name.trim())
+    .bind(&payload.kind)
+    .execute(&mut *conn)
+    .await?
+    .last_insert_rowid();
+
+    Ok(id)
+}
+
+pub async fn update_location(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    id: i64,
+    payload: UpdateStockLocationPayload,
+) -> Result<(), AppError> {
+    let mut conn = conn.acquire().await?;
+
+    // Verifica se existe
+    get_location_by_id(&mut *conn, id).await?;
+
+    if id == DEFAULT_LOCATION_ID && payload.active == Some(false) {
+        return Err(AppError::BusinessLogic("The default location cannot be deactivated".to_string()));
+    }
+
+    // Constrói query dinamicamente
+    let mut query = sqlx::QueryBuilder::new("UPDATE stock_locations SET ");
+
+    let mut has_updates = false;
+
+    if let Some(name) = &payload.name {
+        if name.trim().is_empty() {
+            return Err(AppError::Validation("Location name cannot be empty".to_string()));
+        }
+        query.push("name = ");
+        query.push_bind(name.trim());
+        has_updates = true;
+    }
+
+    if let Some(active) = payload.active {
+        if has_updates {
+            query.push(", ");
+        }
+        query.push("active = ");
+        query.push_bind(active);
+        has_updates = true;
+    }
+
+    if !has_updates {
+        return Ok(());
+    }
+
+    query.push(" WHERE id = ");
+    query.push_bind(id);
+
+    query.build().execute(&mut *conn).await?;
+
+    Ok(())
+}
+
+/// Saldo por produto e local, opcionalmente filtrado por local
+pub async fn get_location_stock(
+    pool: &SqlitePool,
+    location_id: Option<i64>,
+) -> Result<Vec<LocationStock>, AppError> {
+    let stock = sqlx::query_as::<_, LocationStock>(
+        "SELECT ps.product_id, p.name as product_name, ps.location_id, l.name as location_name,
+                ps.stock_full, ps.stock_empty
+         FROM product_stock ps
+         JOIN products p ON ps.product_id = p.id
+         JOIN stock_locations l ON ps.location_id = l.id
+         WHERE ? IS NULL OR ps.location_id = ?
+         ORDER BY l.id, p.name"
+    )
+    .bind(location_id)
+    .bind(location_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(stock)
+}
+
+/// Saldo (cheios, vazios) do produto no local
+pub(crate) async fn location_stock(
+    conn: &mut SqliteConnection,
+    product_id: i64,
+    location_id: i64,
+) -> Result<(i64, i64), AppError> {
+    let stock: (i64, i64) = sqlx::query_as(
+        "SELECT COALESCE(ps.stock_full, 0), COALESCE(ps.stock_empty, 0)
+         FROM products p
+         LEFT JOIN product_stock ps ON ps.product_id = p.id AND ps.location_id = ?
+         WHERE p.id = ?"
+    )
+    .bind(location_id)
+    .bind(product_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Product {} not found", product_id)))?;
+
+    Ok(stock)
+}
+
+/// Veículo do entregador, criado junto com o cadastro
+pub(crate) async fn create_vehicle_for_driver(
+    conn: &mut SqliteConnection,
+    driver_id: i64,
+    driver_name: &str,
+) -> Result<i64, AppError> {
+    let id = sqlx::query(
+        "INSERT INTO stock_locations (name, kind, driver_id) VALUES (?, 'vehicle', ?)"
+    )
+    .bind(format!("Veículo - {}", driver_name))
+    .bind(driver_id)
+    .execute(&mut *conn)
+    .await?
+    .last_insert_rowid();
+
+    Ok(id)
+}
+
+pub(crate) async fn vehicle_for_driver(
+    conn: &mut SqliteConnection,
+    driver_id: i64,
+) -> Result<i64, AppError> {
+    let location: (i64,) = sqlx::query_as(
+        "SELECT id FROM stock_locations WHERE driver_id = ?"
+    )
+    .bind(driver_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Vehicle for driver {} not found", driver_id)))?;
+
+    Ok(location.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_helpers::{
+        setup_test_db, cleanup_test_db, open_test_session, create_test_product, product_payload,
+        product_update, order_payload,
+    };
+    use crate::db::{orders, products, stock};
+    use crate::models::{CreateOrderPayload, CreateProductPayload, TransferStockPayload, UpdateProductPayload};
+
+    async fn stock_at(pool: &SqlitePool, product_id: i64, location_id: i64) -> (i64, i64) {
+        let mut conn = pool.acquire().await.unwrap();
+        location_stock(&mut conn, product_id, location_id).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_transfer_between_locations() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        let product_id = create_test_product(&pool, CreateProductPayload {
+            stock_empty: Some(4),
+            ..product_payload("Água 20L", "water")
+        }).await;
+        let warehouse_id = create_location(&pool, CreateStockLocationPayload {
+            name: "Depósito".to_string(),
+            kind: "warehouse".to_string(),
+        }).await.unwrap();
+
+        // Estoque inicial fica na loja
+        assert_eq!(stock_at(&pool, product_id, DEFAULT_LOCATION_ID).await, (20, 4));
+        assert_eq!(stock_at(&pool, product_id, warehouse_id).await, (0, 0));
+
+        stock::transfer_stock(&pool, TransferStockPayload {
+            product_id,
+            from_location_id: DEFAULT_LOCATION_ID,
+            to_location_id: warehouse_id,
+            stock_kind: None,
+            quantity: 15,
+        }).await.unwrap();
+
+        assert_eq!(stock_at(&pool, product_id, DEFAULT_LOCATION_ID).await, (5, 4));
+        assert_eq!(stock_at(&pool, product_id, warehouse_id).await, (15, 0));
+
+        // Total do produto não muda com a transferência
+        let product = products::get_product_by_id(&pool, product_id).await.unwrap();
+        assert_eq!(product.stock_full, 20);
+
+        let movements = stock::get_all_movements(&pool).await.unwrap();
+        assert_eq!(movements.iter().filter(|m| m.movement_type == "TRANSFER").count(), 2);
+        assert!(movements.iter().any(|m| m.location_id == Some(warehouse_id) && m.quantity == 15));
+
+        // Mais do que o disponível na origem, mesmo local e quantidade inválida
+        for (from, to, quantity) in [
+            (DEFAULT_LOCATION_ID, warehouse_id, 6),
+            (warehouse_id, warehouse_id, 1),
+            (warehouse_id, DEFAULT_LOCATION_ID, 0),
+        ] {
+            assert!(stock::transfer_stock(&pool, TransferStockPayload {
+                product_id,
+                from_location_id: from,
+                to_location_id: to,
+                stock_kind: None,
+                quantity,
+            }).await.is_err());
+        }
+
+        // Crítico por local: o depósito está abastecido, a loja não
+        let critical = stock::get_critical_stock(&pool, Some(DEFAULT_LOCATION_ID)).await.unwrap();
+        assert_eq!(critical.len(), 1);
+        assert_eq!(critical[0].stock_full, 5);
+        assert!(stock::get_critical_stock(&pool, Some(warehouse_id)).await.unwrap().is_empty());
+        assert!(stock::get_critical_stock(&pool, None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_direct_stock_edit_limited_to_store() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        let product_id = create_test_product(&pool, CreateProductPayload {
+            stock_empty: Some(4),
+            ..product_payload("Água 20L", "water")
+        }).await;
+        let warehouse_id = create_location(&pool, CreateStockLocationPayload {
+            name: "Depósito".to_string(),
+            kind: "warehouse".to_string(),
+        }).await.unwrap();
+
+        stock::transfer_stock(&pool, TransferStockPayload {
+            product_id,
+            from_location_id: DEFAULT_LOCATION_ID,
+            to_location_id: warehouse_id,
+            stock_kind: None,
+            quantity: 15,
+        }).await.unwrap();
+
+        // A loja tem só 5 cheios; o depósito não é baixado pela edição direta
+        let result = products::update_product(&pool, product_id, UpdateProductPayload {
+            stock_full: Some(0),
+            ..product_update()
+        }).await;
+        assert!(matches!(result, Err(AppError::BusinessLogic(_))));
+        assert_eq!(stock_at(&pool, product_id, DEFAULT_LOCATION_ID).await, (5, 4));
+
+        products::update_product(&pool, product_id, UpdateProductPayload {
+            stock_full: Some(15),
+            ..product_update()
+        }).await.unwrap();
+        assert_eq!(stock_at(&pool, product_id, DEFAULT_LOCATION_ID).await, (0, 4));
+        assert_eq!(stock_at(&pool, product_id, warehouse_id).await, (15, 0));
+    }
+
+    #[tokio::test]
+    async fn test_order_draws_from_location() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let product_id = create_test_product(&pool, CreateProductPayload {
+            stock_empty: Some(4),
+            ..product_payload("Água 20L", "water")
+        }).await;
+        let warehouse_id = create_location(&pool, CreateStockLocationPayload {
+            name: "Depósito".to_string(),
+            kind: "warehouse".to_string(),
+        }).await.unwrap();
+
+        let order = |location_id: Option<i64>| CreateOrderPayload {
+            location_id,
+            ..order_payload(None, product_id, 3, 12.0)
+        };
+
+        // Depósito vazio não atende a venda
+        assert!(orders::create_order(&pool, order(Some(warehouse_id)), None).await.is_err());
+
+        stock::stock_in(&pool, product_id, 5, None, Some(warehouse_id), None).await.unwrap();
+        orders::create_order(&pool, order(Some(warehouse_id)), None).await.unwrap();
+
+        assert_eq!(stock_at(&pool, product_id, warehouse_id).await, (2, 3));
+        assert_eq!(stock_at(&pool, product_id, DEFAULT_LOCATION_ID).await, (20, 4));
+
+        // Local inativo não vende
+        update_location(&pool, warehouse_id, UpdateStockLocationPayload {
+            name: None,
+            active: Some(false),
+        }).await.unwrap();
+        assert!(orders::create_order(&pool, order(Some(warehouse_id)), None).await.is_err());
+        assert!(update_location(&pool, DEFAULT_LOCATION_ID, UpdateStockLocationPayload {
+            name: None,
+            active: Some(false),
+        }).await.is_err());
+    }
+}
@@ -0,0 +1,393 @@
+use crate::models::StockLot;
+use crate::errors::AppError;
+use crate::db::reports::validate_date;
+use sqlx::{SqliteConnection, SqlitePool};
+
+// Lotes só controlam cheios. A soma dos lotes de um local pode ficar abaixo do
+// saldo do local: unidades que entraram sem validade (troca sem data, ajuste
+// positivo) não têm lote. Saídas consomem primeiro os lotes que vencem antes (FEFO).
+
+/// Tipos de produto cuja venda é bloqueada com lote vencido
+pub const PERISHABLE_PRODUCT_TYPES: [&str; 1] = ["water"];
+
+/// Prazo padrão, em dias, para o lote entrar no alerta de vencimento (ajustável nas configurações)
+pub const EXPIRY_WARNING_DAYS: i64 = 30;
+
+const LOT_SELECT: &str =
+    "SELECT l.id, l.product_id, p.name as product_name, l.location_id, sl.name as location_name,
+            l.expiry_date, l.quantity,
+            CAST(julianday(l.expiry_date) - julianday(date('now')) AS INTEGER) as days_to_expiry,
+            l.created_at
+     FROM stock_lots l
+     JOIN products p ON l.product_id = p.id
+     JOIN stock_locations sl ON l.location_id = sl.id";
+
+/// Quantidade de um lote consumida ou reposta por uma movimentação
+#[derive(Debug, Clone)]
+pub(crate) struct LotUsage {
+    pub expiry_date: String,
+    pub quantity: i64,
+}
+
+/// Lotes com saldo, opcionalmente filtrados por produto e local
+pub async fn get_lots(
+    pool: &SqlitePool,
+    product_id: Option<i64>,
+    location_id: Option<i64>,
+) -> Result<Vec<StockLot>, AppError> {
+    let lots = sqlx::query_as::<_, StockLot>(&format!(
+        "{} WHERE l.quantity > 0
+           AND (? IS NULL OR l.product_id = ?)
+           AND (? IS NULL OR l.location_id = ?)
+         ORDER BY l.expiry_date, p.name, sl.id",
+        LOT_SELECT
+    ))
+    .bind(product_id)
+    .bind(product_id)
+    .bind(location_id)
+    .bind(location_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(lots)
+}
+
+/// Lotes com saldo que vencem nos próximos `days` dias, incluindo os já vencidos
+pub async fn get_expiring_lots(pool: &SqlitePool, days: i64) -> Result<Vec<StockLot>, AppError> {
+    if days < 0 {
+        return Err(AppError::Validation("Days cannot be negative".to_string()));
+    }
+
+    let lots = sqlx::query_as::<_, StockLot>(&format!(
+        "{} WHERE l.quantity > 0 AND l.expiry_date <= date('now', '+' || ? || ' days')
+         ORDER BY l.expiry_date, p.name, sl.id",
+        LOT_SELECT
+    ))
+    .bind(days)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(lots)
+}
+
+/// Unidades vencidas do produto no local. Como a saída é FEFO, são as primeiras a sair.
+pub(crate) async fn expired_quantity(
+    conn: &mut SqliteConnection,
+    product_id: i64,
+    location_id: i64,
+) -> Result<i64, AppError> {
+    let expired: (i64,) = sqlx::query_as(
+        "SELECT COALESCE(SUM(quantity), 0) FROM stock_lots
+         WHERE product_id = ? AND location_id = ? AND expiry_date < date('now')"
+    )
+    .bind(product_id)
+    .bind(location_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(expired.0)
+}
+
+/// Repõe o lote do produto no local com a validade informada, criando-o se preciso.
+/// Sem movimentação (estoque inicial do cadastro), o lote não fica vinculado.
+pub(crate) async fn add_to_lot(
+    conn: &mut SqliteConnection,
+    movement_id: Option<i64>,
+    product_id: i64,
+    location_id: i64,
+    expiry_date: &str,
+    quantity: i64,
+) -> Result<(), AppError> {
+    validate_date(&mut *conn, "expiry date", expiry_date).await?;
+
+    let lot: (i64,) = sqlx::query_as(
+        "INSERT INTO stock_lots (product_id, location_id, expiry_date, quantity) VALUES (?, ?, ?, ?)
+         ON CONFLICT(product_id, location_id, expiry_date) DO UPDATE SET quantity = quantity + excluded.quantity
+         RETURNING id"
+    )
+    .bind(product_id)
+    .bind(location_id)
+    .bind(expiry_date)
+    .bind(quantity)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    match movement_id {
+        Some(movement_id) => link_movement(conn, movement_id, lot.0, quantity).await,
+        None => Ok(()),
+    }
+}
+
+/// Consome os lotes do produto no local, dos que vencem antes para os que vencem depois
+pub(crate) async fn consume_lots(
+    conn: &mut SqliteConnection,
+    movement_id: i64,
+    product_id: i64,
+    location_id: i64,
+    quantity: i64,
+) -> Result<Vec<LotUsage>, AppError> {
+    let lots: Vec<(i64, String, i64)> = sqlx::query_as(
+        "SELECT id, expiry_date, quantity FROM stock_lots
+         WHERE product_id = ? AND location_id = ? AND quantity > 0
+         ORDER BY expiry_date, id"
+    )
+    .bind(product_id)
+    .bind(location_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut remaining = quantity;
+    let mut consumed = Vec::new();
+
+    for (lot_id, expiry_date, available) in lots {
+        if remaining == 0 {
+            break;
+        }
+
+        let taken = available.min(remaining);
+
+        sqlx::query("UPDATE stock_lots SET quantity = quantity - ? WHERE id = ?")
+            .bind(taken)
+            .bind(lot_id)
+            .execute(&mut *conn)
+            .await?;
+
+        link_movement(conn, movement_id, lot_id, -taken).await?;
+
+        consumed.push(LotUsage { expiry_date, quantity: taken });
+        remaining -= taken;
+    }
+
+    Ok(consumed)
+}
+
+async fn link_movement(
+    conn: &mut SqliteConnection,
+    movement_id: i64,
+    lot_id: i64,
+    quantity: i64,
+) -> Result<(), AppError> {
+    sqlx::query("INSERT INTO stock_movement_lots (movement_id, lot_id, quantity) VALUES (?, ?, ?)")
+        .bind(movement_id)
+        .bind(lot_id)
+        .bind(quantity)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Lotes consumidos pelas saídas do pedido, por produto, para o estorno no cancelamento
+pub(crate) async fn order_lots(
+    conn: &mut SqliteConnection,
+    order_id: i64,
+) -> Result<Vec<(i64, LotUsage)>, AppError> {
+    let rows: Vec<(i64, String, i64)> = sqlx::query_as(
+        "SELECT sm.product_id, l.expiry_date, -SUM(sml.quantity)
+         FROM stock_movement_lots sml
+         JOIN stock_movements sm ON sml.movement_id = sm.id
+         JOIN stock_lots l ON sml.lot_id = l.id
+         WHERE sm.order_id = ? AND sm.movement_type = 'OUT'
+         GROUP BY sm.product_id, l.expiry_date
+         ORDER BY l.expiry_date"
+    )
+    .bind(order_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(product_id, expiry_date, quantity)| (product_id, LotUsage { expiry_date, quantity }))
+        .collect())
+}
+
+/// Separa até `quantity` unidades do produto entre os lotes consumidos
+pub(crate) fn take_lots(
+    consumed: &mut Vec<(i64, LotUsage)>,
+    product_id: i64,
+    quantity: i64,
+) -> Vec<LotUsage> {
+    let mut remaining = quantity;
+    let mut taken = Vec::new();
+
+    for (lot_product_id, lot) in consumed.iter_mut() {
+        if remaining == 0 {
+            break;
+        }
+
+        if *lot_product_id != product_id || lot.quantity == 0 {
+            continue;
+        }
+
+        let quantity = lot.quantity.min(remaining);
+        lot.quantity -= quantity;
+        remaining -= quantity;
+        taken.push(LotUsage { expiry_date: lot.expiry_date.clone(), quantity });
+    }
+
+    consumed.retain(|(_, lot)| lot.quantity > 0);
+
+    taken
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_helpers::{
+        setup_test_db, cleanup_test_db, create_test_user, open_test_session, create_test_product,
+        product_payload, order_payload,
+    };
+    use crate::db::{orders, stock, stock_locations};
+    use crate::models::{
+        CancelOrderPayload, CreateOrderPayload, CreateProductPayload, CreateStockLocationPayload,
+        TransferStockPayload,
+    };
+
+    async fn lot_quantities(pool: &SqlitePool, product_id: i64, location_id: i64) -> Vec<(String, i64)> {
+        get_lots(pool, Some(product_id), Some(location_id))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|lot| (lot.expiry_date, lot.quantity))
+            .collect()
+    }
+
+    async fn date_in(pool: &SqlitePool, days: i64) -> String {
+        let date: (String,) = sqlx::query_as("SELECT date('now', ? || ' days')")
+            .bind(days)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        date.0
+    }
+
+    fn order(product_id: i64, quantity: i64) -> CreateOrderPayload {
+        order_payload(None, product_id, quantity, 12.0)
+    }
+
+    #[tokio::test]
+    async fn test_fefo_consumption_and_cancellation() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let product_id = create_test_product(&pool, CreateProductPayload {
+            stock_full: Some(5),
+            expiry_date: Some("2031-06-30".to_string()),
+            ..product_payload("Água 20L", "water")
+        }).await;
+
+        stock::stock_in(&pool, product_id, 4, None, None, Some("2031-03-31".to_string())).await.unwrap();
+        stock::stock_in(&pool, product_id, 2, None, None, None).await.unwrap(); // Sem validade
+        assert!(stock::stock_in(&pool, product_id, 1, None, None, Some("2031-02-30".to_string())).await.is_err());
+
+        // A venda consome primeiro o lote que vence antes
+        let order_id = orders::create_order(&pool, order(product_id, 6), None).await.unwrap();
+        assert_eq!(
+            lot_quantities(&pool, product_id, stock_locations::DEFAULT_LOCATION_ID).await,
+            vec![("2031-06-30".to_string(), 3)],
+        );
+
+        // Cancelamento devolve aos mesmos lotes
+        let user_id = create_test_user(&pool).await;
+        orders::cancel_order(&pool, order_id, CancelOrderPayload {
+            reason: "Pedido em duplicidade".to_string(),
+        }, user_id).await.unwrap();
+        assert_eq!(
+            lot_quantities(&pool, product_id, stock_locations::DEFAULT_LOCATION_ID).await,
+            vec![("2031-03-31".to_string(), 4), ("2031-06-30".to_string(), 5)],
+        );
+
+        // A transferência leva os lotes junto
+        let warehouse_id = stock_locations::create_location(&pool, CreateStockLocationPayload {
+            name: "Depósito".to_string(),
+            kind: "warehouse".to_string(),
+        }).await.unwrap();
+        stock::transfer_stock(&pool, TransferStockPayload {
+            product_id,
+            from_location_id: stock_locations::DEFAULT_LOCATION_ID,
+            to_location_id: warehouse_id,
+            stock_kind: None,
+            quantity: 5,
+        }).await.unwrap();
+        assert_eq!(
+            lot_quantities(&pool, product_id, warehouse_id).await,
+            vec![("2031-03-31".to_string(), 4), ("2031-06-30".to_string(), 1)],
+        );
+        assert_eq!(
+            lot_quantities(&pool, product_id, stock_locations::DEFAULT_LOCATION_ID).await,
+            vec![("2031-06-30".to_string(), 4)],
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expiring_lots() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+
+        let product_id = create_test_product(&pool, CreateProductPayload {
+            stock_full: Some(0),
+            ..product_payload("Água 20L", "water")
+        }).await;
+
+        let expired = date_in(&pool, -2).await;
+        let soon = date_in(&pool, 10).await;
+        let later = date_in(&pool, 90).await;
+        for expiry in [&expired, &soon, &later] {
+            stock::stock_in(&pool, product_id, 3, None, None, Some(expiry.clone())).await.unwrap();
+        }
+
+        let lots = get_expiring_lots(&pool, 30).await.unwrap();
+        assert_eq!(lots.len(), 2);
+        assert_eq!(lots[0].expiry_date, expired);
+        assert_eq!(lots[0].days_to_expiry, -2);
+        assert_eq!(lots[1].days_to_expiry, 10);
+
+        // Lote zerado sai da lista
+        stock::stock_out(&pool, product_id, 3, None).await.unwrap();
+        let lots = get_expiring_lots(&pool, 30).await.unwrap();
+        assert_eq!(lots.len(), 1);
+        assert_eq!(lots[0].expiry_date, soon);
+
+        assert!(get_expiring_lots(&pool, -1).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_expired_sale_blocked_without_override() {
+        let pool = setup_test_db().await;
+        cleanup_test_db(&pool).await;
+        open_test_session(&pool).await;
+
+        let product_id = create_test_product(&pool, CreateProductPayload {
+            stock_full: Some(0),
+            ..product_payload("Água 20L", "water")
+        }).await;
+
+        let expired = date_in(&pool, -1).await;
+        let soon = date_in(&pool, 5).await;
+        stock::stock_in(&pool, product_id, 1, None, None, Some(expired.clone())).await.unwrap();
+        stock::stock_in(&pool, product_id, 4, None, None, Some(soon.clone())).await.unwrap();
+
+        let stats = stock::get_dashboard_stats(&pool).await.unwrap();
+        assert_eq!(stats.expired_lots.len(), 1);
+        assert_eq!(stats.expired_lots[0].expiry_date, expired);
+        assert_eq!(stats.expiring_lots.len(), 1);
+        assert_eq!(stats.expiring_lots[0].expiry_date, soon);
+
+        // A venda pegaria o lote vencido primeiro
+        let err = orders::create_order(&pool, order(product_id, 2), None).await.unwrap_err();
+        assert!(err.to_string().contains("Expired stock"));
+
+        // Com a liberação, o vencido sai junto
+        let mut payload = order(product_id, 2);
+        payload.allow_expired = Some(true);
+        orders::create_order(&pool, payload, None).await.unwrap();
+        assert_eq!(
+            lot_quantities(&pool, product_id, stock_locations::DEFAULT_LOCATION_ID).await,
+            vec![(soon, 3)],
+        );
+
+        // Sem lote vencido, a venda volta a passar normalmente
+        orders::create_order(&pool, order(product_id, 1), None).await.unwrap();
+    }
+}
@@ -0,0 +1,151 @@
+use crate::models::{Supplier, CreateSupplierPayload, UpdateSupplierPayload};
+use crate::errors::AppError;
+use sqlx::{Acquire, Sqlite, SqliteExecutor, SqlitePool};
+
+pub async fn get_all_suppliers(pool: &SqlitePool) -> Result<Vec<Supplier>, AppError> {
+    let suppliers = sqlx::query_as::<_, Supplier>(
+        "SELECT * FROM suppliers ORDER BY name"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(suppliers)
+}
+
+pub async fn get_supplier_by_id(conn: impl SqliteExecutor<'_>, id: i64) -> Result<Supplier, AppError> {
+    let supplier = sqlx::query_as::<_, Supplier>(
+        "SELECT * FROM suppliers WHERE id = ?"
+    )
+    .bind(id)
+    .fetch_optional(conn)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Supplier with id {} not found", id)))?;
+
+    Ok(supplier)
+}
+
+pub async fn create_supplier(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    payload: CreateSupplierPayload,
+) -> Result<i64, AppError> {
+    if payload.name.is_empty() {
+        return Err(AppError::Validation("Supplier name cannot be empty".to_string()));
+    }
+
+    let mut conn = conn.acquire().await?;
+
+    let id = sqlx::query(
+        "INSERT INTO suppliers (name, document, phone, email, notes)
+         VALUES (?, ?, ?, ?, ?)"
+    )
+    .bind(&payload.name)
+    .bind(&payload.document)
+    .bind(&payload.phone)
+    .bind(&payload.email)
+    .bind(&payload.notes)
+    .execute(&mut *conn)
+    .await?
+    .last_insert_rowid();
+
+    Ok(id)
+}
+
+pub async fn update_supplier(
+    conn: impl Acquire<'_, Database = Sqlite>,
+    id: i64,
+    payload: UpdateSupplierPayload,
+) -> Result<(), AppError> {
+    let mut conn = conn.acquire().await?;
+
+    // Verifica se existe
+    get_supplier_by_id(&mut *conn, id).await?;
+
+    // Constrói query dinamicamente
+    let mut query = sqlx::QueryBuilder::new("UPDATE suppliers SET ");
+
+    let mut has_updates = false;
+
+    if let Some(name) = &payload.name {
+        if name.is_empty() {
+            return Err(AppError::Validation("Supplier name cannot be empty".to_string()));
+        }
+        query.push("name = ");
+        query.push_bind(name);
+        has_updates = true;
+    }
+
+    if let Some(document) = &payload.document {
+        if has_updates {
+            query.push(", ");
+        }
+        query.push("document = ");
+        query.push_bind(document);
+        has_updates = true;
+    }
+
+    if let Some(phone) = &payload.phone {
+        if has_updates {
+            query.push(", ");
+        }
+        query.push("phone = ");
+        query.push_bind(phone);
+        has_updates = true;
+    }
+
+    if let Some(email) = &payload.email {
+        if has_updates {
+            query.push(", ");
+        }
+        query.push("email = ");
+        query.push_bind(email);
+        has_updates = true;
+    }
+
+    if let Some(notes) = &payload.notes {
+        if has_updates {
+            query.push(", ");
+        }
+        query.push("notes = ");
+        query.push_bind(notes);
+        has_updates = true;
+    }
+
+    if !has_updates {
+        return Ok(());
+    }
+
+    query.push(" WHERE id = ");
+    query.push_bind(id);
+
+    query.build().execute(&mut *conn).await?;
+
+    Ok(())
+}
+
+pub async fn delete_supplier(conn: impl Acquire<'_, Database = Sqlite>, id: i64) -> Result<(), AppError> {
+    let mut conn = conn.acquire().await?;
+
+    // Verifica se existe
+    get_supplier_by_id(&mut *conn, id).await?;
+
+    // Fornecedor com pedidos de compra precisa ser mantido para rastreabilidade
+    let purchase_orders: (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM purchase_orders WHERE supplier_id = ?"
+    )
+    .bind(id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if purchase_orders.0 > 0 {
+        return Err(AppError::BusinessLogic(
+            format!("Supplier {} has {} purchase order(s) and cannot be deleted", id, purchase_orders.0)
+        ));
+    }
+
+    sqlx::query("DELETE FROM suppliers WHERE id = ?")
+        .bind(id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
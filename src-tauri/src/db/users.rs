@@ -1,4 +1,5 @@
 use super::DbPool;
+use sqlx::{Acquire, Sqlite, SqliteExecutor};
 use crate::{errors::{AppError, Result}, models::{User, UserListItem, CreateUserPayload, UpdateUserPayload}};
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHasher, SaltString},
@@ -14,10 +15,10 @@ pub async fn get_user_by_username(pool: &DbPool, username: &str) -> Result<User>
     Ok(user)
 }
 
-pub async fn get_user_by_id(pool: &DbPool, id: i64) -> Result<User> {
+pub async fn get_user_by_id(conn: impl SqliteExecutor<'_>, id: i64) -> Result<User> {
     let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = ?")
         .bind(id)
-        .fetch_optional(pool)
+        .fetch_optional(conn)
         .await?
         .ok_or_else(|| AppError::NotFound(format!("User with id {} not found", id)))?;
 
@@ -35,7 +36,7 @@ pub async fn get_all_users(pool: &DbPool) -> Result<Vec<UserListItem>> {
 }
 
 pub async fn create_user(
-    pool: &DbPool,
+    conn: impl Acquire<'_, Database = Sqlite>,
     payload: CreateUserPayload,
 ) -> Result<i64> {
     // Validação
@@ -56,11 +57,13 @@ pub async fn create_user(
     }
 
     // Verifica se o usuário já existe
+    let mut conn = conn.acquire().await?;
+
     let user_exists: (bool,) = sqlx::query_as(
         "SELECT EXISTS(SELECT 1 FROM users WHERE username = ?)"
     )
     .bind(&payload.username)
-    .fetch_one(pool)
+    .fetch_one(&mut *conn)
     .await?;
 
     if user_exists.0 {
@@ -81,7 +84,7 @@ pub async fn create_user(
     .bind(&payload.username)
     .bind(password_hash)
     .bind(&payload.role)
-    .execute(pool)
+    .execute(&mut *conn)
     .await?
     .last_insert_rowid();
 
@@ -89,12 +92,14 @@ pub async fn create_user(
 }
 
 pub async fn update_user(
-    pool: &DbPool,
+    conn: impl Acquire<'_, Database = Sqlite>,
     id: i64,
     payload: UpdateUserPayload,
 ) -> Result<()> {
+    let mut conn = conn.acquire().await?;
+
     // Verifica se existe
-    get_user_by_id(pool, id).await?;
+    get_user_by_id(&mut *conn, id).await?;
 
     // Validações
     if let Some(ref username) = payload.username {
@@ -108,7 +113,7 @@ pub async fn update_user(
         )
         .bind(username)
         .bind(id)
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?;
 
         if user_exists.0 {
@@ -178,23 +183,54 @@ pub async fn update_user(
     query_builder.push(", updated_at = CURRENT_TIMESTAMP WHERE id = ");
     query_builder.push_bind(id);
 
-    query_builder.build().execute(pool).await?;
+    query_builder.build().execute(&mut *conn).await?;
 
     Ok(())
 }
 
-pub async fn delete_user(pool: &DbPool, id: i64) -> Result<()> {
+/// Colunas que guardam o operador responsável pelo registro
+const USER_REFERENCES: [(&str, &str); 11] = [
+    ("orders", "user_id"),
+    ("orders", "cancelled_by"),
+    ("cash_sessions", "opened_by"),
+    ("cash_sessions", "closed_by"),
+    ("cash_movements", "user_id"),
+    ("customer_ledger", "user_id"),
+    ("bottle_loans", "user_id"),
+    ("purchase_orders", "user_id"),
+    ("purchase_receipts", "user_id"),
+    ("driver_shifts", "opened_by"),
+    ("driver_shifts", "closed_by"),
+];
+
+pub async fn delete_user(conn: impl Acquire<'_, Database = Sqlite>, id: i64) -> Result<()> {
+    let mut conn = conn.acquire().await?;
+
     // Verifica se existe
-    let user = get_user_by_id(pool, id).await?;
+    let user = get_user_by_id(&mut *conn, id).await?;
 
     // Não permite deletar o usuário admin padrão
     if user.username == "admin" {
         return Err(AppError::Validation("Cannot delete the default admin user".to_string()));
     }
 
+    // Usuário com movimentação fica no histórico
+    for (table, column) in USER_REFERENCES {
+        let count: (i64,) = sqlx::query_as(&format!("SELECT COUNT(*) FROM {} WHERE {} = ?", table, column))
+            .bind(id)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        if count.0 > 0 {
+            return Err(AppError::BusinessLogic(
+                format!("User {} has records in {} and cannot be deleted", user.username, table)
+            ));
+        }
+    }
+
     sqlx::query("DELETE FROM users WHERE id = ?")
         .bind(id)
-        .execute(pool)
+        .execute(&mut *conn)
         .await?;
 
     Ok(())
@@ -19,6 +19,12 @@ pub enum AppError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
+    #[error("Export error: {0}")]
+    Export(String),
+
+    #[error("Printer error: {0}")]
+    Printer(String),
+
     #[error("Invalid credentials")]
     InvalidCredentials,
 
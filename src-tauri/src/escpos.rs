@@ -0,0 +1,219 @@
+// Montagem de impressão para impressoras térmicas no padrão ESC/POS. O texto sai na
+// página de código 850, que cobre os acentos do português na maioria dos modelos.
+
+const ESC: u8 = 0x1B;
+const GS: u8 = 0x1D;
+const LF: u8 = 0x0A;
+
+/// Larguras de papel suportadas, em mm
+pub const PAPER_WIDTHS: [i64; 2] = [58, 80];
+
+/// Caracteres por linha na fonte padrão
+pub fn columns_for(paper_width: i64) -> usize {
+    if paper_width == 58 { 32 } else { 48 }
+}
+
+pub enum Align {
+    Left,
+    Center,
+}
+
+pub struct EscPos {
+    bytes: Vec<u8>,
+    width: usize,
+}
+
+impl EscPos {
+    pub fn new(width: usize) -> Self {
+        // ESC @ reinicia a impressora; ESC t 2 seleciona a página de código 850
+        EscPos {
+            bytes: vec![ESC, b'@', ESC, b't', 2],
+            width,
+        }
+    }
+
+    pub fn align(&mut self, align: Align) -> &mut Self {
+        let n = match align {
+            Align::Left => 0,
+            Align::Center => 1,
+        };
+        self.bytes.extend_from_slice(&[ESC, b'a', n]);
+        self
+    }
+
+    pub fn bold(&mut self, on: bool) -> &mut Self {
+        self.bytes.extend_from_slice(&[ESC, b'E', on as u8]);
+        self
+    }
+
+    /// Altura dupla; a largura da linha continua a mesma
+    pub fn double_height(&mut self, on: bool) -> &mut Self {
+        self.bytes.extend_from_slice(&[GS, b'!', if on { 0x01 } else { 0x00 }]);
+        self
+    }
+
+    /// Texto quebrado em palavras na largura do papel
+    pub fn text(&mut self, text: &str) -> &mut Self {
+        for line in wrap(text, self.width) {
+            self.line(&line);
+        }
+        self
+    }
+
+    /// Texto à esquerda e valor encostado à direita na última linha
+    pub fn columns(&mut self, left: &str, right: &str) -> &mut Self {
+        let right_len = right.chars().count();
+        let mut lines = wrap(left, self.width);
+        let last = lines.pop().unwrap_or_default();
+
+        for line in lines {
+            self.line(&line);
+        }
+
+        let last_len = last.chars().count();
+        if last_len + 1 + right_len <= self.width {
+            let padding = self.width - last_len - right_len;
+            self.line(&format!("{}{}{}", last, " ".repeat(padding), right));
+        } else {
+            self.line(&last);
+            self.line(&format!("{:>width$}", right, width = self.width));
+        }
+        self
+    }
+
+    pub fn separator(&mut self) -> &mut Self {
+        self.line(&"-".repeat(self.width))
+    }
+
+    pub fn feed(&mut self, lines: u8) -> &mut Self {
+        self.bytes.extend_from_slice(&[ESC, b'd', lines]);
+        self
+    }
+
+    /// QR code modelo 2 (GS ( k). Texto grande demais para o comando é ignorado.
+    pub fn qr_code(&mut self, data: &str) -> &mut Self {
+        let data = data.as_bytes();
+        let len = data.len() + 3;
+        if data.is_empty() || len > u16::MAX as usize {
+            return self;
+        }
+
+        // Modelo 2, módulo de 6 pontos, correção de erro nível M
+        self.bytes.extend_from_slice(&[GS, b'(', b'k', 4, 0, 49, 65, 50, 0]);
+        self.bytes.extend_from_slice(&[GS, b'(', b'k', 3, 0, 49, 67, 6]);
+        self.bytes.extend_from_slice(&[GS, b'(', b'k', 3, 0, 49, 69, 49]);
+
+        // Armazena e imprime
+        self.bytes.extend_from_slice(&[GS, b'(', b'k', (len & 0xFF) as u8, (len >> 8) as u8, 49, 80, 48]);
+        self.bytes.extend_from_slice(data);
+        self.bytes.extend_from_slice(&[GS, b'(', b'k', 3, 0, 49, 81, 48]);
+        self.bytes.push(LF);
+        self
+    }
+
+    /// Avança o papel até a guilhotina e faz o corte parcial
+    pub fn cut(&mut self) -> &mut Self {
+        self.bytes.extend_from_slice(&[GS, b'V', 66, 3]);
+        self
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    fn line(&mut self, text: &str) -> &mut Self {
+        self.bytes.extend(encode(text));
+        self.bytes.push(LF);
+        self
+    }
+}
+
+/// Quebra por palavras; palavra maior que a linha é partida
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+
+        loop {
+            let current_len = current.chars().count();
+            let needed = if current.is_empty() { word.len() } else { current_len + 1 + word.len() };
+
+            if needed <= width {
+                if !current.is_empty() {
+                    current.push(' ');
+                }
+                current.extend(word.iter());
+                break;
+            }
+
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+                continue;
+            }
+
+            let rest = word.split_off(width);
+            lines.push(word.into_iter().collect());
+            word = rest;
+        }
+    }
+
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+
+    lines
+}
+
+/// Página de código 850; o que não existe nela sai como '?'
+fn encode(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            c if c.is_ascii() && !c.is_ascii_control() => c as u8,
+            'Ç' => 0x80, 'ü' => 0x81, 'é' => 0x82, 'â' => 0x83, 'à' => 0x85, 'ç' => 0x87,
+            'ê' => 0x88, 'É' => 0x90, 'ô' => 0x93, 'á' => 0xA0, 'í' => 0xA1, 'ó' => 0xA2,
+            'ú' => 0xA3, 'ª' => 0xA6, 'º' => 0xA7, 'Á' => 0xB5, 'Â' => 0xB6, 'À' => 0xB7,
+            'ã' => 0xC6, 'Ã' => 0xC7, 'Ê' => 0xD2, 'Í' => 0xD6, 'Ó' => 0xE0, 'Ô' => 0xE2,
+            'õ' => 0xE4, 'Õ' => 0xE5, 'Ú' => 0xE9,
+            _ => b'?',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("Água mineral 20L com casco", 12), vec!["Água mineral", "20L com", "casco"]);
+        assert_eq!(wrap("Botijão", 4), vec!["Boti", "jão"]);
+        assert_eq!(wrap("", 10), vec![""]);
+    }
+
+    #[test]
+    fn test_escpos_bytes() {
+        let mut printer = EscPos::new(columns_for(58));
+        printer.bold(true).columns("TOTAL", "R$ 24,00").bold(false);
+        printer.columns("Água mineral natural sem gás 20L", "R$ 1.234,00");
+        printer.qr_code("pix").cut();
+        let bytes = printer.into_bytes();
+
+        assert!(bytes.starts_with(&[ESC, b'@', ESC, b't', 2, ESC, b'E', 1]));
+        assert!(bytes.ends_with(&[GS, b'V', 66, 3]));
+
+        // Valor encostado à direita na largura de 32 colunas
+        let total = format!("TOTAL{}R$ 24,00\n", " ".repeat(32 - 5 - 8));
+        assert!(bytes.windows(total.len()).any(|w| w == total.as_bytes()));
+
+        // Acento na página 850; nome ocupando a linha toda empurra o valor para a seguinte
+        let value = format!("\n{}R$ 1.234,00\n", " ".repeat(32 - 11));
+        let item = [&[0xB5u8][..], b"gua mineral natural sem g", &[0xA0], b"s 20L", value.as_bytes()].concat();
+        assert!(bytes.windows(item.len()).any(|w| w == item.as_slice()));
+
+        // QR code: armazena 3 bytes de dados + 3 de cabeçalho
+        let store = [GS, b'(', b'k', 6, 0, 49, 80, 48, b'p', b'i', b'x'];
+        assert!(bytes.windows(store.len()).any(|w| w == store));
+    }
+}
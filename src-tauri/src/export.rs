@@ -0,0 +1,249 @@
+use crate::errors::AppError;
+use crate::models::{CustomerExportRow, OrderExportRow, StockMovementWithProduct};
+use rust_xlsxwriter::{Format, Workbook};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+// Planilhas para a contabilidade. O CSV sai no padrão do Excel em português:
+// separador ';', vírgula decimal e BOM para o UTF-8 ser reconhecido.
+
+pub const EXPORT_FORMATS: [&str; 2] = ["csv", "xlsx"];
+
+pub enum Cell {
+    Text(String),
+    Int(i64),
+    Number(f64),
+    Empty,
+}
+
+impl From<String> for Cell {
+    fn from(value: String) -> Self {
+        Cell::Text(value)
+    }
+}
+
+impl From<Option<String>> for Cell {
+    fn from(value: Option<String>) -> Self {
+        value.map(Cell::Text).unwrap_or(Cell::Empty)
+    }
+}
+
+impl From<i64> for Cell {
+    fn from(value: i64) -> Self {
+        Cell::Int(value)
+    }
+}
+
+impl From<Option<i64>> for Cell {
+    fn from(value: Option<i64>) -> Self {
+        value.map(Cell::Int).unwrap_or(Cell::Empty)
+    }
+}
+
+impl From<f64> for Cell {
+    fn from(value: f64) -> Self {
+        Cell::Number(value)
+    }
+}
+
+impl From<Option<f64>> for Cell {
+    fn from(value: Option<f64>) -> Self {
+        value.map(Cell::Number).unwrap_or(Cell::Empty)
+    }
+}
+
+impl From<bool> for Cell {
+    fn from(value: bool) -> Self {
+        Cell::Text(if value { "Sim" } else { "Não" }.to_string())
+    }
+}
+
+pub struct Table {
+    pub name: &'static str,
+    pub headers: &'static [&'static str],
+    pub rows: Vec<Vec<Cell>>,
+}
+
+pub fn validate_format(format: &str) -> Result<(), AppError> {
+    if !EXPORT_FORMATS.contains(&format) {
+        return Err(AppError::Validation(format!(
+            "Invalid export format: {}. Expected one of: {}",
+            format,
+            EXPORT_FORMATS.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+pub fn orders_table(rows: Vec<OrderExportRow>) -> Table {
+    Table {
+        name: "Pedidos",
+        headers: &[
+            "Pedido", "Data", "Situação", "Cliente", "Operador", "Local", "Pagamento",
+            "Total do Pedido", "Produto", "Quantidade", "Devolveu Casco", "Preço Unitário",
+            "Custo Unitário", "Total do Item",
+        ],
+        rows: rows
+            .into_iter()
+            .map(|r| {
+                let line_total = r.unit_price * r.quantity as f64;
+                vec![
+                    r.order_id.into(), r.created_at.into(), r.status.into(), r.customer_name.into(),
+                    r.operator_name.into(), r.location_name.into(), r.payment_methods.into(),
+                    r.order_total.into(), r.product_name.into(), r.quantity.into(),
+                    r.returned_bottle.into(), r.unit_price.into(), r.unit_cost.into(),
+                    line_total.into(),
+                ]
+            })
+            .collect(),
+    }
+}
+
+pub fn movements_table(rows: Vec<StockMovementWithProduct>) -> Table {
+    Table {
+        name: "Movimentações",
+        headers: &[
+            "Id", "Data", "Produto", "Tipo", "Estoque", "Quantidade", "Custo Unitário", "Local",
+            "Pedido", "Recebimento", "Turno",
+        ],
+        rows: rows
+            .into_iter()
+            .map(|r| {
+                vec![
+                    r.id.into(), r.created_at.into(), r.product_name.into(), r.movement_type.into(),
+                    r.stock_kind.into(), r.quantity.into(), r.unit_cost.into(), r.location_name.into(),
+                    r.order_id.into(), r.purchase_receipt_id.into(), r.driver_shift_id.into(),
+                ]
+            })
+            .collect(),
+    }
+}
+
+pub fn customers_table(rows: Vec<CustomerExportRow>) -> Table {
+    Table {
+        name: "Clientes",
+        headers: &[
+            "Id", "Nome", "Telefone", "Endereço", "Observações", "Limite de Crédito",
+            "Saldo Fiado", "Pedidos no Período", "Compras no Período",
+        ],
+        rows: rows
+            .into_iter()
+            .map(|r| {
+                vec![
+                    r.id.into(), r.name.into(), r.phone.into(), r.address.into(), r.notes.into(),
+                    r.credit_limit.into(), r.balance.into(), r.order_count.into(),
+                    r.total_purchased.into(),
+                ]
+            })
+            .collect(),
+    }
+}
+
+/// Grava a tabela no caminho escolhido, no formato pedido ('csv' ou 'xlsx')
+pub fn write_table(table: &Table, path: &Path, format: &str) -> Result<(), AppError> {
+    validate_format(format)?;
+
+    match format {
+        "csv" => write_csv(table, path),
+        _ => write_xlsx(table, path),
+    }
+}
+
+fn write_csv(table: &Table, path: &Path) -> Result<(), AppError> {
+    let mut file = File::create(path)?;
+    file.write_all("\u{feff}".as_bytes())?;
+
+    let mut writer = csv::WriterBuilder::new().delimiter(b';').from_writer(file);
+    let export_error = |e: csv::Error| AppError::Export(e.to_string());
+
+    writer.write_record(table.headers).map_err(export_error)?;
+    for row in &table.rows {
+        let record: Vec<String> = row
+            .iter()
+            .map(|cell| match cell {
+                Cell::Text(value) => value.clone(),
+                Cell::Int(value) => value.to_string(),
+                Cell::Number(value) => format!("{:.2}", value).replace('.', ","),
+                Cell::Empty => String::new(),
+            })
+            .collect();
+        writer.write_record(&record).map_err(export_error)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_xlsx(table: &Table, path: &Path) -> Result<(), AppError> {
+    let export_error = |e: rust_xlsxwriter::XlsxError| AppError::Export(e.to_string());
+
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(table.name).map_err(export_error)?;
+
+    let header = Format::new().set_bold();
+    let number = Format::new().set_num_format("#,##0.00");
+
+    for (col, title) in table.headers.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, *title, &header).map_err(export_error)?;
+    }
+
+    for (index, row) in table.rows.iter().enumerate() {
+        let line = index as u32 + 1;
+        for (col, cell) in row.iter().enumerate() {
+            let col = col as u16;
+            match cell {
+                Cell::Text(value) => sheet.write_string(line, col, value).map(|_| ()),
+                Cell::Int(value) => sheet.write_number(line, col, *value as f64).map(|_| ()),
+                Cell::Number(value) => sheet.write_number_with_format(line, col, *value, &number).map(|_| ()),
+                Cell::Empty => Ok(()),
+            }
+            .map_err(export_error)?;
+        }
+    }
+
+    sheet.set_freeze_panes(1, 0).map_err(export_error)?;
+    sheet.autofit();
+
+    workbook.save(path).map_err(export_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_table() -> Table {
+        Table {
+            name: "Clientes",
+            headers: &["Id", "Nome", "Telefone", "Saldo"],
+            rows: vec![
+                vec![1.into(), "José; \"Zé\"".to_string().into(), None::<String>.into(), 12.5.into()],
+                vec![2.into(), "Maria".to_string().into(), Some("9999".to_string()).into(), (-3.0).into()],
+            ],
+        }
+    }
+
+    #[test]
+    fn test_write_csv_and_xlsx() {
+        let dir = std::env::temp_dir().join(format!("export-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let csv_path = dir.join("clientes.csv");
+        write_table(&sample_table(), &csv_path, "csv").unwrap();
+        let content = std::fs::read_to_string(&csv_path).unwrap();
+        assert_eq!(
+            content,
+            "\u{feff}Id;Nome;Telefone;Saldo\n1;\"José; \"\"Zé\"\"\";;12,50\n2;Maria;9999;-3,00\n",
+        );
+
+        let xlsx_path = dir.join("clientes.xlsx");
+        write_table(&sample_table(), &xlsx_path, "xlsx").unwrap();
+        let bytes = std::fs::read(&xlsx_path).unwrap();
+        assert!(bytes.starts_with(b"PK")); // XLSX é um zip
+
+        assert!(write_table(&sample_table(), &dir.join("clientes.pdf"), "pdf").is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
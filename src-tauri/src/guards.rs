@@ -3,13 +3,13 @@ use crate::errors::{AppError, Result};
 use crate::models::SafeUser;
 use tauri::State;
 
+/// Usuário logado, se houver; só para o que também roda na tela de entrada
+pub fn current_user(auth_state: &State<AuthState>) -> Option<SafeUser> {
+    auth_state.user.lock().unwrap().clone()
+}
+
 pub fn get_authenticated_user(auth_state: &State<AuthState>) -> Result<SafeUser> {
-    auth_state
-        .user
-        .lock()
-        .unwrap()
-        .clone()
-        .ok_or_else(|| AppError::Auth("User not authenticated".to_string()))
+    current_user(auth_state).ok_or_else(|| AppError::Auth("User not authenticated".to_string()))
 }
 
 pub fn require_admin(auth_state: &State<AuthState>) -> Result<SafeUser> {
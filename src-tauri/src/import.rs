@@ -0,0 +1,139 @@
+use crate::errors::AppError;
+use std::collections::HashMap;
+
+// Leitura de planilhas CSV para cadastro em lote. Aceita tanto o padrão do Excel em
+// português (';' e vírgula decimal, como sai da exportação) quanto ',' com ponto decimal.
+
+/// Linha do CSV com os valores indexados pelo nome da coluna
+pub struct CsvRow {
+    pub line: u64,
+    values: HashMap<String, String>,
+}
+
+impl CsvRow {
+    /// Texto da coluna, sem espaços nas pontas; vazio vira `None`
+    pub fn text(&self, column: &str) -> Option<String> {
+        self.values
+            .get(column)
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    }
+
+    pub fn decimal(&self, column: &str, errors: &mut Vec<String>) -> Option<f64> {
+        let value = self.text(column)?;
+        match parse_decimal(&value) {
+            Some(number) => Some(number),
+            None => {
+                errors.push(format!("Invalid number in {}: {}", column, value));
+                None
+            }
+        }
+    }
+
+    pub fn integer(&self, column: &str, errors: &mut Vec<String>) -> Option<i64> {
+        let value = self.text(column)?;
+        match value.parse::<i64>() {
+            Ok(number) => Some(number),
+            Err(_) => {
+                errors.push(format!("Invalid integer in {}: {}", column, value));
+                None
+            }
+        }
+    }
+}
+
+/// Aceita "12.5", "12,50", "1.234,56" e "R$ 12,50"
+fn parse_decimal(value: &str) -> Option<f64> {
+    let value = value.trim_start_matches("R$").trim();
+    let normalized = if value.contains(',') {
+        value.replace('.', "").replace(',', ".")
+    } else {
+        value.to_string()
+    };
+    normalized.parse::<f64>().ok().filter(|number| number.is_finite())
+}
+
+/// Lê o CSV conferindo o cabeçalho: `name` é obrigatória e colunas fora de
+/// `columns` são recusadas, para um erro de digitação não passar despercebido
+pub fn read_csv(content: &str, columns: &[&str]) -> Result<Vec<CsvRow>, AppError> {
+    let content = content.trim_start_matches('\u{feff}');
+    let header_line = content.lines().next().unwrap_or_default();
+    let delimiter = if header_line.matches(';').count() > header_line.matches(',').count() {
+        b';'
+    } else {
+        b','
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+    let import_error = |e: csv::Error| AppError::Validation(format!("Invalid CSV: {}", e));
+
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(import_error)?
+        .iter()
+        .map(|header| header.trim().to_lowercase())
+        .collect();
+
+    if let Some(unknown) = headers.iter().find(|h| !columns.contains(&h.as_str())) {
+        return Err(AppError::Validation(format!(
+            "Unknown column: {}. Expected: {}",
+            unknown,
+            columns.join(", ")
+        )));
+    }
+
+    if !headers.iter().any(|h| h == "name") {
+        return Err(AppError::Validation("Missing required column: name".to_string()));
+    }
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(import_error)?;
+
+        // Linha em branco no meio ou no fim do arquivo
+        if record.iter().all(|value| value.trim().is_empty()) {
+            continue;
+        }
+
+        rows.push(CsvRow {
+            line: record.position().map(|p| p.line()).unwrap_or_default(),
+            values: headers.iter().cloned().zip(record.iter().map(str::to_string)).collect(),
+        });
+    }
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_csv() {
+        let content = "\u{feff}Name;Price_Full;Stock_Full\nÁgua 20L;\"1.234,50\";3\n;;\nGás;abc;x\n";
+        let rows = read_csv(content, &["name", "price_full", "stock_full"]).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].line, 2);
+        assert_eq!(rows[1].line, 4);
+
+        let mut errors = Vec::new();
+        assert_eq!(rows[0].text("name").as_deref(), Some("Água 20L"));
+        assert_eq!(rows[0].decimal("price_full", &mut errors), Some(1234.5));
+        assert_eq!(rows[0].integer("stock_full", &mut errors), Some(3));
+        assert!(errors.is_empty());
+
+        assert_eq!(rows[1].decimal("price_full", &mut errors), None);
+        assert_eq!(rows[1].integer("stock_full", &mut errors), None);
+        assert_eq!(errors.len(), 2);
+
+        let rows = read_csv("name,price_full\nCarvão,R$ 25.9\n", &["name", "price_full"]).unwrap();
+        assert_eq!(rows[0].decimal("price_full", &mut errors), Some(25.9));
+
+        assert!(read_csv("name;preco\nÁgua;10\n", &["name", "price_full"]).is_err());
+        assert!(read_csv("price_full\n10\n", &["name", "price_full"]).is_err());
+    }
+}
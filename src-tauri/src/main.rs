@@ -7,11 +7,16 @@ mod errors;
 mod commands;
 mod auth;
 mod guards;
+mod export;
+mod import;
+mod profiles;
+mod escpos;
+mod printer;
 
 #[cfg(test)]
 mod test_helpers;
 
-use db::{init_db, DbPool};
+use db::{init_db, recovery::RecoveryState, DbPool, DbStartup};
 use tauri::Manager;
 
 use commands::{
@@ -19,34 +24,82 @@ use commands::{
     get_products, get_product, create_product, update_product, delete_product,
     // Customers
     get_customers, get_customer, search_customers_by_phone, create_customer, update_customer, delete_customer,
+    // Customer accounts
+    get_customer_statement, get_customer_balances, register_customer_payment, adjust_customer_balance,
+    // Bottle loans
+    get_outstanding_bottles, get_bottle_loan_history, return_customer_bottles,
             // Orders
-            create_order, get_orders, get_order, get_orders_by_customer, update_order, delete_order,
+            create_order, get_orders, get_order, get_orders_by_customer, update_order, cancel_order,
+    // Deliveries
+    get_deliveries, get_delivery_queue, dispatch_delivery, confirm_delivery, fail_delivery,
+    // Drivers
+    get_drivers, create_driver, update_driver, get_driver_shifts, get_driver_shift_report,
+    open_driver_shift, load_driver_shift, close_driver_shift,
+    // Cash sessions
+    get_open_cash_session, get_cash_sessions, open_cash_session, add_cash_movement,
+    get_cash_session_report, close_cash_session,
     // Stock
-    stock_in, stock_out, stock_adjust, get_stock_movements,
+    stock_in, stock_out, stock_adjust, supplier_exchange, transfer_stock, get_critical_stock,
+    get_reorder_suggestions, get_stock_lots, get_expiring_lots, get_stock_movements,
+    // Stock locations
+    get_stock_locations, create_stock_location, update_stock_location, get_location_stock,
+    // Suppliers
+    get_suppliers, get_supplier, create_supplier, update_supplier, delete_supplier,
+    // Purchase orders
+    get_purchase_orders, get_purchase_order, create_purchase_order, receive_purchase_order,
+    cancel_purchase_order,
     // Dashboard
     get_dashboard_stats,
+    // Reports
+    get_sales_report,
+    // Exports
+    export_orders, export_stock_movements, export_customers,
+    // Imports
+    import_products, import_customers,
+    // Backups
+    get_backup_settings, update_backup_settings, create_backup, list_backups,
+    verify_backup, restore_backup,
+    // Recovery
+    get_recovery_status, list_recovery_backups, recovery_accept_checksums,
+    recovery_restore_backup, recovery_set_aside_database,
+    // Profiles
+    get_profiles, create_profile, rename_profile, switch_profile,
+    // Settings
+    get_company_settings, update_company_settings, get_printer_settings, update_printer_settings,
     // Receipts
-    generate_receipt,
+    generate_receipt, print_receipt, print_test_page,
             // Users
             login, seed_admin_user, logout, get_current_user,
             get_users, create_user, update_user, delete_user,
+    // Audit
+    get_audit_log,
 };
 
 #[tokio::main]
 async fn main() {
-    let db_pool = init_db().await.expect("Failed to initialize database");
-
-    tauri::Builder::default()
+    let builder = tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
-        .manage(db_pool)
-        .manage(auth::AuthState::default())
+        .plugin(tauri_plugin_dialog::init())
+        .manage(auth::AuthState::default());
+
+    // Com problema nas migrações o app sobe sem banco, só com a tela de recuperação
+    let builder = match init_db().await.expect("Failed to initialize database") {
+        DbStartup::Ready(db_pool) => builder.manage(db_pool).manage(RecoveryState::default()),
+        DbStartup::Recovery(info) => builder.manage(RecoveryState(Some(*info))),
+    };
+
+    builder
         .setup(|app| {
-            let pool = app.state::<DbPool>().inner().clone();
+            let Some(pool) = app.try_state::<DbPool>().map(|pool| pool.inner().clone()) else {
+                return Ok(());
+            };
+            let seed_pool = pool.clone();
             tauri::async_runtime::spawn(async move {
-                if let Err(e) = commands::users::_internal_seed_admin_user(&pool, "admin").await {
+                if let Err(e) = commands::users::_internal_seed_admin_user(&seed_pool, "admin").await {
                     eprintln!("Failed to seed admin user: {}", e);
                 }
             });
+            tauri::async_runtime::spawn(db::backups::run_scheduler(pool));
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -63,22 +116,110 @@ async fn main() {
             create_customer,
             update_customer,
             delete_customer,
+            // Customer accounts
+            get_customer_statement,
+            get_customer_balances,
+            register_customer_payment,
+            adjust_customer_balance,
+            // Bottle loans
+            get_outstanding_bottles,
+            get_bottle_loan_history,
+            return_customer_bottles,
             // Orders
             create_order,
             get_orders,
             get_order,
             get_orders_by_customer,
             update_order,
-            delete_order,
+            cancel_order,
+            // Deliveries
+            get_deliveries,
+            get_delivery_queue,
+            dispatch_delivery,
+            confirm_delivery,
+            fail_delivery,
+            // Drivers
+            get_drivers,
+            create_driver,
+            update_driver,
+            get_driver_shifts,
+            get_driver_shift_report,
+            open_driver_shift,
+            load_driver_shift,
+            close_driver_shift,
+            // Cash sessions
+            get_open_cash_session,
+            get_cash_sessions,
+            open_cash_session,
+            add_cash_movement,
+            get_cash_session_report,
+            close_cash_session,
             // Stock
             stock_in,
             stock_out,
             stock_adjust,
+            supplier_exchange,
+            transfer_stock,
+            get_critical_stock,
+            get_reorder_suggestions,
+            get_stock_lots,
+            get_expiring_lots,
             get_stock_movements,
+            // Stock locations
+            get_stock_locations,
+            create_stock_location,
+            update_stock_location,
+            get_location_stock,
+            // Suppliers
+            get_suppliers,
+            get_supplier,
+            create_supplier,
+            update_supplier,
+            delete_supplier,
+            // Purchase orders
+            get_purchase_orders,
+            get_purchase_order,
+            create_purchase_order,
+            receive_purchase_order,
+            cancel_purchase_order,
             // Dashboard
             get_dashboard_stats,
+            // Reports
+            get_sales_report,
+            // Exports
+            export_orders,
+            export_stock_movements,
+            export_customers,
+            // Imports
+            import_products,
+            import_customers,
+            // Backups
+            get_backup_settings,
+            update_backup_settings,
+            create_backup,
+            list_backups,
+            verify_backup,
+            restore_backup,
+            // Recovery
+            get_recovery_status,
+            list_recovery_backups,
+            recovery_accept_checksums,
+            recovery_restore_backup,
+            recovery_set_aside_database,
+            // Profiles
+            get_profiles,
+            create_profile,
+            rename_profile,
+            switch_profile,
+            // Settings
+            get_company_settings,
+            update_company_settings,
+            get_printer_settings,
+            update_printer_settings,
             // Receipts
             generate_receipt,
+            print_receipt,
+            print_test_page,
             // Users
             login,
             seed_admin_user,
@@ -88,6 +229,8 @@ async fn main() {
             create_user,
             update_user,
             delete_user,
+            // Audit
+            get_audit_log,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -9,10 +9,12 @@ pub struct Product {
     pub r#type: String, // 'water', 'gas', 'coal', 'other'
     pub price_refill: f64,
     pub price_full: f64,
-    pub stock_full: i64,
+    pub stock_full: i64,  // Total de todos os locais
     pub stock_empty: i64,
-    pub expiry_month: Option<i64>,
-    pub expiry_year: Option<i64>,
+    pub cost_price: f64,   // Custo do último recebimento
+    pub average_cost: f64, // Custo médio ponderado
+    pub min_stock: i64,    // Abaixo disso o produto entra no estoque crítico
+    pub reorder_qty: i64,  // Lote padrão de reposição
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -24,8 +26,10 @@ pub struct CreateProductPayload {
     pub price_full: f64,
     pub stock_full: Option<i64>,
     pub stock_empty: Option<i64>,
-    pub expiry_month: Option<i64>,
-    pub expiry_year: Option<i64>,
+    pub expiry_date: Option<String>, // Validade do estoque inicial, 'YYYY-MM-DD'
+    pub cost_price: Option<f64>,
+    pub min_stock: Option<i64>,
+    pub reorder_qty: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -37,8 +41,9 @@ pub struct UpdateProductPayload {
     pub price_full: Option<f64>,
     pub stock_full: Option<i64>,
     pub stock_empty: Option<i64>,
-    pub expiry_month: Option<i64>,
-    pub expiry_year: Option<i64>,
+    pub cost_price: Option<f64>,
+    pub min_stock: Option<i64>,
+    pub reorder_qty: Option<i64>,
 }
 
 // ========== CUSTOMERS ==========
@@ -49,6 +54,7 @@ pub struct Customer {
     pub phone: Option<String>,
     pub address: Option<String>,
     pub notes: Option<String>,
+    pub credit_limit: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -57,6 +63,7 @@ pub struct CreateCustomerPayload {
     pub phone: Option<String>,
     pub address: Option<String>,
     pub notes: Option<String>,
+    pub credit_limit: Option<f64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -65,6 +72,53 @@ pub struct UpdateCustomerPayload {
     pub phone: Option<String>,
     pub address: Option<String>,
     pub notes: Option<String>,
+    pub credit_limit: Option<f64>,
+}
+
+// ========== CUSTOMER ACCOUNTS (FIADO) ==========
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CustomerLedgerEntry {
+    pub id: i64,
+    pub customer_id: i64,
+    pub entry_type: String, // 'CHARGE', 'PAYMENT', 'ADJUST'
+    pub amount: f64,
+    pub method: Option<String>,
+    pub order_id: Option<i64>,
+    pub user_id: Option<i64>,
+    pub session_id: Option<i64>,
+    pub description: Option<String>,
+    pub created_at: String,
+    pub running_balance: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CustomerStatement {
+    pub customer: Customer,
+    pub balance: f64,
+    pub available_credit: f64,
+    pub entries: Vec<CustomerLedgerEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CustomerBalance {
+    pub customer_id: i64,
+    pub customer_name: String,
+    pub phone: Option<String>,
+    pub credit_limit: f64,
+    pub balance: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CustomerPaymentPayload {
+    pub amount: f64,
+    pub method: String, // 'cash', 'pix', 'debit', 'credit'
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CustomerAdjustmentPayload {
+    pub amount: f64, // Positivo aumenta a dívida, negativo reduz
+    pub description: String,
 }
 
 // ========== ORDERS ==========
@@ -74,6 +128,12 @@ pub struct Order {
     pub customer_id: Option<i64>,
     pub total: f64,
     pub created_at: String,
+    pub user_id: Option<i64>,
+    pub session_id: Option<i64>,
+    pub status: String, // 'open', 'completed', 'cancelled'
+    pub cancel_reason: Option<String>,
+    pub cancelled_by: Option<i64>,
+    pub cancelled_at: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
@@ -83,6 +143,14 @@ pub struct OrderWithCustomer {
     pub customer_name: Option<String>,
     pub total: f64,
     pub created_at: String,
+    pub user_id: Option<i64>,
+    pub operator_name: Option<String>,
+    pub session_id: Option<i64>,
+    pub status: String,
+    pub cancel_reason: Option<String>,
+    pub cancelled_by: Option<i64>,
+    pub cancelled_by_username: Option<String>,
+    pub cancelled_at: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
@@ -93,6 +161,7 @@ pub struct OrderItem {
     pub quantity: i64,
     pub returned_bottle: bool,
     pub unit_price: f64,
+    pub unit_cost: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -103,10 +172,21 @@ pub struct OrderItemPayload {
     pub unit_price: f64,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrderPaymentPayload {
+    pub method: String, // 'cash', 'pix', 'debit', 'credit', 'on_account'
+    pub amount: f64,
+    pub tendered: Option<f64>, // Valor entregue pelo cliente (apenas dinheiro)
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateOrderPayload {
     pub customer_id: Option<i64>,
     pub items: Vec<OrderItemPayload>,
+    pub payments: Vec<OrderPaymentPayload>,
+    pub delivery: Option<DeliveryPayload>,
+    pub location_id: Option<i64>, // Local de onde a venda sai; padrão é a loja
+    pub allow_expired: Option<bool>, // Libera venda de lote vencido (só admin)
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -114,10 +194,17 @@ pub struct UpdateOrderPayload {
     pub created_at: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CancelOrderPayload {
+    pub reason: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OrderWithItems {
     pub order: OrderWithCustomer,
     pub items: Vec<OrderItemWithProduct>,
+    pub payments: Vec<OrderPayment>,
+    pub delivery: Option<Delivery>,
 }
 
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
@@ -129,6 +216,360 @@ pub struct OrderItemWithProduct {
     pub quantity: i64,
     pub returned_bottle: bool,
     pub unit_price: f64,
+    pub unit_cost: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct OrderPayment {
+    pub id: i64,
+    pub order_id: i64,
+    pub method: String,
+    pub amount: f64,
+    pub tendered: Option<f64>,
+    pub change_due: f64,
+    pub created_at: String,
+}
+
+// ========== BOTTLE LOANS (COMODATO) ==========
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BottleLoanEntry {
+    pub id: i64,
+    pub customer_id: i64,
+    pub product_id: i64,
+    pub product_name: String,
+    pub entry_type: String, // 'LENT', 'RETURNED'
+    pub quantity: i64,
+    pub deposit_amount: f64,
+    pub order_id: Option<i64>,
+    pub user_id: Option<i64>,
+    pub notes: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CustomerBottleBalance {
+    pub customer_id: i64,
+    pub customer_name: String,
+    pub phone: Option<String>,
+    pub product_id: i64,
+    pub product_name: String,
+    pub quantity: i64,
+    pub deposit_held: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReturnBottlesPayload {
+    pub product_id: i64,
+    pub quantity: i64,
+    pub refund_amount: Option<f64>, // Caução devolvida ao cliente
+    pub notes: Option<String>,
+}
+
+// ========== CASH SESSIONS ==========
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CashSession {
+    pub id: i64,
+    pub status: String, // 'open', 'closed'
+    pub opening_float: f64,
+    pub opened_by: i64,
+    pub opened_by_username: Option<String>,
+    pub opened_at: String,
+    pub closed_by: Option<i64>,
+    pub closed_by_username: Option<String>,
+    pub closed_at: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CashMovement {
+    pub id: i64,
+    pub session_id: i64,
+    pub movement_type: String, // 'WITHDRAWAL' (sangria), 'SUPPLY' (suprimento)
+    pub method: String, // forma de pagamento; 'cash' exceto em estornos
+    pub amount: f64,
+    pub reason: Option<String>,
+    pub user_id: Option<i64>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OpenCashSessionPayload {
+    pub opening_float: f64,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CashMovementPayload {
+    pub movement_type: String,
+    pub amount: f64,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CashCountPayload {
+    pub method: String,
+    pub counted: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CloseCashSessionPayload {
+    pub counts: Vec<CashCountPayload>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CashSessionReportLine {
+    pub method: String,
+    pub expected: f64,
+    pub counted: Option<f64>,
+    pub difference: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CashSessionReport {
+    pub session: CashSession,
+    pub lines: Vec<CashSessionReportLine>,
+    pub order_count: i64,
+    pub sales_total: f64,
+    pub on_account_total: f64,
+    pub supplies: f64,
+    pub withdrawals: f64,
+    pub movements: Vec<CashMovement>,
+}
+
+// ========== DELIVERIES ==========
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Delivery {
+    pub id: i64,
+    pub order_id: i64,
+    pub customer_id: Option<i64>,
+    pub customer_name: Option<String>,
+    pub phone: Option<String>,
+    pub address: String,
+    pub driver_id: Option<i64>,
+    pub driver_name: Option<String>,
+    pub driver_shift_id: Option<i64>, // Turno cujo veículo atende a entrega
+    pub delivery_fee: f64,
+    pub order_total: f64,
+    pub status: String, // 'pending', 'out_for_delivery', 'delivered', 'failed'
+    pub notes: Option<String>,
+    pub failure_reason: Option<String>,
+    pub created_at: String,
+    pub dispatched_at: Option<String>,
+    pub delivered_at: Option<String>,
+    pub failed_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeliveryPayload {
+    pub address: Option<String>, // Se ausente, usa o endereço do cliente
+    pub delivery_fee: Option<f64>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DispatchDeliveryPayload {
+    pub driver_id: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FailDeliveryPayload {
+    pub reason: String,
+}
+
+// ========== DRIVERS ==========
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, Clone)]
+pub struct Driver {
+    pub id: i64,
+    pub name: String,
+    pub phone: Option<String>,
+    pub active: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateDriverPayload {
+    pub name: String,
+    pub phone: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateDriverPayload {
+    pub name: Option<String>,
+    pub phone: Option<String>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, Clone)]
+pub struct DriverShift {
+    pub id: i64,
+    pub driver_id: i64,
+    pub driver_name: String,
+    pub status: String, // 'open', 'closed'
+    pub change_fund: f64,
+    pub cash_expected: Option<f64>,
+    pub cash_counted: Option<f64>,
+    pub opened_by: i64,
+    pub opened_by_username: Option<String>,
+    pub opened_at: String,
+    pub closed_by: Option<i64>,
+    pub closed_by_username: Option<String>,
+    pub closed_at: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DriverLoadPayload {
+    pub product_id: i64,
+    pub quantity: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OpenDriverShiftPayload {
+    pub driver_id: i64,
+    pub change_fund: f64,
+    pub loads: Vec<DriverLoadPayload>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DriverReturnPayload {
+    pub product_id: i64,
+    pub full_returned: i64,
+    pub empty_returned: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CloseDriverShiftPayload {
+    pub cash_counted: f64,
+    pub returns: Vec<DriverReturnPayload>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DriverShiftLine {
+    pub product_id: i64,
+    pub product_name: String,
+    pub full_loaded: i64,
+    pub full_delivered: i64,
+    pub full_expected: i64, // Cheios que deveriam voltar no veículo
+    pub full_returned: Option<i64>,
+    pub full_difference: Option<i64>,
+    pub empty_expected: i64,
+    pub empty_returned: Option<i64>,
+    pub empty_difference: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DriverShiftReport {
+    pub shift: DriverShift,
+    pub lines: Vec<DriverShiftLine>,
+    pub deliveries: Vec<Delivery>,
+    pub cash_collected: f64, // Recebido em dinheiro nas entregas concluídas
+    pub cash_expected: f64,
+    pub cash_counted: Option<f64>,
+    pub cash_difference: Option<f64>,
+}
+
+// ========== SUPPLIERS ==========
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, Clone)]
+pub struct Supplier {
+    pub id: i64,
+    pub name: String,
+    pub document: Option<String>, // CNPJ/CPF
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub notes: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateSupplierPayload {
+    pub name: String,
+    pub document: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateSupplierPayload {
+    pub name: Option<String>,
+    pub document: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub notes: Option<String>,
+}
+
+// ========== PURCHASE ORDERS ==========
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PurchaseOrder {
+    pub id: i64,
+    pub supplier_id: i64,
+    pub supplier_name: String,
+    pub status: String, // 'open', 'partial', 'received', 'cancelled'
+    pub expected_date: Option<String>,
+    pub notes: Option<String>,
+    pub user_id: Option<i64>,
+    pub created_at: String,
+    pub total_cost: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PurchaseOrderItem {
+    pub id: i64,
+    pub purchase_order_id: i64,
+    pub product_id: i64,
+    pub product_name: String,
+    pub quantity_ordered: i64,
+    pub quantity_received: i64,
+    pub unit_cost: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PurchaseReceipt {
+    pub id: i64,
+    pub purchase_order_id: i64,
+    pub invoice_number: Option<String>,
+    pub notes: Option<String>,
+    pub user_id: Option<i64>,
+    pub received_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurchaseOrderWithItems {
+    pub order: PurchaseOrder,
+    pub items: Vec<PurchaseOrderItem>,
+    pub receipts: Vec<PurchaseReceipt>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurchaseOrderItemPayload {
+    pub product_id: i64,
+    pub quantity: i64,
+    pub unit_cost: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatePurchaseOrderPayload {
+    pub supplier_id: i64,
+    pub expected_date: Option<String>, // 'YYYY-MM-DD'
+    pub notes: Option<String>,
+    pub items: Vec<PurchaseOrderItemPayload>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReceiveLinePayload {
+    pub item_id: i64,
+    pub quantity: i64,
+    pub expiry_date: Option<String>, // Validade do lote recebido, 'YYYY-MM-DD'
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReceivePurchaseOrderPayload {
+    pub invoice_number: Option<String>,
+    pub notes: Option<String>,
+    pub lines: Vec<ReceiveLinePayload>,
 }
 
 // ========== STOCK MOVEMENTS ==========
@@ -136,8 +577,14 @@ pub struct OrderItemWithProduct {
 pub struct StockMovement {
     pub id: i64,
     pub product_id: i64,
-    pub movement_type: String, // 'IN', 'OUT', 'ADJUST'
+    pub movement_type: String, // 'IN', 'OUT', 'ADJUST', 'RETURN', 'REVERSAL', 'EXCHANGE', 'TRANSFER'
+    pub stock_kind: String, // 'full', 'empty'
     pub quantity: i64,
+    pub unit_cost: Option<f64>,
+    pub order_id: Option<i64>,
+    pub purchase_receipt_id: Option<i64>,
+    pub driver_shift_id: Option<i64>,
+    pub location_id: Option<i64>,
     pub created_at: String,
 }
 
@@ -147,7 +594,86 @@ pub struct StockMovementWithProduct {
     pub product_id: i64,
     pub product_name: String,
     pub movement_type: String,
+    pub stock_kind: String,
     pub quantity: i64,
+    pub unit_cost: Option<f64>,
+    pub order_id: Option<i64>,
+    pub purchase_receipt_id: Option<i64>,
+    pub driver_shift_id: Option<i64>,
+    pub location_id: Option<i64>,
+    pub location_name: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransferStockPayload {
+    pub product_id: i64,
+    pub from_location_id: i64,
+    pub to_location_id: i64,
+    pub stock_kind: Option<String>, // Padrão: 'full'
+    pub quantity: i64,
+}
+
+/// Produto abaixo do estoque alvo (mínimo ou cobertura pelo giro), com a quantidade a comprar
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReorderSuggestion {
+    pub product_id: i64,
+    pub product_name: String,
+    pub stock_full: i64,
+    pub min_stock: i64,
+    pub reorder_qty: i64,
+    pub on_order: i64, // Ainda a receber em pedidos de compra abertos
+    pub sold_quantity: i64, // Vendido na janela analisada
+    pub avg_daily_sales: f64,
+    pub days_of_cover: Option<f64>, // Dias até zerar no giro atual (None sem vendas)
+    pub target_stock: i64,
+    pub suggested_qty: i64,
+}
+
+// ========== STOCK LOCATIONS ==========
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, Clone)]
+pub struct StockLocation {
+    pub id: i64,
+    pub name: String,
+    pub kind: String, // 'store', 'warehouse', 'vehicle'
+    pub driver_id: Option<i64>,
+    pub active: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateStockLocationPayload {
+    pub name: String,
+    pub kind: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateStockLocationPayload {
+    pub name: Option<String>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct LocationStock {
+    pub product_id: i64,
+    pub product_name: String,
+    pub location_id: i64,
+    pub location_name: String,
+    pub stock_full: i64,
+    pub stock_empty: i64,
+}
+
+// ========== STOCK LOTS ==========
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct StockLot {
+    pub id: i64,
+    pub product_id: i64,
+    pub product_name: String,
+    pub location_id: i64,
+    pub location_name: String,
+    pub expiry_date: String,
+    pub quantity: i64,
+    pub days_to_expiry: i64, // Negativo quando já venceu
     pub created_at: String,
 }
 
@@ -156,7 +682,11 @@ pub struct StockMovementWithProduct {
 pub struct DashboardStats {
     pub sales_today: f64,
     pub sales_month: f64,
+    pub margin_today: f64,
+    pub margin_month: f64,
     pub critical_stock: Vec<Product>,
+    pub expired_lots: Vec<StockLot>,
+    pub expiring_lots: Vec<StockLot>, // Vencem dentro do prazo de alerta
     pub top_products: Vec<TopProduct>,
     pub active_customers: i64,
 }
@@ -167,6 +697,223 @@ pub struct TopProduct {
     pub product_name: String,
     pub total_quantity: i64,
     pub total_revenue: f64,
+    pub total_cost: f64,
+    pub gross_margin: f64,
+}
+
+// ========== REPORTS ==========
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SalesReportLine {
+    pub key: Option<String>, // Data, id ou código do grupo; nulo para venda sem cliente/operador
+    pub label: Option<String>, // Nome do produto, cliente ou operador
+    pub order_count: i64,
+    pub quantity: i64,
+    pub total: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SalesReport {
+    pub start_date: String,
+    pub end_date: String,
+    pub group_by: String,
+    pub order_count: i64,
+    pub quantity: i64,
+    pub total: f64,
+    pub lines: Vec<SalesReportLine>,
+}
+
+// ========== EXPORTS ==========
+/// Linha da exportação de pedidos: um item por linha, com os dados do pedido repetidos
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct OrderExportRow {
+    pub order_id: i64,
+    pub created_at: String,
+    pub status: String,
+    pub customer_name: Option<String>,
+    pub operator_name: Option<String>,
+    pub location_name: Option<String>,
+    pub payment_methods: Option<String>,
+    pub order_total: f64,
+    pub product_name: String,
+    pub quantity: i64,
+    pub returned_bottle: bool,
+    pub unit_price: f64,
+    pub unit_cost: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CustomerExportRow {
+    pub id: i64,
+    pub name: String,
+    pub phone: Option<String>,
+    pub address: Option<String>,
+    pub notes: Option<String>,
+    pub credit_limit: f64,
+    pub balance: f64, // Saldo fiado atual
+    pub order_count: i64, // Pedidos no período
+    pub total_purchased: f64, // Compras no período
+}
+
+// ========== IMPORTS ==========
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportRowResult {
+    pub line: u64, // Linha no arquivo, contando o cabeçalho
+    pub key: Option<String>, // Nome do produto ou telefone do cliente
+    pub action: String, // 'create', 'update' ou 'error'
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub applied: bool, // Só grava quando não é simulação e nenhuma linha tem erro
+    pub created: i64,
+    pub updated: i64,
+    pub failed: i64,
+    pub rows: Vec<ImportRowResult>,
+}
+
+// ========== BACKUPS ==========
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupSettings {
+    pub dir: String,
+    pub interval_hours: i64, // 0 desliga o backup automático
+    pub keep_last: i64, // Quantidade mínima de backups mantidos
+    pub keep_days: i64, // Backups mais novos que isso nunca são apagados
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateBackupSettingsPayload {
+    pub dir: Option<String>,
+    pub interval_hours: Option<i64>,
+    pub keep_last: Option<i64>,
+    pub keep_days: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupFile {
+    pub file_name: String,
+    pub path: String,
+    pub size_bytes: i64,
+    pub created_at: String, // Tirado do nome do arquivo, horário local
+    pub tag: Option<String>, // Cópia automática: 'pre-restore', 'pre-migration' ou 'pre-recovery'
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupCheck {
+    pub file_name: String,
+    pub ok: bool,
+    pub schema_version: Option<i64>,
+    pub message: String,
+}
+
+// ========== COMPANY SETTINGS ==========
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompanySettings {
+    pub company_name: String,
+    pub cnpj: Option<String>, // Formatado: 00.000.000/0000-00
+    pub address: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub receipt_footer: String,
+    pub expiry_warning_days: i64, // Lotes que vencem nesse prazo entram no alerta
+    pub default_min_stock: i64, // Estoque mínimo de produto cadastrado sem um
+    pub currency_symbol: String,
+    pub decimal_separator: String, // ',' ou '.'
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateCompanySettingsPayload {
+    pub company_name: Option<String>,
+    pub cnpj: Option<String>, // Texto vazio apaga
+    pub address: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub receipt_footer: Option<String>,
+    pub expiry_warning_days: Option<i64>,
+    pub default_min_stock: Option<i64>,
+    pub currency_symbol: Option<String>,
+    pub decimal_separator: Option<String>,
+}
+
+// ========== PRINTER SETTINGS ==========
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrinterSettings {
+    pub connection: String, // 'none', 'device' ou 'network'
+    pub device_path: Option<String>, // Ex.: /dev/usb/lp0 ou \\localhost\Termica
+    pub host: Option<String>,
+    pub port: i64, // Impressora de rede em modo RAW, normalmente 9100
+    pub paper_width: i64, // 58 ou 80 (mm)
+    pub cut_paper: bool,
+    pub qr_code: Option<String>, // Texto do QR code no fim do recibo (ex.: chave Pix)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdatePrinterSettingsPayload {
+    pub connection: Option<String>,
+    pub device_path: Option<String>, // Texto vazio apaga
+    pub host: Option<String>,
+    pub port: Option<i64>,
+    pub paper_width: Option<i64>,
+    pub cut_paper: Option<bool>,
+    pub qr_code: Option<String>,
+}
+
+// ========== PROFILES ==========
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    pub dir: String, // Pasta do banco e dos backups do perfil
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfilesConfig {
+    pub active: String, // Perfil aberto na inicialização
+    pub profiles: Vec<Profile>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateProfilePayload {
+    pub name: String,
+    pub dir: Option<String>, // Padrão: subpasta 'profiles' dos dados do app
+}
+
+// ========== RECOVERY ==========
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryInfo {
+    pub kind: String, // 'checksum_mismatch', 'newer_database', 'dirty' ou 'migration_failed'
+    pub version: Option<i64>, // Migração envolvida no erro
+    pub message: String,
+    pub db_path: String,
+    pub backup_dir: String,
+    pub safety_backup: Option<BackupFile>, // Cópia feita ao detectar o problema
+    pub backup_error: Option<String>, // Motivo de a cópia não ter sido feita
+}
+
+// ========== AUDIT LOG ==========
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AuditLogEntry {
+    pub id: i64,
+    pub user_id: i64,
+    pub username: String,
+    pub action: String,
+    pub entity: String,
+    pub entity_id: Option<i64>,
+    pub before_json: Option<String>,
+    pub after_json: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct AuditLogFilter {
+    pub user_id: Option<i64>,
+    pub entity: Option<String>,
+    pub entity_id: Option<i64>,
+    pub action: Option<String>,
+    pub date_from: Option<String>, // 'YYYY-MM-DD'
+    pub date_to: Option<String>,   // 'YYYY-MM-DD', inclusivo
+    pub limit: Option<i64>,
 }
 
 // ========== USERS ==========
@@ -0,0 +1,114 @@
+use crate::errors::AppError;
+use crate::models::PrinterSettings;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+// Envio dos bytes ESC/POS para a impressora: arquivo de dispositivo (/dev/usb/lp0,
+// compartilhamento do Windows) ou porta RAW de impressora de rede
+
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub async fn send(settings: &PrinterSettings, data: &[u8]) -> Result<(), AppError> {
+    match settings.connection.as_str() {
+        "device" => {
+            let path = settings.device_path.as_deref()
+                .ok_or_else(|| AppError::Validation("Printer device path is required".to_string()))?;
+            write_device(path, data).await
+        }
+        "network" => {
+            let host = settings.host.as_deref()
+                .ok_or_else(|| AppError::Validation("Printer host is required".to_string()))?;
+            write_network(host, settings.port, data).await
+        }
+        _ => Err(AppError::BusinessLogic("No thermal printer configured".to_string())),
+    }
+}
+
+async fn write_device(path: &str, data: &[u8]) -> Result<(), AppError> {
+    let failed = |e: std::io::Error| AppError::Printer(format!("{}: {}", path, e));
+
+    // Sem create: caminho errado não deve virar um arquivo comum no disco
+    let mut device = tokio::fs::OpenOptions::new()
+        .write(true)
+        .open(path)
+        .await
+        .map_err(failed)?;
+    device.write_all(data).await.map_err(failed)?;
+    device.flush().await.map_err(failed)?;
+
+    Ok(())
+}
+
+async fn write_network(host: &str, port: i64, data: &[u8]) -> Result<(), AppError> {
+    let address = format!("{}:{}", host, port);
+    let failed = |e: std::io::Error| AppError::Printer(format!("{}: {}", address, e));
+    let timed_out = |_| AppError::Printer(format!("{}: no response", address));
+
+    let mut stream = timeout(NETWORK_TIMEOUT, TcpStream::connect(&address))
+        .await
+        .map_err(timed_out)?
+        .map_err(failed)?;
+    timeout(NETWORK_TIMEOUT, stream.write_all(data))
+        .await
+        .map_err(timed_out)?
+        .map_err(failed)?;
+    stream.shutdown().await.map_err(failed)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    fn settings(connection: &str) -> PrinterSettings {
+        PrinterSettings {
+            connection: connection.to_string(),
+            device_path: None,
+            host: None,
+            port: 9100,
+            paper_width: 80,
+            cut_paper: true,
+            qr_code: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let received = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut data = Vec::new();
+            socket.read_to_end(&mut data).await.unwrap();
+            data
+        });
+
+        let network = PrinterSettings {
+            host: Some("127.0.0.1".to_string()),
+            port: port as i64,
+            ..settings("network")
+        };
+        send(&network, b"\x1b@recibo").await.unwrap();
+        assert_eq!(received.await.unwrap(), b"\x1b@recibo");
+
+        let path = std::env::temp_dir().join(format!("printer-test-{}", std::process::id()));
+        let device = PrinterSettings {
+            device_path: Some(path.to_string_lossy().into_owned()),
+            ..settings("device")
+        };
+
+        // O dispositivo precisa existir
+        assert!(send(&device, b"recibo").await.is_err());
+        std::fs::write(&path, b"").unwrap();
+        send(&device, b"recibo").await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"recibo");
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(send(&settings("none"), b"recibo").await.is_err());
+    }
+}
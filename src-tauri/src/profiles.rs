@@ -0,0 +1,224 @@
+use crate::errors::AppError;
+use crate::models::{CreateProfilePayload, Profile, ProfilesConfig};
+use std::path::{Path, PathBuf};
+
+// Perfis de empresa: cada um com a própria pasta de banco e backups, listados num
+// JSON na pasta de dados do app. Sem o arquivo, vale um perfil único apontando para
+// o banco de sempre, então instalações antigas abrem como antes.
+
+const CONFIG_FILE: &str = "profiles.json";
+const DB_FILE: &str = "distribbuidora.db";
+const DEFAULT_PROFILE_ID: &str = "principal";
+
+fn default_config(config_dir: &Path) -> ProfilesConfig {
+    ProfilesConfig {
+        active: DEFAULT_PROFILE_ID.to_string(),
+        profiles: vec![Profile {
+            id: DEFAULT_PROFILE_ID.to_string(),
+            name: "Principal".to_string(),
+            dir: config_dir.to_string_lossy().into_owned(),
+        }],
+    }
+}
+
+pub fn load(config_dir: &Path) -> Result<ProfilesConfig, AppError> {
+    let path = config_dir.join(CONFIG_FILE);
+    if !path.exists() {
+        return Ok(default_config(config_dir));
+    }
+
+    serde_json::from_str(&std::fs::read_to_string(&path)?)
+        .map_err(|e| AppError::BusinessLogic(format!("Invalid profiles file {}: {}", path.display(), e)))
+}
+
+/// Grava num temporário e renomeia, para uma queda no meio não corromper o arquivo
+fn save(config_dir: &Path, config: &ProfilesConfig) -> Result<(), AppError> {
+    let content = serde_json::to_string_pretty(config)
+        .map_err(|e| AppError::BusinessLogic(format!("Failed to write profiles: {}", e)))?;
+
+    let staging = config_dir.join(format!("{}.tmp", CONFIG_FILE));
+    std::fs::write(&staging, content)?;
+    std::fs::rename(&staging, config_dir.join(CONFIG_FILE))?;
+
+    Ok(())
+}
+
+pub fn db_path(profile: &Profile) -> PathBuf {
+    Path::new(&profile.dir).join(DB_FILE)
+}
+
+pub fn active_profile(config_dir: &Path) -> Result<Profile, AppError> {
+    let config = load(config_dir)?;
+    config
+        .profiles
+        .iter()
+        .find(|profile| profile.id == config.active)
+        .cloned()
+        .ok_or_else(|| AppError::NotFound(format!("Active profile {} not found", config.active)))
+}
+
+/// 'Gás & Cia' -> 'gas-cia'
+fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.to_lowercase().chars() {
+        let c = match c {
+            'á' | 'à' | 'â' | 'ã' => 'a',
+            'é' | 'ê' => 'e',
+            'í' => 'i',
+            'ó' | 'ô' | 'õ' => 'o',
+            'ú' | 'ü' => 'u',
+            'ç' => 'c',
+            c => c,
+        };
+
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    let slug = slug.trim_end_matches('-').to_string();
+    if slug.is_empty() { "perfil".to_string() } else { slug }
+}
+
+fn validate_name(config: &ProfilesConfig, name: &str, except_id: Option<&str>) -> Result<String, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::Validation("Profile name cannot be empty".to_string()));
+    }
+
+    let taken = config
+        .profiles
+        .iter()
+        .any(|p| Some(p.id.as_str()) != except_id && p.name.to_lowercase() == name.to_lowercase());
+    if taken {
+        return Err(AppError::Validation(format!("A profile named {} already exists", name)));
+    }
+
+    Ok(name.to_string())
+}
+
+pub fn create_profile(config_dir: &Path, payload: CreateProfilePayload) -> Result<Profile, AppError> {
+    let mut config = load(config_dir)?;
+    let name = validate_name(&config, &payload.name, None)?;
+
+    let base = slugify(&name);
+    let mut id = base.clone();
+    let mut suffix = 2;
+    while config.profiles.iter().any(|p| p.id == id) {
+        id = format!("{}-{}", base, suffix);
+        suffix += 1;
+    }
+
+    let dir = match payload.dir.as_deref().map(str::trim).filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => config_dir.join("profiles").join(&id),
+    };
+
+    if !dir.is_absolute() {
+        return Err(AppError::Validation("Profile folder must be an absolute path".to_string()));
+    }
+
+    // Dois perfis na mesma pasta dividiriam banco, backups e restauração
+    if let Some(other) = config.profiles.iter().find(|p| Path::new(&p.dir) == dir) {
+        return Err(AppError::Validation(format!("Folder already used by profile {}", other.name)));
+    }
+
+    std::fs::create_dir_all(&dir)?;
+
+    let profile = Profile {
+        id,
+        name,
+        dir: dir.to_string_lossy().into_owned(),
+    };
+    config.profiles.push(profile.clone());
+    save(config_dir, &config)?;
+
+    Ok(profile)
+}
+
+pub fn rename_profile(config_dir: &Path, id: &str, name: &str) -> Result<Profile, AppError> {
+    let mut config = load(config_dir)?;
+    let name = validate_name(&config, name, Some(id))?;
+
+    let profile = config
+        .profiles
+        .iter_mut()
+        .find(|p| p.id == id)
+        .ok_or_else(|| AppError::NotFound(format!("Profile {} not found", id)))?;
+    profile.name = name;
+    let profile = profile.clone();
+
+    save(config_dir, &config)?;
+
+    Ok(profile)
+}
+
+/// Define o perfil aberto na próxima inicialização
+pub fn switch_profile(config_dir: &Path, id: &str) -> Result<Profile, AppError> {
+    let mut config = load(config_dir)?;
+    let profile = config
+        .profiles
+        .iter()
+        .find(|p| p.id == id)
+        .cloned()
+        .ok_or_else(|| AppError::NotFound(format!("Profile {} not found", id)))?;
+
+    config.active = profile.id.clone();
+    save(config_dir, &config)?;
+
+    Ok(profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles() {
+        let dir = std::env::temp_dir().join(format!("profiles-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // Sem arquivo, o perfil principal usa o banco de sempre
+        let principal = active_profile(&dir).unwrap();
+        assert_eq!(db_path(&principal), dir.join("distribbuidora.db"));
+
+        let gas = create_profile(&dir, CreateProfilePayload {
+            name: " Gás & Cia ".to_string(),
+            dir: None,
+        }).unwrap();
+        assert_eq!((gas.id.as_str(), gas.name.as_str()), ("gas-cia", "Gás & Cia"));
+        assert!(Path::new(&gas.dir).is_dir());
+
+        let water_dir = dir.join("agua");
+        let water = create_profile(&dir, CreateProfilePayload {
+            name: "Água".to_string(),
+            dir: Some(water_dir.to_string_lossy().into_owned()),
+        }).unwrap();
+        assert_eq!(Path::new(&water.dir), water_dir);
+
+        // Nome repetido, pasta de outro perfil e caminho relativo são recusados
+        let repeated = |name: &str, dir: Option<&str>| CreateProfilePayload {
+            name: name.to_string(),
+            dir: dir.map(str::to_string),
+        };
+        assert!(create_profile(&dir, repeated("gás & cia", None)).is_err());
+        assert!(create_profile(&dir, repeated("Outra", Some(&water.dir))).is_err());
+        assert!(create_profile(&dir, repeated("Outra", Some("relativa"))).is_err());
+
+        assert!(rename_profile(&dir, &gas.id, "Água").is_err());
+        assert_eq!(rename_profile(&dir, &gas.id, "Gás").unwrap().name, "Gás");
+
+        switch_profile(&dir, &water.id).unwrap();
+        assert_eq!(active_profile(&dir).unwrap().id, water.id);
+        assert!(switch_profile(&dir, "nao-existe").is_err());
+
+        let config = load(&dir).unwrap();
+        let names: Vec<&str> = config.profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Principal", "Gás", "Água"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
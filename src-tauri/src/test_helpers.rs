@@ -1,5 +1,11 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 pub mod test_helpers {
+    use crate::db::{customers, products, suppliers};
+    use crate::models::{
+        CreateCustomerPayload, CreateOrderPayload, CreateProductPayload, CreateSupplierPayload,
+        OrderItemPayload, OrderPaymentPayload, UpdateProductPayload,
+    };
     use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
 
     /// Cria um pool de conexões SQLite em memória para testes
@@ -20,14 +26,157 @@ pub mod test_helpers {
         pool
     }
 
+    /// Cria um usuário administrador para operações que exigem responsável
+    pub async fn create_test_user(pool: &SqlitePool) -> i64 {
+        sqlx::query("INSERT INTO users (username, password_hash, role) VALUES ('admin', 'hash', 'admin')")
+            .execute(pool)
+            .await
+            .expect("Failed to create test user")
+            .last_insert_rowid()
+    }
+
+    /// Abre o caixa com um operador próprio; toda venda exige caixa aberto
+    pub async fn open_test_session(pool: &SqlitePool) -> i64 {
+        let user_id = sqlx::query("INSERT INTO users (username, password_hash, role) VALUES ('operador', 'hash', 'operator')")
+            .execute(pool)
+            .await
+            .expect("Failed to create session operator")
+            .last_insert_rowid();
+
+        sqlx::query("INSERT INTO cash_sessions (opening_float, opened_by) VALUES (0, ?)")
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .expect("Failed to open test cash session")
+            .last_insert_rowid()
+    }
+
+    /// Produto com 20 cheios e nenhum vazio; os testes mudam só o que importa
+    /// com `CreateProductPayload { stock_full: Some(0), ..product_payload(..) }`
+    pub fn product_payload(name: &str, product_type: &str) -> CreateProductPayload {
+        CreateProductPayload {
+            name: name.to_string(),
+            description: None,
+            r#type: product_type.to_string(),
+            price_refill: 100.0,
+            price_full: 250.0,
+            stock_full: Some(20),
+            stock_empty: Some(0),
+            expiry_date: None,
+            cost_price: None,
+            min_stock: None,
+            reorder_qty: None,
+        }
+    }
+
+    pub async fn create_test_product(pool: &SqlitePool, payload: CreateProductPayload) -> i64 {
+        products::create_product(pool, payload)
+            .await
+            .expect("Failed to create test product")
+    }
+
+    /// Atualização que não muda nada; os testes preenchem só os campos alterados
+    pub fn product_update() -> UpdateProductPayload {
+        UpdateProductPayload {
+            name: None,
+            description: None,
+            r#type: None,
+            price_refill: None,
+            price_full: None,
+            stock_full: None,
+            stock_empty: None,
+            cost_price: None,
+            min_stock: None,
+            reorder_qty: None,
+        }
+    }
+
+    pub fn customer_payload(name: &str) -> CreateCustomerPayload {
+        CreateCustomerPayload {
+            name: name.to_string(),
+            phone: None,
+            address: None,
+            notes: None,
+            credit_limit: None,
+        }
+    }
+
+    pub async fn create_test_customer(pool: &SqlitePool, payload: CreateCustomerPayload) -> i64 {
+        customers::create_customer(pool, payload)
+            .await
+            .expect("Failed to create test customer")
+    }
+
+    pub async fn create_test_supplier(pool: &SqlitePool, name: &str) -> i64 {
+        suppliers::create_supplier(pool, CreateSupplierPayload {
+            name: name.to_string(),
+            document: None,
+            phone: None,
+            email: None,
+            notes: None,
+        })
+        .await
+        .expect("Failed to create test supplier")
+    }
+
+    pub fn payment(method: &str, amount: f64) -> OrderPaymentPayload {
+        OrderPaymentPayload {
+            method: method.to_string(),
+            amount,
+            tendered: None,
+        }
+    }
+
+    /// Venda de balcão de um item, com casco devolvido e paga em dinheiro
+    pub fn order_payload(
+        customer_id: Option<i64>,
+        product_id: i64,
+        quantity: i64,
+        unit_price: f64,
+    ) -> CreateOrderPayload {
+        CreateOrderPayload {
+            customer_id,
+            items: vec![OrderItemPayload {
+                product_id,
+                quantity,
+                returned_bottle: true,
+                unit_price,
+            }],
+            payments: vec![payment("cash", unit_price * quantity as f64)],
+            delivery: None,
+            location_id: None,
+            allow_expired: None,
+        }
+    }
+
     /// Limpa todas as tabelas do banco de teste
     pub async fn cleanup_test_db(pool: &SqlitePool) {
+        sqlx::query("DELETE FROM audit_log").execute(pool).await.ok();
+        sqlx::query("DELETE FROM stock_movement_lots").execute(pool).await.ok();
+        sqlx::query("DELETE FROM stock_lots").execute(pool).await.ok();
         sqlx::query("DELETE FROM stock_movements").execute(pool).await.ok();
+        sqlx::query("DELETE FROM purchase_receipts").execute(pool).await.ok();
+        sqlx::query("DELETE FROM purchase_order_items").execute(pool).await.ok();
+        sqlx::query("DELETE FROM purchase_orders").execute(pool).await.ok();
+        sqlx::query("DELETE FROM suppliers").execute(pool).await.ok();
+        sqlx::query("DELETE FROM bottle_loans").execute(pool).await.ok();
+        sqlx::query("DELETE FROM customer_ledger").execute(pool).await.ok();
+        sqlx::query("DELETE FROM order_payments").execute(pool).await.ok();
+        sqlx::query("DELETE FROM deliveries").execute(pool).await.ok();
+        sqlx::query("DELETE FROM driver_shift_counts").execute(pool).await.ok();
+        sqlx::query("DELETE FROM driver_shifts").execute(pool).await.ok();
         sqlx::query("DELETE FROM order_items").execute(pool).await.ok();
         sqlx::query("DELETE FROM orders").execute(pool).await.ok();
+        sqlx::query("DELETE FROM product_stock").execute(pool).await.ok();
+        sqlx::query("DELETE FROM stock_locations WHERE id != 1").execute(pool).await.ok();
+        sqlx::query("DELETE FROM drivers").execute(pool).await.ok();
+        sqlx::query("DELETE FROM cash_session_counts").execute(pool).await.ok();
+        sqlx::query("DELETE FROM cash_movements").execute(pool).await.ok();
+        sqlx::query("DELETE FROM cash_sessions").execute(pool).await.ok();
         sqlx::query("DELETE FROM products").execute(pool).await.ok();
         sqlx::query("DELETE FROM customers").execute(pool).await.ok();
         sqlx::query("DELETE FROM users").execute(pool).await.ok();
+        sqlx::query("DELETE FROM app_settings").execute(pool).await.ok();
     }
 }
 
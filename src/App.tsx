@@ -1,3 +1,4 @@
+import { useEffect, useState } from "react";
 import { BrowserRouter, Routes, Route } from "react-router-dom";
 import Layout from "./components/Layout";
 import Dashboard from "./pages/Dashboard";
@@ -5,12 +6,36 @@ import Products from "./pages/Products";
 import Customers from "./pages/Customers";
 import POS from "./pages/POS";
 import Orders from "./pages/Orders";
+import Deliveries from "./pages/Deliveries";
+import Drivers from "./pages/Drivers";
+import Reports from "./pages/Reports";
 import Settings from "./pages/Settings";
 import Users from "./pages/Users";
 import LoginPage from "./pages/Login";
 import ProtectedRoute from "./components/ProtectedRoute";
+import Recovery from "./pages/Recovery";
+import { recoveryApi } from "./api/recovery";
+import type { RecoveryInfo } from "./types";
 
 function App() {
+  // undefined enquanto consulta; null quando o banco abriu normalmente
+  const [recovery, setRecovery] = useState<RecoveryInfo | null | undefined>(undefined);
+
+  useEffect(() => {
+    recoveryApi
+      .getStatus()
+      .then(setRecovery)
+      .catch(() => setRecovery(null));
+  }, []);
+
+  if (recovery === undefined) {
+    return null;
+  }
+
+  if (recovery) {
+    return <Recovery info={recovery} />;
+  }
+
   return (
     <BrowserRouter
       future={{
@@ -27,6 +52,9 @@ function App() {
             <Route path="/customers" element={<Customers />} />
             <Route path="/pos" element={<POS />} />
             <Route path="/orders" element={<Orders />} />
+            <Route path="/deliveries" element={<Deliveries />} />
+            <Route path="/drivers" element={<Drivers />} />
+            <Route path="/reports" element={<Reports />} />
             <Route path="/settings" element={<Settings />} />
             <Route path="/users" element={<Users />} />
           </Route>
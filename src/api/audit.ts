@@ -0,0 +1,8 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { AuditLogEntry, AuditLogFilter } from "../types";
+
+export const auditApi = {
+  getLog: async (filter?: AuditLogFilter): Promise<AuditLogEntry[]> => {
+    return await invoke("get_audit_log", { filter: filter ?? null });
+  },
+};
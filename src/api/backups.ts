@@ -0,0 +1,34 @@
+import { invoke } from "@tauri-apps/api/core";
+import type {
+  BackupCheck,
+  BackupFile,
+  BackupSettings,
+  UpdateBackupSettingsPayload,
+} from "../types";
+
+export const backupsApi = {
+  getSettings: async (): Promise<BackupSettings> => {
+    return await invoke("get_backup_settings");
+  },
+
+  updateSettings: async (payload: UpdateBackupSettingsPayload): Promise<BackupSettings> => {
+    return await invoke("update_backup_settings", { payload });
+  },
+
+  create: async (): Promise<BackupFile> => {
+    return await invoke("create_backup");
+  },
+
+  list: async (): Promise<BackupFile[]> => {
+    return await invoke("list_backups");
+  },
+
+  verify: async (fileName: string): Promise<BackupCheck> => {
+    return await invoke("verify_backup", { fileName });
+  },
+
+  // O app reinicia para trocar o banco; a promessa não chega a resolver
+  restore: async (fileName: string): Promise<BackupFile> => {
+    return await invoke("restore_backup", { fileName });
+  },
+};
@@ -0,0 +1,25 @@
+import { invoke } from "@tauri-apps/api/core";
+import type {
+  BottleLoanEntry,
+  CustomerBottleBalance,
+  ReturnBottlesPayload,
+} from "../types";
+
+export const bottleLoansApi = {
+  getOutstanding: async (
+    customerId?: number | null
+  ): Promise<CustomerBottleBalance[]> => {
+    return await invoke("get_outstanding_bottles", { customerId: customerId ?? null });
+  },
+
+  getHistory: async (customerId: number): Promise<BottleLoanEntry[]> => {
+    return await invoke("get_bottle_loan_history", { customerId });
+  },
+
+  returnBottles: async (
+    customerId: number,
+    payload: ReturnBottlesPayload
+  ): Promise<number> => {
+    return await invoke("return_customer_bottles", { customerId, payload });
+  },
+};
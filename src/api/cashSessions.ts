@@ -0,0 +1,34 @@
+import { invoke } from "@tauri-apps/api/core";
+import type {
+  CashSession,
+  CashSessionReport,
+  OpenCashSessionPayload,
+  CashMovementPayload,
+  CloseCashSessionPayload,
+} from "../types";
+
+export const cashSessionsApi = {
+  getOpen: async (): Promise<CashSession | null> => {
+    return await invoke("get_open_cash_session");
+  },
+
+  getAll: async (): Promise<CashSession[]> => {
+    return await invoke("get_cash_sessions");
+  },
+
+  open: async (payload: OpenCashSessionPayload): Promise<number> => {
+    return await invoke("open_cash_session", { payload });
+  },
+
+  addMovement: async (payload: CashMovementPayload): Promise<number> => {
+    return await invoke("add_cash_movement", { payload });
+  },
+
+  getReport: async (sessionId: number): Promise<CashSessionReport> => {
+    return await invoke("get_cash_session_report", { sessionId });
+  },
+
+  close: async (payload: CloseCashSessionPayload): Promise<CashSessionReport> => {
+    return await invoke("close_cash_session", { payload });
+  },
+};
@@ -3,6 +3,10 @@ import type {
   Customer,
   CreateCustomerPayload,
   UpdateCustomerPayload,
+  CustomerStatement,
+  CustomerBalance,
+  CustomerPaymentPayload,
+  CustomerAdjustmentPayload,
 } from "../types";
 
 export const customersApi = {
@@ -32,5 +36,27 @@ export const customersApi = {
   delete: async (id: number): Promise<void> => {
     return await invoke("delete_customer", { id });
   },
+
+  getStatement: async (customerId: number): Promise<CustomerStatement> => {
+    return await invoke("get_customer_statement", { customerId });
+  },
+
+  getBalances: async (): Promise<CustomerBalance[]> => {
+    return await invoke("get_customer_balances");
+  },
+
+  registerPayment: async (
+    customerId: number,
+    payload: CustomerPaymentPayload
+  ): Promise<number> => {
+    return await invoke("register_customer_payment", { customerId, payload });
+  },
+
+  adjustBalance: async (
+    customerId: number,
+    payload: CustomerAdjustmentPayload
+  ): Promise<number> => {
+    return await invoke("adjust_customer_balance", { customerId, payload });
+  },
 };
 
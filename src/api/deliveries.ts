@@ -0,0 +1,29 @@
+import { invoke } from "@tauri-apps/api/core";
+import type {
+  Delivery,
+  DeliveryStatus,
+  DispatchDeliveryPayload,
+  FailDeliveryPayload,
+} from "../types";
+
+export const deliveriesApi = {
+  getAll: async (status?: DeliveryStatus): Promise<Delivery[]> => {
+    return await invoke("get_deliveries", { status: status ?? null });
+  },
+
+  getQueue: async (): Promise<Delivery[]> => {
+    return await invoke("get_delivery_queue");
+  },
+
+  dispatch: async (id: number, payload: DispatchDeliveryPayload): Promise<void> => {
+    return await invoke("dispatch_delivery", { id, payload });
+  },
+
+  confirm: async (id: number): Promise<void> => {
+    return await invoke("confirm_delivery", { id });
+  },
+
+  fail: async (id: number, payload: FailDeliveryPayload): Promise<void> => {
+    return await invoke("fail_delivery", { id, payload });
+  },
+};
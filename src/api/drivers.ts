@@ -0,0 +1,48 @@
+import { invoke } from "@tauri-apps/api/core";
+import type {
+  Driver,
+  CreateDriverPayload,
+  UpdateDriverPayload,
+  DriverShift,
+  DriverShiftReport,
+  DriverLoadPayload,
+  OpenDriverShiftPayload,
+  CloseDriverShiftPayload,
+} from "../types";
+
+export const driversApi = {
+  getAll: async (): Promise<Driver[]> => {
+    return await invoke("get_drivers");
+  },
+
+  create: async (payload: CreateDriverPayload): Promise<number> => {
+    return await invoke("create_driver", { payload });
+  },
+
+  update: async (id: number, payload: UpdateDriverPayload): Promise<void> => {
+    return await invoke("update_driver", { id, payload });
+  },
+
+  getShifts: async (status?: "open" | "closed"): Promise<DriverShift[]> => {
+    return await invoke("get_driver_shifts", { status: status ?? null });
+  },
+
+  getShiftReport: async (shiftId: number): Promise<DriverShiftReport> => {
+    return await invoke("get_driver_shift_report", { shiftId });
+  },
+
+  openShift: async (payload: OpenDriverShiftPayload): Promise<number> => {
+    return await invoke("open_driver_shift", { payload });
+  },
+
+  loadShift: async (shiftId: number, loads: DriverLoadPayload[]): Promise<void> => {
+    return await invoke("load_driver_shift", { shiftId, loads });
+  },
+
+  closeShift: async (
+    shiftId: number,
+    payload: CloseDriverShiftPayload
+  ): Promise<DriverShiftReport> => {
+    return await invoke("close_driver_shift", { shiftId, payload });
+  },
+};
@@ -0,0 +1,32 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { ExportFormat } from "../types";
+
+// Cada exportação grava o arquivo no caminho escolhido e devolve o número de linhas
+export const exportsApi = {
+  exportOrders: async (
+    path: string,
+    format: ExportFormat,
+    startDate: string | null,
+    endDate: string | null
+  ): Promise<number> => {
+    return await invoke("export_orders", { path, format, startDate, endDate });
+  },
+
+  exportStockMovements: async (
+    path: string,
+    format: ExportFormat,
+    startDate: string | null,
+    endDate: string | null
+  ): Promise<number> => {
+    return await invoke("export_stock_movements", { path, format, startDate, endDate });
+  },
+
+  exportCustomers: async (
+    path: string,
+    format: ExportFormat,
+    startDate: string | null,
+    endDate: string | null
+  ): Promise<number> => {
+    return await invoke("export_customers", { path, format, startDate, endDate });
+  },
+};
@@ -0,0 +1,13 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { ImportReport } from "../types";
+
+// Com dryRun, valida e simula a importação sem gravar nada
+export const importsApi = {
+  importProducts: async (path: string, dryRun: boolean): Promise<ImportReport> => {
+    return await invoke("import_products", { path, dryRun });
+  },
+
+  importCustomers: async (path: string, dryRun: boolean): Promise<ImportReport> => {
+    return await invoke("import_customers", { path, dryRun });
+  },
+};
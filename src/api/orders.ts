@@ -2,6 +2,7 @@ import { invoke } from "@tauri-apps/api/core";
 import type {
   CreateOrderPayload,
   UpdateOrderPayload,
+  CancelOrderPayload,
   OrderWithCustomer,
   OrderWithItems,
 } from "../types";
@@ -27,8 +28,8 @@ export const ordersApi = {
     return await invoke("update_order", { id, payload });
   },
 
-  delete: async (id: number): Promise<void> => {
-    return await invoke("delete_order", { id });
+  cancel: async (id: number, payload: CancelOrderPayload): Promise<void> => {
+    return await invoke("cancel_order", { id, payload });
   },
 };
 
@@ -0,0 +1,21 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { CreateProfilePayload, Profile, ProfilesConfig } from "../types";
+
+export const profilesApi = {
+  list: async (): Promise<ProfilesConfig> => {
+    return await invoke("get_profiles");
+  },
+
+  create: async (payload: CreateProfilePayload): Promise<Profile> => {
+    return await invoke("create_profile", { payload });
+  },
+
+  rename: async (id: string, name: string): Promise<Profile> => {
+    return await invoke("rename_profile", { id, name });
+  },
+
+  // Reinicia o app com o banco do perfil; a promessa não chega a resolver
+  switch: async (id: string): Promise<void> => {
+    return await invoke("switch_profile", { id });
+  },
+};
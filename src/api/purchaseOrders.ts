@@ -0,0 +1,33 @@
+import { invoke } from "@tauri-apps/api/core";
+import type {
+  PurchaseOrder,
+  PurchaseOrderStatus,
+  PurchaseOrderWithItems,
+  CreatePurchaseOrderPayload,
+  ReceivePurchaseOrderPayload,
+} from "../types";
+
+export const purchaseOrdersApi = {
+  getAll: async (status?: PurchaseOrderStatus): Promise<PurchaseOrder[]> => {
+    return await invoke("get_purchase_orders", { status: status ?? null });
+  },
+
+  getById: async (id: number): Promise<PurchaseOrderWithItems> => {
+    return await invoke("get_purchase_order", { id });
+  },
+
+  create: async (payload: CreatePurchaseOrderPayload): Promise<number> => {
+    return await invoke("create_purchase_order", { payload });
+  },
+
+  receive: async (
+    id: number,
+    payload: ReceivePurchaseOrderPayload
+  ): Promise<number> => {
+    return await invoke("receive_purchase_order", { id, payload });
+  },
+
+  cancel: async (id: number): Promise<void> => {
+    return await invoke("cancel_purchase_order", { id });
+  },
+};
@@ -4,5 +4,14 @@ export const receiptsApi = {
   generate: async (orderId: number): Promise<string> => {
     return await invoke("generate_receipt", { orderId });
   },
+
+  // Cupom ESC/POS na impressora térmica configurada
+  print: async (orderId: number): Promise<void> => {
+    return await invoke("print_receipt", { orderId });
+  },
+
+  printTestPage: async (): Promise<void> => {
+    return await invoke("print_test_page");
+  },
 };
 
@@ -0,0 +1,25 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { BackupFile, RecoveryInfo } from "../types";
+
+// As ações reiniciam o app; as promessas não chegam a resolver
+export const recoveryApi = {
+  getStatus: async (): Promise<RecoveryInfo | null> => {
+    return await invoke("get_recovery_status");
+  },
+
+  listBackups: async (): Promise<BackupFile[]> => {
+    return await invoke("list_recovery_backups");
+  },
+
+  acceptChecksums: async (): Promise<void> => {
+    return await invoke("recovery_accept_checksums");
+  },
+
+  restoreBackup: async (fileName: string): Promise<void> => {
+    return await invoke("recovery_restore_backup", { fileName });
+  },
+
+  setAsideDatabase: async (): Promise<void> => {
+    return await invoke("recovery_set_aside_database");
+  },
+};
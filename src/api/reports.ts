@@ -0,0 +1,12 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { ReportGrouping, SalesReport } from "../types";
+
+export const reportsApi = {
+  getSalesReport: async (
+    startDate: string,
+    endDate: string,
+    groupBy: ReportGrouping
+  ): Promise<SalesReport> => {
+    return await invoke("get_sales_report", { startDate, endDate, groupBy });
+  },
+};
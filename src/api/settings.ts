@@ -0,0 +1,25 @@
+import { invoke } from "@tauri-apps/api/core";
+import type {
+  CompanySettings,
+  PrinterSettings,
+  UpdateCompanySettingsPayload,
+  UpdatePrinterSettingsPayload,
+} from "../types";
+
+export const settingsApi = {
+  getCompany: async (): Promise<CompanySettings> => {
+    return await invoke("get_company_settings");
+  },
+
+  updateCompany: async (payload: UpdateCompanySettingsPayload): Promise<CompanySettings> => {
+    return await invoke("update_company_settings", { payload });
+  },
+
+  getPrinter: async (): Promise<PrinterSettings> => {
+    return await invoke("get_printer_settings");
+  },
+
+  updatePrinter: async (payload: UpdatePrinterSettingsPayload): Promise<PrinterSettings> => {
+    return await invoke("update_printer_settings", { payload });
+  },
+};
@@ -1,21 +1,87 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { StockMovementWithProduct } from "../types";
+import type {
+  Product,
+  ReorderSuggestion,
+  StockLot,
+  StockMovementWithProduct,
+  StockKind,
+  TransferStockPayload,
+} from "../types";
 
+// Sem local informado, a movimentação é feita na loja
 export const stockApi = {
-  stockIn: async (productId: number, quantity: number): Promise<void> => {
-    return await invoke("stock_in", { productId, quantity });
+  stockIn: async (
+    productId: number,
+    quantity: number,
+    unitCost: number | null = null,
+    locationId: number | null = null,
+    expiryDate: string | null = null
+  ): Promise<void> => {
+    return await invoke("stock_in", { productId, quantity, unitCost, locationId, expiryDate });
   },
 
-  stockOut: async (productId: number, quantity: number): Promise<void> => {
-    return await invoke("stock_out", { productId, quantity });
+  stockOut: async (
+    productId: number,
+    quantity: number,
+    locationId: number | null = null
+  ): Promise<void> => {
+    return await invoke("stock_out", { productId, quantity, locationId });
   },
 
-  stockAdjust: async (productId: number, quantity: number): Promise<void> => {
-    return await invoke("stock_adjust", { productId, quantity });
+  stockAdjust: async (
+    productId: number,
+    quantity: number,
+    stockKind: StockKind = "full",
+    locationId: number | null = null
+  ): Promise<void> => {
+    return await invoke("stock_adjust", { productId, quantity, stockKind, locationId });
+  },
+
+  supplierExchange: async (
+    productId: number,
+    quantity: number,
+    unitCost: number,
+    locationId: number | null = null,
+    expiryDate: string | null = null
+  ): Promise<void> => {
+    return await invoke("supplier_exchange", {
+      productId,
+      quantity,
+      unitCost,
+      locationId,
+      expiryDate,
+    });
+  },
+
+  transfer: async (payload: TransferStockPayload): Promise<void> => {
+    return await invoke("transfer_stock", { payload });
+  },
+
+  getCriticalStock: async (locationId: number | null = null): Promise<Product[]> => {
+    return await invoke("get_critical_stock", { locationId });
+  },
+
+  // Giro dos últimos `days` dias (padrão 30) e cobertura alvo de `targetDays` (padrão 7)
+  getReorderSuggestions: async (
+    days: number | null = null,
+    targetDays: number | null = null
+  ): Promise<ReorderSuggestion[]> => {
+    return await invoke("get_reorder_suggestions", { days, targetDays });
+  },
+
+  getLots: async (
+    productId: number | null = null,
+    locationId: number | null = null
+  ): Promise<StockLot[]> => {
+    return await invoke("get_stock_lots", { productId, locationId });
+  },
+
+  // Lotes vencendo nos próximos `days` dias, incluindo os vencidos
+  getExpiringLots: async (days: number): Promise<StockLot[]> => {
+    return await invoke("get_expiring_lots", { days });
   },
 
   getMovements: async (): Promise<StockMovementWithProduct[]> => {
     return await invoke("get_stock_movements");
   },
 };
-
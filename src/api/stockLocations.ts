@@ -0,0 +1,25 @@
+import { invoke } from "@tauri-apps/api/core";
+import type {
+  StockLocation,
+  LocationStock,
+  CreateStockLocationPayload,
+  UpdateStockLocationPayload,
+} from "../types";
+
+export const stockLocationsApi = {
+  getAll: async (): Promise<StockLocation[]> => {
+    return await invoke("get_stock_locations");
+  },
+
+  create: async (payload: CreateStockLocationPayload): Promise<number> => {
+    return await invoke("create_stock_location", { payload });
+  },
+
+  update: async (id: number, payload: UpdateStockLocationPayload): Promise<void> => {
+    return await invoke("update_stock_location", { id, payload });
+  },
+
+  getStock: async (locationId: number | null = null): Promise<LocationStock[]> => {
+    return await invoke("get_location_stock", { locationId });
+  },
+};
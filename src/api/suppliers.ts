@@ -0,0 +1,28 @@
+import { invoke } from "@tauri-apps/api/core";
+import type {
+  Supplier,
+  CreateSupplierPayload,
+  UpdateSupplierPayload,
+} from "../types";
+
+export const suppliersApi = {
+  getAll: async (): Promise<Supplier[]> => {
+    return await invoke("get_suppliers");
+  },
+
+  getById: async (id: number): Promise<Supplier> => {
+    return await invoke("get_supplier", { id });
+  },
+
+  create: async (payload: CreateSupplierPayload): Promise<number> => {
+    return await invoke("create_supplier", { payload });
+  },
+
+  update: async (id: number, payload: UpdateSupplierPayload): Promise<void> => {
+    return await invoke("update_supplier", { id, payload });
+  },
+
+  delete: async (id: number): Promise<void> => {
+    return await invoke("delete_supplier", { id });
+  },
+};
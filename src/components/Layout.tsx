@@ -1,4 +1,4 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import { Link, useLocation, Outlet, useNavigate } from "react-router-dom";
 import { invoke } from "@tauri-apps/api/core";
 import {
@@ -11,15 +11,20 @@ import {
   ChevronsLeft,
   LogOut,
   UserCog,
+  Truck,
+  Bike,
+  BarChart3,
 } from "lucide-react";
 import { cn } from "@/lib/utils";
 import { Button } from "./ui/button";
 import { useAuthStore } from "@/state/authStore";
+import { profilesApi } from "@/api/profiles";
 
 export default function Layout() {
   const location = useLocation();
   const navigate = useNavigate();
   const [isCollapsed, setIsCollapsed] = useState(false);
+  const [profileName, setProfileName] = useState<string | null>(null);
   const { user, logout: logoutFromStore } = useAuthStore((state) => ({
     user: state.user,
     logout: state.logout,
@@ -31,10 +36,25 @@ export default function Layout() {
     { path: "/customers", label: "Clientes", icon: Users, adminOnly: false },
     { path: "/pos", label: "PDV", icon: ShoppingCart, adminOnly: false },
     { path: "/orders", label: "Vendas", icon: History, adminOnly: false },
+    { path: "/deliveries", label: "Entregas", icon: Truck, adminOnly: false },
+    { path: "/drivers", label: "Entregadores", icon: Bike, adminOnly: false },
+    { path: "/reports", label: "Relatórios", icon: BarChart3, adminOnly: false },
     { path: "/users", label: "Usuários", icon: UserCog, adminOnly: true },
     { path: "/settings", label: "Configurações", icon: Settings, adminOnly: true },
   ];
 
+  // Só mostra o nome da empresa quando há mais de um perfil
+  useEffect(() => {
+    profilesApi
+      .list()
+      .then((config) => {
+        if (config.profiles.length > 1) {
+          setProfileName(config.profiles.find((p) => p.id === config.active)?.name ?? null);
+        }
+      })
+      .catch((error) => console.error("Erro ao carregar perfis:", error));
+  }, []);
+
   const navItems = allNavItems.filter(item => !item.adminOnly || user?.role === 'admin');
 
   const handleLogout = async () => {
@@ -67,12 +87,15 @@ export default function Layout() {
             "flex items-center gap-3",
             isCollapsed && "justify-center"
           )}>
-            <h1 className={cn(
-              "text-xl font-bold text-foreground transition-all duration-300 overflow-hidden whitespace-nowrap",
+            <div className={cn(
+              "transition-all duration-300 overflow-hidden whitespace-nowrap",
               isCollapsed ? "w-0 opacity-0" : "w-auto opacity-100"
             )}>
-              S.G.D.
-            </h1>
+              <h1 className="text-xl font-bold text-foreground">S.G.D.</h1>
+              {profileName && (
+                <p className="text-xs text-muted-foreground truncate">{profileName}</p>
+              )}
+            </div>
           </div>
         </div>
 
@@ -1,5 +1,5 @@
 import { describe, it, expect } from 'vitest';
-import { cn } from '../utils';
+import { cn, formatMoney } from '../utils';
 
 describe('cn', () => {
   it('deve combinar classes CSS corretamente', () => {
@@ -34,3 +34,15 @@ describe('cn', () => {
   });
 });
 
+
+describe('formatMoney', () => {
+  it('deve usar real com vírgula decimal por padrão', () => {
+    expect(formatMoney(1234.5)).toBe('R$ 1.234,50');
+    expect(formatMoney(-0.5)).toBe('-R$ 0,50');
+  });
+
+  it('deve seguir a moeda configurada', () => {
+    const format = { currency_symbol: 'US$', decimal_separator: '.' };
+    expect(formatMoney(1234567, format)).toBe('US$ 1,234,567.00');
+  });
+});
@@ -5,3 +5,18 @@ export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs))
 }
 
+
+type MoneyFormat = { currency_symbol: string; decimal_separator: string }
+
+// Mesmo formato do recibo: 1234.5 -> "R$ 1.234,50" (ou "R$ 1,234.50" com ponto decimal)
+export function formatMoney(value: number, format?: MoneyFormat | null) {
+  const symbol = format?.currency_symbol ?? "R$"
+  const decimal = format?.decimal_separator ?? ","
+  const thousands = decimal === "." ? "," : "."
+
+  const [integer, cents] = Math.abs(value).toFixed(2).split(".")
+  const grouped = integer.replace(/\B(?=(\d{3})+(?!\d))/g, thousands)
+  const sign = value < 0 && Math.abs(value) >= 0.005 ? "-" : ""
+
+  return `${sign}${symbol} ${grouped}${decimal}${cents}`
+}
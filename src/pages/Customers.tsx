@@ -41,6 +41,7 @@ export default function Customers() {
     phone: "",
     address: "",
     notes: "",
+    credit_limit: 0,
   });
 
   useEffect(() => {
@@ -59,7 +60,7 @@ export default function Customers() {
       }
       setShowModal(false);
       setEditingCustomer(null);
-      setFormData({ name: "", phone: "", address: "", notes: "" });
+      setFormData({ name: "", phone: "", address: "", notes: "", credit_limit: 0 });
     } catch (error) {
       alert("Erro ao salvar cliente: " + error);
     }
@@ -72,6 +73,7 @@ export default function Customers() {
       phone: customer.phone || "",
       address: customer.address || "",
       notes: customer.notes || "",
+      credit_limit: customer.credit_limit,
     });
     setShowModal(true);
   };
@@ -135,7 +137,7 @@ export default function Customers() {
         <Button
           onClick={() => {
             setEditingCustomer(null);
-            setFormData({ name: "", phone: "", address: "", notes: "" });
+            setFormData({ name: "", phone: "", address: "", notes: "", credit_limit: 0 });
             setShowModal(true);
           }}
         >
@@ -268,6 +270,19 @@ export default function Customers() {
                 }
               />
             </div>
+            <div className="space-y-2">
+              <Label htmlFor="credit_limit">Limite de Crédito (Fiado)</Label>
+              <Input
+                id="credit_limit"
+                type="number"
+                min="0"
+                step="0.01"
+                value={formData.credit_limit ?? 0}
+                onChange={(e) =>
+                  setFormData({ ...formData, credit_limit: parseFloat(e.target.value) || 0 })
+                }
+              />
+            </div>
             <div className="space-y-2">
               <Label htmlFor="notes">Observações</Label>
               <Textarea
@@ -1,12 +1,15 @@
-import { useEffect } from "react";
+import { useEffect, useState } from "react";
 import { useDashboardStore } from "../state/dashboardStore";
 import { useProductsStore } from "../state/productsStore";
+import { stockApi } from "../api/stock";
+import type { ReorderSuggestion, StockLot } from "../types";
 import {
   TrendingUp,
   DollarSign,
   Package,
   Users,
   AlertTriangle,
+  CalendarClock,
 } from "lucide-react";
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
 import {
@@ -19,13 +22,21 @@ import {
 } from "@/components/ui/table";
 import { Badge } from "@/components/ui/badge";
 
+// 'YYYY-MM-DD' -> 'DD/MM/YYYY' sem passar por Date (evita deslocamento de fuso)
+const formatExpiry = (date: string) => date.split("-").reverse().join("/");
+
 export default function Dashboard() {
   const { stats, loading, fetchStats } = useDashboardStore();
   const { fetchProducts } = useProductsStore();
+  const [suggestions, setSuggestions] = useState<ReorderSuggestion[]>([]);
 
   useEffect(() => {
     fetchStats();
     fetchProducts();
+    stockApi
+      .getReorderSuggestions()
+      .then(setSuggestions)
+      .catch((error) => console.error("Erro ao carregar sugestões de compra:", error));
   }, [fetchStats, fetchProducts]);
 
   if (loading) {
@@ -64,6 +75,9 @@ export default function Dashboard() {
             <div className="text-2xl font-bold">
               R$ {stats.sales_today.toFixed(2)}
             </div>
+            <p className="text-xs text-muted-foreground">
+              Margem bruta: R$ {stats.margin_today.toFixed(2)}
+            </p>
           </CardContent>
         </Card>
 
@@ -78,6 +92,9 @@ export default function Dashboard() {
             <div className="text-2xl font-bold">
               R$ {stats.sales_month.toFixed(2)}
             </div>
+            <p className="text-xs text-muted-foreground">
+              Margem bruta: R$ {stats.margin_month.toFixed(2)}
+            </p>
           </CardContent>
         </Card>
 
@@ -110,13 +127,60 @@ export default function Dashboard() {
         </Card>
       </div>
 
+      {/* Validade */}
+      {(stats.expired_lots.length > 0 || stats.expiring_lots.length > 0) && (
+        <Card>
+          <CardHeader>
+            <CardTitle className="flex items-center gap-2">
+              <CalendarClock className="h-5 w-5 text-destructive" />
+              Validade
+            </CardTitle>
+            <CardDescription>
+              Lotes vencidos e a vencer nos próximos 30 dias
+            </CardDescription>
+          </CardHeader>
+          <CardContent>
+            <Table>
+              <TableHeader>
+                <TableRow>
+                  <TableHead>Produto</TableHead>
+                  <TableHead>Local</TableHead>
+                  <TableHead>Validade</TableHead>
+                  <TableHead className="text-right">Quantidade</TableHead>
+                  <TableHead className="text-right">Situação</TableHead>
+                </TableRow>
+              </TableHeader>
+              <TableBody>
+                {[...stats.expired_lots, ...stats.expiring_lots].map((lot: StockLot) => (
+                  <TableRow key={lot.id}>
+                    <TableCell className="font-medium">{lot.product_name}</TableCell>
+                    <TableCell>{lot.location_name}</TableCell>
+                    <TableCell>{formatExpiry(lot.expiry_date)}</TableCell>
+                    <TableCell className="text-right">{lot.quantity}</TableCell>
+                    <TableCell className="text-right">
+                      {lot.days_to_expiry < 0 ? (
+                        <Badge variant="destructive">VENCIDO</Badge>
+                      ) : (
+                        <Badge variant="secondary">
+                          {lot.days_to_expiry === 0 ? "Vence hoje" : `${lot.days_to_expiry} dia(s)`}
+                        </Badge>
+                      )}
+                    </TableCell>
+                  </TableRow>
+                ))}
+              </TableBody>
+            </Table>
+          </CardContent>
+        </Card>
+      )}
+
       {/* Estoque Crítico */}
       {stats.critical_stock.length > 0 && (
         <Card>
           <CardHeader>
             <CardTitle>Estoque Crítico</CardTitle>
             <CardDescription>
-              Produtos com estoque baixo que precisam de atenção
+              Produtos no estoque mínimo ou abaixo dele
             </CardDescription>
           </CardHeader>
           <CardContent>
@@ -126,6 +190,7 @@ export default function Dashboard() {
                   <TableHead>Produto</TableHead>
                   <TableHead>Estoque Cheio</TableHead>
                   <TableHead>Estoque Vazio</TableHead>
+                  <TableHead>Mínimo</TableHead>
                 </TableRow>
               </TableHeader>
               <TableBody>
@@ -138,6 +203,53 @@ export default function Dashboard() {
                       </Badge>
                     </TableCell>
                     <TableCell>{product.stock_empty}</TableCell>
+                    <TableCell>{product.min_stock}</TableCell>
+                  </TableRow>
+                ))}
+              </TableBody>
+            </Table>
+          </CardContent>
+        </Card>
+      )}
+
+      {/* Sugestão de Compra */}
+      {suggestions.length > 0 && (
+        <Card>
+          <CardHeader>
+            <CardTitle>Sugestão de Compra</CardTitle>
+            <CardDescription>
+              Pelo giro dos últimos 30 dias, para cobrir 7 dias de vendas
+            </CardDescription>
+          </CardHeader>
+          <CardContent>
+            <Table>
+              <TableHeader>
+                <TableRow>
+                  <TableHead>Produto</TableHead>
+                  <TableHead className="text-right">Estoque</TableHead>
+                  <TableHead className="text-right">Venda/dia</TableHead>
+                  <TableHead className="text-right">Cobertura</TableHead>
+                  <TableHead className="text-right">Pedido em aberto</TableHead>
+                  <TableHead className="text-right">Comprar</TableHead>
+                </TableRow>
+              </TableHeader>
+              <TableBody>
+                {suggestions.map((suggestion) => (
+                  <TableRow key={suggestion.product_id}>
+                    <TableCell className="font-medium">{suggestion.product_name}</TableCell>
+                    <TableCell className="text-right">{suggestion.stock_full}</TableCell>
+                    <TableCell className="text-right">
+                      {suggestion.avg_daily_sales.toFixed(1)}
+                    </TableCell>
+                    <TableCell className="text-right">
+                      {suggestion.days_of_cover === null
+                        ? "-"
+                        : `${suggestion.days_of_cover.toFixed(1)} dias`}
+                    </TableCell>
+                    <TableCell className="text-right">{suggestion.on_order}</TableCell>
+                    <TableCell className="text-right">
+                      <Badge>{suggestion.suggested_qty}</Badge>
+                    </TableCell>
                   </TableRow>
                 ))}
               </TableBody>
@@ -162,6 +274,7 @@ export default function Dashboard() {
                   <TableHead>Produto</TableHead>
                   <TableHead>Quantidade</TableHead>
                   <TableHead className="text-right">Receita</TableHead>
+                  <TableHead className="text-right">Margem</TableHead>
                 </TableRow>
               </TableHeader>
               <TableBody>
@@ -174,6 +287,9 @@ export default function Dashboard() {
                     <TableCell className="text-right font-semibold">
                       R$ {product.total_revenue.toFixed(2)}
                     </TableCell>
+                    <TableCell className="text-right">
+                      R$ {product.gross_margin.toFixed(2)}
+                    </TableCell>
                   </TableRow>
                 ))}
               </TableBody>
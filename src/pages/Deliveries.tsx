@@ -0,0 +1,265 @@
+import { useEffect, useState } from "react";
+import { deliveriesApi } from "../api/deliveries";
+import { driversApi } from "../api/drivers";
+import type { Delivery, DeliveryStatus, Driver } from "../types";
+import { Truck, CheckCircle, XCircle } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/ui/dialog";
+import { Label } from "@/components/ui/label";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import {
+  Table,
+  TableBody,
+  TableCell,
+  TableHead,
+  TableHeader,
+  TableRow,
+} from "@/components/ui/table";
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
+import { Badge } from "@/components/ui/badge";
+
+const STATUS_LABELS: Record<DeliveryStatus, string> = {
+  pending: "Aguardando",
+  out_for_delivery: "Em rota",
+  delivered: "Entregue",
+  failed: "Não entregue",
+};
+
+export default function Deliveries() {
+  const [queue, setQueue] = useState<Delivery[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [drivers, setDrivers] = useState<Driver[]>([]);
+  const [dispatching, setDispatching] = useState<Delivery | null>(null);
+  const [driverId, setDriverId] = useState<string>("");
+
+  useEffect(() => {
+    loadQueue();
+    loadDrivers();
+  }, []);
+
+  const loadDrivers = async () => {
+    try {
+      const data = await driversApi.getAll();
+      setDrivers(data.filter((driver) => driver.active));
+    } catch (error) {
+      alert("Erro ao carregar entregadores: " + error);
+    }
+  };
+
+  const loadQueue = async () => {
+    setLoading(true);
+    try {
+      const data = await deliveriesApi.getQueue();
+      setQueue(data);
+    } catch (error) {
+      alert("Erro ao carregar entregas: " + error);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleOpenDispatch = (delivery: Delivery) => {
+    setDispatching(delivery);
+    setDriverId(delivery.driver_id ? String(delivery.driver_id) : "");
+  };
+
+  const handleDispatch = async () => {
+    if (!dispatching || !driverId) {
+      return;
+    }
+
+    try {
+      await deliveriesApi.dispatch(dispatching.id, { driver_id: Number(driverId) });
+      setDispatching(null);
+      await loadQueue();
+    } catch (error) {
+      alert("Erro ao despachar entrega: " + error);
+    }
+  };
+
+  const handleConfirm = async (delivery: Delivery) => {
+    try {
+      await deliveriesApi.confirm(delivery.id);
+      await loadQueue();
+    } catch (error) {
+      alert("Erro ao confirmar entrega: " + error);
+    }
+  };
+
+  const handleFail = async (delivery: Delivery) => {
+    const reason = prompt("Motivo da entrega não realizada:");
+    if (!reason || !reason.trim()) {
+      return;
+    }
+
+    try {
+      await deliveriesApi.fail(delivery.id, { reason });
+      await loadQueue();
+    } catch (error) {
+      alert("Erro ao registrar falha na entrega: " + error);
+    }
+  };
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center h-full">
+        <div className="text-xl text-muted-foreground">Carregando...</div>
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-6">
+      <div>
+        <h1 className="text-3xl font-bold tracking-tight">Entregas</h1>
+        <p className="text-muted-foreground">
+          Acompanhe os pedidos aguardando saída e em rota
+        </p>
+      </div>
+
+      <Card>
+        <CardHeader>
+          <CardTitle>Fila de Entregas</CardTitle>
+          <CardDescription>
+            Pedidos mais antigos primeiro
+          </CardDescription>
+        </CardHeader>
+        <CardContent>
+          <Table>
+            <TableHeader>
+              <TableRow>
+                <TableHead>Pedido</TableHead>
+                <TableHead>Cliente</TableHead>
+                <TableHead>Endereço</TableHead>
+                <TableHead>Entregador</TableHead>
+                <TableHead>Situação</TableHead>
+                <TableHead className="text-right">Total</TableHead>
+                <TableHead className="text-right">Ações</TableHead>
+              </TableRow>
+            </TableHeader>
+            <TableBody>
+              {queue.length === 0 && (
+                <TableRow>
+                  <TableCell colSpan={7} className="text-center text-muted-foreground">
+                    Nenhuma entrega pendente
+                  </TableCell>
+                </TableRow>
+              )}
+              {queue.map((delivery) => (
+                <TableRow key={delivery.id}>
+                  <TableCell className="font-medium">#{delivery.order_id}</TableCell>
+                  <TableCell>
+                    {delivery.customer_name || (
+                      <span className="text-muted-foreground">Consumidor Final</span>
+                    )}
+                    {delivery.phone && (
+                      <div className="text-xs text-muted-foreground">{delivery.phone}</div>
+                    )}
+                  </TableCell>
+                  <TableCell>
+                    {delivery.address}
+                    {delivery.notes && (
+                      <div className="text-xs text-muted-foreground">{delivery.notes}</div>
+                    )}
+                  </TableCell>
+                  <TableCell>{delivery.driver_name ?? "-"}</TableCell>
+                  <TableCell>
+                    <Badge variant={delivery.status === "pending" ? "secondary" : "default"}>
+                      {STATUS_LABELS[delivery.status]}
+                    </Badge>
+                  </TableCell>
+                  <TableCell className="text-right font-semibold">
+                    R$ {delivery.order_total.toFixed(2)}
+                  </TableCell>
+                  <TableCell className="text-right">
+                    <div className="flex justify-end gap-2">
+                      {delivery.status === "pending" && (
+                        <Button
+                          variant="ghost"
+                          size="icon"
+                          onClick={() => handleOpenDispatch(delivery)}
+                          title="Saiu para entrega"
+                        >
+                          <Truck className="w-4 h-4" />
+                        </Button>
+                      )}
+                      {delivery.status === "out_for_delivery" && (
+                        <>
+                          <Button
+                            variant="ghost"
+                            size="icon"
+                            onClick={() => handleConfirm(delivery)}
+                            title="Confirmar entrega"
+                          >
+                            <CheckCircle className="w-4 h-4" />
+                          </Button>
+                          <Button
+                            variant="ghost"
+                            size="icon"
+                            onClick={() => handleFail(delivery)}
+                            title="Entrega não realizada"
+                          >
+                            <XCircle className="w-4 h-4 text-destructive" />
+                          </Button>
+                        </>
+                      )}
+                    </div>
+                  </TableCell>
+                </TableRow>
+              ))}
+            </TableBody>
+          </Table>
+        </CardContent>
+      </Card>
+
+      {/* Dialog de Saída para Entrega */}
+      <Dialog open={dispatching !== null} onOpenChange={(open) => !open && setDispatching(null)}>
+        <DialogContent className="sm:max-w-[400px]">
+          <DialogHeader>
+            <DialogTitle>Saída do Pedido #{dispatching?.order_id}</DialogTitle>
+            <DialogDescription>
+              Com turno aberto, os cheios saem do veículo do entregador
+            </DialogDescription>
+          </DialogHeader>
+          <div className="grid gap-2 py-4">
+            <Label htmlFor="driver">Entregador</Label>
+            <Select value={driverId} onValueChange={setDriverId}>
+              <SelectTrigger id="driver">
+                <SelectValue placeholder="Selecione o entregador" />
+              </SelectTrigger>
+              <SelectContent>
+                {drivers.map((driver) => (
+                  <SelectItem key={driver.id} value={String(driver.id)}>
+                    {driver.name}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </div>
+          <DialogFooter>
+            <Button variant="outline" onClick={() => setDispatching(null)}>
+              Cancelar
+            </Button>
+            <Button onClick={handleDispatch} disabled={!driverId}>
+              <Truck className="w-4 h-4 mr-2" />
+              Despachar
+            </Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
+    </div>
+  );
+}
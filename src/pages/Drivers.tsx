@@ -0,0 +1,439 @@
+import { useEffect, useState } from "react";
+import { driversApi } from "../api/drivers";
+import { productsApi } from "../api/products";
+import type { Driver, DriverShift, DriverShiftReport, Product } from "../types";
+import { Plus, Truck, ClipboardCheck, UserX, UserCheck } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/ui/dialog";
+import {
+  Table,
+  TableBody,
+  TableCell,
+  TableHead,
+  TableHeader,
+  TableRow,
+} from "@/components/ui/table";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
+import { Badge } from "@/components/ui/badge";
+
+type Returns = Record<number, { full: string; empty: string }>;
+
+export default function Drivers() {
+  const [drivers, setDrivers] = useState<Driver[]>([]);
+  const [openShifts, setOpenShifts] = useState<DriverShift[]>([]);
+  const [products, setProducts] = useState<Product[]>([]);
+  const [loading, setLoading] = useState(false);
+
+  // Abertura de turno
+  const [shiftDriver, setShiftDriver] = useState<Driver | null>(null);
+  const [changeFund, setChangeFund] = useState("0");
+  const [loads, setLoads] = useState<Record<number, string>>({});
+
+  // Acerto do turno
+  const [settlement, setSettlement] = useState<DriverShiftReport | null>(null);
+  const [returns, setReturns] = useState<Returns>({});
+  const [cashCounted, setCashCounted] = useState("");
+
+  useEffect(() => {
+    loadData();
+  }, []);
+
+  const loadData = async () => {
+    setLoading(true);
+    try {
+      const [driversData, shiftsData, productsData] = await Promise.all([
+        driversApi.getAll(),
+        driversApi.getShifts("open"),
+        productsApi.getAll(),
+      ]);
+      setDrivers(driversData);
+      setOpenShifts(shiftsData);
+      setProducts(productsData);
+    } catch (error) {
+      alert("Erro ao carregar entregadores: " + error);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleCreateDriver = async () => {
+    const name = prompt("Nome do entregador:");
+    if (!name || !name.trim()) {
+      return;
+    }
+    const phone = prompt("Telefone (opcional):");
+
+    try {
+      await driversApi.create({ name, phone: phone || null });
+      await loadData();
+    } catch (error) {
+      alert("Erro ao cadastrar entregador: " + error);
+    }
+  };
+
+  const handleToggleActive = async (driver: Driver) => {
+    try {
+      await driversApi.update(driver.id, { active: !driver.active });
+      await loadData();
+    } catch (error) {
+      alert("Erro ao atualizar entregador: " + error);
+    }
+  };
+
+  const handleOpenShiftDialog = (driver: Driver) => {
+    setShiftDriver(driver);
+    setChangeFund("0");
+    setLoads({});
+  };
+
+  const handleOpenShift = async () => {
+    if (!shiftDriver) {
+      return;
+    }
+
+    const shiftLoads = Object.entries(loads)
+      .map(([productId, quantity]) => ({
+        product_id: Number(productId),
+        quantity: parseInt(quantity) || 0,
+      }))
+      .filter((load) => load.quantity > 0);
+
+    try {
+      await driversApi.openShift({
+        driver_id: shiftDriver.id,
+        change_fund: parseFloat(changeFund) || 0,
+        loads: shiftLoads,
+      });
+      setShiftDriver(null);
+      await loadData();
+    } catch (error) {
+      alert("Erro ao abrir turno: " + error);
+    }
+  };
+
+  const handleOpenSettlement = async (shift: DriverShift) => {
+    try {
+      const report = await driversApi.getShiftReport(shift.id);
+      setSettlement(report);
+      setCashCounted(report.cash_expected.toFixed(2));
+      setReturns(
+        Object.fromEntries(
+          report.lines.map((line) => [
+            line.product_id,
+            { full: String(line.full_expected), empty: String(line.empty_expected) },
+          ])
+        )
+      );
+    } catch (error) {
+      alert("Erro ao carregar acerto: " + error);
+    }
+  };
+
+  const handleCloseShift = async () => {
+    if (!settlement) {
+      return;
+    }
+
+    try {
+      const report = await driversApi.closeShift(settlement.shift.id, {
+        cash_counted: parseFloat(cashCounted) || 0,
+        returns: settlement.lines.map((line) => ({
+          product_id: line.product_id,
+          full_returned: parseInt(returns[line.product_id]?.full) || 0,
+          empty_returned: parseInt(returns[line.product_id]?.empty) || 0,
+        })),
+      });
+      setSettlement(null);
+      alert(
+        `Acerto concluído. Diferença em dinheiro: R$ ${(report.cash_difference ?? 0).toFixed(2)}`
+      );
+      await loadData();
+    } catch (error) {
+      alert("Erro ao fechar turno: " + error);
+    }
+  };
+
+  const hasOpenShift = (driverId: number) =>
+    openShifts.some((shift) => shift.driver_id === driverId);
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center h-full">
+        <div className="text-xl text-muted-foreground">Carregando...</div>
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-6">
+      <div className="flex items-center justify-between">
+        <div>
+          <h1 className="text-3xl font-bold tracking-tight">Entregadores</h1>
+          <p className="text-muted-foreground">
+            Saída de vasilhames no veículo e acerto no fim do turno
+          </p>
+        </div>
+        <Button onClick={handleCreateDriver}>
+          <Plus className="w-4 h-4 mr-2" />
+          Novo Entregador
+        </Button>
+      </div>
+
+      <Card>
+        <CardHeader>
+          <CardTitle>Turnos Abertos</CardTitle>
+          <CardDescription>Entregadores na rua com vasilhames no veículo</CardDescription>
+        </CardHeader>
+        <CardContent>
+          <Table>
+            <TableHeader>
+              <TableRow>
+                <TableHead>Turno</TableHead>
+                <TableHead>Entregador</TableHead>
+                <TableHead>Saída</TableHead>
+                <TableHead className="text-right">Troco</TableHead>
+                <TableHead className="text-right">Ações</TableHead>
+              </TableRow>
+            </TableHeader>
+            <TableBody>
+              {openShifts.length === 0 && (
+                <TableRow>
+                  <TableCell colSpan={5} className="text-center text-muted-foreground">
+                    Nenhum turno aberto
+                  </TableCell>
+                </TableRow>
+              )}
+              {openShifts.map((shift) => (
+                <TableRow key={shift.id}>
+                  <TableCell className="font-medium">#{shift.id}</TableCell>
+                  <TableCell>{shift.driver_name}</TableCell>
+                  <TableCell>{new Date(shift.opened_at).toLocaleString("pt-BR")}</TableCell>
+                  <TableCell className="text-right">R$ {shift.change_fund.toFixed(2)}</TableCell>
+                  <TableCell className="text-right">
+                    <Button
+                      variant="ghost"
+                      size="icon"
+                      onClick={() => handleOpenSettlement(shift)}
+                      title="Fazer acerto"
+                    >
+                      <ClipboardCheck className="w-4 h-4" />
+                    </Button>
+                  </TableCell>
+                </TableRow>
+              ))}
+            </TableBody>
+          </Table>
+        </CardContent>
+      </Card>
+
+      <Card>
+        <CardHeader>
+          <CardTitle>Cadastro</CardTitle>
+          <CardDescription>Entregadores inativos não recebem novas entregas</CardDescription>
+        </CardHeader>
+        <CardContent>
+          <Table>
+            <TableHeader>
+              <TableRow>
+                <TableHead>Nome</TableHead>
+                <TableHead>Telefone</TableHead>
+                <TableHead>Situação</TableHead>
+                <TableHead className="text-right">Ações</TableHead>
+              </TableRow>
+            </TableHeader>
+            <TableBody>
+              {drivers.map((driver) => (
+                <TableRow key={driver.id}>
+                  <TableCell className="font-medium">{driver.name}</TableCell>
+                  <TableCell>{driver.phone ?? "-"}</TableCell>
+                  <TableCell>
+                    <Badge variant={driver.active ? "default" : "secondary"}>
+                      {driver.active ? "Ativo" : "Inativo"}
+                    </Badge>
+                  </TableCell>
+                  <TableCell className="text-right">
+                    <div className="flex justify-end gap-2">
+                      {driver.active && !hasOpenShift(driver.id) && (
+                        <Button
+                          variant="ghost"
+                          size="icon"
+                          onClick={() => handleOpenShiftDialog(driver)}
+                          title="Abrir turno"
+                        >
+                          <Truck className="w-4 h-4" />
+                        </Button>
+                      )}
+                      <Button
+                        variant="ghost"
+                        size="icon"
+                        onClick={() => handleToggleActive(driver)}
+                        title={driver.active ? "Desativar" : "Reativar"}
+                      >
+                        {driver.active ? (
+                          <UserX className="w-4 h-4" />
+                        ) : (
+                          <UserCheck className="w-4 h-4" />
+                        )}
+                      </Button>
+                    </div>
+                  </TableCell>
+                </TableRow>
+              ))}
+            </TableBody>
+          </Table>
+        </CardContent>
+      </Card>
+
+      {/* Dialog de Abertura de Turno */}
+      <Dialog open={shiftDriver !== null} onOpenChange={(open) => !open && setShiftDriver(null)}>
+        <DialogContent className="sm:max-w-[500px]">
+          <DialogHeader>
+            <DialogTitle>Abrir Turno - {shiftDriver?.name}</DialogTitle>
+            <DialogDescription>
+              Informe o troco e os cheios carregados no veículo
+            </DialogDescription>
+          </DialogHeader>
+          <div className="grid gap-4 py-4 max-h-[60vh] overflow-y-auto">
+            <div className="grid gap-2">
+              <Label htmlFor="change_fund">Troco (R$)</Label>
+              <Input
+                id="change_fund"
+                type="number"
+                step="0.01"
+                min="0"
+                value={changeFund}
+                onChange={(e) => setChangeFund(e.target.value)}
+              />
+            </div>
+            {products.map((product) => (
+              <div key={product.id} className="grid grid-cols-3 items-center gap-2">
+                <Label htmlFor={`load-${product.id}`} className="col-span-2">
+                  {product.name}{" "}
+                  <span className="text-xs text-muted-foreground">
+                    ({product.stock_full} na loja)
+                  </span>
+                </Label>
+                <Input
+                  id={`load-${product.id}`}
+                  type="number"
+                  min="0"
+                  max={product.stock_full}
+                  value={loads[product.id] ?? ""}
+                  onChange={(e) => setLoads({ ...loads, [product.id]: e.target.value })}
+                />
+              </div>
+            ))}
+          </div>
+          <DialogFooter>
+            <Button variant="outline" onClick={() => setShiftDriver(null)}>
+              Cancelar
+            </Button>
+            <Button onClick={handleOpenShift}>Abrir Turno</Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
+
+      {/* Dialog de Acerto */}
+      <Dialog open={settlement !== null} onOpenChange={(open) => !open && setSettlement(null)}>
+        <DialogContent className="max-w-2xl max-h-[80vh]">
+          <DialogHeader>
+            <DialogTitle>
+              Acerto do Turno #{settlement?.shift.id} - {settlement?.shift.driver_name}
+            </DialogTitle>
+            <DialogDescription>
+              Confira os vasilhames que voltaram no veículo e o dinheiro recebido
+            </DialogDescription>
+          </DialogHeader>
+          {settlement && (
+            <div className="space-y-4 overflow-y-auto">
+              <Table>
+                <TableHeader>
+                  <TableRow>
+                    <TableHead>Produto</TableHead>
+                    <TableHead className="text-right">Saíram</TableHead>
+                    <TableHead className="text-right">Entregues</TableHead>
+                    <TableHead>Cheios de volta</TableHead>
+                    <TableHead>Cascos de volta</TableHead>
+                  </TableRow>
+                </TableHeader>
+                <TableBody>
+                  {settlement.lines.map((line) => (
+                    <TableRow key={line.product_id}>
+                      <TableCell>{line.product_name}</TableCell>
+                      <TableCell className="text-right">{line.full_loaded}</TableCell>
+                      <TableCell className="text-right">{line.full_delivered}</TableCell>
+                      <TableCell>
+                        <Input
+                          type="number"
+                          min="0"
+                          value={returns[line.product_id]?.full ?? ""}
+                          onChange={(e) =>
+                            setReturns({
+                              ...returns,
+                              [line.product_id]: { ...returns[line.product_id], full: e.target.value },
+                            })
+                          }
+                        />
+                        <p className="text-xs text-muted-foreground">
+                          Esperado: {line.full_expected}
+                        </p>
+                      </TableCell>
+                      <TableCell>
+                        <Input
+                          type="number"
+                          min="0"
+                          value={returns[line.product_id]?.empty ?? ""}
+                          onChange={(e) =>
+                            setReturns({
+                              ...returns,
+                              [line.product_id]: { ...returns[line.product_id], empty: e.target.value },
+                            })
+                          }
+                        />
+                        <p className="text-xs text-muted-foreground">
+                          Esperado: {line.empty_expected}
+                        </p>
+                      </TableCell>
+                    </TableRow>
+                  ))}
+                </TableBody>
+              </Table>
+
+              <div className="grid gap-2">
+                <Label htmlFor="cash_counted">Dinheiro entregue (R$)</Label>
+                <Input
+                  id="cash_counted"
+                  type="number"
+                  step="0.01"
+                  min="0"
+                  value={cashCounted}
+                  onChange={(e) => setCashCounted(e.target.value)}
+                />
+                <p className="text-xs text-muted-foreground">
+                  Troco R$ {settlement.shift.change_fund.toFixed(2)} + recebido nas entregas
+                  R$ {settlement.cash_collected.toFixed(2)} = esperado R${" "}
+                  {settlement.cash_expected.toFixed(2)}
+                </p>
+              </div>
+            </div>
+          )}
+          <DialogFooter>
+            <Button variant="outline" onClick={() => setSettlement(null)}>
+              Cancelar
+            </Button>
+            <Button onClick={handleCloseShift}>Fechar Turno</Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
+    </div>
+  );
+}
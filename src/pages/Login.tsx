@@ -2,7 +2,8 @@ import { useState, useEffect } from 'react';
 import { useNavigate } from 'react-router-dom';
 import { invoke } from '@tauri-apps/api/core';
 import { useAuthStore } from '@/state/authStore';
-import { SafeUser } from '@/types';
+import { ProfilesConfig, SafeUser } from '@/types';
+import { profilesApi } from '@/api/profiles';
 import { Button } from '@/components/ui/button';
 import {
   Card,
@@ -15,6 +16,13 @@ import { Input } from '@/components/ui/input';
 import { Label } from '@/components/ui/label';
 import { AlertCircle, Lock, User, Loader2, Store, Eye, EyeOff } from 'lucide-react';
 import { Alert, AlertDescription } from '@/components/ui/alert';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
 
 export default function LoginPage() {
   const [username, setUsername] = useState('');
@@ -22,6 +30,7 @@ export default function LoginPage() {
   const [error, setError] = useState<string | null>(null);
   const [isLoading, setIsLoading] = useState(false);
   const [showPassword, setShowPassword] = useState(false);
+  const [profiles, setProfiles] = useState<ProfilesConfig | null>(null);
   const navigate = useNavigate();
   const { setUser, setToken } = useAuthStore((state) => ({
     setUser: state.setUser,
@@ -34,6 +43,26 @@ export default function LoginPage() {
     usernameInput?.focus();
   }, []);
 
+  // Empresas cadastradas; cada uma tem o próprio banco e usuários
+  useEffect(() => {
+    profilesApi
+      .list()
+      .then(setProfiles)
+      .catch((err) => console.error('Erro ao carregar perfis:', err));
+  }, []);
+
+  // Trocar de empresa reinicia o app com o banco dela
+  const handleSwitchProfile = async (id: string) => {
+    if (id === profiles?.active) return;
+    setIsLoading(true);
+    try {
+      await profilesApi.switch(id);
+    } catch (err) {
+      setError('Erro ao trocar de empresa: ' + String(err));
+      setIsLoading(false);
+    }
+  };
+
   // Limpa erro quando o usuário começa a digitar
   useEffect(() => {
     if (error) {
@@ -121,6 +150,29 @@ export default function LoginPage() {
           </CardHeader>
           <CardContent>
             <form onSubmit={handleLogin} className="space-y-4">
+              {/* Empresa */}
+              {profiles && profiles.profiles.length > 1 && (
+                <div className="space-y-2">
+                  <Label className="text-sm font-medium">Empresa</Label>
+                  <Select
+                    value={profiles.active}
+                    onValueChange={handleSwitchProfile}
+                    disabled={isLoading}
+                  >
+                    <SelectTrigger className="h-11">
+                      <SelectValue />
+                    </SelectTrigger>
+                    <SelectContent>
+                      {profiles.profiles.map((profile) => (
+                        <SelectItem key={profile.id} value={profile.id}>
+                          {profile.name}
+                        </SelectItem>
+                      ))}
+                    </SelectContent>
+                  </Select>
+                </div>
+              )}
+
               {/* Campo Usuário */}
               <div className="space-y-2">
                 <Label htmlFor="username" className="text-sm font-medium">
@@ -1,9 +1,10 @@
 import { useEffect, useState } from "react";
 import { ordersApi } from "../api/orders";
 import { receiptsApi } from "../api/receipts";
+import { settingsApi } from "../api/settings";
 import { useAuthStore } from "@/state/authStore";
 import type { OrderWithCustomer, OrderWithItems } from "../types";
-import { FileText, Eye, Trash2 } from "lucide-react";
+import { FileText, Eye, Ban } from "lucide-react";
 import { Button } from "@/components/ui/button";
 import {
   Dialog,
@@ -60,6 +61,13 @@ export default function Orders() {
 
   const handleGenerateReceipt = async (orderId: number) => {
     try {
+      // Com impressora térmica configurada, o cupom sai direto nela
+      const printer = await settingsApi.getPrinter();
+      if (printer.connection !== "none") {
+        await receiptsApi.print(orderId);
+        return;
+      }
+
       const html = await receiptsApi.generate(orderId);
       // Abre em nova janela para impressão
       const newWindow = window.open();
@@ -72,16 +80,17 @@ export default function Orders() {
     }
   };
 
-  const handleDeleteOrder = async (orderId: number) => {
-    if (!confirm("Tem certeza que deseja excluir esta venda? Esta ação não pode ser desfeita e o estoque será revertido.")) {
+  const handleCancelOrder = async (orderId: number) => {
+    const reason = prompt("Informe o motivo do cancelamento. O estoque e o fiado serão estornados.");
+    if (!reason || !reason.trim()) {
       return;
     }
 
     try {
-      await ordersApi.delete(orderId);
+      await ordersApi.cancel(orderId, { reason });
       await loadOrders();
     } catch (error) {
-      alert("Erro ao excluir venda: " + error);
+      alert("Erro ao cancelar venda: " + error);
     }
   };
 
@@ -123,7 +132,18 @@ export default function Orders() {
             <TableBody>
               {orders.map((order) => (
                 <TableRow key={order.id}>
-                  <TableCell className="font-medium">#{order.id}</TableCell>
+                  <TableCell className="font-medium">
+                    #{order.id}
+                    {order.status === "cancelled" && (
+                      <Badge
+                        variant="destructive"
+                        className="ml-2"
+                        title={order.cancel_reason ?? undefined}
+                      >
+                        Cancelada
+                      </Badge>
+                    )}
+                  </TableCell>
                   <TableCell>
                     {order.customer_name || (
                       <span className="text-muted-foreground">Consumidor Final</span>
@@ -153,14 +173,14 @@ export default function Orders() {
                       >
                         <FileText className="w-4 h-4" />
                       </Button>
-                      {user?.role === 'admin' && (
+                      {user?.role === 'admin' && order.status !== "cancelled" && (
                         <Button
                           variant="ghost"
                           size="icon"
-                          onClick={() => handleDeleteOrder(order.id)}
-                          title="Excluir venda"
+                          onClick={() => handleCancelOrder(order.id)}
+                          title="Cancelar venda"
                         >
-                          <Trash2 className="w-4 h-4 text-destructive" />
+                          <Ban className="w-4 h-4 text-destructive" />
                         </Button>
                       )}
                     </div>
@@ -5,7 +5,8 @@ import { useCartStore } from "../state/cartStore";
 import { useAuthStore } from "@/state/authStore";
 import { ordersApi } from "../api/orders";
 import { receiptsApi } from "../api/receipts";
-import type { Customer, Product, OrderWithCustomer, OrderWithItems } from "../types";
+import { settingsApi } from "../api/settings";
+import type { Customer, Product, OrderWithCustomer, OrderWithItems, PaymentMethod } from "../types";
 import {
   CheckCircle2, Trash2, Copy,
   RotateCcw, AlertTriangle,
@@ -66,6 +67,7 @@ export default function POS() {
   const user = useAuthStore((state) => state.user);
 
   const [selectedCustomer, setSelectedCustomer] = useState<Customer | null>(null);
+  const [paymentMethod, setPaymentMethod] = useState<PaymentMethod>("cash");
   const [orderSuccess, setOrderSuccess] = useState(false);
   const [rows, setRows] = useState<ReceiptRow[]>([
     { id: 1, product: null, quantity: 1, unitPrice: 0, returnedBottle: false },
@@ -226,22 +228,47 @@ export default function POS() {
         };
       });
 
-      await ordersApi.create({
-        customer_id: selectedCustomer?.id || null,
-        items: orderItems,
-      });
+      const submitOrder = async (allowExpired: boolean) => {
+        await ordersApi.create({
+          customer_id: selectedCustomer?.id || null,
+          items: orderItems,
+          payments: [{ method: paymentMethod, amount: totalAmount, tendered: null }],
+          delivery: null,
+          location_id: null,
+          allow_expired: allowExpired,
+        });
 
-      setLastOrderTotal(totalAmount);
-      clearAll();
-      setOrderSuccess(true);
-      setTimeout(() => setOrderSuccess(false), 3000);
-      await fetchProducts();
-      await loadRecentOrders();
-      setShowNewSaleModal(false);
+        setLastOrderTotal(totalAmount);
+        clearAll();
+        setOrderSuccess(true);
+        setTimeout(() => setOrderSuccess(false), 3000);
+        await fetchProducts();
+        await loadRecentOrders();
+        setShowNewSaleModal(false);
+      };
+
+      try {
+        await submitOrder(false);
+      } catch (error) {
+        // Lote vencido: só o admin pode liberar a venda
+        if (String(error).includes("Expired stock") && user?.role === "admin") {
+          showConfirm(
+            "Produto Vencido",
+            `${error}. Deseja vender mesmo assim?`,
+            () => {
+              submitOrder(true).catch((err) =>
+                showAlert("Erro", "Erro ao finalizar pedido: " + err, "error")
+              );
+            }
+          );
+          return;
+        }
+        throw error;
+      }
     } catch (error) {
       showAlert("Erro", "Erro ao finalizar pedido: " + error, "error");
     }
-  }, [rows, selectedCustomer, getItemPrice, clearAll, fetchProducts, showAlert, totalAmount]);
+  }, [rows, selectedCustomer, paymentMethod, getItemPrice, clearAll, fetchProducts, loadRecentOrders, showAlert, showConfirm, totalAmount, user]);
 
   // Atalhos de teclado
   useEffect(() => {
@@ -441,6 +468,13 @@ export default function POS() {
 
   const handleGenerateReceipt = useCallback(async (orderId: number) => {
     try {
+      // Com impressora térmica configurada, o cupom sai direto nela
+      const printer = await settingsApi.getPrinter();
+      if (printer.connection !== "none") {
+        await receiptsApi.print(orderId);
+        return;
+      }
+
       const html = await receiptsApi.generate(orderId);
       // Abre em nova janela para impressão
       const newWindow = window.open();
@@ -735,6 +769,24 @@ export default function POS() {
                   <span className="font-bold">DATA:</span>
                   <span>{new Date().toLocaleDateString('pt-BR', { day: '2-digit', month: '2-digit', year: '2-digit' })}</span>
                 </div>
+                <div className="flex items-center gap-2">
+                  <span className="font-bold">PAGAMENTO:</span>
+                  <Select
+                    value={paymentMethod}
+                    onValueChange={(value) => setPaymentMethod(value as PaymentMethod)}
+                  >
+                    <SelectTrigger className="h-8 w-[250px]">
+                      <SelectValue />
+                    </SelectTrigger>
+                    <SelectContent>
+                      <SelectItem value="cash">Dinheiro</SelectItem>
+                      <SelectItem value="pix">Pix</SelectItem>
+                      <SelectItem value="debit">Débito</SelectItem>
+                      <SelectItem value="credit">Crédito</SelectItem>
+                      <SelectItem value="on_account">Fiado</SelectItem>
+                    </SelectContent>
+                  </Select>
+                </div>
               </div>
             </div>
 
@@ -4,6 +4,9 @@ import { useAuthStore } from "@/state/authStore";
 import { productsApi } from "../api/products";
 import { stockApi } from "../api/stock";
 import type { Product, CreateProductPayload, StockMovementWithProduct } from "../types";
+
+// 'YYYY-MM-DD' -> 'DD/MM/YYYY' sem passar por Date (evita deslocamento de fuso)
+const formatExpiry = (date: string) => date.split("-").reverse().join("/");
 import { Plus, Edit, Trash2, Package, History } from "lucide-react";
 import { Button } from "@/components/ui/button";
 import {
@@ -51,6 +54,7 @@ export default function Products() {
   const products = useProductsStore((state) => state.products);
   const loading = useProductsStore((state) => state.loading);
   const fetchProducts = useProductsStore((state) => state.fetchProducts);
+  const [nextExpiry, setNextExpiry] = useState<Record<number, string>>({});
   const user = useAuthStore((state) => state.user);
   
   const [activeTab, setActiveTab] = useState<TabType>("products");
@@ -64,8 +68,7 @@ export default function Products() {
     price_full: 0,
     stock_full: 0,
     stock_empty: 0,
-    expiry_month: null,
-    expiry_year: null,
+    expiry_date: null,
   });
 
   // Estados para movimentações
@@ -89,6 +92,20 @@ export default function Products() {
     }
   }, [fetchProducts, activeTab]);
 
+  // Próximo vencimento de cada produto, pelo lote que vence antes
+  useEffect(() => {
+    stockApi
+      .getLots()
+      .then((lots) => {
+        const expiry: Record<number, string> = {};
+        for (const lot of lots) {
+          expiry[lot.product_id] ??= lot.expiry_date;
+        }
+        setNextExpiry(expiry);
+      })
+      .catch((error) => console.error("Erro ao carregar lotes:", error));
+  }, [products]);
+
   // Resetar formulário quando abrir modal para novo produto
   useEffect(() => {
     if (showModal && !editingProduct) {
@@ -100,8 +117,7 @@ export default function Products() {
         price_full: 0,
         stock_full: 0,
         stock_empty: 0,
-        expiry_month: null,
-        expiry_year: null,
+        expiry_date: null,
       });
     }
   }, [showModal, editingProduct]);
@@ -138,8 +154,7 @@ export default function Products() {
         price_full: 0,
         stock_full: 0,
         stock_empty: 0,
-        expiry_month: null,
-        expiry_year: null,
+        expiry_date: null,
       });
     } catch (error) {
       alert("Erro ao salvar produto: " + error);
@@ -156,8 +171,8 @@ export default function Products() {
       price_full: product.price_full,
       stock_full: product.stock_full,
       stock_empty: product.stock_empty,
-      expiry_month: product.expiry_month,
-      expiry_year: product.expiry_year,
+      min_stock: product.min_stock,
+      reorder_qty: product.reorder_qty,
     });
     setShowModal(true);
   };
@@ -265,8 +280,7 @@ export default function Products() {
               price_full: 0,
               stock_full: 0,
               stock_empty: 0,
-              expiry_month: null,
-              expiry_year: null,
+              expiry_date: null,
             });
             setShowModal(true);
           }}
@@ -376,10 +390,8 @@ export default function Products() {
                   </TableCell>
                   <TableCell>{product.stock_empty}</TableCell>
                   <TableCell>
-                    {product.type === "water" && product.expiry_month && product.expiry_year ? (
-                      <span className="text-sm">
-                        {String(product.expiry_month).padStart(2, "0")}/{product.expiry_year}
-                      </span>
+                    {nextExpiry[product.id] ? (
+                      <span className="text-sm">{formatExpiry(nextExpiry[product.id])}</span>
                     ) : (
                       <span className="text-muted-foreground text-sm">-</span>
                     )}
@@ -527,8 +539,7 @@ export default function Products() {
               price_full: 0,
               stock_full: 0,
               stock_empty: 0,
-              expiry_month: null,
-              expiry_year: null,
+              expiry_date: null,
             });
           }
         }}
@@ -653,48 +664,56 @@ export default function Products() {
                 />
               </div>
             </div>
-            {formData.type === "water" && (
-              <div className="grid grid-cols-2 gap-4">
-                <div className="space-y-2">
-                  <Label htmlFor="expiry_month">Mês de Vencimento</Label>
-                  <Select
-                    value={formData.expiry_month?.toString() || undefined}
-                    onValueChange={(value) =>
-                      setFormData({
-                        ...formData,
-                        expiry_month: value ? parseInt(value) : null,
-                      })
-                    }
-                  >
-                    <SelectTrigger id="expiry_month">
-                      <SelectValue placeholder="Selecione o mês" />
-                    </SelectTrigger>
-                    <SelectContent>
-                      {Array.from({ length: 12 }, (_, i) => i + 1).map((month) => (
-                        <SelectItem key={month} value={month.toString()}>
-                          {String(month).padStart(2, "0")}
-                        </SelectItem>
-                      ))}
-                    </SelectContent>
-                  </Select>
-                </div>
-                <div className="space-y-2">
-                  <Label htmlFor="expiry_year">Ano de Vencimento</Label>
-                  <Input
-                    id="expiry_year"
-                    type="number"
-                    min={new Date().getFullYear()}
-                    max={new Date().getFullYear() + 10}
-                    value={formData.expiry_year || ""}
-                    onChange={(e) =>
-                      setFormData({
-                        ...formData,
-                        expiry_year: e.target.value ? parseInt(e.target.value) : null,
-                      })
-                    }
-                    placeholder="Ex: 2024"
-                  />
-                </div>
+            <div className="grid grid-cols-2 gap-4">
+              <div className="space-y-2">
+                <Label htmlFor="min_stock">Estoque Mínimo</Label>
+                <Input
+                  id="min_stock"
+                  type="number"
+                  min="0"
+                  placeholder="10"
+                  value={formData.min_stock ?? ""}
+                  onChange={(e) =>
+                    setFormData({
+                      ...formData,
+                      min_stock: e.target.value === "" ? null : parseInt(e.target.value) || 0,
+                    })
+                  }
+                />
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="reorder_qty">Lote de Reposição</Label>
+                <Input
+                  id="reorder_qty"
+                  type="number"
+                  min="0"
+                  value={formData.reorder_qty ?? ""}
+                  onChange={(e) =>
+                    setFormData({
+                      ...formData,
+                      reorder_qty: e.target.value === "" ? null : parseInt(e.target.value) || 0,
+                    })
+                  }
+                />
+              </div>
+            </div>
+            {!editingProduct && (
+              <div className="space-y-2">
+                <Label htmlFor="expiry_date">Validade do Estoque Inicial</Label>
+                <Input
+                  id="expiry_date"
+                  type="date"
+                  value={formData.expiry_date || ""}
+                  onChange={(e) =>
+                    setFormData({
+                      ...formData,
+                      expiry_date: e.target.value || null,
+                    })
+                  }
+                />
+                <p className="text-xs text-muted-foreground">
+                  Novos lotes recebem a validade na entrada de estoque
+                </p>
               </div>
             )}
             <DialogFooter>
@@ -0,0 +1,199 @@
+import { useEffect, useState } from "react";
+import { recoveryApi } from "../api/recovery";
+import type { BackupFile, RecoveryInfo, RecoveryKind } from "../types";
+import { AlertTriangle, Archive, Loader2, RotateCcw, ShieldCheck } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
+import { Alert, AlertDescription } from "@/components/ui/alert";
+
+const KIND_HINTS: Record<RecoveryKind, string> = {
+  checksum_mismatch:
+    "Um arquivo de atualização do banco foi alterado depois de aplicado. Se a alteração foi só " +
+    "de forma (comentários, espaçamento), aceite a versão atual; senão, restaure um backup.",
+  newer_database:
+    "O banco foi atualizado por uma versão mais nova do sistema. Instale a versão mais nova " +
+    "ou restaure um backup feito antes da atualização.",
+  dirty:
+    "Uma atualização do banco ficou pela metade. Restaure o backup feito antes da atualização.",
+  migration_failed:
+    "A atualização do banco falhou e nada foi alterado. Restaure um backup ou procure o suporte " +
+    "com a mensagem abaixo.",
+};
+
+// 'YYYY-MM-DD HH:MM:SS' -> 'DD/MM/YYYY HH:MM'
+const formatBackupDate = (value: string) => {
+  const [date, time] = value.split(" ");
+  return `${date.split("-").reverse().join("/")} ${time.slice(0, 5)}`;
+};
+
+export default function Recovery({ info }: { info: RecoveryInfo }) {
+  const [backups, setBackups] = useState<BackupFile[]>([]);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    recoveryApi
+      .listBackups()
+      .then(setBackups)
+      .catch((err) => setError(String(err)));
+  }, []);
+
+  const run = async (message: string, action: () => Promise<void>) => {
+    if (!confirm(message)) return;
+
+    setBusy(true);
+    setError(null);
+    try {
+      await action();
+    } catch (err) {
+      setError(String(err));
+      setBusy(false);
+    }
+  };
+
+  return (
+    <div className="min-h-screen flex items-center justify-center bg-gradient-to-br from-background via-background to-muted/20 p-4">
+      <div className="w-full max-w-3xl space-y-6">
+        <div className="text-center space-y-2">
+          <div className="flex items-center justify-center mb-4">
+            <div className="p-3 rounded-full bg-destructive/10">
+              <AlertTriangle className="w-8 h-8 text-destructive" />
+            </div>
+          </div>
+          <h1 className="text-3xl font-bold tracking-tight">Recuperação do Banco de Dados</h1>
+          <p className="text-muted-foreground">
+            O sistema não abriu o banco para não arriscar os dados. Escolha como continuar.
+          </p>
+        </div>
+
+        <Card>
+          <CardHeader>
+            <CardTitle>O que aconteceu</CardTitle>
+            <CardDescription>{KIND_HINTS[info.kind]}</CardDescription>
+          </CardHeader>
+          <CardContent className="space-y-3 text-sm">
+            <p className="font-mono bg-muted rounded p-2">{info.message}</p>
+            <p>
+              <span className="text-muted-foreground">Banco: </span>
+              {info.db_path}
+            </p>
+            {info.safety_backup ? (
+              <p className="flex items-center gap-2">
+                <ShieldCheck className="w-4 h-4 text-green-600" />
+                Cópia do banco atual guardada em {info.safety_backup.path}
+              </p>
+            ) : (
+              <Alert variant="destructive">
+                <AlertDescription>
+                  Não foi possível copiar o banco atual: {info.backup_error}. Faça uma cópia
+                  manual do arquivo antes de continuar.
+                </AlertDescription>
+              </Alert>
+            )}
+          </CardContent>
+        </Card>
+
+        {error && (
+          <Alert variant="destructive">
+            <AlertDescription>{error}</AlertDescription>
+          </Alert>
+        )}
+
+        {info.kind === "checksum_mismatch" && (
+          <Card>
+            <CardHeader>
+              <CardTitle>Aceitar a versão atual</CardTitle>
+              <CardDescription>
+                Mantém todos os dados e registra os arquivos de atualização como estão agora.
+              </CardDescription>
+            </CardHeader>
+            <CardContent>
+              <Button
+                disabled={busy}
+                onClick={() =>
+                  run(
+                    "Aceitar a versão atual das atualizações do banco? O sistema será reiniciado.",
+                    recoveryApi.acceptChecksums
+                  )
+                }
+              >
+                <ShieldCheck className="w-4 h-4 mr-2" />
+                Aceitar e Reiniciar
+              </Button>
+            </CardContent>
+          </Card>
+        )}
+
+        <Card>
+          <CardHeader>
+            <CardTitle>Restaurar um backup</CardTitle>
+            <CardDescription>
+              O backup escolhido é conferido antes e substitui o banco ao reiniciar. Backups em{" "}
+              {info.backup_dir}
+            </CardDescription>
+          </CardHeader>
+          <CardContent>
+            {backups.length === 0 ? (
+              <p className="text-sm text-muted-foreground">Nenhum backup encontrado</p>
+            ) : (
+              <div className="divide-y">
+                {backups.map((backup) => (
+                  <div key={backup.file_name} className="flex items-center justify-between py-2">
+                    <div className="text-sm">
+                      <p className="font-medium">{formatBackupDate(backup.created_at)}</p>
+                      <p className="text-muted-foreground">{backup.file_name}</p>
+                    </div>
+                    <Button
+                      variant="outline"
+                      size="sm"
+                      disabled={busy}
+                      onClick={() =>
+                        run(
+                          `Restaurar o backup de ${formatBackupDate(backup.created_at)}? ` +
+                            "O que foi registrado depois dele não estará no banco restaurado.",
+                          () => recoveryApi.restoreBackup(backup.file_name)
+                        )
+                      }
+                    >
+                      <RotateCcw className="w-4 h-4 mr-2" />
+                      Restaurar
+                    </Button>
+                  </div>
+                ))}
+              </div>
+            )}
+          </CardContent>
+        </Card>
+
+        <Card>
+          <CardHeader>
+            <CardTitle>Começar com banco novo</CardTitle>
+            <CardDescription>
+              O banco atual é renomeado e fica na mesma pasta; o sistema cria um banco vazio.
+            </CardDescription>
+          </CardHeader>
+          <CardContent>
+            <Button
+              variant="destructive"
+              disabled={busy}
+              onClick={() =>
+                run(
+                  "Começar com um banco vazio? O banco atual será renomeado, não apagado, " +
+                    "e o sistema será reiniciado.",
+                  recoveryApi.setAsideDatabase
+                )
+              }
+            >
+              {busy ? (
+                <Loader2 className="w-4 h-4 mr-2 animate-spin" />
+              ) : (
+                <Archive className="w-4 h-4 mr-2" />
+              )}
+              Guardar Banco Atual e Recomeçar
+            </Button>
+          </CardContent>
+        </Card>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,358 @@
+import { useCallback, useEffect, useState } from "react";
+import { save } from "@tauri-apps/plugin-dialog";
+import { reportsApi } from "../api/reports";
+import { exportsApi } from "../api/exports";
+import { settingsApi } from "../api/settings";
+import { formatMoney } from "@/lib/utils";
+import { useAuthStore } from "@/state/authStore";
+import type {
+  CompanySettings,
+  ExportFormat,
+  ReportGrouping,
+  SalesReport,
+  SalesReportLine,
+} from "../types";
+import { BarChart3, Download } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import {
+  Table,
+  TableBody,
+  TableCell,
+  TableHead,
+  TableHeader,
+  TableRow,
+} from "@/components/ui/table";
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
+
+const GROUPINGS: { value: ReportGrouping; label: string }[] = [
+  { value: "day", label: "Dia" },
+  { value: "week", label: "Semana" },
+  { value: "month", label: "Mês" },
+  { value: "product", label: "Produto" },
+  { value: "product_type", label: "Tipo de produto" },
+  { value: "customer", label: "Cliente" },
+  { value: "operator", label: "Operador" },
+  { value: "payment_method", label: "Forma de pagamento" },
+];
+
+type ExportDataset = "orders" | "movements" | "customers";
+
+const EXPORT_DATASETS: { value: ExportDataset; label: string; fileName: string }[] = [
+  { value: "orders", label: "Pedidos com itens", fileName: "pedidos" },
+  { value: "movements", label: "Movimentações de estoque", fileName: "movimentacoes" },
+  { value: "customers", label: "Clientes", fileName: "clientes" },
+];
+
+const TYPE_LABELS: Record<string, string> = {
+  water: "Água",
+  gas: "Gás",
+  coal: "Carvão",
+  other: "Outro",
+};
+
+const PAYMENT_LABELS: Record<string, string> = {
+  cash: "Dinheiro",
+  pix: "Pix",
+  debit: "Cartão de Débito",
+  credit: "Cartão de Crédito",
+  on_account: "Fiado",
+};
+
+// 'YYYY-MM-DD' -> 'DD/MM/YYYY' sem passar por Date (evita deslocamento de fuso)
+const formatDate = (date: string) => date.split("-").reverse().join("/");
+
+// Data local no formato 'YYYY-MM-DD'
+const toInputDate = (date: Date) => {
+  const month = String(date.getMonth() + 1).padStart(2, "0");
+  const day = String(date.getDate()).padStart(2, "0");
+  return `${date.getFullYear()}-${month}-${day}`;
+};
+
+const lineLabel = (groupBy: ReportGrouping, line: SalesReportLine) => {
+  switch (groupBy) {
+    case "day":
+      return formatDate(line.key!);
+    case "week":
+      return `Semana de ${formatDate(line.key!)}`;
+    case "month":
+      return line.key!.split("-").reverse().join("/");
+    case "product_type":
+      return TYPE_LABELS[line.key!] || line.key!;
+    case "payment_method":
+      return PAYMENT_LABELS[line.key!] || line.key!;
+    case "customer":
+      return line.label ?? "Sem cliente";
+    case "operator":
+      return line.label ?? "Sem operador";
+    default:
+      return line.label ?? line.key ?? "-";
+  }
+};
+
+export default function Reports() {
+  const today = new Date();
+  const [startDate, setStartDate] = useState(
+    toInputDate(new Date(today.getFullYear(), today.getMonth(), 1))
+  );
+  const [endDate, setEndDate] = useState(toInputDate(today));
+  const [groupBy, setGroupBy] = useState<ReportGrouping>("day");
+  const [report, setReport] = useState<SalesReport | null>(null);
+  const [loading, setLoading] = useState(false);
+  const user = useAuthStore((state) => state.user);
+  const [dataset, setDataset] = useState<ExportDataset>("orders");
+  const [exportFormat, setExportFormat] = useState<ExportFormat>("xlsx");
+  const [exporting, setExporting] = useState(false);
+  const [company, setCompany] = useState<CompanySettings | null>(null);
+
+  const loadReport = useCallback(async () => {
+    setLoading(true);
+    try {
+      setReport(await reportsApi.getSalesReport(startDate, endDate, groupBy));
+    } catch (error) {
+      alert("Erro ao gerar relatório: " + error);
+    } finally {
+      setLoading(false);
+    }
+  }, [startDate, endDate, groupBy]);
+
+  const handleExport = async () => {
+    const target = EXPORT_DATASETS.find((d) => d.value === dataset)!;
+    const path = await save({
+      defaultPath: `${target.fileName}_${startDate}_${endDate}.${exportFormat}`,
+      filters: [{ name: exportFormat.toUpperCase(), extensions: [exportFormat] }],
+    });
+    if (!path) {
+      return;
+    }
+
+    setExporting(true);
+    try {
+      const start = startDate || null;
+      const end = endDate || null;
+      const rows =
+        dataset === "orders"
+          ? await exportsApi.exportOrders(path, exportFormat, start, end)
+          : dataset === "movements"
+            ? await exportsApi.exportStockMovements(path, exportFormat, start, end)
+            : await exportsApi.exportCustomers(path, exportFormat, start, end);
+      alert(`Arquivo gerado com ${rows} linha(s):\n${path}`);
+    } catch (error) {
+      alert("Erro ao exportar: " + error);
+    } finally {
+      setExporting(false);
+    }
+  };
+
+  useEffect(() => {
+    settingsApi
+      .getCompany()
+      .then(setCompany)
+      .catch((error) => console.error("Erro ao carregar configurações:", error));
+  }, []);
+
+  useEffect(() => {
+    loadReport();
+    // Carrega só na abertura; depois, pelo botão
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, []);
+
+  return (
+    <div className="space-y-6">
+      <div>
+        <h1 className="text-3xl font-bold tracking-tight">Relatórios</h1>
+        <p className="text-muted-foreground">
+          Vendas por período{company && ` — ${company.company_name}`}
+        </p>
+      </div>
+
+      <Card>
+        <CardContent className="pt-6">
+          <div className="flex flex-wrap items-end gap-4">
+            <div className="space-y-2">
+              <Label htmlFor="start-date">De</Label>
+              <Input
+                id="start-date"
+                type="date"
+                value={startDate}
+                onChange={(e) => setStartDate(e.target.value)}
+              />
+            </div>
+            <div className="space-y-2">
+              <Label htmlFor="end-date">Até</Label>
+              <Input
+                id="end-date"
+                type="date"
+                value={endDate}
+                onChange={(e) => setEndDate(e.target.value)}
+              />
+            </div>
+            <div className="space-y-2">
+              <Label>Agrupar por</Label>
+              <Select
+                value={groupBy}
+                onValueChange={(value) => setGroupBy(value as ReportGrouping)}
+              >
+                <SelectTrigger className="w-[200px]">
+                  <SelectValue />
+                </SelectTrigger>
+                <SelectContent>
+                  {GROUPINGS.map((grouping) => (
+                    <SelectItem key={grouping.value} value={grouping.value}>
+                      {grouping.label}
+                    </SelectItem>
+                  ))}
+                </SelectContent>
+              </Select>
+            </div>
+            <Button onClick={loadReport} disabled={loading || !startDate || !endDate}>
+              <BarChart3 className="w-4 h-4 mr-2" />
+              {loading ? "Gerando..." : "Gerar"}
+            </Button>
+          </div>
+        </CardContent>
+      </Card>
+
+      {user?.role === "admin" && (
+        <Card>
+          <CardHeader>
+            <CardTitle>Exportar para Contabilidade</CardTitle>
+            <CardDescription>
+              Gera planilha com os dados do período selecionado acima
+            </CardDescription>
+          </CardHeader>
+          <CardContent>
+            <div className="flex flex-wrap items-end gap-4">
+              <div className="space-y-2">
+                <Label>Dados</Label>
+                <Select
+                  value={dataset}
+                  onValueChange={(value) => setDataset(value as ExportDataset)}
+                >
+                  <SelectTrigger className="w-[240px]">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    {EXPORT_DATASETS.map((d) => (
+                      <SelectItem key={d.value} value={d.value}>
+                        {d.label}
+                      </SelectItem>
+                    ))}
+                  </SelectContent>
+                </Select>
+              </div>
+              <div className="space-y-2">
+                <Label>Formato</Label>
+                <Select
+                  value={exportFormat}
+                  onValueChange={(value) => setExportFormat(value as ExportFormat)}
+                >
+                  <SelectTrigger className="w-[140px]">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectItem value="xlsx">Excel (XLSX)</SelectItem>
+                    <SelectItem value="csv">CSV</SelectItem>
+                  </SelectContent>
+                </Select>
+              </div>
+              <Button variant="outline" onClick={handleExport} disabled={exporting}>
+                <Download className="w-4 h-4 mr-2" />
+                {exporting ? "Exportando..." : "Exportar"}
+              </Button>
+            </div>
+          </CardContent>
+        </Card>
+      )}
+
+      {report && (
+        <>
+          <div className="grid gap-4 md:grid-cols-3">
+            <Card>
+              <CardHeader className="pb-2">
+                <CardTitle className="text-sm font-medium">Total Vendido</CardTitle>
+              </CardHeader>
+              <CardContent>
+                <div className="text-2xl font-bold">{formatMoney(report.total, company)}</div>
+              </CardContent>
+            </Card>
+            <Card>
+              <CardHeader className="pb-2">
+                <CardTitle className="text-sm font-medium">Pedidos</CardTitle>
+              </CardHeader>
+              <CardContent>
+                <div className="text-2xl font-bold">{report.order_count}</div>
+              </CardContent>
+            </Card>
+            <Card>
+              <CardHeader className="pb-2">
+                <CardTitle className="text-sm font-medium">Itens</CardTitle>
+              </CardHeader>
+              <CardContent>
+                <div className="text-2xl font-bold">{report.quantity}</div>
+              </CardContent>
+            </Card>
+          </div>
+
+          <Card>
+            <CardHeader>
+              <CardTitle>
+                Por {GROUPINGS.find((g) => g.value === report.group_by)?.label.toLowerCase()}
+              </CardTitle>
+              <CardDescription>
+                {formatDate(report.start_date)} a {formatDate(report.end_date)}, sem pedidos
+                cancelados
+                {report.group_by === "payment_method" &&
+                  ". Pedido com pagamento dividido conta em cada forma"}
+                {(report.group_by === "product" || report.group_by === "product_type") &&
+                  ". Valores dos itens, sem taxa de entrega"}
+              </CardDescription>
+            </CardHeader>
+            <CardContent>
+              {report.lines.length === 0 ? (
+                <p className="text-sm text-muted-foreground text-center py-8">
+                  Nenhuma venda no período
+                </p>
+              ) : (
+                <Table>
+                  <TableHeader>
+                    <TableRow>
+                      <TableHead>
+                        {GROUPINGS.find((g) => g.value === report.group_by)?.label}
+                      </TableHead>
+                      <TableHead className="text-right">Pedidos</TableHead>
+                      <TableHead className="text-right">Quantidade</TableHead>
+                      <TableHead className="text-right">Total</TableHead>
+                    </TableRow>
+                  </TableHeader>
+                  <TableBody>
+                    {report.lines.map((line) => (
+                      <TableRow key={line.key ?? "none"}>
+                        <TableCell className="font-medium">
+                          {lineLabel(report.group_by, line)}
+                        </TableCell>
+                        <TableCell className="text-right">{line.order_count}</TableCell>
+                        <TableCell className="text-right">{line.quantity}</TableCell>
+                        <TableCell className="text-right font-semibold">
+                          {formatMoney(line.total, company)}
+                        </TableCell>
+                      </TableRow>
+                    ))}
+                  </TableBody>
+                </Table>
+              )}
+            </CardContent>
+          </Card>
+        </>
+      )}
+    </div>
+  );
+}
@@ -1,18 +1,280 @@
-import { useState } from "react";
-import { Save } from "lucide-react";
+import { useCallback, useEffect, useState } from "react";
+import { open } from "@tauri-apps/plugin-dialog";
+import {
+  Building2,
+  DatabaseBackup,
+  FolderOpen,
+  Pencil,
+  Plus,
+  Printer,
+  RotateCcw,
+  Save,
+  ShieldCheck,
+  Upload,
+} from "lucide-react";
+import { importsApi } from "../api/imports";
+import { backupsApi } from "../api/backups";
+import { profilesApi } from "../api/profiles";
+import { settingsApi } from "../api/settings";
+import { receiptsApi } from "../api/receipts";
+import type {
+  BackupFile,
+  BackupSettings,
+  CompanySettings,
+  ImportReport,
+  PrinterConnection,
+  PrinterSettings,
+  Profile,
+  ProfilesConfig,
+} from "../types";
+
+type ImportTarget = "products" | "customers";
+
+const IMPORT_COLUMNS: Record<ImportTarget, string> = {
+  products:
+    "name, type, price_refill, price_full, description, stock_full, stock_empty, cost_price, min_stock, reorder_qty, expiry_date",
+  customers: "name, phone, address, notes, credit_limit",
+};
+
+// Cópias automáticas de segurança
+const BACKUP_TAG_LABELS: Record<string, string> = {
+  "pre-restore": "antes de restaurar",
+  "pre-migration": "antes de atualizar",
+  "pre-recovery": "antes de recuperar",
+};
+
+const formatSize = (bytes: number) =>
+  bytes >= 1024 * 1024
+    ? `${(bytes / (1024 * 1024)).toFixed(1)} MB`
+    : `${Math.ceil(bytes / 1024)} KB`;
+
+// 'YYYY-MM-DD HH:MM:SS' -> 'DD/MM/YYYY HH:MM'
+const formatBackupDate = (value: string) => {
+  const [date, time] = value.split(" ");
+  return `${date.split("-").reverse().join("/")} ${time.slice(0, 5)}`;
+};
 
 export default function Settings() {
-  const [settings, setSettings] = useState({
-    companyName: "Distribuidora",
-    address: "",
-    phone: "",
-    email: "",
-  });
-
-  const handleSave = () => {
-    // Aqui você pode salvar as configurações (localStorage ou banco)
-    localStorage.setItem("settings", JSON.stringify(settings));
-    alert("Configurações salvas com sucesso!");
+  const [company, setCompany] = useState<CompanySettings | null>(null);
+  const [printer, setPrinter] = useState<PrinterSettings | null>(null);
+  const [printing, setPrinting] = useState(false);
+
+  const [importTarget, setImportTarget] = useState<ImportTarget>("products");
+  const [importPath, setImportPath] = useState<string | null>(null);
+  const [importReport, setImportReport] = useState<ImportReport | null>(null);
+  const [importing, setImporting] = useState(false);
+
+  const [profiles, setProfiles] = useState<ProfilesConfig | null>(null);
+  const [newProfile, setNewProfile] = useState({ name: "", dir: "" });
+
+  const [backupSettings, setBackupSettings] = useState<BackupSettings | null>(null);
+  const [backups, setBackups] = useState<BackupFile[]>([]);
+  const [backupBusy, setBackupBusy] = useState(false);
+
+  const loadBackups = useCallback(async () => {
+    try {
+      const [settings, files] = await Promise.all([backupsApi.getSettings(), backupsApi.list()]);
+      setBackupSettings(settings);
+      setBackups(files);
+    } catch (error) {
+      alert("Erro ao carregar backups: " + error);
+    }
+  }, []);
+
+  useEffect(() => {
+    loadBackups();
+  }, [loadBackups]);
+
+  const loadProfiles = useCallback(async () => {
+    try {
+      setProfiles(await profilesApi.list());
+    } catch (error) {
+      alert("Erro ao carregar perfis: " + error);
+    }
+  }, []);
+
+  useEffect(() => {
+    loadProfiles();
+  }, [loadProfiles]);
+
+  const handleChooseProfileDir = async () => {
+    const dir = await open({ directory: true, multiple: false });
+    if (typeof dir === "string") {
+      setNewProfile({ ...newProfile, dir });
+    }
+  };
+
+  const handleCreateProfile = async () => {
+    try {
+      const profile = await profilesApi.create({
+        name: newProfile.name,
+        dir: newProfile.dir || undefined,
+      });
+      setNewProfile({ name: "", dir: "" });
+      await loadProfiles();
+      alert(`Perfil "${profile.name}" criado. O banco dele é criado ao abrir o perfil.`);
+    } catch (error) {
+      alert("Erro ao criar perfil: " + error);
+    }
+  };
+
+  const handleRenameProfile = async (profile: Profile) => {
+    const name = prompt("Novo nome do perfil:", profile.name);
+    if (!name || name === profile.name) return;
+    try {
+      await profilesApi.rename(profile.id, name);
+      await loadProfiles();
+    } catch (error) {
+      alert("Erro ao renomear perfil: " + error);
+    }
+  };
+
+  const handleSwitchProfile = async (profile: Profile) => {
+    if (!confirm(`Abrir o perfil "${profile.name}"? O sistema será reiniciado.`)) return;
+    try {
+      await profilesApi.switch(profile.id);
+    } catch (error) {
+      alert("Erro ao trocar de perfil: " + error);
+    }
+  };
+
+  const handleChooseBackupDir = async () => {
+    const dir = await open({ directory: true, multiple: false });
+    if (typeof dir === "string" && backupSettings) {
+      setBackupSettings({ ...backupSettings, dir });
+    }
+  };
+
+  const handleSaveBackupSettings = async () => {
+    if (!backupSettings) return;
+    try {
+      setBackupSettings(await backupsApi.updateSettings(backupSettings));
+      setBackups(await backupsApi.list());
+      alert("Configurações de backup salvas!");
+    } catch (error) {
+      alert("Erro ao salvar configurações de backup: " + error);
+    }
+  };
+
+  const handleCreateBackup = async () => {
+    setBackupBusy(true);
+    try {
+      const backup = await backupsApi.create();
+      setBackups(await backupsApi.list());
+      alert(`Backup criado: ${backup.file_name}`);
+    } catch (error) {
+      alert("Erro ao criar backup: " + error);
+    } finally {
+      setBackupBusy(false);
+    }
+  };
+
+  const handleVerifyBackup = async (backup: BackupFile) => {
+    setBackupBusy(true);
+    try {
+      const check = await backupsApi.verify(backup.file_name);
+      alert(
+        check.ok
+          ? `Backup íntegro (versão do banco: ${check.schema_version}).`
+          : `Backup com problema: ${check.message}`
+      );
+    } catch (error) {
+      alert("Erro ao verificar backup: " + error);
+    } finally {
+      setBackupBusy(false);
+    }
+  };
+
+  const handleRestoreBackup = async (backup: BackupFile) => {
+    const confirmed = confirm(
+      `Restaurar o backup de ${formatBackupDate(backup.created_at)}?\n\n` +
+        "Tudo o que foi registrado depois dele será substituído. Uma cópia do banco atual " +
+        "é guardada antes, e o sistema será reiniciado."
+    );
+    if (!confirmed) return;
+
+    setBackupBusy(true);
+    try {
+      await backupsApi.restore(backup.file_name);
+    } catch (error) {
+      alert("Erro ao restaurar backup: " + error);
+      setBackupBusy(false);
+    }
+  };
+
+  const handleChooseFile = async () => {
+    const path = await open({
+      multiple: false,
+      filters: [{ name: "CSV", extensions: ["csv"] }],
+    });
+    if (typeof path === "string") {
+      setImportPath(path);
+      setImportReport(null);
+    }
+  };
+
+  const runImport = async (dryRun: boolean) => {
+    if (!importPath) return;
+
+    setImporting(true);
+    try {
+      const report =
+        importTarget === "products"
+          ? await importsApi.importProducts(importPath, dryRun)
+          : await importsApi.importCustomers(importPath, dryRun);
+      setImportReport(report);
+      if (report.applied) {
+        alert(
+          `Importação concluída: ${report.created} cadastrado(s), ${report.updated} atualizado(s).`
+        );
+      }
+    } catch (error) {
+      alert("Erro ao importar: " + error);
+    } finally {
+      setImporting(false);
+    }
+  };
+
+  useEffect(() => {
+    settingsApi
+      .getCompany()
+      .then(setCompany)
+      .catch((error) => alert("Erro ao carregar configurações: " + error));
+    settingsApi
+      .getPrinter()
+      .then(setPrinter)
+      .catch((error) => alert("Erro ao carregar impressora: " + error));
+  }, []);
+
+  const handleSave = async () => {
+    if (!company) return;
+    try {
+      setCompany(await settingsApi.updateCompany(company));
+      alert("Configurações salvas com sucesso!");
+    } catch (error) {
+      alert("Erro ao salvar configurações: " + error);
+    }
+  };
+
+  const handleSavePrinter = async () => {
+    if (!printer) return;
+    try {
+      setPrinter(await settingsApi.updatePrinter(printer));
+      alert("Impressora salva com sucesso!");
+    } catch (error) {
+      alert("Erro ao salvar impressora: " + error);
+    }
+  };
+
+  const handleTestPrint = async () => {
+    setPrinting(true);
+    try {
+      await receiptsApi.printTestPage();
+    } catch (error) {
+      alert("Erro ao imprimir: " + error);
+    } finally {
+      setPrinting(false);
+    }
   };
 
   return (
@@ -20,62 +282,565 @@ export default function Settings() {
       <h1 className="text-3xl font-bold text-gray-800">Configurações</h1>
 
       <div className="bg-white rounded-lg shadow-md p-6">
-        <h2 className="text-xl font-semibold mb-4">Dados da Empresa</h2>
-        <div className="space-y-4">
-          <div>
-            <label className="block text-sm font-medium mb-1">
-              Nome da Empresa
-            </label>
-            <input
-              type="text"
-              value={settings.companyName}
-              onChange={(e) =>
-                setSettings({ ...settings, companyName: e.target.value })
-              }
-              className="w-full border rounded-lg px-3 py-2"
-            />
+        <h2 className="text-xl font-semibold mb-1">Dados da Empresa</h2>
+        <p className="text-sm text-gray-500 mb-4">Impressos no cabeçalho e no rodapé do recibo</p>
+        {company && (
+          <div className="space-y-4">
+            <div className="grid gap-4 md:grid-cols-2">
+              <div>
+                <label className="block text-sm font-medium mb-1">Nome da Empresa</label>
+                <input
+                  type="text"
+                  value={company.company_name}
+                  onChange={(e) => setCompany({ ...company, company_name: e.target.value })}
+                  className="w-full border rounded-lg px-3 py-2"
+                />
+              </div>
+              <div>
+                <label className="block text-sm font-medium mb-1">CNPJ</label>
+                <input
+                  type="text"
+                  placeholder="00.000.000/0000-00"
+                  value={company.cnpj ?? ""}
+                  onChange={(e) => setCompany({ ...company, cnpj: e.target.value })}
+                  className="w-full border rounded-lg px-3 py-2"
+                />
+              </div>
+            </div>
+            <div>
+              <label className="block text-sm font-medium mb-1">Endereço</label>
+              <textarea
+                value={company.address ?? ""}
+                onChange={(e) => setCompany({ ...company, address: e.target.value })}
+                className="w-full border rounded-lg px-3 py-2"
+              />
+            </div>
+            <div className="grid gap-4 md:grid-cols-2">
+              <div>
+                <label className="block text-sm font-medium mb-1">Telefone</label>
+                <input
+                  type="text"
+                  value={company.phone ?? ""}
+                  onChange={(e) => setCompany({ ...company, phone: e.target.value })}
+                  className="w-full border rounded-lg px-3 py-2"
+                />
+              </div>
+              <div>
+                <label className="block text-sm font-medium mb-1">E-mail</label>
+                <input
+                  type="email"
+                  value={company.email ?? ""}
+                  onChange={(e) => setCompany({ ...company, email: e.target.value })}
+                  className="w-full border rounded-lg px-3 py-2"
+                />
+              </div>
+            </div>
+            <div>
+              <label className="block text-sm font-medium mb-1">Rodapé do recibo</label>
+              <input
+                type="text"
+                value={company.receipt_footer}
+                onChange={(e) => setCompany({ ...company, receipt_footer: e.target.value })}
+                className="w-full border rounded-lg px-3 py-2"
+              />
+            </div>
+            <div className="grid gap-4 md:grid-cols-4">
+              <div>
+                <label className="block text-sm font-medium mb-1">Alerta de validade (dias)</label>
+                <input
+                  type="number"
+                  min={0}
+                  max={365}
+                  value={company.expiry_warning_days}
+                  onChange={(e) =>
+                    setCompany({ ...company, expiry_warning_days: Number(e.target.value) })
+                  }
+                  className="w-full border rounded-lg px-3 py-2"
+                />
+              </div>
+              <div>
+                <label className="block text-sm font-medium mb-1">Estoque mínimo padrão</label>
+                <input
+                  type="number"
+                  min={0}
+                  value={company.default_min_stock}
+                  onChange={(e) =>
+                    setCompany({ ...company, default_min_stock: Number(e.target.value) })
+                  }
+                  className="w-full border rounded-lg px-3 py-2"
+                />
+              </div>
+              <div>
+                <label className="block text-sm font-medium mb-1">Símbolo da moeda</label>
+                <input
+                  type="text"
+                  maxLength={5}
+                  value={company.currency_symbol}
+                  onChange={(e) => setCompany({ ...company, currency_symbol: e.target.value })}
+                  className="w-full border rounded-lg px-3 py-2"
+                />
+              </div>
+              <div>
+                <label className="block text-sm font-medium mb-1">Separador decimal</label>
+                <select
+                  value={company.decimal_separator}
+                  onChange={(e) =>
+                    setCompany({
+                      ...company,
+                      decimal_separator: e.target.value as CompanySettings["decimal_separator"],
+                    })
+                  }
+                  className="w-full border rounded-lg px-3 py-2"
+                >
+                  <option value=",">Vírgula (1.234,50)</option>
+                  <option value=".">Ponto (1,234.50)</option>
+                </select>
+              </div>
+            </div>
+            <div className="flex justify-end">
+              <button
+                onClick={handleSave}
+                className="bg-blue-600 text-white px-4 py-2 rounded-lg flex items-center gap-2 hover:bg-blue-700"
+              >
+                <Save className="w-5 h-5" />
+                Salvar Configurações
+              </button>
+            </div>
           </div>
-          <div>
-            <label className="block text-sm font-medium mb-1">Endereço</label>
-            <textarea
-              value={settings.address}
-              onChange={(e) =>
-                setSettings({ ...settings, address: e.target.value })
-              }
-              className="w-full border rounded-lg px-3 py-2"
-            />
+        )}
+      </div>
+
+      <div className="bg-white rounded-lg shadow-md p-6">
+        <h2 className="text-xl font-semibold mb-1">Impressora Térmica</h2>
+        <p className="text-sm text-gray-500 mb-4">
+          Cupom ESC/POS para impressoras de 58 ou 80 mm. Sem impressora, o recibo abre em uma
+          janela para impressão comum.
+        </p>
+        {printer && (
+          <div className="space-y-4">
+            <div className="grid gap-4 md:grid-cols-3">
+              <div>
+                <label className="block text-sm font-medium mb-1">Conexão</label>
+                <select
+                  value={printer.connection}
+                  onChange={(e) =>
+                    setPrinter({ ...printer, connection: e.target.value as PrinterConnection })
+                  }
+                  className="w-full border rounded-lg px-3 py-2"
+                >
+                  <option value="none">Nenhuma</option>
+                  <option value="device">Dispositivo (USB/serial/compartilhada)</option>
+                  <option value="network">Rede (porta RAW)</option>
+                </select>
+              </div>
+              <div>
+                <label className="block text-sm font-medium mb-1">Largura do papel</label>
+                <select
+                  value={printer.paper_width}
+                  onChange={(e) =>
+                    setPrinter({
+                      ...printer,
+                      paper_width: Number(e.target.value) as PrinterSettings["paper_width"],
+                    })
+                  }
+                  className="w-full border rounded-lg px-3 py-2"
+                >
+                  <option value={58}>58 mm (32 colunas)</option>
+                  <option value={80}>80 mm (48 colunas)</option>
+                </select>
+              </div>
+              <label className="flex items-center gap-2 text-sm font-medium md:mt-7">
+                <input
+                  type="checkbox"
+                  checked={printer.cut_paper}
+                  onChange={(e) => setPrinter({ ...printer, cut_paper: e.target.checked })}
+                />
+                Cortar o papel no fim
+              </label>
+            </div>
+            {printer.connection === "device" && (
+              <div>
+                <label className="block text-sm font-medium mb-1">Caminho do dispositivo</label>
+                <input
+                  type="text"
+                  placeholder="/dev/usb/lp0 ou \\localhost\Termica"
+                  value={printer.device_path ?? ""}
+                  onChange={(e) => setPrinter({ ...printer, device_path: e.target.value })}
+                  className="w-full border rounded-lg px-3 py-2"
+                />
+              </div>
+            )}
+            {printer.connection === "network" && (
+              <div className="grid gap-4 md:grid-cols-3">
+                <div className="md:col-span-2">
+                  <label className="block text-sm font-medium mb-1">Endereço (IP ou nome)</label>
+                  <input
+                    type="text"
+                    placeholder="192.168.0.50"
+                    value={printer.host ?? ""}
+                    onChange={(e) => setPrinter({ ...printer, host: e.target.value })}
+                    className="w-full border rounded-lg px-3 py-2"
+                  />
+                </div>
+                <div>
+                  <label className="block text-sm font-medium mb-1">Porta</label>
+                  <input
+                    type="number"
+                    min={1}
+                    max={65535}
+                    value={printer.port}
+                    onChange={(e) => setPrinter({ ...printer, port: Number(e.target.value) })}
+                    className="w-full border rounded-lg px-3 py-2"
+                  />
+                </div>
+              </div>
+            )}
+            <div>
+              <label className="block text-sm font-medium mb-1">QR code no recibo (opcional)</label>
+              <input
+                type="text"
+                placeholder="Chave Pix, site ou WhatsApp"
+                value={printer.qr_code ?? ""}
+                onChange={(e) => setPrinter({ ...printer, qr_code: e.target.value })}
+                className="w-full border rounded-lg px-3 py-2"
+              />
+            </div>
+            <div className="flex justify-end gap-2">
+              <button
+                onClick={handleTestPrint}
+                disabled={printing || printer.connection === "none"}
+                className="border px-4 py-2 rounded-lg flex items-center gap-2 hover:bg-gray-50 disabled:opacity-50"
+              >
+                <Printer className="w-5 h-5" />
+                Imprimir Teste
+              </button>
+              <button
+                onClick={handleSavePrinter}
+                className="bg-blue-600 text-white px-4 py-2 rounded-lg flex items-center gap-2 hover:bg-blue-700"
+              >
+                <Save className="w-5 h-5" />
+                Salvar Impressora
+              </button>
+            </div>
           </div>
-          <div>
-            <label className="block text-sm font-medium mb-1">Telefone</label>
-            <input
-              type="text"
-              value={settings.phone}
-              onChange={(e) =>
-                setSettings({ ...settings, phone: e.target.value })
-              }
-              className="w-full border rounded-lg px-3 py-2"
-            />
+        )}
+      </div>
+
+      <div className="bg-white rounded-lg shadow-md p-6">
+        <h2 className="text-xl font-semibold mb-1">Empresas (Perfis)</h2>
+        <p className="text-sm text-gray-500 mb-4">
+          Cada perfil tem banco de dados, usuários e backups próprios. A empresa é escolhida
+          na tela de entrada.
+        </p>
+        {profiles && (
+          <div className="space-y-4">
+            <table className="w-full text-sm">
+              <thead>
+                <tr className="text-left border-b">
+                  <th className="py-1 pr-4">Nome</th>
+                  <th className="py-1 pr-4">Pasta</th>
+                  <th className="py-1"></th>
+                </tr>
+              </thead>
+              <tbody>
+                {profiles.profiles.map((profile) => (
+                  <tr key={profile.id} className="border-b">
+                    <td className="py-1 pr-4 font-medium">
+                      {profile.name}
+                      {profile.id === profiles.active && (
+                        <span className="ml-2 text-xs text-green-600">em uso</span>
+                      )}
+                    </td>
+                    <td className="py-1 pr-4 text-gray-600">{profile.dir}</td>
+                    <td className="py-1 text-right whitespace-nowrap">
+                      <button
+                        onClick={() => handleRenameProfile(profile)}
+                        className="p-1 text-gray-600 hover:text-gray-900"
+                        title="Renomear"
+                      >
+                        <Pencil className="w-4 h-4" />
+                      </button>
+                      {profile.id !== profiles.active && (
+                        <button
+                          onClick={() => handleSwitchProfile(profile)}
+                          className="p-1 text-blue-600 hover:text-blue-800"
+                          title="Abrir perfil"
+                        >
+                          <Building2 className="w-4 h-4" />
+                        </button>
+                      )}
+                    </td>
+                  </tr>
+                ))}
+              </tbody>
+            </table>
+            <div className="flex flex-wrap items-end gap-3">
+              <div className="flex-1 min-w-[200px]">
+                <label className="block text-sm font-medium mb-1">Novo perfil</label>
+                <input
+                  type="text"
+                  placeholder="Nome da empresa"
+                  value={newProfile.name}
+                  onChange={(e) => setNewProfile({ ...newProfile, name: e.target.value })}
+                  className="w-full border rounded-lg px-3 py-2"
+                />
+              </div>
+              <div className="flex-1 min-w-[200px]">
+                <label className="block text-sm font-medium mb-1">Pasta (opcional)</label>
+                <div className="flex gap-2">
+                  <input
+                    type="text"
+                    placeholder="Padrão: pasta de dados do sistema"
+                    value={newProfile.dir}
+                    onChange={(e) => setNewProfile({ ...newProfile, dir: e.target.value })}
+                    className="w-full border rounded-lg px-3 py-2"
+                  />
+                  <button
+                    onClick={handleChooseProfileDir}
+                    className="border px-3 py-2 rounded-lg hover:bg-gray-50"
+                    title="Escolher pasta"
+                  >
+                    <FolderOpen className="w-5 h-5" />
+                  </button>
+                </div>
+              </div>
+              <button
+                onClick={handleCreateProfile}
+                disabled={!newProfile.name.trim()}
+                className="bg-blue-600 text-white px-4 py-2 rounded-lg flex items-center gap-2 hover:bg-blue-700 disabled:opacity-50"
+              >
+                <Plus className="w-5 h-5" />
+                Criar Perfil
+              </button>
+            </div>
           </div>
-          <div>
-            <label className="block text-sm font-medium mb-1">E-mail</label>
-            <input
-              type="email"
-              value={settings.email}
-              onChange={(e) =>
-                setSettings({ ...settings, email: e.target.value })
-              }
-              className="w-full border rounded-lg px-3 py-2"
-            />
+        )}
+      </div>
+
+      <div className="bg-white rounded-lg shadow-md p-6">
+        <h2 className="text-xl font-semibold mb-1">Backup do Banco de Dados</h2>
+        <p className="text-sm text-gray-500 mb-4">
+          Os backups mais recentes e os feitos dentro do prazo de retenção são mantidos; os
+          demais são apagados automaticamente.
+        </p>
+        {backupSettings && (
+          <div className="space-y-4">
+            <div>
+              <label className="block text-sm font-medium mb-1">Pasta dos backups</label>
+              <div className="flex gap-2">
+                <input
+                  type="text"
+                  value={backupSettings.dir}
+                  onChange={(e) => setBackupSettings({ ...backupSettings, dir: e.target.value })}
+                  className="w-full border rounded-lg px-3 py-2"
+                />
+                <button
+                  onClick={handleChooseBackupDir}
+                  className="border px-3 py-2 rounded-lg hover:bg-gray-50"
+                  title="Escolher pasta"
+                >
+                  <FolderOpen className="w-5 h-5" />
+                </button>
+              </div>
+            </div>
+            <div className="grid gap-4 md:grid-cols-3">
+              <div>
+                <label className="block text-sm font-medium mb-1">
+                  Intervalo (horas, 0 desliga)
+                </label>
+                <input
+                  type="number"
+                  min={0}
+                  value={backupSettings.interval_hours}
+                  onChange={(e) =>
+                    setBackupSettings({ ...backupSettings, interval_hours: Number(e.target.value) })
+                  }
+                  className="w-full border rounded-lg px-3 py-2"
+                />
+              </div>
+              <div>
+                <label className="block text-sm font-medium mb-1">Manter no mínimo</label>
+                <input
+                  type="number"
+                  min={1}
+                  value={backupSettings.keep_last}
+                  onChange={(e) =>
+                    setBackupSettings({ ...backupSettings, keep_last: Number(e.target.value) })
+                  }
+                  className="w-full border rounded-lg px-3 py-2"
+                />
+              </div>
+              <div>
+                <label className="block text-sm font-medium mb-1">Manter por (dias)</label>
+                <input
+                  type="number"
+                  min={0}
+                  value={backupSettings.keep_days}
+                  onChange={(e) =>
+                    setBackupSettings({ ...backupSettings, keep_days: Number(e.target.value) })
+                  }
+                  className="w-full border rounded-lg px-3 py-2"
+                />
+              </div>
+            </div>
+            <div className="flex justify-end gap-3">
+              <button
+                onClick={handleCreateBackup}
+                disabled={backupBusy}
+                className="border px-4 py-2 rounded-lg flex items-center gap-2 hover:bg-gray-50 disabled:opacity-50"
+              >
+                <DatabaseBackup className="w-5 h-5" />
+                Fazer Backup Agora
+              </button>
+              <button
+                onClick={handleSaveBackupSettings}
+                className="bg-blue-600 text-white px-4 py-2 rounded-lg flex items-center gap-2 hover:bg-blue-700"
+              >
+                <Save className="w-5 h-5" />
+                Salvar
+              </button>
+            </div>
+
+            {backups.length === 0 ? (
+              <p className="text-sm text-gray-500">Nenhum backup na pasta</p>
+            ) : (
+              <table className="w-full text-sm">
+                <thead>
+                  <tr className="text-left border-b">
+                    <th className="py-1 pr-4">Data</th>
+                    <th className="py-1 pr-4">Arquivo</th>
+                    <th className="py-1 pr-4 text-right">Tamanho</th>
+                    <th className="py-1"></th>
+                  </tr>
+                </thead>
+                <tbody>
+                  {backups.map((backup) => (
+                    <tr key={backup.file_name} className="border-b">
+                      <td className="py-1 pr-4">
+                        {formatBackupDate(backup.created_at)}
+                        {backup.tag && (
+                          <span className="ml-2 text-xs text-amber-600">
+                            {BACKUP_TAG_LABELS[backup.tag] ?? backup.tag}
+                          </span>
+                        )}
+                      </td>
+                      <td className="py-1 pr-4 text-gray-600">{backup.file_name}</td>
+                      <td className="py-1 pr-4 text-right">{formatSize(backup.size_bytes)}</td>
+                      <td className="py-1 text-right whitespace-nowrap">
+                        <button
+                          onClick={() => handleVerifyBackup(backup)}
+                          disabled={backupBusy}
+                          className="p-1 text-gray-600 hover:text-gray-900 disabled:opacity-50"
+                          title="Verificar integridade"
+                        >
+                          <ShieldCheck className="w-4 h-4" />
+                        </button>
+                        <button
+                          onClick={() => handleRestoreBackup(backup)}
+                          disabled={backupBusy}
+                          className="p-1 text-red-600 hover:text-red-800 disabled:opacity-50"
+                          title="Restaurar"
+                        >
+                          <RotateCcw className="w-4 h-4" />
+                        </button>
+                      </td>
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+            )}
           </div>
-          <div className="flex justify-end">
+        )}
+      </div>
+
+      <div className="bg-white rounded-lg shadow-md p-6">
+        <h2 className="text-xl font-semibold mb-1">Importar Cadastros (CSV)</h2>
+        <p className="text-sm text-gray-500 mb-4">
+          Produtos são atualizados pelo nome e clientes pelo telefone. Estoque inicial e
+          validade só valem para produtos novos. Nada é gravado se alguma linha tiver erro.
+        </p>
+        <div className="space-y-4">
+          <div className="flex flex-wrap items-center gap-3">
+            <select
+              value={importTarget}
+              onChange={(e) => {
+                setImportTarget(e.target.value as ImportTarget);
+                setImportReport(null);
+              }}
+              className="border rounded-lg px-3 py-2"
+            >
+              <option value="products">Produtos</option>
+              <option value="customers">Clientes</option>
+            </select>
             <button
-              onClick={handleSave}
-              className="bg-blue-600 text-white px-4 py-2 rounded-lg flex items-center gap-2 hover:bg-blue-700"
+              onClick={handleChooseFile}
+              className="border px-4 py-2 rounded-lg flex items-center gap-2 hover:bg-gray-50"
             >
-              <Save className="w-5 h-5" />
-              Salvar Configurações
+              <Upload className="w-5 h-5" />
+              Escolher Arquivo
             </button>
+            <span className="text-sm text-gray-600 truncate max-w-md">
+              {importPath ?? "Nenhum arquivo selecionado"}
+            </span>
           </div>
+          <p className="text-xs text-gray-500">
+            Colunas aceitas: {IMPORT_COLUMNS[importTarget]}
+          </p>
+          <div className="flex gap-3">
+            <button
+              onClick={() => runImport(true)}
+              disabled={!importPath || importing}
+              className="border px-4 py-2 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+            >
+              Simular
+            </button>
+            <button
+              onClick={() => runImport(false)}
+              disabled={
+                !importPath ||
+                importing ||
+                !importReport?.dry_run ||
+                importReport.failed > 0
+              }
+              className="bg-blue-600 text-white px-4 py-2 rounded-lg hover:bg-blue-700 disabled:opacity-50"
+            >
+              Importar
+            </button>
+          </div>
+
+          {importReport && (
+            <div className="space-y-2">
+              <p className="text-sm">
+                {importReport.dry_run ? "Simulação: " : ""}
+                {importReport.created} novo(s), {importReport.updated} atualizado(s),{" "}
+                <span className={importReport.failed > 0 ? "text-red-600 font-semibold" : ""}>
+                  {importReport.failed} com erro
+                </span>
+              </p>
+              {importReport.failed > 0 && (
+                <table className="w-full text-sm">
+                  <thead>
+                    <tr className="text-left border-b">
+                      <th className="py-1 pr-4">Linha</th>
+                      <th className="py-1 pr-4">Registro</th>
+                      <th className="py-1">Erros</th>
+                    </tr>
+                  </thead>
+                  <tbody>
+                    {importReport.rows
+                      .filter((row) => row.action === "error")
+                      .map((row) => (
+                        <tr key={row.line} className="border-b align-top">
+                          <td className="py-1 pr-4">{row.line}</td>
+                          <td className="py-1 pr-4">{row.key ?? "-"}</td>
+                          <td className="py-1 text-red-600">{row.errors.join("; ")}</td>
+                        </tr>
+                      ))}
+                  </tbody>
+                </table>
+              )}
+            </div>
+          )}
         </div>
       </div>
     </div>
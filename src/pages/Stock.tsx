@@ -2,9 +2,18 @@ import { useEffect, useState, useCallback, useMemo } from "react";
 import { useProductsStore } from "../state/productsStore";
 import { stockApi } from "../api/stock";
 import { productsApi } from "../api/products";
+import { stockLocationsApi } from "../api/stockLocations";
+import { settingsApi } from "../api/settings";
 import { useAuthStore } from "@/state/authStore";
-import type { StockMovementWithProduct, Product, UpdateProductPayload } from "../types";
-import { Plus, Edit, Trash2, Warehouse, History, Package } from "lucide-react";
+import type {
+  StockMovementWithProduct,
+  MovementType,
+  Product,
+  StockLocation,
+  StockLot,
+  UpdateProductPayload,
+} from "../types";
+import { Plus, Edit, Trash2, Warehouse, History, Package, CalendarClock } from "lucide-react";
 import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
@@ -45,7 +54,32 @@ import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import { Badge } from "@/components/ui/badge";
 import { Separator } from "@/components/ui/separator";
 
-type TabType = "stock" | "movements";
+type TabType = "stock" | "movements" | "lots";
+
+// Lotes vencendo nesse prazo ficam destacados; o prazo vem das configurações
+const DEFAULT_EXPIRY_WARNING_DAYS = 30;
+
+// 'YYYY-MM-DD' -> 'DD/MM/YYYY' sem passar por Date (evita deslocamento de fuso)
+const formatExpiry = (date: string) => date.split("-").reverse().join("/");
+
+type MovementFormType = "IN" | "OUT" | "ADJUST" | "EXCHANGE" | "TRANSFER";
+
+// Loja: local padrão das movimentações
+const DEFAULT_LOCATION_ID = 1;
+
+const MOVEMENT_LABELS: Record<MovementType, string> = {
+  IN: "ENTRADA",
+  OUT: "SAÍDA",
+  ADJUST: "AJUSTE",
+  RETURN: "DEVOLUÇÃO",
+  REVERSAL: "ESTORNO",
+  EXCHANGE: "TROCA FORNECEDOR",
+  TRANSFER: "TRANSFERÊNCIA",
+};
+
+// "OUT" é gravado com quantidade positiva; os demais tipos já trazem o sinal
+const signedQuantity = (movement: StockMovementWithProduct) =>
+  movement.movement_type === "OUT" ? -movement.quantity : movement.quantity;
 
 export default function Stock() {
   const products = useProductsStore((state) => state.products);
@@ -59,9 +93,16 @@ export default function Stock() {
   
   // Modal de movimentação
   const [showMovementModal, setShowMovementModal] = useState(false);
-  const [movementType, setMovementType] = useState<"IN" | "OUT" | "ADJUST">("IN");
+  const [movementType, setMovementType] = useState<MovementFormType>("IN");
   const [selectedProduct, setSelectedProduct] = useState<number | null>(null);
   const [quantity, setQuantity] = useState<number>(0);
+  const [unitCost, setUnitCost] = useState<number>(0);
+  const [locations, setLocations] = useState<StockLocation[]>([]);
+  const [locationId, setLocationId] = useState<number>(DEFAULT_LOCATION_ID);
+  const [toLocationId, setToLocationId] = useState<number | null>(null);
+  const [expiryDate, setExpiryDate] = useState("");
+  const [lots, setLots] = useState<StockLot[]>([]);
+  const [loadingLots, setLoadingLots] = useState(false);
   
   // Modal de edição de produto
   const [showEditModal, setShowEditModal] = useState(false);
@@ -72,6 +113,15 @@ export default function Stock() {
   const [showDeleteDialog, setShowDeleteDialog] = useState(false);
   const [productToDelete, setProductToDelete] = useState<Product | null>(null);
 
+  const [expiryWarningDays, setExpiryWarningDays] = useState(DEFAULT_EXPIRY_WARNING_DAYS);
+
+  useEffect(() => {
+    settingsApi
+      .getCompany()
+      .then((settings) => setExpiryWarningDays(settings.expiry_warning_days))
+      .catch((error) => console.error("Erro ao carregar configurações:", error));
+  }, []);
+
   useEffect(() => {
     fetchProducts();
     if (activeTab === "movements") {
@@ -79,6 +129,13 @@ export default function Stock() {
     }
   }, [fetchProducts, activeTab]);
 
+  useEffect(() => {
+    stockLocationsApi
+      .getAll()
+      .then((data) => setLocations(data.filter((location) => location.active)))
+      .catch((error) => console.error("Erro ao carregar locais:", error));
+  }, []);
+
   const loadMovements = useCallback(async () => {
     setLoadingMovements(true);
     try {
@@ -91,6 +148,17 @@ export default function Stock() {
     }
   }, []);
 
+  const loadLots = useCallback(async () => {
+    setLoadingLots(true);
+    try {
+      setLots(await stockApi.getLots());
+    } catch (error) {
+      console.error("Erro ao carregar lotes:", error);
+    } finally {
+      setLoadingLots(false);
+    }
+  }, []);
+
   const handleMovement = useCallback(async () => {
     if (!selectedProduct || quantity <= 0) {
       alert("Selecione um produto e informe a quantidade");
@@ -101,26 +169,68 @@ export default function Stock() {
     try {
       switch (movementType) {
         case "IN":
-          await stockApi.stockIn(selectedProduct, quantity);
+          await stockApi.stockIn(
+            selectedProduct,
+            quantity,
+            unitCost || null,
+            locationId,
+            expiryDate || null
+          );
           break;
         case "OUT":
-          await stockApi.stockOut(selectedProduct, quantity);
+          await stockApi.stockOut(selectedProduct, quantity, locationId);
           break;
         case "ADJUST":
-          await stockApi.stockAdjust(selectedProduct, quantity);
+          await stockApi.stockAdjust(selectedProduct, quantity, "full", locationId);
+          break;
+        case "EXCHANGE":
+          await stockApi.supplierExchange(
+            selectedProduct,
+            quantity,
+            unitCost,
+            locationId,
+            expiryDate || null
+          );
+          break;
+        case "TRANSFER":
+          if (!toLocationId) {
+            alert("Selecione o local de destino");
+            return;
+          }
+          await stockApi.transfer({
+            product_id: selectedProduct,
+            from_location_id: locationId,
+            to_location_id: toLocationId,
+            quantity,
+          });
           break;
       }
       await fetchProducts();
       await loadMovements();
+      await loadLots();
       setShowMovementModal(false);
       setSelectedProduct(null);
       setQuantity(0);
+      setUnitCost(0);
+      setToLocationId(null);
+      setExpiryDate("");
     } catch (error) {
       alert("Erro ao realizar movimentação: " + error);
     } finally {
       setLoading(false);
     }
-  }, [selectedProduct, quantity, movementType, fetchProducts, loadMovements]);
+  }, [
+    selectedProduct,
+    quantity,
+    unitCost,
+    movementType,
+    locationId,
+    toLocationId,
+    expiryDate,
+    fetchProducts,
+    loadMovements,
+    loadLots,
+  ]);
 
   const handleEdit = useCallback((product: Product) => {
     setEditingProduct(product);
@@ -223,6 +333,22 @@ export default function Stock() {
             Movimentações
           </div>
         </button>
+        <button
+          onClick={() => {
+            setActiveTab("lots");
+            loadLots();
+          }}
+          className={`px-4 py-2 font-medium transition-colors ${
+            activeTab === "lots"
+              ? "border-b-2 border-primary text-primary"
+              : "text-muted-foreground hover:text-foreground"
+          }`}
+        >
+          <div className="flex items-center gap-2">
+            <CalendarClock className="w-4 h-4" />
+            Lotes e Validades
+          </div>
+        </button>
       </div>
 
       {/* Conteúdo das Tabs */}
@@ -315,7 +441,7 @@ export default function Stock() {
               )}
             </CardContent>
           </Card>
-        ) : (
+        ) : activeTab === "movements" ? (
           <Card className="h-full flex flex-col">
             <CardHeader>
               <div className="flex items-center justify-between">
@@ -352,6 +478,7 @@ export default function Stock() {
                         <TableHead>Data/Hora</TableHead>
                         <TableHead>Produto</TableHead>
                         <TableHead>Tipo</TableHead>
+                        <TableHead>Local</TableHead>
                         <TableHead className="text-right">Quantidade</TableHead>
                       </TableRow>
                     </TableHeader>
@@ -380,23 +507,25 @@ export default function Stock() {
                                   : "secondary"
                               }
                             >
-                              {movement.movement_type === "IN"
-                                ? "ENTRADA"
-                                : movement.movement_type === "OUT"
-                                ? "SAÍDA"
-                                : "AJUSTE"}
+                              {MOVEMENT_LABELS[movement.movement_type]}
                             </Badge>
+                            {movement.stock_kind === "empty" && (
+                              <Badge variant="outline" className="ml-2">
+                                CASCO
+                              </Badge>
+                            )}
                           </TableCell>
+                          <TableCell>{movement.location_name ?? "-"}</TableCell>
                           <TableCell className="text-right font-semibold">
                             <span
                               className={
-                                movement.movement_type === "OUT"
+                                signedQuantity(movement) < 0
                                   ? "text-destructive"
                                   : "text-green-600"
                               }
                             >
-                              {movement.movement_type === "OUT" ? "-" : "+"}
-                              {movement.quantity}
+                              {signedQuantity(movement) < 0 ? "-" : "+"}
+                              {Math.abs(movement.quantity)}
                             </span>
                           </TableCell>
                         </TableRow>
@@ -407,6 +536,63 @@ export default function Stock() {
               )}
             </CardContent>
           </Card>
+        ) : (
+          <Card className="h-full flex flex-col">
+            <CardHeader>
+              <CardTitle className="flex items-center gap-2">
+                <CalendarClock className="w-5 h-5" />
+                Lotes em Estoque
+              </CardTitle>
+            </CardHeader>
+            <CardContent className="flex-1 overflow-y-auto p-0">
+              {loadingLots ? (
+                <div className="flex items-center justify-center h-32">
+                  <p className="text-sm text-muted-foreground">Carregando...</p>
+                </div>
+              ) : lots.length === 0 ? (
+                <div className="flex items-center justify-center h-32">
+                  <p className="text-sm text-muted-foreground">
+                    Nenhum lote com validade registrado
+                  </p>
+                </div>
+              ) : (
+                <div className="overflow-x-auto">
+                  <Table>
+                    <TableHeader>
+                      <TableRow>
+                        <TableHead>Validade</TableHead>
+                        <TableHead>Produto</TableHead>
+                        <TableHead>Local</TableHead>
+                        <TableHead className="text-right">Quantidade</TableHead>
+                        <TableHead className="text-right">Situação</TableHead>
+                      </TableRow>
+                    </TableHeader>
+                    <TableBody>
+                      {lots.map((lot) => (
+                        <TableRow key={lot.id}>
+                          <TableCell>{formatExpiry(lot.expiry_date)}</TableCell>
+                          <TableCell className="font-medium">{lot.product_name}</TableCell>
+                          <TableCell>{lot.location_name}</TableCell>
+                          <TableCell className="text-right">{lot.quantity}</TableCell>
+                          <TableCell className="text-right">
+                            {lot.days_to_expiry < 0 ? (
+                              <Badge variant="destructive">VENCIDO</Badge>
+                            ) : lot.days_to_expiry <= expiryWarningDays ? (
+                              <Badge variant="secondary">
+                                Vence em {lot.days_to_expiry} dia(s)
+                              </Badge>
+                            ) : (
+                              <Badge variant="outline">OK</Badge>
+                            )}
+                          </TableCell>
+                        </TableRow>
+                      ))}
+                    </TableBody>
+                  </Table>
+                </div>
+              )}
+            </CardContent>
+          </Card>
         )}
       </div>
 
@@ -416,7 +602,7 @@ export default function Stock() {
           <DialogHeader>
             <DialogTitle>Nova Movimentação de Estoque</DialogTitle>
             <DialogDescription>
-              Registre uma entrada, saída ou ajuste no estoque
+              Registre uma entrada, saída, ajuste ou transferência entre locais
             </DialogDescription>
           </DialogHeader>
           <div className="space-y-4 py-4">
@@ -425,7 +611,7 @@ export default function Stock() {
               <Select
                 value={movementType}
                 onValueChange={(value) =>
-                  setMovementType(value as "IN" | "OUT" | "ADJUST")
+                  setMovementType(value as MovementFormType)
                 }
               >
                 <SelectTrigger id="movement-type">
@@ -435,9 +621,53 @@ export default function Stock() {
                   <SelectItem value="IN">Entrada</SelectItem>
                   <SelectItem value="OUT">Saída</SelectItem>
                   <SelectItem value="ADJUST">Ajuste</SelectItem>
+                  <SelectItem value="EXCHANGE">Troca com Fornecedor</SelectItem>
+                  <SelectItem value="TRANSFER">Transferência</SelectItem>
+                </SelectContent>
+              </Select>
+            </div>
+            <div className="space-y-2">
+              <Label htmlFor="location">
+                {movementType === "TRANSFER" ? "Local de Origem" : "Local"}
+              </Label>
+              <Select
+                value={locationId.toString()}
+                onValueChange={(value) => setLocationId(parseInt(value))}
+              >
+                <SelectTrigger id="location">
+                  <SelectValue />
+                </SelectTrigger>
+                <SelectContent>
+                  {locations.map((location) => (
+                    <SelectItem key={location.id} value={location.id.toString()}>
+                      {location.name}
+                    </SelectItem>
+                  ))}
                 </SelectContent>
               </Select>
             </div>
+            {movementType === "TRANSFER" && (
+              <div className="space-y-2">
+                <Label htmlFor="to-location">Local de Destino</Label>
+                <Select
+                  value={toLocationId?.toString() || ""}
+                  onValueChange={(value) => setToLocationId(parseInt(value) || null)}
+                >
+                  <SelectTrigger id="to-location">
+                    <SelectValue placeholder="Selecione o destino" />
+                  </SelectTrigger>
+                  <SelectContent>
+                    {locations
+                      .filter((location) => location.id !== locationId)
+                      .map((location) => (
+                        <SelectItem key={location.id} value={location.id.toString()}>
+                          {location.name}
+                        </SelectItem>
+                      ))}
+                  </SelectContent>
+                </Select>
+              </div>
+            )}
             <div className="space-y-2">
               <Label htmlFor="product">Produto</Label>
               <Select
@@ -473,7 +703,39 @@ export default function Stock() {
                   Use valores negativos para reduzir o estoque
                 </p>
               )}
+              {movementType === "EXCHANGE" && (
+                <p className="text-xs text-muted-foreground">
+                  Cascos vazios enviados e cheios recebidos na mesma quantidade
+                </p>
+              )}
             </div>
+            {(movementType === "EXCHANGE" || movementType === "IN") && (
+              <div className="space-y-2">
+                <Label htmlFor="expiry-date">Validade do Lote</Label>
+                <Input
+                  id="expiry-date"
+                  type="date"
+                  value={expiryDate}
+                  onChange={(e) => setExpiryDate(e.target.value)}
+                />
+                <p className="text-xs text-muted-foreground">
+                  Deixe em branco para produtos sem controle de validade
+                </p>
+              </div>
+            )}
+            {(movementType === "EXCHANGE" || movementType === "IN") && (
+              <div className="space-y-2">
+                <Label htmlFor="unit-cost">Custo por Unidade (R$)</Label>
+                <Input
+                  id="unit-cost"
+                  type="number"
+                  min="0"
+                  step="0.01"
+                  value={unitCost || ""}
+                  onChange={(e) => setUnitCost(parseFloat(e.target.value) || 0)}
+                />
+              </div>
+            )}
           </div>
           <DialogFooter>
             <Button
@@ -8,8 +8,10 @@ export interface Product {
   price_full: number;
   stock_full: number;
   stock_empty: number;
-  expiry_month: number | null;
-  expiry_year: number | null;
+  cost_price: number;
+  average_cost: number;
+  min_stock: number; // Abaixo disso o produto entra no estoque crítico
+  reorder_qty: number; // Lote padrão de reposição
 }
 
 export interface CreateProductPayload {
@@ -20,8 +22,10 @@ export interface CreateProductPayload {
   price_full: number;
   stock_full?: number;
   stock_empty?: number;
-  expiry_month?: number | null;
-  expiry_year?: number | null;
+  expiry_date?: string | null; // Validade do estoque inicial, 'YYYY-MM-DD'
+  cost_price?: number | null;
+  min_stock?: number | null;
+  reorder_qty?: number | null;
 }
 
 export interface UpdateProductPayload {
@@ -32,8 +36,9 @@ export interface UpdateProductPayload {
   price_full?: number;
   stock_full?: number;
   stock_empty?: number;
-  expiry_month?: number | null;
-  expiry_year?: number | null;
+  cost_price?: number | null;
+  min_stock?: number | null;
+  reorder_qty?: number | null;
 }
 
 // ========== CUSTOMERS ==========
@@ -43,6 +48,7 @@ export interface Customer {
   phone: string | null;
   address: string | null;
   notes: string | null;
+  credit_limit: number;
 }
 
 export interface CreateCustomerPayload {
@@ -50,6 +56,7 @@ export interface CreateCustomerPayload {
   phone?: string | null;
   address?: string | null;
   notes?: string | null;
+  credit_limit?: number | null;
 }
 
 export interface UpdateCustomerPayload {
@@ -57,6 +64,48 @@ export interface UpdateCustomerPayload {
   phone?: string | null;
   address?: string | null;
   notes?: string | null;
+  credit_limit?: number | null;
+}
+
+// ========== CUSTOMER ACCOUNTS (FIADO) ==========
+export interface CustomerLedgerEntry {
+  id: number;
+  customer_id: number;
+  entry_type: "CHARGE" | "PAYMENT" | "ADJUST";
+  amount: number;
+  method: string | null;
+  order_id: number | null;
+  user_id: number | null;
+  session_id: number | null;
+  description: string | null;
+  created_at: string;
+  running_balance: number;
+}
+
+export interface CustomerStatement {
+  customer: Customer;
+  balance: number;
+  available_credit: number;
+  entries: CustomerLedgerEntry[];
+}
+
+export interface CustomerBalance {
+  customer_id: number;
+  customer_name: string;
+  phone: string | null;
+  credit_limit: number;
+  balance: number;
+}
+
+export interface CustomerPaymentPayload {
+  amount: number;
+  method: Exclude<PaymentMethod, "on_account">;
+  description?: string | null;
+}
+
+export interface CustomerAdjustmentPayload {
+  amount: number;
+  description: string;
 }
 
 // ========== ORDERS ==========
@@ -65,14 +114,30 @@ export interface Order {
   customer_id: number | null;
   total: number;
   created_at: string;
+  user_id: number | null;
+  session_id: number | null;
+  status: OrderStatus;
+  cancel_reason: string | null;
+  cancelled_by: number | null;
+  cancelled_at: string | null;
 }
 
+export type OrderStatus = "open" | "completed" | "cancelled";
+
 export interface OrderWithCustomer {
   id: number;
   customer_id: number | null;
   customer_name: string | null;
   total: number;
   created_at: string;
+  user_id: number | null;
+  operator_name: string | null;
+  session_id: number | null;
+  status: OrderStatus;
+  cancel_reason: string | null;
+  cancelled_by: number | null;
+  cancelled_by_username: string | null;
+  cancelled_at: string | null;
 }
 
 export interface OrderItem {
@@ -82,6 +147,7 @@ export interface OrderItem {
   quantity: number;
   returned_bottle: boolean;
   unit_price: number;
+  unit_cost: number;
 }
 
 export interface OrderItemPayload {
@@ -91,15 +157,41 @@ export interface OrderItemPayload {
   unit_price: number;
 }
 
+export type PaymentMethod = "cash" | "pix" | "debit" | "credit" | "on_account";
+
+export interface OrderPaymentPayload {
+  method: PaymentMethod;
+  amount: number;
+  tendered?: number | null;
+}
+
+export interface OrderPayment {
+  id: number;
+  order_id: number;
+  method: PaymentMethod;
+  amount: number;
+  tendered: number | null;
+  change_due: number;
+  created_at: string;
+}
+
 export interface CreateOrderPayload {
   customer_id?: number | null;
   items: OrderItemPayload[];
+  payments: OrderPaymentPayload[];
+  delivery?: DeliveryPayload | null;
+  location_id?: number | null; // Local de onde a venda sai (padrão: loja)
+  allow_expired?: boolean | null; // Libera venda de lote vencido (só admin)
 }
 
 export interface UpdateOrderPayload {
   created_at?: string;
 }
 
+export interface CancelOrderPayload {
+  reason: string;
+}
+
 export interface OrderItemWithProduct {
   id: number;
   order_id: number;
@@ -108,19 +200,354 @@ export interface OrderItemWithProduct {
   quantity: number;
   returned_bottle: boolean;
   unit_price: number;
+  unit_cost: number;
 }
 
 export interface OrderWithItems {
   order: OrderWithCustomer;
   items: OrderItemWithProduct[];
+  payments: OrderPayment[];
+  delivery: Delivery | null;
+}
+
+// ========== BOTTLE LOANS (COMODATO) ==========
+export interface BottleLoanEntry {
+  id: number;
+  customer_id: number;
+  product_id: number;
+  product_name: string;
+  entry_type: "LENT" | "RETURNED";
+  quantity: number;
+  deposit_amount: number;
+  order_id: number | null;
+  user_id: number | null;
+  notes: string | null;
+  created_at: string;
+}
+
+export interface CustomerBottleBalance {
+  customer_id: number;
+  customer_name: string;
+  phone: string | null;
+  product_id: number;
+  product_name: string;
+  quantity: number;
+  deposit_held: number;
+}
+
+export interface ReturnBottlesPayload {
+  product_id: number;
+  quantity: number;
+  refund_amount?: number | null;
+  notes?: string | null;
+}
+
+// ========== CASH SESSIONS ==========
+export interface CashSession {
+  id: number;
+  status: "open" | "closed";
+  opening_float: number;
+  opened_by: number;
+  opened_by_username: string | null;
+  opened_at: string;
+  closed_by: number | null;
+  closed_by_username: string | null;
+  closed_at: string | null;
+  notes: string | null;
+}
+
+export interface CashMovement {
+  id: number;
+  session_id: number;
+  movement_type: "WITHDRAWAL" | "SUPPLY";
+  method: Exclude<PaymentMethod, "on_account">;
+  amount: number;
+  reason: string | null;
+  user_id: number | null;
+  created_at: string;
+}
+
+export interface OpenCashSessionPayload {
+  opening_float: number;
+  notes?: string | null;
+}
+
+export interface CashMovementPayload {
+  movement_type: "WITHDRAWAL" | "SUPPLY";
+  method: Exclude<PaymentMethod, "on_account">;
+  amount: number;
+  reason?: string | null;
+}
+
+export interface CashCountPayload {
+  method: Exclude<PaymentMethod, "on_account">;
+  counted: number;
+}
+
+export interface CloseCashSessionPayload {
+  counts: CashCountPayload[];
+  notes?: string | null;
+}
+
+export interface CashSessionReportLine {
+  method: string;
+  expected: number;
+  counted: number | null;
+  difference: number | null;
+}
+
+export interface CashSessionReport {
+  session: CashSession;
+  lines: CashSessionReportLine[];
+  order_count: number;
+  sales_total: number;
+  on_account_total: number;
+  supplies: number;
+  withdrawals: number;
+  movements: CashMovement[];
+}
+
+// ========== DELIVERIES ==========
+export type DeliveryStatus =
+  | "pending"
+  | "out_for_delivery"
+  | "delivered"
+  | "failed";
+
+export interface Delivery {
+  id: number;
+  order_id: number;
+  customer_id: number | null;
+  customer_name: string | null;
+  phone: string | null;
+  address: string;
+  driver_id: number | null;
+  driver_name: string | null;
+  driver_shift_id: number | null;
+  delivery_fee: number;
+  order_total: number;
+  status: DeliveryStatus;
+  notes: string | null;
+  failure_reason: string | null;
+  created_at: string;
+  dispatched_at: string | null;
+  delivered_at: string | null;
+  failed_at: string | null;
+}
+
+export interface DeliveryPayload {
+  address?: string | null;
+  delivery_fee?: number | null;
+  notes?: string | null;
+}
+
+export interface DispatchDeliveryPayload {
+  driver_id: number;
+}
+
+export interface FailDeliveryPayload {
+  reason: string;
+}
+
+// ========== DRIVERS ==========
+export interface Driver {
+  id: number;
+  name: string;
+  phone: string | null;
+  active: boolean;
+  created_at: string;
+}
+
+export interface CreateDriverPayload {
+  name: string;
+  phone?: string | null;
+}
+
+export interface UpdateDriverPayload {
+  name?: string | null;
+  phone?: string | null;
+  active?: boolean | null;
+}
+
+export interface DriverShift {
+  id: number;
+  driver_id: number;
+  driver_name: string;
+  status: "open" | "closed";
+  change_fund: number;
+  cash_expected: number | null;
+  cash_counted: number | null;
+  opened_by: number;
+  opened_by_username: string | null;
+  opened_at: string;
+  closed_by: number | null;
+  closed_by_username: string | null;
+  closed_at: string | null;
+  notes: string | null;
+}
+
+export interface DriverLoadPayload {
+  product_id: number;
+  quantity: number;
+}
+
+export interface OpenDriverShiftPayload {
+  driver_id: number;
+  change_fund: number;
+  loads: DriverLoadPayload[];
+  notes?: string | null;
+}
+
+export interface DriverReturnPayload {
+  product_id: number;
+  full_returned: number;
+  empty_returned: number;
+}
+
+export interface CloseDriverShiftPayload {
+  cash_counted: number;
+  returns: DriverReturnPayload[];
+  notes?: string | null;
+}
+
+export interface DriverShiftLine {
+  product_id: number;
+  product_name: string;
+  full_loaded: number;
+  full_delivered: number;
+  full_expected: number;
+  full_returned: number | null;
+  full_difference: number | null;
+  empty_expected: number;
+  empty_returned: number | null;
+  empty_difference: number | null;
+}
+
+export interface DriverShiftReport {
+  shift: DriverShift;
+  lines: DriverShiftLine[];
+  deliveries: Delivery[];
+  cash_collected: number;
+  cash_expected: number;
+  cash_counted: number | null;
+  cash_difference: number | null;
+}
+
+// ========== SUPPLIERS ==========
+export interface Supplier {
+  id: number;
+  name: string;
+  document: string | null;
+  phone: string | null;
+  email: string | null;
+  notes: string | null;
+  created_at: string;
+}
+
+export interface CreateSupplierPayload {
+  name: string;
+  document?: string | null;
+  phone?: string | null;
+  email?: string | null;
+  notes?: string | null;
+}
+
+export interface UpdateSupplierPayload {
+  name?: string;
+  document?: string | null;
+  phone?: string | null;
+  email?: string | null;
+  notes?: string | null;
+}
+
+// ========== PURCHASE ORDERS ==========
+export type PurchaseOrderStatus = "open" | "partial" | "received" | "cancelled";
+
+export interface PurchaseOrder {
+  id: number;
+  supplier_id: number;
+  supplier_name: string;
+  status: PurchaseOrderStatus;
+  expected_date: string | null;
+  notes: string | null;
+  user_id: number | null;
+  created_at: string;
+  total_cost: number;
+}
+
+export interface PurchaseOrderItem {
+  id: number;
+  purchase_order_id: number;
+  product_id: number;
+  product_name: string;
+  quantity_ordered: number;
+  quantity_received: number;
+  unit_cost: number;
+}
+
+export interface PurchaseReceipt {
+  id: number;
+  purchase_order_id: number;
+  invoice_number: string | null;
+  notes: string | null;
+  user_id: number | null;
+  received_at: string;
+}
+
+export interface PurchaseOrderWithItems {
+  order: PurchaseOrder;
+  items: PurchaseOrderItem[];
+  receipts: PurchaseReceipt[];
+}
+
+export interface PurchaseOrderItemPayload {
+  product_id: number;
+  quantity: number;
+  unit_cost: number;
+}
+
+export interface CreatePurchaseOrderPayload {
+  supplier_id: number;
+  expected_date?: string | null;
+  notes?: string | null;
+  items: PurchaseOrderItemPayload[];
+}
+
+export interface ReceiveLinePayload {
+  item_id: number;
+  quantity: number;
+  expiry_date?: string | null; // Validade do lote recebido, 'YYYY-MM-DD'
+}
+
+export interface ReceivePurchaseOrderPayload {
+  invoice_number?: string | null;
+  notes?: string | null;
+  lines: ReceiveLinePayload[];
 }
 
 // ========== STOCK MOVEMENTS ==========
+export type MovementType =
+  | "IN"
+  | "OUT"
+  | "ADJUST"
+  | "RETURN"
+  | "REVERSAL"
+  | "EXCHANGE"
+  | "TRANSFER";
+
+export type StockKind = "full" | "empty";
+
 export interface StockMovement {
   id: number;
   product_id: number;
-  movement_type: "IN" | "OUT" | "ADJUST";
+  movement_type: MovementType;
+  stock_kind: StockKind;
   quantity: number;
+  unit_cost: number | null;
+  order_id: number | null;
+  purchase_receipt_id: number | null;
+  driver_shift_id: number | null;
+  location_id: number | null;
   created_at: string;
 }
 
@@ -128,8 +555,81 @@ export interface StockMovementWithProduct {
   id: number;
   product_id: number;
   product_name: string;
-  movement_type: "IN" | "OUT" | "ADJUST";
+  movement_type: MovementType;
+  stock_kind: StockKind;
+  quantity: number;
+  unit_cost: number | null;
+  order_id: number | null;
+  purchase_receipt_id: number | null;
+  driver_shift_id: number | null;
+  location_id: number | null;
+  location_name: string | null;
+  created_at: string;
+}
+
+export interface TransferStockPayload {
+  product_id: number;
+  from_location_id: number;
+  to_location_id: number;
+  stock_kind?: StockKind | null;
+  quantity: number;
+}
+
+export interface ReorderSuggestion {
+  product_id: number;
+  product_name: string;
+  stock_full: number;
+  min_stock: number;
+  reorder_qty: number;
+  on_order: number; // Ainda a receber em pedidos de compra abertos
+  sold_quantity: number; // Vendido na janela analisada
+  avg_daily_sales: number;
+  days_of_cover: number | null; // Dias até zerar no giro atual (null sem vendas)
+  target_stock: number;
+  suggested_qty: number;
+}
+
+// ========== STOCK LOCATIONS ==========
+export type StockLocationKind = "store" | "warehouse" | "vehicle";
+
+export interface StockLocation {
+  id: number;
+  name: string;
+  kind: StockLocationKind;
+  driver_id: number | null;
+  active: boolean;
+  created_at: string;
+}
+
+export interface CreateStockLocationPayload {
+  name: string;
+  kind: StockLocationKind;
+}
+
+export interface UpdateStockLocationPayload {
+  name?: string;
+  active?: boolean;
+}
+
+export interface LocationStock {
+  product_id: number;
+  product_name: string;
+  location_id: number;
+  location_name: string;
+  stock_full: number;
+  stock_empty: number;
+}
+
+// ========== STOCK LOTS ==========
+export interface StockLot {
+  id: number;
+  product_id: number;
+  product_name: string;
+  location_id: number;
+  location_name: string;
+  expiry_date: string;
   quantity: number;
+  days_to_expiry: number; // Negativo quando já venceu
   created_at: string;
 }
 
@@ -137,7 +637,11 @@ export interface StockMovementWithProduct {
 export interface DashboardStats {
   sales_today: number;
   sales_month: number;
+  margin_today: number;
+  margin_month: number;
   critical_stock: Product[];
+  expired_lots: StockLot[];
+  expiring_lots: StockLot[];
   top_products: TopProduct[];
   active_customers: number;
 }
@@ -147,6 +651,150 @@ export interface TopProduct {
   product_name: string;
   total_quantity: number;
   total_revenue: number;
+  total_cost: number;
+  gross_margin: number;
+}
+
+// ========== REPORTS ==========
+export type ReportGrouping =
+  | "day"
+  | "week"
+  | "month"
+  | "product"
+  | "product_type"
+  | "customer"
+  | "operator"
+  | "payment_method";
+
+export interface SalesReportLine {
+  key: string | null; // Data, id ou código do grupo; nulo para venda sem cliente/operador
+  label: string | null; // Nome do produto, cliente ou operador
+  order_count: number;
+  quantity: number;
+  total: number;
+}
+
+export interface SalesReport {
+  start_date: string;
+  end_date: string;
+  group_by: ReportGrouping;
+  order_count: number;
+  quantity: number;
+  total: number;
+  lines: SalesReportLine[];
+}
+
+// ========== EXPORTS ==========
+export type ExportFormat = "csv" | "xlsx";
+
+// ========== IMPORTS ==========
+export interface ImportRowResult {
+  line: number; // Linha no arquivo, contando o cabeçalho
+  key: string | null; // Nome do produto ou telefone do cliente
+  action: "create" | "update" | "error";
+  errors: string[];
+}
+
+export interface ImportReport {
+  dry_run: boolean;
+  applied: boolean; // Só grava quando não é simulação e nenhuma linha tem erro
+  created: number;
+  updated: number;
+  failed: number;
+  rows: ImportRowResult[];
+}
+
+// ========== BACKUPS ==========
+export interface BackupSettings {
+  dir: string;
+  interval_hours: number; // 0 desliga o backup automático
+  keep_last: number;
+  keep_days: number;
+}
+
+export interface UpdateBackupSettingsPayload {
+  dir?: string;
+  interval_hours?: number;
+  keep_last?: number;
+  keep_days?: number;
+}
+
+export interface BackupFile {
+  file_name: string;
+  path: string;
+  size_bytes: number;
+  created_at: string; // Horário local, 'YYYY-MM-DD HH:MM:SS'
+  tag: string | null; // Cópia automática: 'pre-restore', 'pre-migration' ou 'pre-recovery'
+}
+
+export interface BackupCheck {
+  file_name: string;
+  ok: boolean;
+  schema_version: number | null;
+  message: string;
+}
+
+// ========== COMPANY SETTINGS ==========
+export interface CompanySettings {
+  company_name: string;
+  cnpj: string | null; // Formatado: 00.000.000/0000-00
+  address: string | null;
+  phone: string | null;
+  email: string | null;
+  receipt_footer: string;
+  expiry_warning_days: number;
+  default_min_stock: number;
+  currency_symbol: string;
+  decimal_separator: "," | ".";
+}
+
+// Campo opcional com texto vazio é apagado
+export type UpdateCompanySettingsPayload = Partial<CompanySettings>;
+
+// ========== PRINTER SETTINGS ==========
+export type PrinterConnection = "none" | "device" | "network";
+
+export interface PrinterSettings {
+  connection: PrinterConnection;
+  device_path: string | null; // Ex.: /dev/usb/lp0 ou \\localhost\Termica
+  host: string | null;
+  port: number; // Impressora de rede em modo RAW, normalmente 9100
+  paper_width: 58 | 80;
+  cut_paper: boolean;
+  qr_code: string | null; // Texto do QR code no fim do recibo (ex.: chave Pix)
+}
+
+// Campo opcional com texto vazio é apagado
+export type UpdatePrinterSettingsPayload = Partial<PrinterSettings>;
+
+// ========== PROFILES ==========
+export interface Profile {
+  id: string;
+  name: string;
+  dir: string; // Pasta do banco e dos backups do perfil
+}
+
+export interface ProfilesConfig {
+  active: string;
+  profiles: Profile[];
+}
+
+export interface CreateProfilePayload {
+  name: string;
+  dir?: string;
+}
+
+// ========== RECOVERY ==========
+export type RecoveryKind = "checksum_mismatch" | "newer_database" | "dirty" | "migration_failed";
+
+export interface RecoveryInfo {
+  kind: RecoveryKind;
+  version: number | null;
+  message: string;
+  db_path: string;
+  backup_dir: string;
+  safety_backup: BackupFile | null; // Cópia feita ao detectar o problema
+  backup_error: string | null;
 }
 
 // ========== CART ==========
@@ -157,6 +805,29 @@ export interface CartItem {
   customPrice?: number; // Preço unitário customizado (opcional)
 }
 
+// ========== AUDIT LOG ==========
+export interface AuditLogEntry {
+  id: number;
+  user_id: number;
+  username: string;
+  action: string;
+  entity: string;
+  entity_id: number | null;
+  before_json: string | null;
+  after_json: string | null;
+  created_at: string;
+}
+
+export interface AuditLogFilter {
+  user_id?: number | null;
+  entity?: string | null;
+  entity_id?: number | null;
+  action?: string | null;
+  date_from?: string | null;
+  date_to?: string | null;
+  limit?: number | null;
+}
+
 // ========== USERS ==========
 export interface SafeUser {
   id: number;